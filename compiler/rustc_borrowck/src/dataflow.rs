@@ -320,9 +320,9 @@ fn new(body: &'a Body<'tcx>, regioncx: &'a RegionInferenceContext<'tcx>) -> Self
 }
 
 impl<'tcx> PoloniusOutOfScopePrecomputer<'_, 'tcx> {
-    /// Loans are in scope while they are live: whether they are contained within any live region.
-    /// In the location-insensitive analysis, a loan will be contained in a region if the issuing
-    /// region can reach it in the subset graph. So this is a reachability problem.
+    /// Loans are in scope while they are live: whether they can reach a live region in the localized
+    /// constraint graph. Loan liveness is precomputed in `PoloniusContext::compute_loan_liveness`,
+    /// and a loan goes out of scope at the first point where it's not live anymore.
     fn precompute_loans_out_of_scope(
         &mut self,
         loan_idx: BorrowIndex,
@@ -332,11 +332,10 @@ fn precompute_loans_out_of_scope(
         let sccs = self.regioncx.constraint_sccs();
         let universal_regions = self.regioncx.universal_regions();
 
-        // We first handle the cases where the loan doesn't go out of scope, depending on the
+        // Applied member constraints are not part of the localized constraint graph, so we first
+        // handle the case where the loan doesn't go out of scope because of them, depending on the
         // issuing region's successors.
         for successor in graph::depth_first_search(&self.regioncx.region_graph(), issuing_region) {
-            // 1. Via applied member constraints
-            //
             // The issuing region can flow into the choice regions, and they are either:
             // - placeholders or free regions themselves,
             // - or also transitively outlive a free region.
@@ -353,14 +352,6 @@ fn precompute_loans_out_of_scope(
                     return;
                 }
             }
-
-            // 2. Via regions that are live at all points: placeholders and free regions.
-            //
-            // If the issuing region outlives such a region, its loan escapes the function and
-            // cannot go out of scope. We can early return.
-            if self.regioncx.is_region_live_at_all_points(successor) {
-                return;
-            }
         }
 
         let first_block = loan_issued_at.block;
@@ -442,12 +433,6 @@ fn loan_kill_location(
 
             // - the loan goes out of scope at `location` if it's not contained within any regions
             // live at this point.
-            //
-            // FIXME: if the issuing region `i` can reach a live region `r` at point `p`, and `r` is
-            // live at point `q`, then it's guaranteed that `i` would reach `r` at point `q`.
-            // Reachability is location-insensitive, and we could take advantage of that, by jumping
-            // to a further point than just the next statement: we can jump to the furthest point
-            // within the block where `r` is live.
             if self.regioncx.is_loan_live_at(loan_idx, location) {
                 continue;
             }
@@ -468,33 +453,25 @@ pub fn new(
         regioncx: &RegionInferenceContext<'tcx>,
         borrow_set: &'a BorrowSet<'tcx>,
     ) -> Self {
-        let mut borrows_out_of_scope_at_location =
-            calculate_borrows_out_of_scope_at_location(body, regioncx, borrow_set);
-
         // The in-tree polonius analysis computes loans going out of scope using the set-of-loans
-        // model, and makes sure they're identical to the existing computation of the set-of-points
-        // model.
-        if tcx.sess.opts.unstable_opts.polonius.is_next_enabled() {
-            let mut polonius_prec = PoloniusOutOfScopePrecomputer::new(body, regioncx);
-            for (loan_idx, loan_data) in borrow_set.iter_enumerated() {
-                let issuing_region = loan_data.region;
-                let loan_issued_at = loan_data.reserve_location;
-
-                polonius_prec.precompute_loans_out_of_scope(
-                    loan_idx,
-                    issuing_region,
-                    loan_issued_at,
-                );
-            }
-
-            assert_eq!(
-                borrows_out_of_scope_at_location, polonius_prec.loans_out_of_scope_at_location,
-                "polonius loan scopes differ from NLL borrow scopes, for body {:?}",
-                body.span,
-            );
-
-            borrows_out_of_scope_at_location = polonius_prec.loans_out_of_scope_at_location;
-        }
+        // model: loans are in scope while they are live, which is computed location-sensitively.
+        let borrows_out_of_scope_at_location =
+            if tcx.sess.opts.unstable_opts.polonius.is_next_enabled() {
+                let mut polonius_prec = PoloniusOutOfScopePrecomputer::new(body, regioncx);
+                for (loan_idx, loan_data) in borrow_set.iter_enumerated() {
+                    let issuing_region = loan_data.region;
+                    let loan_issued_at = loan_data.reserve_location;
+
+                    polonius_prec.precompute_loans_out_of_scope(
+                        loan_idx,
+                        issuing_region,
+                        loan_issued_at,
+                    );
+                }
+                polonius_prec.loans_out_of_scope_at_location
+            } else {
+                calculate_borrows_out_of_scope_at_location(body, regioncx, borrow_set)
+            };
 
         Borrows { tcx, body, borrow_set, borrows_out_of_scope_at_location }
     }
@@ -2016,6 +2016,7 @@ fn suggest_using_local_if_applicable(
                 _,
                 LaterUseKind::Call | LaterUseKind::Other,
                 _call_span,
+                _,
                 _
             )
         );
@@ -2031,7 +2032,7 @@ fn suggest_using_local_if_applicable(
             return;
         }
 
-        let use_span = if let BorrowExplanation::UsedLater(_, LaterUseKind::Other, use_span, _) =
+        let use_span = if let BorrowExplanation::UsedLater(_, LaterUseKind::Other, use_span, _, _) =
             explanation
         {
             Some(use_span)
@@ -2886,7 +2887,13 @@ pub(crate) fn report_borrowed_value_does_not_live_long_enough(
             // and `move` will not help here.
             (
                 Some(name),
-                BorrowExplanation::UsedLater(_, LaterUseKind::ClosureCapture, var_or_use_span, _),
+                BorrowExplanation::UsedLater(
+                    _,
+                    LaterUseKind::ClosureCapture,
+                    var_or_use_span,
+                    _,
+                    _,
+                ),
             ) if borrow_spans.for_coroutine() || borrow_spans.for_closure() => self
                 .report_escaping_closure_capture(
                     borrow_spans,
@@ -22,6 +22,7 @@
 use rustc_trait_selection::error_reporting::traits::FindExprBySpan;
 use tracing::{debug, instrument};
 
+use super::region_errors::note_constraint_path;
 use super::{RegionName, UseSpans, find_use};
 use crate::borrow_set::BorrowData;
 use crate::nll::ConstraintDescription;
@@ -30,7 +31,9 @@
 
 #[derive(Debug)]
 pub(crate) enum BorrowExplanation<'tcx> {
-    UsedLater(Local, LaterUseKind, Span, Option<Span>),
+    /// The last field is the constraint path from the borrow to the region live at the later
+    /// use, with `-Z explain-borrow-constraints`.
+    UsedLater(Local, LaterUseKind, Span, Option<Span>, Option<ExtraConstraintInfo>),
     UsedLaterInLoop(LaterUseKind, Span, Option<Span>),
     UsedLaterWhenDropped {
         drop_loc: Location,
@@ -104,6 +107,7 @@ pub(crate) fn add_explanation_to_diagnostic(
                 later_use_kind,
                 var_or_use_span,
                 path_span,
+                ref constraint_path,
             ) => {
                 let message = match later_use_kind {
                     LaterUseKind::TraitCapture => "captured here by trait object",
@@ -153,6 +157,9 @@ pub(crate) fn add_explanation_to_diagnostic(
                         err.span_label(path_span, path_label);
                     }
                 }
+                if let Some(ExtraConstraintInfo::ConstraintPath(steps)) = constraint_path {
+                    note_constraint_path(err, steps);
+                }
             }
             BorrowExplanation::UsedLaterInLoop(later_use_kind, var_or_use_span, path_span) => {
                 let message = match later_use_kind {
@@ -333,6 +340,9 @@ pub(crate) fn add_explanation_to_diagnostic(
                         ExtraConstraintInfo::PlaceholderFromPredicate(span) => {
                             err.span_note(*span, "due to current limitations in the borrow checker, this implies a `'static` lifetime");
                         }
+                        ExtraConstraintInfo::ConstraintPath(steps) => {
+                            note_constraint_path(err, steps);
+                        }
                     }
                 }
 
@@ -488,11 +498,14 @@ fn free_region_constraint_info(
         borrow_region: RegionVid,
         outlived_region: RegionVid,
     ) -> (ConstraintCategory<'tcx>, bool, Span, Option<RegionName>, Vec<ExtraConstraintInfo>) {
-        let (blame_constraint, extra_info) = self.regioncx.best_blame_constraint(
+        let (blame_constraint, mut extra_info) = self.regioncx.best_blame_constraint(
             borrow_region,
             NllRegionVariableOrigin::FreeRegion,
             |r| self.regioncx.provides_universal_region(r, borrow_region, outlived_region),
         );
+        extra_info.extend(self.explain_constraint_path(borrow_region, |r| {
+            self.regioncx.provides_universal_region(r, borrow_region, outlived_region)
+        }));
         let BlameConstraint { category, from_closure, cause, .. } = blame_constraint;
 
         let outlived_fr_name = self.give_region_a_name(outlived_region);
@@ -572,6 +585,7 @@ pub(crate) fn explain_why_borrow_contains_point(
                         later_use_kind,
                         var_or_use_span,
                         path_span,
+                        self.explain_constraint_path(borrow_region_vid, |r| r == region_sub),
                     )
                 }
             }
@@ -441,10 +441,13 @@ pub(crate) fn report_region_error(
     ) {
         debug!("report_region_error(fr={:?}, outlived_fr={:?})", fr, outlived_fr);
 
-        let (blame_constraint, extra_info) =
+        let (blame_constraint, mut extra_info) =
             self.regioncx.best_blame_constraint(fr, fr_origin, |r| {
                 self.regioncx.provides_universal_region(r, fr, outlived_fr)
             });
+        extra_info.extend(self.explain_constraint_path(fr, |r| {
+            self.regioncx.provides_universal_region(r, fr, outlived_fr)
+        }));
         let BlameConstraint { category, cause, variance_info, .. } = blame_constraint;
 
         debug!("report_region_error: category={:?} {:?} {:?}", category, cause, variance_info);
@@ -560,12 +563,77 @@ pub(crate) fn report_region_error(
                 ExtraConstraintInfo::PlaceholderFromPredicate(span) => {
                     diag.span_note(span, "due to current limitations in the borrow checker, this implies a `'static` lifetime");
                 }
+                ExtraConstraintInfo::ConstraintPath(steps) => {
+                    note_constraint_path(&mut diag, &steps)
+                }
             }
         }
 
         self.buffer_error(diag);
     }
 
+    /// With `-Z explain-borrow-constraints`, describes the shortest chain of outlives constraints
+    /// from `from_region` to a region meeting `target_test`, which is the chain responsible for
+    /// an error blamed on that path. Consecutive constraints created at the same span, like the
+    /// ones relating the regions of a type, are merged into a single step.
+    pub(crate) fn explain_constraint_path(
+        &self,
+        from_region: RegionVid,
+        target_test: impl Fn(RegionVid) -> bool,
+    ) -> Option<ExtraConstraintInfo> {
+        if !self.infcx.tcx.sess.opts.unstable_opts.explain_borrow_constraints {
+            return None;
+        }
+        let (path, _) =
+            self.regioncx.find_constraint_paths_between_regions(from_region, target_test)?;
+
+        let mut steps: Vec<(Span, ConstraintCategory<'tcx>, RegionVid, RegionVid)> = vec![];
+        for constraint in &path {
+            if constraint.span.is_dummy() {
+                continue;
+            }
+            match steps.last_mut() {
+                Some((span, _, _, sub)) if *span == constraint.span => *sub = constraint.sub,
+                _ => steps.push((
+                    constraint.span,
+                    constraint.category,
+                    constraint.sup,
+                    constraint.sub,
+                )),
+            }
+        }
+        if steps.is_empty() {
+            return None;
+        }
+
+        // Named regions are shown with their name, the others as in `-Z dump-nll` output.
+        let region_name = |r: RegionVid| match self
+            .regioncx
+            .region_definition(r)
+            .external_name
+            .and_then(|r| r.get_name())
+        {
+            Some(name) => name.to_string(),
+            None => format!("{r:?}"),
+        };
+        Some(ExtraConstraintInfo::ConstraintPath(
+            steps
+                .into_iter()
+                .enumerate()
+                .map(|(i, (span, category, sup, sub))| {
+                    let label = format!(
+                        "{}: {}requires that `{}: {}`",
+                        i + 1,
+                        category.description(),
+                        region_name(sup),
+                        region_name(sub),
+                    );
+                    (span, label)
+                })
+                .collect(),
+        ))
+    }
+
     /// Report a specialized error when `FnMut` closures return a reference to a captured variable.
     /// This function expects `fr` to be local and `outlived_fr` to not be local.
     ///
@@ -1215,3 +1283,14 @@ fn suggest_move_on_borrowing_closure(&self, diag: &mut Diag<'_>) {
         }
     }
 }
+
+/// Points at every constraint of a path described by `explain_constraint_path`.
+#[allow(rustc::diagnostic_outside_of_impl)]
+#[allow(rustc::untranslatable_diagnostic)]
+pub(super) fn note_constraint_path(diag: &mut Diag<'_>, steps: &[(Span, String)]) {
+    let mut spans = MultiSpan::from_spans(steps.iter().map(|(span, _)| *span).collect());
+    for (span, label) in steps {
+        spans.push_span_label(*span, label.clone());
+    }
+    diag.span_note(spans, "the lifetime requirement comes from these constraints, in this order");
+}
@@ -96,19 +96,37 @@ pub(crate) fn compute_regions<'a, 'tcx>(
     let elements = Rc::new(DenseLocationMap::new(body));
 
     // Run the MIR type-checker.
-    let MirTypeckResults { constraints, universal_region_relations, opaque_type_values } =
-        type_check::type_check(
-            infcx,
+    let MirTypeckResults {
+        mut constraints,
+        universal_region_relations,
+        opaque_type_values,
+        polonius_context,
+    } = type_check::type_check(
+        infcx,
+        body,
+        promoted,
+        universal_regions,
+        location_table,
+        borrow_set,
+        &mut all_facts,
+        flow_inits,
+        move_data,
+        Rc::clone(&elements),
+    );
+
+    // When using `-Zpolonius=next`, compute the set of loans that are live at each point, now that
+    // all the liveness and outlives constraints are known.
+    if let Some(polonius_context) = &polonius_context {
+        let live_loans = polonius_context.compute_loan_liveness(
+            infcx.tcx,
             body,
-            promoted,
-            universal_regions,
-            location_table,
+            &elements,
+            &constraints,
+            &universal_region_relations.universal_regions,
             borrow_set,
-            &mut all_facts,
-            flow_inits,
-            move_data,
-            Rc::clone(&elements),
         );
+        constraints.liveness_constraints.loans = Some(live_loans);
+    }
 
     // Create the region inference context, taking ownership of the
     // region inference data that was contained in `infcx`, and the
@@ -160,6 +178,13 @@ pub(crate) fn compute_regions<'a, 'tcx>(
     let (closure_region_requirements, nll_errors) =
         regioncx.solve(infcx, body, polonius_output.clone());
 
+    // The location-sensitive loan liveness computed for `-Zpolonius=next` can only be more precise
+    // than the NLL borrow scopes, check that it is. This visits every live loan at every point, so
+    // it's only done in builds of the compiler with debug assertions.
+    if cfg!(debug_assertions) && polonius_context.is_some() {
+        regioncx.check_polonius_loan_liveness(body.span, borrow_set);
+    }
+
     if let Some(guar) = nll_errors.has_errors() {
         // Suppress unhelpful extra errors in `infer_opaque_types`.
         infcx.set_tainted_by_errors(guar);
@@ -0,0 +1,230 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use either::Either;
+use rustc_data_structures::captures::Captures;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexSet};
+use rustc_index::bit_set::{GrowableBitSet, SparseBitMatrix};
+use rustc_index::{Idx, IndexVec};
+use rustc_middle::mir::{Body, Location};
+use rustc_middle::ty::{RegionVid, TyCtxt};
+use rustc_mir_dataflow::points::{DenseLocationMap, PointIndex};
+
+use super::{ConstraintDirection, PoloniusContext, typeck_constraints};
+use crate::BorrowIndex;
+use crate::borrow_set::BorrowSet;
+use crate::region_infer::values::LiveLoans;
+use crate::type_check::MirTypeckRegionConstraints;
+use crate::universal_regions::UniversalRegions;
+
+/// A region at a given point of the CFG: a node of the localized constraint graph.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub(super) struct LocalizedNode {
+    pub(super) region: RegionVid,
+    pub(super) point: PointIndex,
+}
+
+rustc_index::newtype_index! {
+    /// The index of a [`LocalizedNode`] reached during the traversal of the localized constraint
+    /// graph.
+    #[orderable]
+    #[debug_format = "LocalizedNodeIndex({})"]
+    struct LocalizedNodeIndex {}
+}
+
+/// The localized constraint graph: loans flow from the `sup` to the `sub` region of the outlives
+/// constraints at the points where they hold, and from a live region at a point to the same region
+/// at the neighbouring points where it's also live.
+///
+/// The liveness edges are not materialized, they are computed during traversal.
+#[derive(Default)]
+pub(super) struct LocalizedConstraintGraph {
+    /// The edges of the constraints that hold at a single point, localized at that point.
+    pub(super) physical_edges: FxHashMap<LocalizedNode, Vec<LocalizedNode>>,
+
+    /// The edges of the constraints that hold at all points, from `sup` to `sub` regions.
+    pub(super) logical_edges: FxHashMap<RegionVid, Vec<RegionVid>>,
+}
+
+impl PoloniusContext {
+    /// Computes the loans that are live at each point of the CFG.
+    ///
+    /// A loan is live at a point if it can reach, in the localized constraint graph, a region that
+    /// is live at that point. The traversal starts at the issuing region, at the point where the
+    /// loan is issued, so loans only flow into the regions they're actually stored in at a given
+    /// point: e.g. a loan returned from the function on one branch of a conditional is not live on
+    /// the other branches, unlike with NLLs where it is contained in a universal region everywhere.
+    ///
+    /// Instead of traversing the graph once per loan, the sets of loans reaching each node are
+    /// propagated together along the edges until they reach a fixpoint: a node is only revisited
+    /// when new loans reach it, so the paths shared by many loans are walked once per change
+    /// instead of once per loan. The nodes are visited in reverse postorder of the CFG, so that the
+    /// loans flowing forward into a region all reach a node before it's visited, and are propagated
+    /// from it together.
+    ///
+    /// Loans are not killed here: assignments to the borrowed places are handled by the borrows
+    /// dataflow analysis, like with NLLs.
+    pub(crate) fn compute_loan_liveness<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        body: &Body<'tcx>,
+        elements: &DenseLocationMap,
+        constraints: &MirTypeckRegionConstraints<'tcx>,
+        universal_regions: &UniversalRegions<'tcx>,
+        borrow_set: &BorrowSet<'tcx>,
+    ) -> LiveLoans {
+        let _prof_timer = tcx.prof.generic_activity("polonius_loan_liveness");
+
+        let mut graph = LocalizedConstraintGraph::default();
+        typeck_constraints::localize_typeck_constraints(
+            tcx,
+            body,
+            elements,
+            &constraints.outlives_constraints,
+            &mut graph,
+        );
+
+        // Free regions and placeholders are live at all points, and so are the loans flowing into
+        // them.
+        let placeholders: FxHashSet<RegionVid> =
+            constraints.placeholder_index_to_region.iter().map(|region| region.as_var()).collect();
+        let is_live_everywhere = |region: RegionVid| {
+            universal_regions.is_universal_region(region) || placeholders.contains(&region)
+        };
+        let liveness = &constraints.liveness_constraints;
+
+        // The loans reaching each node of the graph, and the nodes whose set of loans has changed
+        // since they were last visited, ordered by the rank of their point.
+        let mut nodes: FxIndexSet<LocalizedNode> = FxIndexSet::default();
+        let mut node_loans: SparseBitMatrix<LocalizedNodeIndex, BorrowIndex> =
+            SparseBitMatrix::new(borrow_set.len());
+        let point_ranks = point_ranks(body, elements);
+        let mut worklist = BinaryHeap::new();
+        let mut in_worklist = GrowableBitSet::new_empty();
+
+        for (loan_idx, loan) in borrow_set.iter_enumerated() {
+            let point = elements.point_from_location(loan.reserve_location);
+            let start = intern(&mut nodes, LocalizedNode { region: loan.region, point });
+            node_loans.insert(start, loan_idx);
+            if in_worklist.insert(start) {
+                worklist.push(Reverse((point_ranks[point], start)));
+            }
+        }
+
+        let mut live_loans = LiveLoans::new(borrow_set.len());
+        let mut successors = Vec::new();
+        while let Some(Reverse((_, node_idx))) = worklist.pop() {
+            in_worklist.remove(node_idx);
+            let node = nodes[node_idx.as_usize()];
+
+            let live_everywhere = is_live_everywhere(node.region);
+            if live_everywhere || liveness.is_live_at_point(node.region, node.point) {
+                if let Some(loans) = node_loans.row(node_idx) {
+                    live_loans.union_row(node.point, loans);
+                }
+            }
+
+            // Outlives edges.
+            if let Some(edges) = graph.physical_edges.get(&node) {
+                successors.extend(edges.iter().copied());
+            }
+            if let Some(edges) = graph.logical_edges.get(&node.region) {
+                successors.extend(
+                    edges.iter().map(|&region| LocalizedNode { region, point: node.point }),
+                );
+            }
+
+            // Liveness edges. The loans of free regions only need to flow forward, and regions
+            // whose variance was not recorded are conservatively assumed invariant.
+            let direction = if live_everywhere {
+                ConstraintDirection::Forward
+            } else {
+                self.live_region_variances
+                    .get(&node.region)
+                    .copied()
+                    .unwrap_or(ConstraintDirection::Bidirectional)
+            };
+            let is_live_at = |&point: &PointIndex| {
+                live_everywhere || liveness.is_live_at_point(node.region, point)
+            };
+            let to_node = |point| LocalizedNode { region: node.region, point };
+            if direction != ConstraintDirection::Backward {
+                successors.extend(
+                    successor_points(body, elements, node.point).filter(is_live_at).map(to_node),
+                );
+            }
+            if direction != ConstraintDirection::Forward {
+                successors.extend(
+                    predecessor_points(body, elements, node.point).filter(is_live_at).map(to_node),
+                );
+            }
+
+            for successor in successors.drain(..) {
+                let rank = point_ranks[successor.point];
+                let successor = intern(&mut nodes, successor);
+                if node_loans.union_rows(node_idx, successor) && in_worklist.insert(successor) {
+                    worklist.push(Reverse((rank, successor)));
+                }
+            }
+        }
+
+        live_loans
+    }
+}
+
+/// Returns the index of `node`, adding it to the `nodes` reached so far if needed.
+fn intern(nodes: &mut FxIndexSet<LocalizedNode>, node: LocalizedNode) -> LocalizedNodeIndex {
+    LocalizedNodeIndex::from_usize(nodes.insert_full(node).0)
+}
+
+/// Returns the rank of each point in reverse postorder of the CFG. The points of unreachable blocks
+/// come last.
+fn point_ranks(body: &Body<'_>, elements: &DenseLocationMap) -> IndexVec<PointIndex, u32> {
+    let mut ranks = IndexVec::from_elem_n(u32::MAX, elements.num_points());
+    let mut rank = 0;
+    for &block in body.basic_blocks.reverse_postorder() {
+        let entry_point = elements.entry_point(block);
+        for statement_index in 0..=body[block].statements.len() {
+            ranks[entry_point.plus(statement_index)] = rank;
+            rank += 1;
+        }
+    }
+    ranks
+}
+
+/// Returns the points following `point` in the CFG.
+fn successor_points<'a, 'tcx>(
+    body: &'a Body<'tcx>,
+    elements: &'a DenseLocationMap,
+    point: PointIndex,
+) -> impl Iterator<Item = PointIndex> + Captures<'tcx> + 'a {
+    let location = elements.to_location(point);
+    let block_data = &body[location.block];
+    if location.statement_index < block_data.statements.len() {
+        Either::Left(std::iter::once(
+            elements.point_from_location(location.successor_within_block()),
+        ))
+    } else {
+        Either::Right(block_data.terminator().successors().map(|block| elements.entry_point(block)))
+    }
+}
+
+/// Returns the points preceding `point` in the CFG.
+fn predecessor_points<'a, 'tcx>(
+    body: &'a Body<'tcx>,
+    elements: &'a DenseLocationMap,
+    point: PointIndex,
+) -> impl Iterator<Item = PointIndex> + Captures<'tcx> + 'a {
+    let location = elements.to_location(point);
+    if location.statement_index > 0 {
+        let predecessor =
+            Location { block: location.block, statement_index: location.statement_index - 1 };
+        Either::Left(std::iter::once(elements.point_from_location(predecessor)))
+    } else {
+        Either::Right(
+            body.basic_blocks.predecessors()[location.block]
+                .iter()
+                .map(|&block| elements.point_from_location(body.terminator_loc(block))),
+        )
+    }
+}
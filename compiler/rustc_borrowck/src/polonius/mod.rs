@@ -1,10 +1,16 @@
-//! Functions dedicated to fact generation for the `-Zpolonius=legacy` datalog implementation.
+//! Polonius-related borrowck code:
 //!
-//! Will be removed in the future, once the in-tree `-Zpolonius=next` implementation reaches feature
-//! parity.
-
+//! - the location-sensitive analysis of the in-tree `-Zpolonius=next` implementation. During MIR
+//!   typeck, the variance of the live regions is recorded in a [`PoloniusContext`]. Loan liveness
+//!   is then computed as reachability in the localized constraint graph, whose nodes are regions at
+//!   a given point in the CFG: its edges are the outlives constraints, localized at the point where
+//!   they hold, and the liveness of regions between points of the CFG.
+//! - functions dedicated to fact generation for the `-Zpolonius=legacy` datalog implementation.
+//!   They will be removed in the future, once the in-tree implementation reaches feature parity.
+
+use rustc_data_structures::fx::FxIndexMap;
 use rustc_middle::mir::{Body, LocalKind, Location, START_BLOCK};
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{RegionVid, TyCtxt};
 use rustc_mir_dataflow::move_paths::{InitKind, InitLocation, MoveData};
 use tracing::debug;
 
@@ -13,8 +19,34 @@
 use crate::location::LocationTable;
 use crate::type_check::free_region_relations::UniversalRegionRelations;
 
+mod liveness_constraints;
 mod loan_invalidations;
 mod loan_kills;
+mod loan_liveness;
+mod typeck_constraints;
+
+/// When using `-Zpolonius=next`, the data recorded during MIR typeck that is needed to compute
+/// loan liveness in a location-sensitive manner.
+#[derive(Default)]
+pub(crate) struct PoloniusContext {
+    /// The direction in which the loans contained in a live region flow through the CFG, computed
+    /// from the variance of the region in the types of the live values.
+    live_region_variances: FxIndexMap<RegionVid, ConstraintDirection>,
+}
+
+/// The direction of the edges that propagate the loans of a live region to the neighbouring points
+/// of the CFG.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum ConstraintDirection {
+    /// The region is covariant: loans flow to the successor points.
+    Forward,
+
+    /// The region is contravariant: loans flow to the predecessor points.
+    Backward,
+
+    /// The region is invariant: loans flow both to the successor and predecessor points.
+    Bidirectional,
+}
 
 /// When requested, emit most of the facts needed by polonius:
 /// - moves and assignments
@@ -0,0 +1,92 @@
+use rustc_middle::mir::{Body, Location, StatementKind, TerminatorKind};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_mir_dataflow::points::{DenseLocationMap, PointIndex};
+
+use super::loan_liveness::{LocalizedConstraintGraph, LocalizedNode};
+use crate::constraints::{OutlivesConstraint, OutlivesConstraintSet};
+use crate::type_check::Locations;
+
+/// Adds the outlives constraints from MIR typeck to the localized constraint graph.
+///
+/// Constraints that hold at all points become logical edges between regions. The other ones are
+/// localized at the point where they arise: they usually relate the regions at that point, but when
+/// they relate a value to the place it's written to, the regions of the place are related at the
+/// point where the write is visible. That is, at the successor statement for an assignment, and at
+/// the start of the target block for the destination of a call.
+pub(super) fn localize_typeck_constraints<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    elements: &DenseLocationMap,
+    outlives_constraints: &OutlivesConstraintSet<'tcx>,
+    graph: &mut LocalizedConstraintGraph,
+) {
+    for constraint in outlives_constraints.outlives() {
+        match constraint.locations {
+            Locations::All(_) => {
+                graph.logical_edges.entry(constraint.sup).or_default().push(constraint.sub);
+            }
+            Locations::Single(location) => {
+                let (from, to) = localize_constraint(tcx, body, elements, constraint, location);
+                let from = LocalizedNode { region: constraint.sup, point: from };
+                let to = LocalizedNode { region: constraint.sub, point: to };
+                graph.physical_edges.entry(from).or_default().push(to);
+            }
+        }
+    }
+}
+
+/// Returns the points where the `sup` and `sub` regions of a constraint arising at `location` are
+/// related, depending on whether they appear in the type of the place written at `location`.
+fn localize_constraint<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    elements: &DenseLocationMap,
+    constraint: &OutlivesConstraint<'tcx>,
+    location: Location,
+) -> (PointIndex, PointIndex) {
+    let current_point = elements.point_from_location(location);
+    let Some((written_ty, successor_point)) = written_place(tcx, body, elements, location) else {
+        return (current_point, current_point);
+    };
+
+    let mut from = current_point;
+    let mut to = current_point;
+    tcx.for_each_free_region(&written_ty, |region| {
+        // Regions like `'static` can appear in the type of a field, they are not renumbered.
+        let ty::ReVar(region) = region.kind() else { return };
+        if region == constraint.sup {
+            from = successor_point;
+        }
+        if region == constraint.sub {
+            to = successor_point;
+        }
+    });
+    (from, to)
+}
+
+/// Returns the type of the place written at `location`, if any, and the point where the written
+/// value is available.
+fn written_place<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    elements: &DenseLocationMap,
+    location: Location,
+) -> Option<(Ty<'tcx>, PointIndex)> {
+    let block_data = &body[location.block];
+    if location.statement_index < block_data.statements.len() {
+        let StatementKind::Assign(box (place, _)) =
+            &block_data.statements[location.statement_index].kind
+        else {
+            return None;
+        };
+        let successor = elements.point_from_location(location.successor_within_block());
+        Some((place.ty(body, tcx).ty, successor))
+    } else {
+        let TerminatorKind::Call { destination, target: Some(target), .. } =
+            &block_data.terminator().kind
+        else {
+            return None;
+        };
+        Some((destination.ty(body, tcx).ty, elements.entry_point(*target)))
+    }
+}
@@ -11,7 +11,6 @@
 use rustc_infer::infer::outlives::test_type_match;
 use rustc_infer::infer::region_constraints::{GenericKind, VarInfos, VerifyBound, VerifyIfEq};
 use rustc_infer::infer::{InferCtxt, NllRegionVariableOrigin, RegionVariableOrigin};
-use rustc_middle::bug;
 use rustc_middle::mir::{
     BasicBlock, Body, ClosureOutlivesRequirement, ClosureOutlivesSubject, ClosureOutlivesSubjectTy,
     ClosureRegionRequirements, ConstraintCategory, Local, Location, ReturnConstraint,
@@ -20,11 +19,13 @@
 use rustc_middle::traits::{ObligationCause, ObligationCauseCode};
 use rustc_middle::ty::fold::fold_regions;
 use rustc_middle::ty::{self, RegionVid, Ty, TyCtxt, TypeFoldable, UniverseIndex};
+use rustc_middle::{bug, span_bug};
 use rustc_mir_dataflow::points::DenseLocationMap;
 use rustc_span::Span;
 use tracing::{debug, instrument, trace};
 
 use crate::BorrowckInferCtxt;
+use crate::borrow_set::BorrowSet;
 use crate::constraints::graph::{self, NormalConstraintGraph, RegionGraph};
 use crate::constraints::{ConstraintSccIndex, OutlivesConstraint, OutlivesConstraintSet};
 use crate::dataflow::BorrowIndex;
@@ -318,6 +319,9 @@ enum Trace<'tcx> {
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub(crate) enum ExtraConstraintInfo {
     PlaceholderFromPredicate(Span),
+    /// The labelled spans of the constraints leading to the error, in the order in which they
+    /// are followed, for `-Z explain-borrow-constraints`.
+    ConstraintPath(Vec<(Span, String)>),
 }
 
 #[instrument(skip(infcx, sccs), level = "debug")]
@@ -2191,28 +2195,43 @@ pub(crate) fn region_graph(&self) -> RegionGraph<'_, 'tcx, graph::Normal> {
         self.constraint_graph.region_graph(&self.constraints, self.universal_regions().fr_static)
     }
 
-    /// Returns whether the given region is considered live at all points: whether it is a
-    /// placeholder or a free region.
-    pub(crate) fn is_region_live_at_all_points(&self, region: RegionVid) -> bool {
-        // FIXME: there must be a cleaner way to find this information. At least, when
-        // higher-ranked subtyping is abstracted away from the borrowck main path, we'll only
-        // need to check whether this is a universal region.
-        let origin = self.region_definition(region).origin;
-        let live_at_all_points = matches!(
-            origin,
-            NllRegionVariableOrigin::Placeholder(_) | NllRegionVariableOrigin::FreeRegion
-        );
-        live_at_all_points
-    }
-
-    /// Returns whether the `loan_idx` is live at the given `location`: whether its issuing
-    /// region is contained within the type of a variable that is live at this point.
+    /// Returns whether the `loan_idx` is live at the given `location`: whether it flows into a
+    /// region that is live at this point.
     /// Note: for now, the sets of live loans is only available when using `-Zpolonius=next`.
     pub(crate) fn is_loan_live_at(&self, loan_idx: BorrowIndex, location: Location) -> bool {
         let point = self.liveness_constraints.point_from_location(location);
         self.liveness_constraints.is_loan_live_at(loan_idx, point)
     }
 
+    /// When using `-Zpolonius=next`, checks that the live loans are in scope for NLLs as well.
+    ///
+    /// The localized constraint graph is more precise than the subset graph, so a loan that is
+    /// live at a point must reach a region that is live there through outlives constraints, and
+    /// its issuing region must contain this point. Loans flowing into placeholders are live at all
+    /// points for polonius but not for NLLs, and are not checked.
+    ///
+    /// Reports an ICE for the first loan that isn't, and panics if called before `solve()`
+    /// executes.
+    pub(crate) fn check_polonius_loan_liveness(
+        &self,
+        body_span: Span,
+        borrow_set: &BorrowSet<'tcx>,
+    ) {
+        for (location, loan_idx) in self.liveness_constraints.live_loans() {
+            let issuing_region = borrow_set[loan_idx].region;
+            if self.placeholders_contained_in(issuing_region).next().is_some() {
+                continue;
+            }
+            if !self.region_contains(issuing_region, location) {
+                span_bug!(
+                    body_span,
+                    "polonius loan {loan_idx:?} is live at {location:?}, outside of its NLL borrow \
+                    scope",
+                );
+            }
+        }
+    }
+
     /// Returns the representative `RegionVid` for a given SCC.
     /// See `RegionTracker` for how a region variable ID is chosen.
     ///
@@ -50,30 +50,12 @@ pub(crate) struct LivenessValues {
     /// region is live, only that it is.
     points: Option<SparseIntervalMatrix<RegionVid, PointIndex>>,
 
-    /// When using `-Zpolonius=next`, for each point: the loans flowing into the live regions at
-    /// that point.
+    /// When using `-Zpolonius=next`, the set of loans that are live at a given point in the CFG.
     pub(crate) loans: Option<LiveLoans>,
 }
 
-/// Data used to compute the loans that are live at a given point in the CFG, when using
-/// `-Zpolonius=next`.
-pub(crate) struct LiveLoans {
-    /// The set of loans that flow into a given region. When individual regions are marked as live
-    /// in the CFG, these inflowing loans are recorded as live.
-    pub(crate) inflowing_loans: SparseBitMatrix<RegionVid, BorrowIndex>,
-
-    /// The set of loans that are live at a given point in the CFG.
-    pub(crate) live_loans: SparseBitMatrix<PointIndex, BorrowIndex>,
-}
-
-impl LiveLoans {
-    pub(crate) fn new(num_loans: usize) -> Self {
-        LiveLoans {
-            live_loans: SparseBitMatrix::new(num_loans),
-            inflowing_loans: SparseBitMatrix::new(num_loans),
-        }
-    }
-}
+/// When using `-Zpolonius=next`: the set of loans that are live at a given point in the CFG.
+pub(crate) type LiveLoans = SparseBitMatrix<PointIndex, BorrowIndex>;
 
 impl LivenessValues {
     /// Create an empty map of regions to locations where they're live.
@@ -121,13 +103,6 @@ pub(crate) fn add_location(&mut self, region: RegionVid, location: Location) {
         } else if self.elements.point_in_range(point) {
             self.live_regions.as_mut().unwrap().insert(region);
         }
-
-        // When available, record the loans flowing into this region as live at the given point.
-        if let Some(loans) = self.loans.as_mut() {
-            if let Some(inflowing) = loans.inflowing_loans.row(region) {
-                loans.live_loans.union_row(point, inflowing);
-            }
-        }
     }
 
     /// Records `region` as being live at all the given `points`.
@@ -138,17 +113,6 @@ pub(crate) fn add_points(&mut self, region: RegionVid, points: &IntervalSet<Poin
         } else if points.iter().any(|point| self.elements.point_in_range(point)) {
             self.live_regions.as_mut().unwrap().insert(region);
         }
-
-        // When available, record the loans flowing into this region as live at the given points.
-        if let Some(loans) = self.loans.as_mut() {
-            if let Some(inflowing) = loans.inflowing_loans.row(region) {
-                if !inflowing.is_empty() {
-                    for point in points.iter() {
-                        loans.live_loans.union_row(point, inflowing);
-                    }
-                }
-            }
-        }
     }
 
     /// Records `region` as being live at all the control-flow points.
@@ -163,6 +127,11 @@ pub(crate) fn add_all_points(&mut self, region: RegionVid) {
     /// Returns whether `region` is marked live at the given `location`.
     pub(crate) fn is_live_at(&self, region: RegionVid, location: Location) -> bool {
         let point = self.elements.point_from_location(location);
+        self.is_live_at_point(region, point)
+    }
+
+    /// Returns whether `region` is marked live at the given `point`.
+    pub(crate) fn is_live_at_point(&self, region: RegionVid, point: PointIndex) -> bool {
         if let Some(points) = &self.points {
             points.row(region).is_some_and(|r| r.contains(point))
         } else {
@@ -204,9 +173,17 @@ pub(crate) fn is_loan_live_at(&self, loan_idx: BorrowIndex, point: PointIndex) -
         self.loans
             .as_ref()
             .expect("Accessing live loans requires `-Zpolonius=next`")
-            .live_loans
             .contains(point, loan_idx)
     }
+
+    /// When using `-Zpolonius=next`, returns the loans that are live at each location.
+    pub(crate) fn live_loans(&self) -> impl Iterator<Item = (Location, BorrowIndex)> + '_ {
+        let loans = self.loans.as_ref().expect("Accessing live loans requires `-Zpolonius=next`");
+        loans.rows().flat_map(move |point| {
+            let location = self.elements.to_location(point);
+            loans.iter(point).map(move |loan_idx| (location, loan_idx))
+        })
+    }
 }
 
 /// Maps from `ty::PlaceholderRegion` values that are used in the rest of
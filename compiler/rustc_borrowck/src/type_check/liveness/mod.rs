@@ -37,11 +37,21 @@ pub(super) fn generate<'a, 'tcx>(
 ) {
     debug!("liveness::generate");
 
-    let free_regions = regions_that_outlive_free_regions(
-        typeck.infcx.num_region_vars(),
-        &typeck.universal_regions,
-        &typeck.constraints.outlives_constraints,
-    );
+    // When using `-Zpolonius=next`, loans only flow into the regions of a local at the points where
+    // it's live, so we need to trace the liveness of all the locals whose type contains local
+    // regions, even those known to outlive a free region. This is only needed when there are loans
+    // to flow into these regions: the other bodies keep the cheaper NLL set of relevant locals.
+    let free_regions = if typeck.tcx().sess.opts.unstable_opts.polonius.is_next_enabled()
+        && typeck.borrow_set.len() > 0
+    {
+        typeck.universal_regions.universal_regions_iter().collect()
+    } else {
+        regions_that_outlive_free_regions(
+            typeck.infcx.num_region_vars(),
+            &typeck.universal_regions,
+            &typeck.constraints.outlives_constraints,
+        )
+    };
     let (relevant_live_locals, boring_locals) =
         compute_relevant_live_locals(typeck.tcx(), &free_regions, body);
 
@@ -11,6 +11,7 @@
 use rustc_codegen_ssa::assert_module_sources::CguReuse;
 use rustc_codegen_ssa::back::link::ensure_removed;
 use rustc_codegen_ssa::back::metadata::create_compressed_metadata_file;
+use rustc_codegen_ssa::back::mono_sizes::dump_mono_sizes;
 use rustc_codegen_ssa::base::determine_cgu_reuse;
 use rustc_codegen_ssa::{
     CodegenResults, CompiledModule, CrateInfo, ModuleKind, errors as ssa_errors,
@@ -128,6 +129,7 @@ pub(crate) fn join(
             crate_info: self.crate_info,
         };
 
+        dump_mono_sizes(sess, &codegen_results.crate_info, &codegen_results.modules);
         produce_final_output_artifacts(sess, &codegen_results, outputs);
 
         (codegen_results, work_products)
@@ -30,6 +30,9 @@ codegen_ssa_copy_path = could not copy {$from} to {$to}: {$error}
 
 codegen_ssa_copy_path_buf = unable to copy {$source_file} to {$output_path}: {$error}
 
+codegen_ssa_couldnt_dump_mono_sizes =
+    unexpected error occurred while dumping mono item sizes: {$error}
+
 codegen_ssa_create_temp_dir = couldn't create a temp dir: {$error}
 
 codegen_ssa_dlltool_fail_import_library =
@@ -174,6 +177,8 @@ codegen_ssa_lib_def_write_failure = failed to write lib.def file: {$error}
 
 codegen_ssa_link_exe_unexpected_error = `link.exe` returned an unexpected error
 
+codegen_ssa_link_map_summary_write_failed = failed to write the summary of the link map {$path}: {$error}
+
 codegen_ssa_link_script_unavailable = can only use link script when linking with GNU-like linker
 
 codegen_ssa_link_script_write_failure = failed to write link script to {$path}: {$error}
@@ -203,6 +208,14 @@ codegen_ssa_missing_query_depgraph =
 
 codegen_ssa_msvc_missing_linker = the msvc targets depend on the msvc linker but `link.exe` was not found
 
+codegen_ssa_mono_sizes_unreadable_objects =
+    the sizes of the mono items in {$count ->
+        [one] an object file
+        *[other] {$count} object files
+    } are not reported
+    .note = couldn't read the symbols of `{$path}`: {$error}
+    .help = object files containing LLVM bitcode, such as with `-C linker-plugin-lto`, have no machine code yet
+
 codegen_ssa_multiple_external_func_decl = multiple declarations of external function `{$function}` from library `{$library_name}` have different calling conventions
 
 codegen_ssa_multiple_main_functions = entry symbol `main` declared multiple times
@@ -47,6 +47,7 @@
 
 use super::archive::{ArchiveBuilder, ArchiveBuilderBuilder, ImportLibraryItem};
 use super::command::Command;
+use super::link_map::write_removed_symbols_summary;
 use super::linker::{self, Linker};
 use super::metadata::{MetadataPosition, create_wrapper_file};
 use super::rpath::{self, RPathConfig};
@@ -1047,6 +1048,17 @@ fn is_illegal_instruction(_status: &ExitStatus) -> bool {
             }
             info!("linker stderr:\n{}", escape_string(&prog.stderr));
             info!("linker stdout:\n{}", escape_string(&prog.stdout));
+
+            if let Some(map_path) = &sess.opts.unstable_opts.link_map {
+                let mut output = prog.stderr.clone();
+                output.extend_from_slice(&prog.stdout);
+                if let Err(error) = write_removed_symbols_summary(map_path, &output) {
+                    sess.dcx().emit_err(errors::LinkMapSummaryWriteFailed {
+                        path: map_path.clone(),
+                        error,
+                    });
+                }
+            }
         }
         Err(e) => {
             let linker_not_found = e.kind() == io::ErrorKind::NotFound;
@@ -2537,6 +2549,10 @@ fn add_order_independent_options(
         }
     }
 
+    if let Some(path) = &sess.opts.unstable_opts.link_map {
+        cmd.link_map(path);
+    }
+
     cmd.set_output_kind(link_output_kind, crate_type, out_filename);
 
     add_relro_args(cmd, sess);
@@ -0,0 +1,146 @@
+//! Summarises the Rust symbols that the linker garbage collected, for `-Z link-map`.
+//!
+//! The linkers are asked to write a map file and to report the sections they remove (see
+//! `Linker::link_map`). Depending on the linker, the removed sections are found in its output
+//! (`--print-gc-sections` for GNU ld and LLD, `/VERBOSE:REF` for MSVC) or in the map itself
+//! (ld64 and the "Discarded input sections" of GNU ld). Every removed Rust symbol is demangled
+//! and attributed to a crate, and the result is written next to the map file.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// Writes the summary of the Rust symbols removed by the linker to `<map>.gc.md`, based on the
+/// linker output and on the map file at `map_path`.
+pub(crate) fn write_removed_symbols_summary(
+    map_path: &Path,
+    linker_output: &[u8],
+) -> io::Result<()> {
+    // Not all linkers support map files, the summary is then only based on their output.
+    let map = fs::read(map_path).unwrap_or_default();
+    let mut removed = removed_symbols(&String::from_utf8_lossy(linker_output));
+    removed.extend(removed_symbols(&String::from_utf8_lossy(&map)));
+
+    let mut summary_path = map_path.as_os_str().to_owned();
+    summary_path.push(".gc.md");
+    let summary_path = PathBuf::from(summary_path);
+    let mut file = io::BufWriter::new(fs::File::create(&summary_path)?);
+    write_summary(&mut file, &removed_symbols_by_crate(&removed))?;
+    file.flush()
+}
+
+/// Returns the mangled names of the symbols listed as removed in the linker output or map file.
+/// Symbols which aren't Rust symbols are returned as well, they are filtered out when demangling.
+pub(crate) fn removed_symbols(text: &str) -> BTreeSet<String> {
+    let mut symbols = BTreeSet::new();
+    let mut in_ld64_dead_stripped = false;
+    let mut in_gnu_discarded = false;
+
+    for line in text.lines() {
+        if line.starts_with("# Dead Stripped Symbols:") {
+            in_ld64_dead_stripped = true;
+            continue;
+        }
+        if line.starts_with("Discarded input sections") {
+            in_gnu_discarded = true;
+            continue;
+        }
+        if in_gnu_discarded && (line.starts_with("Memory Configuration") || line.starts_with('#')) {
+            in_gnu_discarded = false;
+        }
+
+        if let Some((_, rest)) = line.split_once("removing unused section ") {
+            // GNU ld: `removing unused section '.text.foo' in file 'foo.o'`
+            // LLD:    `removing unused section foo.o:(.text.foo)`
+            let section = if let Some(rest) = rest.strip_prefix('\'') {
+                rest.split('\'').next()
+            } else {
+                rest.rsplit_once(":(").and_then(|(_, s)| s.strip_suffix(')'))
+            };
+            symbols.extend(section.and_then(section_symbol).map(str::to_owned));
+        } else if let Some((_, rest)) = line.split_once("Discarded ") {
+            // MSVC: `Discarded _ZN3foo3bar17h0123456789abcdefE from foo.o`
+            symbols.extend(rest.split(" from ").next().map(|s| s.trim().to_owned()));
+        } else if in_ld64_dead_stripped && line.starts_with("<<dead>>") {
+            // ld64: `<<dead>>	0x00000010	[  1] __ZN3foo3bar17h0123456789abcdefE`
+            symbols.extend(line.split_whitespace().last().map(str::to_owned));
+        } else if in_gnu_discarded && line.starts_with(' ') {
+            // GNU ld: ` .text.foo    0x0000000000000000       0x10 foo.o`, the addresses are
+            // on a line of their own when the section name is long.
+            let section = line.split_whitespace().next().filter(|s| s.starts_with('.'));
+            symbols.extend(section.and_then(section_symbol).map(str::to_owned));
+        }
+    }
+
+    symbols
+}
+
+/// Returns the name of the symbol defined by the section `section`. Rust symbols get a section
+/// of their own, named after the symbol, like `.text._ZN3foo3bar17h0123456789abcdefE`.
+fn section_symbol(section: &str) -> Option<&str> {
+    if !section.starts_with('.') {
+        // wasm-ld names the removed functions directly.
+        return Some(section);
+    }
+    section
+        .match_indices("._")
+        .map(|(i, _)| &section[i + 1..])
+        .find(|symbol| rustc_demangle::try_demangle(symbol).is_ok())
+}
+
+/// Demangles the Rust symbols in `symbols` and groups them by the crate they come from. Other
+/// symbols are ignored.
+pub(crate) fn removed_symbols_by_crate(
+    symbols: &BTreeSet<String>,
+) -> BTreeMap<String, BTreeSet<String>> {
+    let mut by_crate: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for symbol in symbols {
+        let Ok(demangled) = rustc_demangle::try_demangle(symbol) else { continue };
+        // The alternate format omits the hash suffix.
+        let path = format!("{demangled:#}");
+        let krate = crate_name(&path).unwrap_or("<unknown>").to_owned();
+        by_crate.entry(krate).or_default().insert(path);
+    }
+    by_crate
+}
+
+/// Returns the crate of the demangled symbol path `path`. Trait impls for types which aren't
+/// paths, like `<&T as core::fmt::Debug>::fmt`, are attributed to the crate of the trait.
+fn crate_name(path: &str) -> Option<&str> {
+    let path = path.trim_start_matches(['<', '&', '*', '[', '(']);
+    let path = path.strip_prefix("mut ").or_else(|| path.strip_prefix("const ")).unwrap_or(path);
+    let ident_len = path.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(path.len());
+    if ident_len > 0 && path[ident_len..].starts_with("::") {
+        return Some(&path[..ident_len]);
+    }
+    let (_, trait_path) = path.split_once(" as ")?;
+    crate_name(trait_path)
+}
+
+fn write_summary(
+    w: &mut impl Write,
+    by_crate: &BTreeMap<String, BTreeSet<String>>,
+) -> io::Result<()> {
+    let mut crates: Vec<_> = by_crate.iter().collect();
+    crates.sort_by(|(a, a_symbols), (b, b_symbols)| {
+        b_symbols.len().cmp(&a_symbols.len()).then_with(|| a.cmp(b))
+    });
+
+    writeln!(w, "# Rust symbols removed by the linker\n")?;
+    writeln!(w, "| Crate | Removed symbols |")?;
+    writeln!(w, "| :--- | ---: |")?;
+    for (krate, symbols) in &crates {
+        writeln!(w, "| `{krate}` | {} |", symbols.len())?;
+    }
+    for (krate, symbols) in &crates {
+        writeln!(w, "\n## `{krate}`\n")?;
+        for symbol in symbols.iter() {
+            writeln!(w, "- `{symbol}`")?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use super::*;
+
+const FOO_BAR: &str = "_ZN3foo3bar17h0123456789abcdefE";
+const VEC_DROP: &str = "_ZN70_$LT$alloc..vec..Vec$LT$T$C$A$GT$$u20$as$u20$core..ops..drop..Drop$GT$4drop17h0123456789abcdefE";
+const REF_DEBUG: &str = "_ZN42_$LT$$RF$T$u20$as$u20$core..fmt..Debug$GT$3fmt17h0123456789abcdefE";
+
+#[test]
+fn test_gnu_ld_output() {
+    let output = format!(
+        "/usr/bin/ld: removing unused section '.text.{FOO_BAR}' in file 'foo.o'\n\
+         /usr/bin/ld: removing unused section '.rodata.cst16' in file 'foo.o'\n"
+    );
+    assert_eq!(removed_symbols(&output), BTreeSet::from([FOO_BAR.to_owned()]));
+}
+
+#[test]
+fn test_lld_output() {
+    let output = format!(
+        "ld.lld: removing unused section /tmp/foo.o:(.text.unlikely.{FOO_BAR})\n\
+         ld.lld: removing unused section /tmp/foo.o:(.data.rel.ro.{VEC_DROP})\n"
+    );
+    assert_eq!(removed_symbols(&output), BTreeSet::from([FOO_BAR.to_owned(), VEC_DROP.to_owned()]));
+}
+
+#[test]
+fn test_msvc_output() {
+    let output = format!("        Discarded {FOO_BAR} from foo.o\n");
+    assert_eq!(removed_symbols(&output), BTreeSet::from([FOO_BAR.to_owned()]));
+}
+
+#[test]
+fn test_ld64_map() {
+    let map = format!(
+        "# Symbols:\n\
+         0x100003F50\t0x00000010\t[  1] _main\n\
+         # Dead Stripped Symbols:\n\
+         #        \tSize    \tSymbol\n\
+         <<dead>> \t0x00000010\t[  1] _{FOO_BAR}\n"
+    );
+    assert_eq!(removed_symbols(&map), BTreeSet::from([format!("_{FOO_BAR}")]));
+}
+
+#[test]
+fn test_gnu_ld_map() {
+    let map = format!(
+        "Discarded input sections\n\n \
+         .text.{FOO_BAR}\n                0x0000000000000000       0x10 foo.o\n\n\
+         Memory Configuration\n\n \
+         .text.{VEC_DROP}\n"
+    );
+    assert_eq!(removed_symbols(&map), BTreeSet::from([FOO_BAR.to_owned()]));
+}
+
+#[test]
+fn test_removed_symbols_by_crate() {
+    let symbols = BTreeSet::from([
+        FOO_BAR.to_owned(),
+        VEC_DROP.to_owned(),
+        REF_DEBUG.to_owned(),
+        "memcpy".to_owned(),
+    ]);
+    assert_eq!(
+        removed_symbols_by_crate(&symbols),
+        BTreeMap::from([
+            (
+                "alloc".to_owned(),
+                BTreeSet::from(
+                    ["<alloc::vec::Vec<T,A> as core::ops::drop::Drop>::drop".to_owned()]
+                )
+            ),
+            ("core".to_owned(), BTreeSet::from(["<&T as core::fmt::Debug>::fmt".to_owned()])),
+            ("foo".to_owned(), BTreeSet::from(["foo::bar".to_owned()])),
+        ])
+    );
+}
@@ -329,6 +329,9 @@ fn add_object(&mut self, path: &Path) {
     }
     fn gc_sections(&mut self, keep_metadata: bool);
     fn no_gc_sections(&mut self);
+    /// Asks the linker to write a map file to `path` and, where possible, to
+    /// report the sections it removed while garbage collecting.
+    fn link_map(&mut self, _path: &Path) {}
     fn full_relro(&mut self);
     fn partial_relro(&mut self);
     fn no_relro(&mut self);
@@ -679,6 +682,18 @@ fn no_gc_sections(&mut self) {
         }
     }
 
+    fn link_map(&mut self, path: &Path) {
+        if self.sess.target.is_like_osx {
+            // ld64 lists the symbols removed by `-dead_strip` in the map itself.
+            self.link_arg("-map").link_arg(path);
+        } else if self.is_gnu || self.sess.target.is_like_wasm {
+            let mut arg = OsString::from("-Map=");
+            arg.push(path);
+            self.link_arg(arg);
+            self.link_arg("--print-gc-sections");
+        }
+    }
+
     fn optimize(&mut self) {
         if !self.is_gnu && !self.sess.target.is_like_wasm {
             return;
@@ -982,6 +997,14 @@ fn no_gc_sections(&mut self) {
         self.link_arg("/OPT:NOREF,NOICF");
     }
 
+    fn link_map(&mut self, path: &Path) {
+        let mut arg = OsString::from("/MAP:");
+        arg.push(path);
+        self.link_arg(arg);
+        // `/VERBOSE:REF` reports the functions and data discarded by `/OPT:REF`.
+        self.link_arg("/VERBOSE:REF");
+    }
+
     fn full_relro(&mut self) {
         // noop
     }
@@ -1379,6 +1402,13 @@ fn no_gc_sections(&mut self) {
         self.link_arg("--no-gc-sections");
     }
 
+    fn link_map(&mut self, path: &Path) {
+        let mut arg = OsString::from("-Map=");
+        arg.push(path);
+        self.link_arg(arg);
+        self.link_arg("--print-gc-sections");
+    }
+
     fn optimize(&mut self) {
         // The -O flag is, as of late 2023, only used for merging of strings and debuginfo, and
         // only differentiates -O0 and -O1. It does not apply to LTO.
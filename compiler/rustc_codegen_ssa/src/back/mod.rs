@@ -6,9 +6,11 @@
 pub mod archive;
 pub(crate) mod command;
 pub mod link;
+pub(crate) mod link_map;
 pub(crate) mod linker;
 pub mod lto;
 pub mod metadata;
+pub mod mono_sizes;
 pub(crate) mod rpath;
 pub mod symbol_export;
 pub mod write;
@@ -0,0 +1,197 @@
+//! Reports the machine code size of every mono item of the local crate, for
+//! `-Z dump-mono-sizes`.
+//!
+//! The sizes are those of the symbols of the items in the object files produced by the codegen
+//! backend, so they account for inlining and optimizations, unlike the size estimates used for
+//! partitioning. Each item is reported with both the crate defining it and the crate its size is
+//! attributed to in the per-crate totals: instantiations of generic items count towards the local
+//! crate, which they are made for, even if the generic item comes from another crate.
+//!
+//! The object files are read when codegen finishes, before they are removed or linked, so the
+//! report is written for any `--emit` kinds.
+
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use object::{BinaryFormat, Object, ObjectSection, ObjectSymbol};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_session::Session;
+use rustc_session::config::{DumpMonoStatsFormat, SwitchWithOptPath};
+use rustc_span::Symbol;
+use serde_json::json;
+
+use crate::{CompiledModule, CrateInfo, MonoItemInfo, errors};
+
+/// Writes the report of `-Z dump-mono-sizes`, if enabled, from the object files of `modules`.
+/// This must be called before the object files are removed.
+pub fn dump_mono_sizes(sess: &Session, crate_info: &CrateInfo, modules: &[CompiledModule]) {
+    if let SwitchWithOptPath::Enabled(ref path) = sess.opts.unstable_opts.dump_mono_sizes {
+        if let Err(err) = dump_mono_item_sizes(sess, crate_info, modules, path) {
+            sess.dcx().emit_fatal(errors::CouldntDumpMonoSizes { error: err.to_string() });
+        }
+    }
+}
+
+/// Writes the sizes of the mono items recorded in the `CrateInfo`, and their totals per
+/// instantiating crate, to a file in the given output directory.
+fn dump_mono_item_sizes(
+    sess: &Session,
+    crate_info: &CrateInfo,
+    modules: &[CompiledModule],
+    output_directory: &Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let output_directory = if let Some(ref directory) = output_directory {
+        fs::create_dir_all(directory)?;
+        directory
+    } else {
+        Path::new(".")
+    };
+
+    let format = sess.opts.unstable_opts.dump_mono_stats_format;
+    let ext = format.extension();
+    let crate_name = crate_info.local_crate_name;
+    let filename = format!("{crate_name}.mono_sizes.{ext}");
+    let mut file = File::create_buffered(output_directory.join(&filename))?;
+
+    let mut module_symbols = FxIndexMap::default();
+    let mut unreadable = Vec::new();
+    for module in modules {
+        if let Some(ref object) = module.object {
+            let data = fs::read(object)?;
+            // Object files may hold LLVM bitcode instead of machine code, e.g. with
+            // `-C linker-plugin-lto`. The sizes of their items are not known until they are linked.
+            match object::File::parse(&*data) {
+                Ok(file) => {
+                    module_symbols.insert(module.name.as_str(), symbol_sizes(&file));
+                }
+                Err(err) => unreadable.push((object, err)),
+            }
+        }
+    }
+    if let Some((path, error)) = unreadable.first() {
+        sess.dcx().emit_warn(errors::MonoSizesUnreadableObjects {
+            count: unreadable.len(),
+            path: path.display().to_string(),
+            error: error.to_string(),
+        });
+    }
+
+    // Items without a symbol in any object file have been inlined or optimized away, or are in
+    // an object file that couldn't be read.
+    let mut items: Vec<(&MonoItemInfo, Option<u64>)> = crate_info
+        .mono_items
+        .iter()
+        .map(|item| {
+            let symbol = item.symbol_name.as_str();
+            // With LTO, the modules are merged, and the item may not be in its CGU's module.
+            let size = module_symbols
+                .get(item.cgu_name.as_str())
+                .and_then(|sizes| sizes.get(symbol))
+                .or_else(|| module_symbols.values().find_map(|sizes| sizes.get(symbol)))
+                .copied();
+            (item, size)
+        })
+        .collect();
+    items.sort_by(|(a, a_size), (b, b_size)| {
+        b_size.cmp(a_size).then_with(|| (&a.name, &a.cgu_name).cmp(&(&b.name, &b.cgu_name)))
+    });
+
+    let mut crates: FxIndexMap<Symbol, (usize, u64)> = Default::default();
+    for &(item, size) in &items {
+        let (count, total) = crates.entry(item.instantiating_crate).or_default();
+        *count += 1;
+        *total += size.unwrap_or(0);
+    }
+    let mut crates: Vec<_> = crates.into_iter().collect();
+    crates.sort_by(|(a, (_, a_total)), (b, (_, b_total))| {
+        b_total.cmp(a_total).then_with(|| a.as_str().cmp(b.as_str()))
+    });
+
+    match format {
+        DumpMonoStatsFormat::Json => {
+            let items: Vec<_> = items
+                .iter()
+                .map(|(item, size)| {
+                    json!({
+                        "name": item.name,
+                        "symbol": item.symbol_name,
+                        "cgu": item.cgu_name,
+                        "crate": item.krate.as_str(),
+                        "instantiating_crate": item.instantiating_crate.as_str(),
+                        "generic_args": item.generic_args,
+                        "size": size,
+                        "size_estimate": item.size_estimate,
+                    })
+                })
+                .collect();
+            let crates: Vec<_> = crates
+                .iter()
+                .map(|(krate, (count, total))| {
+                    json!({ "name": krate.as_str(), "items": count, "size": total })
+                })
+                .collect();
+            serde_json::to_writer(file, &json!({ "items": items, "crates": crates }))?;
+        }
+        DumpMonoStatsFormat::Markdown => {
+            writeln!(file, "| Crate | Items | Total Size |")?;
+            writeln!(file, "| --- | ---: | ---: |")?;
+            for (krate, (count, total)) in crates {
+                writeln!(file, "| `{krate}` | {count} | {total} |")?;
+            }
+
+            writeln!(file)?;
+            writeln!(file, "| Item | Crate | Instantiating Crate | CGU | Size | Estimated Cost |")?;
+            writeln!(file, "| --- | --- | --- | --- | ---: | ---: |")?;
+            for (item, size) in items {
+                let MonoItemInfo {
+                    name, cgu_name, krate, instantiating_crate, size_estimate, ..
+                } = item;
+                let size = size.map_or_else(|| "-".to_string(), |size| size.to_string());
+                writeln!(
+                    file,
+                    "| `{name}` | `{krate}` | `{instantiating_crate}` | `{cgu_name}` | {size} \
+                     | {size_estimate} |"
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the sizes of the symbols defined in the object `file`, by symbol name.
+fn symbol_sizes(file: &object::File<'_>) -> FxHashMap<String, u64> {
+    let mut symbols: Vec<_> = file
+        .symbols()
+        .filter(|symbol| symbol.is_definition())
+        .filter_map(|symbol| {
+            Some((symbol.section_index()?, symbol.address(), symbol.size(), symbol.name().ok()?))
+        })
+        .collect();
+    symbols.sort_by_key(|&(section, address, ..)| (section.0, address));
+
+    let mut sizes = FxHashMap::default();
+    for (i, &(section, address, size, name)) in symbols.iter().enumerate() {
+        // Mach-O symbols have no size: a symbol extends up to the next symbol of its section, or
+        // to the end of the section. Their names also have a leading underscore.
+        let size = if size != 0 {
+            size
+        } else {
+            let end = match symbols.get(i + 1) {
+                Some(&(next_section, next_address, ..)) if next_section == section => next_address,
+                _ => file
+                    .section_by_index(section)
+                    .map_or(address, |section| section.address() + section.size()),
+            };
+            end.saturating_sub(address)
+        };
+        let name = match file.format() {
+            BinaryFormat::MachO => name.strip_prefix('_').unwrap_or(name),
+            _ => name,
+        };
+        sizes.insert(name.to_owned(), size);
+    }
+    sizes
+}
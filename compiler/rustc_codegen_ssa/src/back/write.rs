@@ -40,6 +40,7 @@
 
 use super::link::{self, ensure_removed};
 use super::lto::{self, SerializedModule};
+use super::mono_sizes::dump_mono_sizes;
 use super::symbol_export::symbol_name_for_instance_in_crate;
 use crate::errors::ErrorCreatingRemarkDir;
 use crate::traits::*;
@@ -2046,6 +2047,7 @@ pub fn join(self, sess: &Session) -> (CodegenResults, FxIndexMap<WorkProductId,
 
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
+        dump_mono_sizes(sess, &self.crate_info, &compiled_modules.modules);
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
 
         // FIXME: time_llvm_passes support - does this use a global context or
@@ -22,6 +22,7 @@
 use rustc_middle::mir::mono::{CodegenUnit, CodegenUnitNameBuilder, MonoItem};
 use rustc_middle::query::Providers;
 use rustc_middle::ty::layout::{HasTyCtxt, HasTypingEnv, LayoutOf, TyAndLayout};
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
 use rustc_session::Session;
 use rustc_session::config::{self, CrateType, EntryFnType, OptLevel, OutputType};
@@ -45,7 +46,8 @@
 use crate::mir::place::PlaceRef;
 use crate::traits::*;
 use crate::{
-    CachedModuleCodegen, CompiledModule, CrateInfo, ModuleCodegen, ModuleKind, errors, meth, mir,
+    CachedModuleCodegen, CompiledModule, CrateInfo, ModuleCodegen, ModuleKind, MonoItemInfo,
+    errors, meth, mir,
 };
 
 pub(crate) fn bin_op_to_icmp_predicate(op: BinOp, signed: bool) -> IntPredicate {
@@ -926,6 +928,7 @@ pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
             dependency_formats: Lrc::clone(tcx.dependency_formats(())),
             windows_subsystem,
             natvis_debugger_visualizers: Default::default(),
+            mono_items: Default::default(),
         };
 
         info.native_libraries.reserve(n_crates);
@@ -1027,10 +1030,54 @@ pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
                 collect_debugger_visualizers_transitive(tcx, DebuggerVisualizerType::Natvis);
         }
 
+        if tcx.sess.opts.unstable_opts.dump_mono_sizes.enabled() {
+            info.mono_items = collect_mono_item_infos(tcx);
+        }
+
         info
     }
 }
 
+/// Records every mono item of every codegen unit, so that their sizes can be read from the
+/// object files once codegen is done.
+fn collect_mono_item_infos(tcx: TyCtxt<'_>) -> Vec<MonoItemInfo> {
+    let (_, codegen_units) = tcx.collect_and_partition_mono_items(());
+    let mut infos = vec![];
+    for cgu in codegen_units {
+        for (&item, data) in cgu.items() {
+            let (def_id, generic_args) = match item {
+                MonoItem::Fn(instance) => {
+                    let generic_args = instance
+                        .args
+                        .iter()
+                        .filter(|arg| !matches!(arg.unpack(), ty::GenericArgKind::Lifetime(_)))
+                        .map(|arg| with_no_trimmed_paths!(arg.to_string()))
+                        .collect();
+                    (instance.def_id(), generic_args)
+                }
+                MonoItem::Static(def_id) => (def_id, vec![]),
+                // Global assembly does not define a symbol of its own.
+                MonoItem::GlobalAsm(_) => continue,
+            };
+            let krate = tcx.crate_name(def_id.krate);
+            infos.push(MonoItemInfo {
+                name: with_no_trimmed_paths!(item.to_string()),
+                symbol_name: item.symbol_name(tcx).name.to_string(),
+                cgu_name: cgu.name().to_string(),
+                krate,
+                instantiating_crate: if generic_args.is_empty() {
+                    krate
+                } else {
+                    tcx.crate_name(LOCAL_CRATE)
+                },
+                generic_args,
+                size_estimate: data.size_estimate,
+            });
+        }
+    }
+    infos
+}
+
 pub(crate) fn provide(providers: &mut Providers) {
     providers.backend_optimization_level = |tcx, cratenum| {
         let for_speed = match tcx.sess.opts.optimize {
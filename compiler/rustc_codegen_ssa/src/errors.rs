@@ -179,6 +179,22 @@ pub struct IgnoringOutput {
     pub extension: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_couldnt_dump_mono_sizes)]
+pub(crate) struct CouldntDumpMonoSizes {
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_mono_sizes_unreadable_objects)]
+#[note]
+#[help]
+pub(crate) struct MonoSizesUnreadableObjects {
+    pub count: usize,
+    pub path: String,
+    pub error: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_create_temp_dir)]
 pub(crate) struct CreateTempDir {
@@ -473,6 +489,13 @@ pub(crate) struct StaticLibraryNativeArtifactsToFile<'a> {
     pub path: &'a Path,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_link_map_summary_write_failed)]
+pub(crate) struct LinkMapSummaryWriteFailed {
+    pub path: PathBuf,
+    pub error: Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_link_script_unavailable)]
 pub(crate) struct LinkScriptUnavailable;
@@ -200,6 +200,26 @@ pub struct CrateInfo {
     pub dependency_formats: Lrc<Dependencies>,
     pub windows_subsystem: Option<String>,
     pub natvis_debugger_visualizers: BTreeSet<DebuggerVisualizerFile>,
+    /// The mono items codegened in this crate, only collected for `-Z dump-mono-sizes`.
+    pub mono_items: Vec<MonoItemInfo>,
+}
+
+/// A mono item codegened in the local crate, whose machine code size is reported by
+/// `-Z dump-mono-sizes`.
+#[derive(Debug, Encodable, Decodable)]
+pub struct MonoItemInfo {
+    pub name: String,
+    pub symbol_name: String,
+    /// The codegen unit the item was placed in, which is also the name of its module.
+    pub cgu_name: String,
+    /// The crate defining the item.
+    pub krate: Symbol,
+    /// The crate the size of the item is attributed to: the local crate for instantiations of
+    /// generic items, which are made for it, and the defining crate for other items.
+    pub instantiating_crate: Symbol,
+    /// The generic arguments of the instance, other than lifetimes.
+    pub generic_args: Vec<String>,
+    pub size_estimate: usize,
 }
 
 #[derive(Encodable, Decodable)]
@@ -1,4 +1,5 @@
 use std::sync::atomic::Ordering::Relaxed;
+use std::time::{Duration, Instant};
 
 use either::{Left, Right};
 use rustc_abi::{self as abi, BackendRepr};
@@ -10,7 +11,8 @@
 use rustc_middle::ty::layout::{HasTypingEnv, LayoutOf};
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, Ty, TyCtxt};
-use rustc_span::def_id::LocalDefId;
+use rustc_session::code_stats::{CompileTimeInfo, CompileTimeKind};
+use rustc_span::def_id::{DefId, LocalDefId};
 use rustc_span::{DUMMY_SP, Span};
 use tracing::{debug, instrument, trace};
 
@@ -361,6 +363,10 @@ fn eval_in_interpreter<'tcx, R: InterpretationResult<'tcx>>(
 ) -> Result<R, ErrorHandled> {
     let def = cid.instance.def.def_id();
     let is_static = tcx.is_static(def);
+    // Reading the clock is only needed to check the budgets.
+    let opts = &tcx.sess.opts.unstable_opts;
+    let start = (opts.compile_time_budget.is_some() || opts.const_eval_step_budget.is_some())
+        .then(Instant::now);
 
     let mut ecx = InterpCx::new(
         tcx,
@@ -373,9 +379,38 @@ fn eval_in_interpreter<'tcx, R: InterpretationResult<'tcx>>(
         CompileTimeMachine::new(CanAccessMutGlobal::from(is_static), CheckAlignment::Error),
     );
     let res = ecx.load_mir(cid.instance.def, cid.promoted);
-    res.and_then(|body| eval_body_using_ecx(&mut ecx, cid, body))
+    let res = res
+        .and_then(|body| eval_body_using_ecx(&mut ecx, cid, body))
         .report_err()
-        .map_err(|error| report_eval_error(&ecx, cid, error))
+        .map_err(|error| report_eval_error(&ecx, cid, error));
+
+    if let Some(start) = start {
+        record_compile_time(tcx, def, start.elapsed(), ecx.machine.num_evaluated_steps);
+    }
+
+    res
+}
+
+/// Records the evaluation of the constant or static `def` if it exceeded the budgets set with
+/// `-Z compile-time-budget` or `-Z const-eval-step-budget`. It is linted once the analysis is
+/// done rather than here, so that the warning is not saved with the query result and replayed
+/// by incremental compilation.
+fn record_compile_time(tcx: TyCtxt<'_>, def: DefId, elapsed: Duration, steps: usize) {
+    // Items of other crates are evaluated on behalf of the local items using them, which are
+    // accounted for separately.
+    let Some(def_id) = def.as_local() else { return };
+    let opts = &tcx.sess.opts.unstable_opts;
+    let over_time_budget =
+        opts.compile_time_budget.is_some_and(|budget| elapsed > Duration::from_millis(budget));
+    let over_step_budget = opts.const_eval_step_budget.is_some_and(|budget| steps > budget);
+    if over_time_budget || over_step_budget {
+        tcx.sess.code_stats.record_compile_time(CompileTimeInfo {
+            def_id,
+            span: tcx.def_span(def),
+            elapsed,
+            kind: CompileTimeKind::ConstEval { steps },
+        });
+    }
 }
 
 #[inline(always)]
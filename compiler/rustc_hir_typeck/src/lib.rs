@@ -39,8 +39,11 @@
 mod upvar;
 mod writeback;
 
+use std::time::{Duration, Instant};
+
 pub use coercion::can_coerce;
 use fn_ctxt::FnCtxt;
+use rustc_data_structures::profiling::{SelfProfilerRef, TimingGuard};
 use rustc_data_structures::unord::UnordSet;
 use rustc_errors::codes::*;
 use rustc_errors::{Applicability, ErrorGuaranteed, pluralize, struct_span_code_err};
@@ -54,6 +57,7 @@
 use rustc_middle::query::Providers;
 use rustc_middle::ty::{self, Ty, TyCtxt};
 use rustc_middle::{bug, span_bug};
+use rustc_session::code_stats::{CompileTimeInfo, CompileTimeKind};
 use rustc_session::config;
 use rustc_span::Span;
 use rustc_span::def_id::LocalDefId;
@@ -131,6 +135,10 @@ fn typeck_with_fallback<'tcx>(
         return tcx.typeck(typeck_root_def_id);
     }
 
+    let start = Instant::now();
+    let mut activities = TypeckActivities::new(tcx);
+    activities.start("typeck_check_body");
+
     let id = tcx.local_def_id_to_hir_id(def_id);
     let node = tcx.hir_node(id);
     let span = tcx.hir().span(id);
@@ -182,6 +190,7 @@ fn typeck_with_fallback<'tcx>(
         fcx.write_ty(id, expected_type);
     };
 
+    activities.start("typeck_fallback");
     fcx.type_inference_fallback();
 
     // Even though coercion casts provide type hints, we check casts after fallback for
@@ -191,6 +200,7 @@ fn typeck_with_fallback<'tcx>(
 
     // Closure and coroutine analysis may run after fallback
     // because they don't constrain other type variables.
+    activities.start("typeck_closure_analysis");
     fcx.closure_analyze(body);
     assert!(fcx.deferred_call_resolutions.borrow().is_empty());
     // Before the coroutine analysis, temporary scopes shall be marked to provide more
@@ -207,6 +217,7 @@ fn typeck_with_fallback<'tcx>(
     debug!(pending_obligations = ?fcx.fulfillment_cx.borrow().pending_obligations());
 
     // This must be the last thing before `report_ambiguity_errors`.
+    activities.start("typeck_late_checks");
     fcx.resolve_coroutine_interiors();
 
     debug!(pending_obligations = ?fcx.fulfillment_cx.borrow().pending_obligations());
@@ -221,6 +232,7 @@ fn typeck_with_fallback<'tcx>(
 
     fcx.check_asms();
 
+    activities.start("typeck_writeback");
     let typeck_results = fcx.resolve_type_vars_in_body(body);
 
     // We clone the defined opaque types during writeback in the new solver
@@ -231,9 +243,58 @@ fn typeck_with_fallback<'tcx>(
     // it will need to hold.
     assert_eq!(typeck_results.hir_owner, id.owner);
 
+    let activities = activities.finish();
+    let elapsed = start.elapsed();
+    // The body is linted once the analysis is done rather than here, so that the warning is not
+    // saved with the query result and replayed by incremental compilation.
+    if let Some(budget) = tcx.sess.opts.unstable_opts.compile_time_budget
+        && elapsed > Duration::from_millis(budget)
+    {
+        let trait_solving = fcx.infcx.trait_solving_time.get();
+        tcx.sess.code_stats.record_compile_time(CompileTimeInfo {
+            def_id,
+            span: tcx.def_span(def_id),
+            elapsed,
+            kind: CompileTimeKind::Typeck { activities, trait_solving },
+        });
+    }
+
     typeck_results
 }
 
+/// Times the phases of type-checking a body as self-profile activities, which also make up the
+/// breakdown of the bodies exceeding `-Z compile-time-budget`.
+struct TypeckActivities<'a> {
+    prof: &'a SelfProfilerRef,
+    current: Option<(&'static str, Instant, TimingGuard<'a>)>,
+    activities: Vec<(&'static str, Duration)>,
+}
+
+impl<'a> TypeckActivities<'a> {
+    fn new(tcx: TyCtxt<'a>) -> TypeckActivities<'a> {
+        TypeckActivities { prof: &tcx.sess.prof, current: None, activities: Vec::new() }
+    }
+
+    /// Ends the current activity, and starts the activity `label`.
+    fn start(&mut self, label: &'static str) {
+        self.end_current();
+        self.current = Some((label, Instant::now(), self.prof.generic_activity(label)));
+    }
+
+    fn end_current(&mut self) {
+        if let Some((label, start, guard)) = self.current.take() {
+            drop(guard);
+            self.activities.push((label, start.elapsed()));
+        }
+    }
+
+    /// Ends the current activity, and returns the time spent in each activity.
+    fn finish(mut self) -> Vec<(&'static str, Duration)> {
+        self.end_current();
+        self.activities
+    }
+}
+
 fn infer_type_if_missing<'tcx>(fcx: &FnCtxt<'_, 'tcx>, node: Node<'tcx>) -> Option<Ty<'tcx>> {
     let tcx = fcx.tcx;
     let def_id = fcx.body_id;
@@ -82,6 +82,8 @@ pub fn fork(&self) -> Self {
             universe: self.universe.clone(),
             next_trait_solver: self.next_trait_solver,
             obligation_inspector: self.obligation_inspector.clone(),
+            trait_solving_time: Default::default(),
+            solving_obligation: self.solving_obligation.clone(),
         }
     }
 
@@ -106,6 +108,8 @@ pub fn fork_with_typing_mode(&self, typing_mode: TypingMode<'tcx>) -> Self {
             universe: self.universe.clone(),
             next_trait_solver: self.next_trait_solver,
             obligation_inspector: self.obligation_inspector.clone(),
+            trait_solving_time: Default::default(),
+            solving_obligation: self.solving_obligation.clone(),
         };
         forked.inner.borrow_mut().projection_cache().clear();
         forked
@@ -1,5 +1,6 @@
 use std::cell::{Cell, RefCell};
 use std::fmt;
+use std::time::Duration;
 
 pub use BoundRegionConversionTime::*;
 pub use RegionVariableOrigin::*;
@@ -301,6 +302,14 @@ pub struct InferCtxt<'tcx> {
     next_trait_solver: bool,
 
     pub obligation_inspector: Cell<Option<ObligationInspector<'tcx>>>,
+
+    /// The time spent solving trait obligations in this inference context, only measured with
+    /// `-Z compile-time-budget` or `-Z trait-obligation-budget`.
+    pub trait_solving_time: Cell<Duration>,
+
+    /// Whether a trait obligation is being solved, so that the time of the obligations solved
+    /// while solving it is not accounted twice.
+    pub solving_obligation: Cell<bool>,
 }
 
 /// See the `error_reporting` module for more details.
@@ -591,6 +600,8 @@ pub fn build(&mut self, typing_mode: TypingMode<'tcx>) -> InferCtxt<'tcx> {
             universe: Cell::new(ty::UniverseIndex::ROOT),
             next_trait_solver,
             obligation_inspector: Cell::new(None),
+            trait_solving_time: Cell::new(Duration::ZERO),
+            solving_obligation: Cell::new(false),
         }
     }
 }
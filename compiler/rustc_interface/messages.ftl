@@ -1,6 +1,18 @@
 interface_cant_emit_mir =
     could not emit MIR: {$error}
 
+interface_compile_time_budget_steps =
+    this exceeds the budget of {$budget} steps set with `-Z const-eval-step-budget`
+
+interface_compile_time_budget_time =
+    this exceeds the budget of {$budget}ms set with `-Z compile-time-budget`
+
+interface_const_eval_budget_exceeded =
+    evaluating `{$item}` took {$elapsed_ms}ms and {$steps} {$steps ->
+        [one] step
+        *[other] steps
+    }
+
 interface_emoji_identifier =
     identifiers cannot contain emoji: `{$ident}`
 
@@ -48,3 +60,17 @@ interface_rustc_error_unexpected_annotation =
 
 interface_temps_dir_error =
     failed to find or create the directory specified by `--temps-dir`
+
+interface_trait_obligation_budget_exceeded =
+    solving the trait obligation `{$predicate}` took {$elapsed_ms}ms
+    .note = this exceeds the budget of {$budget}ms set with `-Z trait-obligation-budget`
+
+interface_trait_solving_time =
+    {$trait_solving_ms}ms of it were spent solving trait obligations
+
+interface_typeck_activities =
+    by self-profile activity: {$activities}
+
+interface_typeck_time_budget_exceeded =
+    type-checking `{$item}` took {$elapsed_ms}ms
+    .note = this exceeds the budget of {$budget}ms set with `-Z compile-time-budget`
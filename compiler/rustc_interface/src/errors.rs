@@ -1,7 +1,7 @@
 use std::io;
 use std::path::Path;
 
-use rustc_macros::Diagnostic;
+use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_span::{Span, Symbol};
 
 #[derive(Diagnostic)]
@@ -103,3 +103,43 @@ pub struct FailedWritingFile<'a> {
 #[derive(Diagnostic)]
 #[diag(interface_multiple_output_types_to_stdout)]
 pub struct MultipleOutputTypesToStdout;
+
+#[derive(LintDiagnostic)]
+#[diag(interface_typeck_time_budget_exceeded)]
+#[note]
+#[note(interface_trait_solving_time)]
+#[note(interface_typeck_activities)]
+pub(crate) struct TypeckTimeBudgetExceeded {
+    pub item: String,
+    pub elapsed_ms: u128,
+    pub budget: u64,
+    pub trait_solving_ms: u128,
+    pub activities: String,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(interface_const_eval_budget_exceeded)]
+pub(crate) struct ConstEvalBudgetExceeded {
+    pub item: String,
+    pub elapsed_ms: u128,
+    pub steps: usize,
+    #[subdiagnostic]
+    pub budget: ConstEvalBudget,
+}
+
+#[derive(Subdiagnostic)]
+pub(crate) enum ConstEvalBudget {
+    #[note(interface_compile_time_budget_time)]
+    Time { budget: u64 },
+    #[note(interface_compile_time_budget_steps)]
+    Steps { budget: usize },
+}
+
+#[derive(LintDiagnostic)]
+#[diag(interface_trait_obligation_budget_exceeded)]
+#[note]
+pub(crate) struct TraitObligationBudgetExceeded {
+    pub predicate: String,
+    pub elapsed_ms: u128,
+    pub budget: u64,
+}
@@ -13,6 +13,7 @@
 use rustc_expand::base::{ExtCtxt, LintStoreExpand};
 use rustc_feature::Features;
 use rustc_fs_util::try_canonicalize;
+use rustc_hir::CRATE_HIR_ID;
 use rustc_hir::def_id::{LOCAL_CRATE, StableCrateId, StableCrateIdMap};
 use rustc_hir::definitions::Definitions;
 use rustc_incremental::setup_dep_graph;
@@ -26,9 +27,10 @@
 };
 use rustc_passes::{abi_test, input_stats, layout_test};
 use rustc_resolve::Resolver;
-use rustc_session::code_stats::VTableSizeInfo;
+use rustc_session::code_stats::{CompileTimeInfo, CompileTimeKind, VTableSizeInfo};
 use rustc_session::config::{CrateType, Input, OutFileName, OutputFilenames, OutputType};
 use rustc_session::cstore::Untracked;
+use rustc_session::lint::builtin::COMPILE_TIME_BUDGET_EXCEEDED;
 use rustc_session::output::{collect_crate_types, filename_for_input, find_crate_name};
 use rustc_session::search_paths::PathKind;
 use rustc_session::{Limit, Session};
@@ -967,6 +969,18 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
         let _ = tcx.all_diagnostic_items(());
     });
 
+    if let Some(path) = &sess.opts.unstable_opts.extern_usage_report {
+        sess.time("extern_usage_report", || {
+            rustc_passes::extern_usage::write_extern_usage_report(tcx, path)
+        });
+    }
+
+    if let Some(path) = &sess.opts.unstable_opts.unused_pub_report {
+        sess.time("unused_pub_report", || {
+            rustc_passes::unused_pub::write_unused_pub_report(tcx, path)
+        });
+    }
+
     if sess.opts.unstable_opts.print_vtable_sizes {
         let traits = tcx.traits(LOCAL_CRATE);
 
@@ -1088,6 +1102,82 @@ fn check_for_rustc_errors_attr(tcx: TyCtxt<'_>) {
     }
 }
 
+/// Lints the items recorded as exceeding the budgets set with `-Z compile-time-budget`,
+/// `-Z const-eval-step-budget` and `-Z trait-obligation-budget`. They are measured by the queries
+/// computing them but linted here, so that the warnings are neither saved with the query results
+/// nor replayed by incremental compilation, and are emitted in a deterministic order.
+pub(crate) fn report_compile_time_budgets(tcx: TyCtxt<'_>) {
+    let mut infos = tcx.sess.code_stats.take_compile_times();
+    infos.sort_by_key(|info| {
+        let kind = match info.kind {
+            CompileTimeKind::Typeck { .. } => 0,
+            CompileTimeKind::ConstEval { .. } => 1,
+            CompileTimeKind::TraitObligation { .. } => 2,
+        };
+        (info.span, kind)
+    });
+
+    let opts = &tcx.sess.opts.unstable_opts;
+    for CompileTimeInfo { def_id, span, elapsed, kind } in infos {
+        // Obligations may be registered for definitions created after lowering, which have no
+        // HIR to attach the lint level to.
+        let hir_id = tcx.opt_local_def_id_to_hir_id(def_id).unwrap_or(CRATE_HIR_ID);
+        let elapsed_ms = elapsed.as_millis();
+        match kind {
+            CompileTimeKind::Typeck { activities, trait_solving } => {
+                let activities = activities
+                    .iter()
+                    .map(|(label, time)| format!("`{label}`: {}ms", time.as_millis()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                tcx.emit_node_span_lint(
+                    COMPILE_TIME_BUDGET_EXCEEDED,
+                    hir_id,
+                    span,
+                    errors::TypeckTimeBudgetExceeded {
+                        item: tcx.def_path_str(def_id),
+                        elapsed_ms,
+                        budget: opts.compile_time_budget.unwrap(),
+                        trait_solving_ms: trait_solving.as_millis(),
+                        activities,
+                    },
+                );
+            }
+            CompileTimeKind::ConstEval { steps } => {
+                let budget = match opts.const_eval_step_budget {
+                    Some(budget) if steps > budget => errors::ConstEvalBudget::Steps { budget },
+                    _ => {
+                        errors::ConstEvalBudget::Time { budget: opts.compile_time_budget.unwrap() }
+                    }
+                };
+                tcx.emit_node_span_lint(
+                    COMPILE_TIME_BUDGET_EXCEEDED,
+                    hir_id,
+                    span,
+                    errors::ConstEvalBudgetExceeded {
+                        item: tcx.def_path_str(def_id),
+                        elapsed_ms,
+                        steps,
+                        budget,
+                    },
+                );
+            }
+            CompileTimeKind::TraitObligation { predicate } => {
+                tcx.emit_node_span_lint(
+                    COMPILE_TIME_BUDGET_EXCEEDED,
+                    hir_id,
+                    span,
+                    errors::TraitObligationBudgetExceeded {
+                        predicate,
+                        elapsed_ms,
+                        budget: opts.trait_obligation_budget.unwrap(),
+                    },
+                );
+            }
+        }
+    }
+}
+
 /// Runs the codegen backend, after which the AST and analysis can
 /// be discarded.
 pub(crate) fn start_codegen<'tcx>(
@@ -129,10 +129,15 @@ pub fn codegen_and_build_linker(
     ) -> Result<Linker> {
         let ongoing_codegen = passes::start_codegen(codegen_backend, tcx)?;
 
+        // This must run after monomorphization, which evaluates constants too.
+        passes::report_compile_time_budgets(tcx);
+
         // This must run after monomorphization so that all generic types
         // have been instantiated.
         if tcx.sess.opts.unstable_opts.print_type_sizes {
-            tcx.sess.code_stats.print_type_sizes();
+            tcx.sess
+                .code_stats
+                .print_type_sizes(tcx.sess.opts.unstable_opts.print_type_sizes_format);
         }
 
         if tcx.sess.opts.unstable_opts.print_vtable_sizes {
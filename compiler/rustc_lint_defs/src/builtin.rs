@@ -29,6 +29,7 @@
         BREAK_WITH_LABEL_AND_LOOP,
         CENUM_IMPL_DROP_CAST,
         COHERENCE_LEAK_CHECK,
+        COMPILE_TIME_BUDGET_EXCEEDED,
         CONFLICTING_REPR_HINTS,
         CONST_EVALUATABLE_UNCHECKED,
         CONST_ITEM_MUTATION,
@@ -3442,6 +3443,42 @@
     report_in_external_macro
 }
 
+declare_lint! {
+    /// The `compile_time_budget_exceeded` lint detects items that take longer to compile than
+    /// the budget set with `-Z compile-time-budget`, `-Z const-eval-step-budget` or
+    /// `-Z trait-obligation-budget`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs command line option)
+    /// // compiled with `-Z const-eval-step-budget=1000`
+    /// const SUM: u64 = {
+    ///     let mut sum = 0;
+    ///     let mut i = 0;
+    ///     while i < 10_000 {
+    ///         sum += i;
+    ///         i += 1;
+    ///     }
+    ///     sum
+    /// };
+    /// ```
+    ///
+    /// This produces a warning pointing at `SUM`, with the time and the number of steps its
+    /// evaluation took.
+    ///
+    /// ### Explanation
+    ///
+    /// A few items, like constants computing large tables or functions with complex trait
+    /// bounds, can dominate the compile time of a crate. Setting a budget reports the items
+    /// exceeding it: the type-checking of bodies, with the time of its phases and the part of it
+    /// spent solving trait obligations, the evaluation of constants and statics, and the solving
+    /// of single trait obligations. The lint is only emitted when a budget is set, once the
+    /// crate has been analyzed, and can be allowed on the items known to be expensive.
+    pub COMPILE_TIME_BUDGET_EXCEEDED,
+    Warn,
+    "detects items that take longer to compile than the configured budget"
+}
+
 declare_lint! {
     /// The `unused_associated_type_bounds` lint is emitted when an
     /// associated type bound is added to a trait object, but the associated
@@ -1487,6 +1487,11 @@ fn exported_symbols<'tcx>(
         tcx.arena.alloc_from_iter(self.root.exported_symbols.decode((self, tcx)))
     }
 
+    fn get_used_extern_items(self, tcx: TyCtxt<'_>) -> Option<&[DefId]> {
+        let used_extern_items = self.root.used_extern_items?;
+        Some(tcx.arena.alloc_from_iter(used_extern_items.decode((self, tcx))))
+    }
+
     fn get_macro(self, id: DefIndex, sess: &Session) -> ast::MacroDef {
         match self.def_kind(id) {
             DefKind::Macro(_) => {
@@ -402,6 +402,7 @@ fn into_args(self) -> (DefId, SimplifiedType) {
         cdata.get_stability_implications(tcx).iter().copied().collect()
     }
     stripped_cfg_items => { cdata.get_stripped_cfg_items(cdata.cnum, tcx) }
+    used_extern_items => { cdata.get_used_extern_items(tcx) }
     intrinsic_raw => { cdata.get_intrinsic(def_id.index) }
     defined_lang_items => { cdata.get_lang_items(tcx) }
     diagnostic_items => { cdata.get_diagnostic_items() }
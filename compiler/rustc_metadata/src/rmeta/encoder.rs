@@ -679,6 +679,8 @@ macro_rules! stat {
             self.encode_exported_symbols(tcx.exported_symbols(LOCAL_CRATE))
         });
 
+        let used_extern_items = stat!("used-extern-items", || self.encode_used_extern_items());
+
         // Encode the hygiene data.
         // IMPORTANT: this *must* be the last thing that we encode (other than `SourceMap`). The
         // process of encoding other items (e.g. `optimized_mir`) may cause us to load data from
@@ -736,6 +738,7 @@ macro_rules! stat {
                 impls,
                 incoherent_impls,
                 exported_symbols,
+                used_extern_items,
                 interpret_alloc_index,
                 tables,
                 syntax_contexts,
@@ -2051,6 +2054,14 @@ fn encode_stripped_cfg_items(&mut self) -> LazyArray<StrippedCfgItem<DefIndex>>
         )
     }
 
+    fn encode_used_extern_items(&mut self) -> Option<LazyArray<DefId>> {
+        if self.is_proc_macro || !self.tcx.sess.opts.unstable_opts.record_used_extern_items {
+            return None;
+        }
+        let used_extern_items = self.tcx.used_extern_items(LOCAL_CRATE).unwrap_or_default();
+        Some(self.lazy_array(used_extern_items))
+    }
+
     fn encode_traits(&mut self) -> LazyArray<DefIndex> {
         empty_proc_macro!(self);
         self.lazy_array(self.tcx.traits(LOCAL_CRATE).iter().map(|def_id| def_id.index))
@@ -276,6 +276,9 @@ pub(crate) struct CrateRoot {
 
     exported_symbols: LazyArray<(ExportedSymbol<'static>, SymbolExportInfo)>,
 
+    /// The items of other crates used by this crate, with `-Zrecord-used-extern-items`.
+    used_extern_items: Option<LazyArray<DefId>>,
+
     syntax_contexts: SyntaxContextTable,
     expn_data: ExpnDataTable,
     expn_hashes: ExpnHashTable,
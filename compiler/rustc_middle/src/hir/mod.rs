@@ -116,6 +116,16 @@ pub fn hir(self) -> map::Map<'tcx> {
         map::Map { tcx: self }
     }
 
+    /// Returns the `HirId` of `def_id`, or `None` if it has no HIR, like the definitions created
+    /// after lowering.
+    pub fn opt_local_def_id_to_hir_id(self, def_id: LocalDefId) -> Option<HirId> {
+        match self.hir_crate(()).owners.get(def_id)? {
+            MaybeOwner::Owner(_) => Some(HirId::make_owner(def_id)),
+            &MaybeOwner::NonOwner(hir_id) => Some(hir_id),
+            MaybeOwner::Phantom => None,
+        }
+    }
+
     pub fn parent_module(self, id: HirId) -> LocalModDefId {
         if !id.is_owner() && self.def_kind(id.owner) == DefKind::Mod {
             LocalModDefId::new_unchecked(id.owner.def_id)
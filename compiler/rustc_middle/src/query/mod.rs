@@ -2342,6 +2342,13 @@
         separate_provide_extern
     }
 
+    /// Get the items of other crates that are used by a particular crate. For extern crates, this
+    /// is only available when they were compiled with `-Zrecord-used-extern-items`.
+    query used_extern_items(cnum: CrateNum) -> Option<&'tcx [DefId]> {
+        desc { "looking up the items of other crates used by a crate" }
+        separate_provide_extern
+    }
+
     query generics_require_sized_self(def_id: DefId) -> bool {
         desc { "check whether the item has a `where Self: Sized` bound" }
     }
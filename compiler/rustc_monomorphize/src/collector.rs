@@ -205,9 +205,10 @@
 //! this is not implemented however: a mono item will be produced
 //! regardless of whether it is actually needed or not.
 
+use std::collections::VecDeque;
 use std::path::PathBuf;
 
-use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
 use rustc_data_structures::sync::{LRef, MTLock, par_for_each_in};
 use rustc_data_structures::unord::{UnordMap, UnordSet};
 use rustc_hir as hir;
@@ -261,11 +262,18 @@ pub(crate) struct UsageMap<'tcx> {
 
     // Maps every mono item to the mono items that use it.
     user_map: UnordMap<MonoItem<'tcx>, Vec<MonoItem<'tcx>>>,
+
+    // The roots of the collection, from which all the other items are used.
+    roots: UnordSet<MonoItem<'tcx>>,
 }
 
 impl<'tcx> UsageMap<'tcx> {
-    fn new() -> UsageMap<'tcx> {
-        UsageMap { used_map: Default::default(), user_map: Default::default() }
+    fn new(roots: &[MonoItem<'tcx>]) -> UsageMap<'tcx> {
+        UsageMap {
+            used_map: Default::default(),
+            user_map: Default::default(),
+            roots: roots.iter().copied().collect(),
+        }
     }
 
     fn record_used<'a>(&mut self, user_item: MonoItem<'tcx>, used_items: &'a MonoItems<'tcx>)
@@ -283,6 +291,36 @@ pub(crate) fn get_user_items(&self, item: MonoItem<'tcx>) -> &[MonoItem<'tcx>] {
         self.user_map.get(&item).map(|items| items.as_slice()).unwrap_or(&[])
     }
 
+    /// Returns a shortest chain of items, starting at a root and ending at `item`, in which each
+    /// item uses the next one.
+    pub(crate) fn chain_from_root(&self, item: MonoItem<'tcx>) -> Option<Vec<MonoItem<'tcx>>> {
+        // Search breadth-first from `item` through the users of each item, until reaching a root,
+        // remembering which item each user was reached from.
+        let mut used_by_user = FxHashMap::default();
+        let mut visited = FxHashSet::from_iter([item]);
+        let mut queue = VecDeque::from([item]);
+        while let Some(current) = queue.pop_front() {
+            if self.roots.contains(&current) {
+                let mut chain = vec![current];
+                while let Some(&used) = used_by_user.get(chain.last().unwrap()) {
+                    chain.push(used);
+                }
+                return Some(chain);
+            }
+            // Users are recorded in a nondeterministic order with a parallel compiler, so they
+            // are sorted for the chain to be deterministic.
+            let mut users = self.get_user_items(current).to_vec();
+            users.sort_by_cached_key(|user| with_no_trimmed_paths!(user.to_string()));
+            for user in users {
+                if visited.insert(user) {
+                    used_by_user.insert(user, current);
+                    queue.push_back(user);
+                }
+            }
+        }
+        None
+    }
+
     /// Internally iterate over all inlined items used by `item`.
     pub(crate) fn for_each_inlined_used_item<F>(
         &self,
@@ -1611,7 +1649,7 @@ pub(crate) fn collect_crate_mono_items<'tcx>(
     let mut state = SharedState {
         visited: MTLock::new(UnordSet::default()),
         mentioned: MTLock::new(UnordSet::default()),
-        usage_map: MTLock::new(UsageMap::new()),
+        usage_map: MTLock::new(UsageMap::new(&roots)),
     };
     let recursion_limit = tcx.recursion_limit();
 
@@ -1240,9 +1240,71 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
         }
     }
 
+    if let Some(ref pattern) = tcx.sess.opts.unstable_opts.print_mono_item_chains {
+        print_mono_item_chains(&items, &usage_map, pattern);
+    }
+
+    if let Some(count) = tcx.sess.opts.unstable_opts.print_mono_item_counts {
+        print_mono_item_counts(tcx, &items, count);
+    }
+
     (tcx.arena.alloc(mono_items), codegen_units)
 }
 
+/// Prints, for each collected item whose name contains `pattern`, the chain of uses from a root
+/// (like `main` or an exported function) that led to the item being collected.
+fn print_mono_item_chains<'tcx>(
+    items: &[MonoItem<'tcx>],
+    usage_map: &UsageMap<'tcx>,
+    pattern: &str,
+) {
+    let mut matching_items: Vec<_> = items
+        .iter()
+        .map(|&item| (with_no_trimmed_paths!(item.to_string()), item))
+        .filter(|(name, _)| name.contains(pattern))
+        .collect();
+    matching_items.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (name, item) in matching_items {
+        match usage_map.chain_from_root(item) {
+            Some(chain) => {
+                let chain = chain
+                    .iter()
+                    .map(|item| with_no_trimmed_paths!(item.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                println!("MONO_ITEM_CHAIN {chain}");
+            }
+            None => println!("MONO_ITEM_CHAIN {name} (not used from a root)"),
+        }
+    }
+}
+
+/// Prints the `count` generic functions with the most instantiations among the collected items,
+/// with their number of instantiations.
+fn print_mono_item_counts<'tcx>(tcx: TyCtxt<'tcx>, items: &[MonoItem<'tcx>], count: usize) {
+    let mut instances_per_def_id: FxIndexMap<DefId, usize> = Default::default();
+    for item in items {
+        if let MonoItem::Fn(instance) = item
+            && instance.args.non_erasable_generics().next().is_some()
+        {
+            *instances_per_def_id.entry(instance.def_id()).or_default() += 1;
+        }
+    }
+
+    let mut counts: Vec<_> = instances_per_def_id
+        .into_iter()
+        .map(|(def_id, instances)| (instances, with_no_trimmed_paths!(tcx.def_path_str(def_id))))
+        .collect();
+    counts.sort_by(|(a_instances, a_path), (b_instances, b_path)| {
+        b_instances.cmp(a_instances).then_with(|| a_path.cmp(b_path))
+    });
+
+    for (instances, path) in counts.into_iter().take(count) {
+        println!("MONO_ITEM_COUNT {instances} {path}");
+    }
+}
+
 /// Outputs stats about instantiation counts and estimated size, per `MonoItem`'s
 /// def, to a file in the given output directory.
 fn dump_mono_items_stats<'tcx>(
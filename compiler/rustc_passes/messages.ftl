@@ -318,6 +318,9 @@ passes_export_name =
 passes_extern_main =
     the `main` function cannot be declared in an `extern` block
 
+passes_extern_usage_report_write_failed =
+    failed to write the `--extern` crates usage report to `{$path}`: {$error}
+
 passes_feature_previously_declared =
     feature `{$feature}` is declared {$declared}, but was previously declared {$prev_declared}
 
@@ -804,6 +807,9 @@ passes_unused_multiple =
 passes_unused_no_lints_note =
     attribute `{$name}` without any lints has no effect
 
+passes_unused_pub_report_write_failed =
+    failed to write the unused public items report to `{$path}`: {$error}
+
 passes_unused_var_assigned_only = variable `{$name}` is assigned to, but never used
     .note = consider using `_{$name}` instead
 
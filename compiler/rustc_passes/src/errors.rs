@@ -810,6 +810,13 @@ pub(crate) struct UnusedMultiple {
     pub name: Symbol,
 }
 
+#[derive(Diagnostic)]
+#[diag(passes_unused_pub_report_write_failed)]
+pub(crate) struct UnusedPubReportWriteFailed<'a> {
+    pub path: &'a Path,
+    pub error: Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(passes_rustc_lint_opt_ty)]
 pub(crate) struct RustcLintOptTy {
@@ -1291,6 +1298,13 @@ pub(crate) struct ExternMain {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(passes_extern_usage_report_write_failed)]
+pub(crate) struct ExternUsageReportWriteFailed<'a> {
+    pub path: &'a Path,
+    pub error: Error,
+}
+
 pub(crate) struct NoMainErr {
     pub sp: Span,
     pub crate_name: Symbol,
@@ -0,0 +1,261 @@
+//! Finding how the crate being compiled uses its `--extern` crates, for `-Zextern-usage-report`.
+//!
+//! The `unused_crate_dependencies` lint only knows whether the name of a crate was resolved, and
+//! can't tell apart the crates only needed by tests or by macros. This report classifies each
+//! `--extern` crate by its uses in the HIR and the macro expansions of the local crate, and is
+//! written as JSON so that build tools can merge the reports of all the targets of a package:
+//! e.g. a dependency unused by the library and only used by its tests is a dev-dependency.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use rustc_ast::MetaItemInner;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::unord::UnordSet;
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE, LocalDefId};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::{ExternEntry, ExternLocation};
+use rustc_span::hygiene::{ExpnKind, all_local_expn_data};
+use rustc_span::symbol::{Symbol, kw, sym};
+use serde::Serialize;
+
+use crate::errors::ExternUsageReportWriteFailed;
+
+#[derive(Serialize)]
+struct ExternUsageReport {
+    crate_name: String,
+    /// Whether the crate is compiled as a test harness, so that its `#[cfg(test)]` code is
+    /// included.
+    test: bool,
+    externs: Vec<ExternReport>,
+}
+
+#[derive(Serialize)]
+struct ExternReport {
+    name: String,
+    /// Whether the name of the crate was resolved, which loads it even if nothing of it is used.
+    loaded: bool,
+    usage: ExternUsage,
+}
+
+/// How an `--extern` crate is used, from the least to the most.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ExternUsage {
+    /// The crate is not referenced.
+    Unused,
+    /// The crate is only referenced by test code: the items under `#[cfg(test)]`, and the
+    /// `#[test]` and `#[bench]` functions.
+    Test,
+    /// The crate is only referenced by the invocations of its macros, and by the code produced
+    /// by the macros of other crates.
+    Macros,
+    /// The crate is referenced by the code of the local crate.
+    Used,
+}
+
+/// Writes how each `--extern` crate is used by the local crate to `path`.
+pub fn write_extern_usage_report(tcx: TyCtxt<'_>, path: &Path) {
+    if let Err(error) = write_report(tcx, path) {
+        tcx.dcx().emit_err(ExternUsageReportWriteFailed { path, error });
+    }
+}
+
+fn write_report(tcx: TyCtxt<'_>, path: &Path) -> io::Result<()> {
+    // Like the `unused_crate_dependencies` lint, ignore the sysroot crates and the crates which
+    // are not meant to be referenced.
+    let externs: Vec<(&str, Option<CrateNum>)> = tcx
+        .sess
+        .opts
+        .externs
+        .iter()
+        .filter(|(_, entry)| {
+            matches!(entry.location, ExternLocation::ExactPaths(_))
+                && !entry.nounused_dep
+                && !entry.force
+        })
+        .map(|(name, entry)| (name.as_str(), extern_crate_num(tcx, entry)))
+        .collect();
+
+    let mut collector = ExternUsageCollector {
+        tcx,
+        test_fns: UnordSet::default(),
+        is_test_code: FxHashMap::default(),
+        usages: FxHashMap::default(),
+    };
+    for item_id in tcx.hir().items() {
+        if tcx
+            .hir()
+            .attrs(item_id.hir_id())
+            .iter()
+            .any(|attr| attr.has_name(sym::rustc_test_marker))
+        {
+            let def_id = item_id.owner_id.def_id;
+            collector.test_fns.insert((tcx.local_parent(def_id), tcx.item_name(def_id.into())));
+        }
+    }
+    tcx.hir().visit_all_item_likes_in_crate(&mut collector);
+
+    // The invocations of macros are not in the HIR. The macros of a crate can also be re-exported
+    // by another one, as proc-macro crates can't export anything else.
+    for (_, expn_data) in all_local_expn_data() {
+        let ExpnKind::Macro(..) = expn_data.kind else { continue };
+        let Some(macro_def_id) = expn_data.macro_def_id.filter(|def_id| !def_id.is_local()) else {
+            continue;
+        };
+        let Some(module) = expn_data.parent_module.and_then(DefId::as_local) else { continue };
+        let is_test = collector.is_test_code(module);
+        for &(_, cnum) in &externs {
+            if let Some(cnum) = cnum
+                && (cnum == macro_def_id.krate || reexports_macro(tcx, cnum, macro_def_id))
+            {
+                collector.record(cnum, is_test, true);
+            }
+        }
+    }
+
+    let report = ExternUsageReport {
+        crate_name: tcx.crate_name(LOCAL_CRATE).to_string(),
+        test: tcx.sess.is_test_crate(),
+        externs: externs
+            .into_iter()
+            .map(|(name, cnum)| ExternReport {
+                name: name.to_owned(),
+                loaded: cnum.is_some_and(|cnum| {
+                    tcx.extern_crate(cnum).is_some_and(|extern_crate| extern_crate.is_direct())
+                }),
+                usage: cnum
+                    .and_then(|cnum| collector.usages.get(&cnum).copied())
+                    .unwrap_or(ExternUsage::Unused),
+            })
+            .collect(),
+    };
+
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut file, &report)?;
+    writeln!(file)?;
+    file.flush()
+}
+
+/// Returns the loaded crate of an `--extern` entry, if any, by the paths it was loaded from.
+fn extern_crate_num(tcx: TyCtxt<'_>, entry: &ExternEntry) -> Option<CrateNum> {
+    let ExternLocation::ExactPaths(paths) = &entry.location else { return None };
+    tcx.crates(()).iter().copied().find(|&cnum| {
+        tcx.used_crate_source(cnum)
+            .paths()
+            .any(|source| paths.iter().any(|path| path.canonicalized() == source))
+    })
+}
+
+fn reexports_macro(tcx: TyCtxt<'_>, krate: CrateNum, macro_def_id: DefId) -> bool {
+    tcx.module_children(krate.as_def_id()).iter().any(
+        |child| matches!(child.res, Res::Def(DefKind::Macro(_), def_id) if def_id == macro_def_id),
+    )
+}
+
+/// Returns whether the `cfg` predicate only holds when compiling tests.
+fn requires_test(predicate: &MetaItemInner) -> bool {
+    let Some(list) = predicate.meta_item_list() else {
+        return predicate.is_word() && predicate.name_or_empty() == sym::test;
+    };
+    match predicate.name_or_empty() {
+        sym::all => list.iter().any(requires_test),
+        sym::any => !list.is_empty() && list.iter().all(requires_test),
+        _ => false,
+    }
+}
+
+struct ExternUsageCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The `#[test]` and `#[bench]` functions, by their parent and name: they are not marked
+    /// themselves, unlike the test case consts generated next to them.
+    test_fns: UnordSet<(LocalDefId, Symbol)>,
+    is_test_code: FxHashMap<LocalDefId, bool>,
+    usages: FxHashMap<CrateNum, ExternUsage>,
+}
+
+impl ExternUsageCollector<'_> {
+    fn record(&mut self, krate: CrateNum, is_test: bool, by_macro: bool) {
+        let usage = if is_test {
+            ExternUsage::Test
+        } else if by_macro {
+            ExternUsage::Macros
+        } else {
+            ExternUsage::Used
+        };
+        let entry = self.usages.entry(krate).or_insert(usage);
+        *entry = (*entry).max(usage);
+    }
+
+    /// Returns whether `def_id` is only compiled in tests, because it or one of its parents is.
+    fn is_test_code(&mut self, def_id: LocalDefId) -> bool {
+        if let Some(&is_test) = self.is_test_code.get(&def_id) {
+            return is_test;
+        }
+
+        let tcx = self.tcx;
+        let is_test = tcx
+            .get_attrs(def_id, sym::cfg)
+            .any(|attr| attr.meta_item_list().is_some_and(|list| list.iter().any(requires_test)))
+            || (tcx.def_kind(def_id) == DefKind::Fn
+                && self
+                    .test_fns
+                    .contains(&(tcx.local_parent(def_id), tcx.item_name(def_id.into()))))
+            || tcx.opt_local_parent(def_id).is_some_and(|parent| self.is_test_code(parent));
+        self.is_test_code.insert(def_id, is_test);
+        is_test
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for ExternUsageCollector<'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) {
+        if let hir::ItemKind::ExternCrate(_) = item.kind
+            && let Some(cnum) = self.tcx.extern_mod_stmt_cnum(item.owner_id.def_id)
+        {
+            let is_test = self.is_test_code(item.owner_id.def_id);
+            let by_macro = self
+                .tcx
+                .hir()
+                .attrs(item.hir_id())
+                .iter()
+                .any(|attr| attr.has_name(sym::macro_use))
+                || item.span.from_expansion();
+            self.record(cnum, is_test, by_macro);
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    fn visit_path(&mut self, path: &hir::Path<'tcx>, hir_id: hir::HirId) {
+        // Only the first segment names the crate the path goes through: the following ones may
+        // name crates re-exported by the first one, which don't need to be dependencies.
+        if let Some(segment) =
+            path.segments.iter().find(|segment| segment.ident.name != kw::PathRoot)
+            && let Res::Def(DefKind::Mod, def_id) = segment.res
+            && let Some(cnum) = def_id.as_crate_root()
+            && cnum != LOCAL_CRATE
+        {
+            let is_test = self.is_test_code(hir_id.owner.def_id);
+            // The imports of macros are uses of the crate by macros, like their invocations.
+            let by_macro = matches!(path.res, Res::Def(DefKind::Macro(_), _))
+                || path
+                    .span
+                    .ctxt()
+                    .outer_expn_data()
+                    .macro_def_id
+                    .is_some_and(|def_id| !def_id.is_local());
+            self.record(cnum, is_test, by_macro);
+        }
+        intravisit::walk_path(self, path);
+    }
+}
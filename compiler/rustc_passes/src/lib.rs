@@ -24,6 +24,7 @@
 mod diagnostic_items;
 pub mod entry;
 mod errors;
+pub mod extern_usage;
 #[cfg(debug_assertions)]
 pub mod hir_id_validator;
 pub mod input_stats;
@@ -35,6 +36,7 @@
 mod naked_functions;
 mod reachable;
 pub mod stability;
+pub mod unused_pub;
 mod upvars;
 mod weak_lang_items;
 
@@ -53,5 +55,6 @@ pub fn provide(providers: &mut Providers) {
     liveness::provide(providers);
     reachable::provide(providers);
     stability::provide(providers);
+    unused_pub::provide(providers);
     upvars::provide(providers);
 }
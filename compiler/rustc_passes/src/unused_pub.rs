@@ -0,0 +1,257 @@
+//! Finding the public items that no other crate of the build graph uses, for
+//! `-Zunused-pub-report`.
+//!
+//! The `dead_code` lint only knows about the uses within a crate, so it can't report public items.
+//! Instead, the crates compiled with `-Zrecord-used-extern-items` record the items of other crates
+//! they use in their metadata. The crate compiled with `-Zunused-pub-report` then lists the public
+//! items of these crates with the crates using them, among these crates and itself. The uses of an
+//! item within its own crate are not recorded: an item only used there could be private.
+//!
+//! The report is written as JSON and keyed by def path, made unique when several items share it,
+//! so that build tools can merge the reports of several final crates, e.g. all the binaries of a
+//! workspace.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexSet};
+use rustc_hir as hir;
+use rustc_hir::def::{CtorOf, DefKind, Res};
+use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::query::{LocalCrate, Providers};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::Symbol;
+use serde::Serialize;
+
+use crate::errors::UnusedPubReportWriteFailed;
+
+#[derive(Serialize)]
+struct UnusedPubReport {
+    /// The crate compiled with `-Zunused-pub-report`.
+    crate_name: String,
+    /// The public items of the crates compiled with `-Zrecord-used-extern-items`, by def path,
+    /// followed by `#` and the hash of the def path for the items sharing it.
+    items: BTreeMap<String, ItemReport>,
+}
+
+#[derive(Serialize)]
+struct ItemReport {
+    #[serde(rename = "crate")]
+    krate: String,
+    kind: &'static str,
+    span: String,
+    /// The other crates using the item, sorted by name. The item is unused when this is empty in
+    /// the reports of all the final crates of the build graph.
+    used_by: Vec<String>,
+}
+
+/// Collects the items of other crates used by the local crate: the items named by paths, the
+/// associated items resolved during typeck, and the types of all expressions, so that the types
+/// only used through the values of other items count as used.
+fn used_extern_items(tcx: TyCtxt<'_>, _: LocalCrate) -> Option<&[DefId]> {
+    let mut collector = UsedExternItemsCollector { tcx, items: FxIndexSet::default() };
+    tcx.hir().visit_all_item_likes_in_crate(&mut collector);
+
+    for def_id in tcx.hir().body_owners() {
+        // Closures and inline consts share the typeck results of their parent.
+        if tcx.typeck_root_def_id(def_id.to_def_id()) != def_id.to_def_id() {
+            continue;
+        }
+        let typeck_results = tcx.typeck(def_id);
+        for (_, res) in typeck_results.type_dependent_defs().items_in_stable_order() {
+            if let Ok((_, def_id)) = res {
+                collector.record(*def_id);
+            }
+        }
+        for (_, ty) in typeck_results.node_types().items_in_stable_order() {
+            for ty in ty.walk().filter_map(|arg| arg.as_type()) {
+                match *ty.kind() {
+                    ty::Adt(adt_def, _) => collector.record(adt_def.did()),
+                    ty::Foreign(def_id) => collector.record(def_id),
+                    ty::Dynamic(predicates, ..) => {
+                        if let Some(def_id) = predicates.principal_def_id() {
+                            collector.record(def_id);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let mut items: Vec<_> = collector.items.into_iter().collect();
+    items.sort_by_cached_key(|&def_id| tcx.def_path_hash(def_id));
+    Some(tcx.arena.alloc_from_iter(items))
+}
+
+struct UsedExternItemsCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    items: FxIndexSet<DefId>,
+}
+
+impl UsedExternItemsCollector<'_> {
+    fn record(&mut self, def_id: DefId) {
+        if def_id.is_local() {
+            return;
+        }
+
+        // Constructors and variants are used through their type.
+        let tcx = self.tcx;
+        let def_id = match tcx.def_kind(def_id) {
+            DefKind::Ctor(CtorOf::Struct, _) | DefKind::Variant => tcx.parent(def_id),
+            DefKind::Ctor(CtorOf::Variant, _) => tcx.parent(tcx.parent(def_id)),
+            _ => def_id,
+        };
+        if !self.items.insert(def_id) {
+            return;
+        }
+
+        // Associated items are used through their trait, or the type of their inherent impl.
+        if let DefKind::AssocFn | DefKind::AssocConst | DefKind::AssocTy = tcx.def_kind(def_id) {
+            let parent = tcx.parent(def_id);
+            match tcx.def_kind(parent) {
+                DefKind::Trait => self.record(parent),
+                DefKind::Impl { of_trait: false } => {
+                    if let Some(adt_def) = tcx.type_of(parent).instantiate_identity().ty_adt_def() {
+                        self.record(adt_def.did());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for UsedExternItemsCollector<'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_path(&mut self, path: &hir::Path<'tcx>, _: hir::HirId) {
+        if let Res::Def(_, def_id) = path.res {
+            self.record(def_id);
+        }
+        intravisit::walk_path(self, path);
+    }
+}
+
+/// Writes the public items of the crates compiled with `-Zrecord-used-extern-items`, and the
+/// crates of the build graph using them, to `path`.
+pub fn write_unused_pub_report(tcx: TyCtxt<'_>, path: &Path) {
+    if let Err(error) = write_report(tcx, path) {
+        tcx.dcx().emit_err(UnusedPubReportWriteFailed { path, error });
+    }
+}
+
+fn write_report(tcx: TyCtxt<'_>, path: &Path) -> io::Result<()> {
+    let recorded_crates: Vec<CrateNum> = tcx
+        .crates(())
+        .iter()
+        .copied()
+        .filter(|&cnum| tcx.used_extern_items(cnum).is_some())
+        .collect();
+
+    let mut used_by: FxHashMap<DefId, Vec<Symbol>> = FxHashMap::default();
+    for cnum in recorded_crates.iter().copied().chain([LOCAL_CRATE]) {
+        let crate_name = tcx.crate_name(cnum);
+        for &def_id in tcx.used_extern_items(cnum).unwrap_or_default() {
+            used_by.entry(def_id).or_default().push(crate_name);
+        }
+    }
+
+    let mut items = Vec::new();
+    for cnum in recorded_crates {
+        let krate = tcx.crate_name(cnum).to_string();
+        for def_id in public_items(tcx, cnum) {
+            let mut used_by: Vec<String> = used_by
+                .get(&def_id)
+                .into_iter()
+                .flatten()
+                .map(|crate_name| crate_name.to_string())
+                .collect();
+            used_by.sort();
+            let item = ItemReport {
+                krate: krate.clone(),
+                kind: tcx.def_descr(def_id),
+                span: tcx.sess.source_map().span_to_embeddable_string(tcx.def_span(def_id)),
+                used_by,
+            };
+            items.push((def_id, ty::print::with_no_trimmed_paths!(tcx.def_path_str(def_id)), item));
+        }
+    }
+
+    // Items of different namespaces, like a function and a braced struct, can have the same def
+    // path. These are disambiguated with their `DefPathHash`, which is the same in the reports of
+    // all the final crates.
+    let mut path_counts: FxHashMap<String, usize> = FxHashMap::default();
+    for (_, path, _) in &items {
+        *path_counts.entry(path.clone()).or_default() += 1;
+    }
+    let items = items
+        .into_iter()
+        .map(|(def_id, path, item)| {
+            let key = if path_counts[&path] > 1 {
+                format!("{path}#{:x}", tcx.def_path_hash(def_id).local_hash())
+            } else {
+                path
+            };
+            (key, item)
+        })
+        .collect();
+
+    let report = UnusedPubReport { crate_name: tcx.crate_name(LOCAL_CRATE).to_string(), items };
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut file, &report)?;
+    writeln!(file)?;
+    file.flush()
+}
+
+/// Returns the public items defined in the crate `krate`, as reachable from its root module, and
+/// the public associated items of their inherent impls. Modules, macros and the items of trait
+/// impls are not included.
+fn public_items(tcx: TyCtxt<'_>, krate: CrateNum) -> Vec<DefId> {
+    let mut items = Vec::new();
+    let mut visited = FxHashSet::default();
+    let mut modules = vec![krate.as_def_id()];
+    while let Some(module) = modules.pop() {
+        for child in tcx.module_children(module) {
+            let Res::Def(kind, def_id) = child.res else { continue };
+            // Re-exports of the items of other crates are reported in their own crate.
+            if !child.vis.is_public() || def_id.krate != krate || !visited.insert(def_id) {
+                continue;
+            }
+            match kind {
+                DefKind::Mod => modules.push(def_id),
+                DefKind::Struct | DefKind::Enum | DefKind::Union => {
+                    items.push(def_id);
+                    for &impl_def_id in tcx.inherent_impls(def_id) {
+                        for &item in tcx.associated_item_def_ids(impl_def_id) {
+                            if tcx.visibility(item).is_public() && visited.insert(item) {
+                                items.push(item);
+                            }
+                        }
+                    }
+                }
+                DefKind::Trait
+                | DefKind::TraitAlias
+                | DefKind::TyAlias
+                | DefKind::ForeignTy
+                | DefKind::Fn
+                | DefKind::Const
+                | DefKind::Static { .. } => items.push(def_id),
+                _ => {}
+            }
+        }
+    }
+    items
+}
+
+pub(crate) fn provide(providers: &mut Providers) {
+    providers.used_extern_items = used_extern_items;
+}
@@ -15,10 +15,11 @@
 use rustc_hir::PrimTy;
 use rustc_hir::def::Namespace::{self, *};
 use rustc_hir::def::{self, CtorKind, CtorOf, DefKind, NonMacroAttrKind, PerNS};
-use rustc_hir::def_id::{CRATE_DEF_ID, DefId};
+use rustc_hir::def_id::{CRATE_DEF_ID, CrateNum, DefId};
 use rustc_middle::bug;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::Session;
+use rustc_session::config::CrateType;
 use rustc_session::lint::builtin::{
     ABSOLUTE_PATHS_NOT_STARTING_WITH_CRATE, AMBIGUOUS_GLOB_IMPORTS,
     MACRO_EXPANDED_MACRO_EXPORTS_ACCESSED_BY_ABSOLUTE_PATHS,
@@ -123,6 +124,21 @@ fn reduce_impl_span_to_impl_keyword(sm: &SourceMap, impl_span: Span) -> Span {
     sm.span_until_whitespace(impl_span)
 }
 
+/// If we're recommending `TryFrom`, `TryInto` or `FromIterator`, returns a note about editions.
+fn edition_2021_prelude_note(tcx: TyCtxt<'_>, did: DefId, path: &Path) -> Option<String> {
+    let requires_note = !did.is_local()
+        && tcx.get_attrs(did, sym::rustc_diagnostic_item).any(|attr| {
+            [sym::TryInto, sym::TryFrom, sym::FromIterator].map(Some).contains(&attr.value_str())
+        });
+
+    requires_note.then(|| {
+        format!(
+            "'{}' is included in the prelude starting in Edition 2021",
+            path_names_to_string(path)
+        )
+    })
+}
+
 impl<'ra, 'tcx> Resolver<'ra, 'tcx> {
     pub(crate) fn dcx(&self) -> DiagCtxtHandle<'tcx> {
         self.tcx.dcx()
@@ -1261,28 +1277,8 @@ fn lookup_import_candidates_from_module<FilterFn>(
                     }
 
                     if candidates.iter().all(|v: &ImportSuggestion| v.did != did) {
-                        // See if we're recommending TryFrom, TryInto, or FromIterator and add
-                        // a note about editions
-                        let note = if let Some(did) = did {
-                            let requires_note = !did.is_local()
-                                && this.tcx.get_attrs(did, sym::rustc_diagnostic_item).any(
-                                    |attr| {
-                                        [sym::TryInto, sym::TryFrom, sym::FromIterator]
-                                            .map(|x| Some(x))
-                                            .contains(&attr.value_str())
-                                    },
-                                );
-
-                            requires_note.then(|| {
-                                format!(
-                                    "'{}' is included in the prelude starting in Edition 2021",
-                                    path_names_to_string(&path)
-                                )
-                            })
-                        } else {
-                            None
-                        };
-
+                        let note =
+                            did.and_then(|did| edition_2021_prelude_note(this.tcx, did, &path));
                         candidates.push(ImportSuggestion {
                             did,
                             descr: res.descr(),
@@ -1361,13 +1357,16 @@ pub(crate) fn lookup_import_candidates<FilterFn>(
         );
 
         if lookup_ident.span.at_least_rust_2018() {
+            let mut indexed_crates = FxHashSet::default();
             for ident in self.extern_prelude.clone().into_keys() {
                 if ident.span.from_expansion() {
                     // Idents are adjusted to the root context before being
                     // resolved in the extern prelude, so reporting this to the
                     // user is no help. This skips the injected
                     // `extern crate std` in the 2018 edition, which would
-                    // otherwise cause duplicate suggestions.
+                    // otherwise cause duplicate suggestions: `std` is
+                    // looked up, and indexed, through its entry without
+                    // expansion.
                     continue;
                 }
                 let crate_id = self.crate_loader(|c| c.maybe_process_path_extern(ident.name));
@@ -1403,21 +1402,112 @@ pub(crate) fn lookup_import_candidates<FilterFn>(
                     }
                     crate_path.push(ast::PathSegment::from_ident(ident));
 
-                    suggestions.extend(self.lookup_import_candidates_from_module(
+                    if namespace == TypeNS {
+                        if !indexed_crates.insert(crate_id) {
+                            continue;
+                        }
+                        suggestions.extend(self.lookup_import_candidates_from_index(
+                            lookup_ident,
+                            crate_id,
+                            crate_path,
+                            &filter_fn,
+                        ));
+                    } else {
+                        suggestions.extend(self.lookup_import_candidates_from_module(
+                            lookup_ident,
+                            namespace,
+                            parent_scope,
+                            crate_root,
+                            crate_path,
+                            &filter_fn,
+                        ));
+                    }
+                }
+            }
+            // `alloc` and `proc_macro` are only in the extern prelude when passed with `--extern`,
+            // or after an `extern crate` item at the crate root. Their items are still suggested,
+            // with a note, in the crates where they're meant to be used: `alloc` in `#![no_std]`
+            // crates, where there is no `std` re-exporting them, and `proc_macro` in proc-macro
+            // crates.
+            if namespace == TypeNS {
+                let tcx = self.tcx;
+                let is_no_std =
+                    !self.extern_prelude.contains_key(&Ident::with_dummy_span(sym::std));
+                let is_proc_macro = tcx.crate_types().contains(&CrateType::ProcMacro);
+                for (name, needed) in [(sym::alloc, is_no_std), (sym::proc_macro, is_proc_macro)] {
+                    if !needed {
+                        continue;
+                    }
+                    let Some(crate_id) = self.crate_loader(|c| c.maybe_process_path_extern(name))
+                    else {
+                        continue;
+                    };
+                    if !indexed_crates.insert(crate_id) {
+                        continue;
+                    }
+                    let crate_path =
+                        thin_vec![ast::PathSegment::from_ident(Ident::with_dummy_span(name))];
+                    let mut candidates = self.lookup_import_candidates_from_index(
                         lookup_ident,
-                        namespace,
-                        parent_scope,
-                        crate_root,
+                        crate_id,
                         crate_path,
                         &filter_fn,
-                    ));
+                    );
+                    for candidate in &mut candidates {
+                        candidate.note = Some(format!(
+                            "add `extern crate {name};` to use `{}`",
+                            path_names_to_string(&candidate.path)
+                        ));
+                    }
+                    suggestions.extend(candidates);
                 }
             }
+            self.extern_path_index.save(self.tcx);
         }
 
         suggestions
     }
 
+    /// Looks up the candidates of the type namespace named `lookup_ident` in the extern crate
+    /// `krate`, using the index of its public paths. Unlike when walking the modules of the
+    /// crate, only the shortest path to each item is suggested, whether it goes through
+    /// re-exports or not.
+    fn lookup_import_candidates_from_index(
+        &mut self,
+        lookup_ident: Ident,
+        krate: CrateNum,
+        crate_path: ThinVec<ast::PathSegment>,
+        filter_fn: impl Fn(Res) -> bool,
+    ) -> Vec<ImportSuggestion> {
+        let tcx = self.tcx;
+        let mut candidates = Vec::new();
+        for item in self.extern_path_index.crate_paths(tcx, krate).get(lookup_ident.name) {
+            let Some(did) = tcx.def_path_hash_to_def_id(item.def_path_hash) else { continue };
+            let res = Res::Def(tcx.def_kind(did), did);
+            if !filter_fn(res) {
+                continue;
+            }
+
+            let mut segments = crate_path.clone();
+            segments.extend(
+                item.path
+                    .iter()
+                    .map(|&name| ast::PathSegment::from_ident(Ident::with_dummy_span(name))),
+            );
+            let path = Path { span: rustc_span::DUMMY_SP, segments, tokens: None };
+            candidates.push(ImportSuggestion {
+                did: Some(did),
+                descr: res.descr(),
+                note: edition_2021_prelude_note(tcx, did, &path),
+                path,
+                accessible: true,
+                doc_visible: item.doc_visible,
+                via_import: item.via_import,
+            });
+        }
+        candidates
+    }
+
     pub(crate) fn unresolved_macro_suggestions(
         &mut self,
         err: &mut Diag<'_>,
@@ -0,0 +1,286 @@
+//! An index of the public paths of the items of the type namespace of the extern crates, used to
+//! suggest imports for the names that fail to resolve.
+//!
+//! Walking the module tree of every crate of the extern prelude for each unresolved name is
+//! costly, so the paths of a crate are gathered once, from its metadata, the first time an
+//! import suggestion needs them. As the extern crates rarely change between two compilations, the
+//! index is also cached in the incremental directory, where it is kept for the crates whose hash
+//! is unchanged.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
+use rustc_data_structures::svh::Svh;
+use rustc_hir::def::{DefKind, Namespace, Res};
+use rustc_hir::def_id::{CrateNum, DefPathHash};
+use rustc_middle::ty::TyCtxt;
+use rustc_serialize::opaque::{FileEncoder, MemDecoder};
+use rustc_serialize::{Decoder, Encodable, Encoder};
+use rustc_span::symbol::{Symbol, kw};
+use tracing::debug;
+
+const CACHE_FILE_NAME: &str = "extern-path-index.bin";
+
+/// Change this if the format of the cache changes.
+const CACHE_FORMAT_VERSION: u64 = 2;
+
+/// The smallest encoded size of a crate in the cache: its stable crate id, its hash and its
+/// number of items.
+const MIN_CRATE_SIZE: usize = 8 + 16 + 8;
+
+/// The smallest encoded size of an item in the cache: its def path hash, the length of its path,
+/// a name of the path and the two flags.
+const MIN_ITEM_SIZE: usize = 16 + 8 + 8 + 2;
+
+/// The public paths of the extern crates, by crate.
+#[derive(Default)]
+pub(crate) struct ExternPathIndex {
+    crates: FxIndexMap<CrateNum, CratePaths>,
+    /// The indexes of the previous compilations, by stable crate id, until they are used.
+    cache: Option<FxHashMap<u64, CratePaths>>,
+    /// Whether indexes were built that are not in the cache yet.
+    dirty: bool,
+}
+
+/// The items of the type namespace reachable from the root of a crate through public paths, by
+/// name.
+pub(crate) struct CratePaths {
+    /// The hash of the crate the paths were gathered from.
+    svh: Svh,
+    items: FxIndexMap<Symbol, Vec<IndexedItem>>,
+}
+
+pub(crate) struct IndexedItem {
+    pub def_path_hash: DefPathHash,
+    /// The shortest path to the item from the root of the crate. The paths that don't traverse
+    /// a `#[doc(hidden)]` item are preferred.
+    pub path: Vec<Symbol>,
+    /// Whether the path doesn't traverse a `#[doc(hidden)]` item.
+    pub doc_visible: bool,
+    /// Whether the item is named through a re-export.
+    pub via_import: bool,
+}
+
+impl CratePaths {
+    pub(crate) fn get(&self, name: Symbol) -> &[IndexedItem] {
+        self.items.get(&name).map_or(&[], |items| &items[..])
+    }
+
+    fn build(tcx: TyCtxt<'_>, krate: CrateNum) -> CratePaths {
+        let _timer = tcx.sess.timer("build_extern_path_index");
+
+        // The modules are visited breadth-first, so that the first path found to an item is one
+        // of the shortest, whether the item is re-exported closer to the root or not.
+        let mut items = FxIndexMap::<_, IndexedItem>::default();
+        let root = krate.as_def_id();
+        let mut seen_modules = FxHashSet::from_iter([root]);
+        let mut worklist = VecDeque::from([(root, DefKind::Mod, Vec::new(), true)]);
+        while let Some((module, module_kind, path, doc_visible)) = worklist.pop_front() {
+            for child in tcx.module_children(module) {
+                let Res::Def(kind, def_id) = child.res else { continue };
+                if !child.vis.is_public()
+                    || child.ident.name == kw::Underscore
+                    || child.ident.span.normalize_to_macros_2_0().from_expansion()
+                {
+                    continue;
+                }
+                // The paths of variants are assumed to be the path of their enum and their name,
+                // so the variants re-exported in modules are skipped.
+                if kind == DefKind::Variant && module_kind != DefKind::Enum {
+                    continue;
+                }
+
+                let doc_visible = doc_visible && !tcx.is_doc_hidden(def_id);
+                let mut path = path.clone();
+                path.push(child.ident.name);
+
+                if kind.ns() == Some(Namespace::TypeNS)
+                    && items.get(&def_id).is_none_or(|item| doc_visible && !item.doc_visible)
+                {
+                    items.insert(def_id, IndexedItem {
+                        def_path_hash: tcx.def_path_hash(def_id),
+                        path: path.clone(),
+                        doc_visible,
+                        via_import: !child.reexport_chain.is_empty(),
+                    });
+                }
+                if matches!(kind, DefKind::Mod | DefKind::Enum) && seen_modules.insert(def_id) {
+                    worklist.push_back((def_id, kind, path, doc_visible));
+                }
+            }
+        }
+
+        let mut by_name = FxIndexMap::<_, Vec<_>>::default();
+        for (_, item) in items {
+            by_name.entry(*item.path.last().unwrap()).or_default().push(item);
+        }
+        CratePaths { svh: tcx.crate_hash(krate), items: by_name }
+    }
+
+    fn encode(&self, e: &mut FileEncoder) {
+        self.svh.encode(e);
+        emit_len(e, self.items.values().map(Vec::len).sum());
+        for item in self.items.values().flatten() {
+            item.def_path_hash.0.encode(e);
+            emit_len(e, item.path.len());
+            for name in &item.path {
+                emit_len(e, name.as_str().len());
+                e.emit_raw_bytes(name.as_str().as_bytes());
+            }
+            e.emit_bool(item.doc_visible);
+            e.emit_bool(item.via_import);
+        }
+    }
+
+    /// Decodes the paths of a crate, or returns `None` if the cache is corrupted.
+    fn decode(d: &mut MemDecoder<'_>) -> Option<CratePaths> {
+        let svh = Svh::new(read_fingerprint(d)?);
+        let mut items = FxIndexMap::<_, Vec<_>>::default();
+        for _ in 0..read_len(d, MIN_ITEM_SIZE)? {
+            let def_path_hash = DefPathHash(read_fingerprint(d)?);
+            let path = (0..read_len(d, 8)?)
+                .map(|_| {
+                    let len = read_len(d, 1)?;
+                    Some(Symbol::intern(std::str::from_utf8(read_bytes(d, len)?).ok()?))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            let doc_visible = read_bool(d)?;
+            let via_import = read_bool(d)?;
+            items.entry(*path.last()?).or_default().push(IndexedItem {
+                def_path_hash,
+                path,
+                doc_visible,
+                via_import,
+            });
+        }
+        Some(CratePaths { svh, items })
+    }
+}
+
+impl ExternPathIndex {
+    /// Returns the paths of the crate `krate`, from the cache if its hash is unchanged.
+    pub(crate) fn crate_paths(&mut self, tcx: TyCtxt<'_>, krate: CrateNum) -> &CratePaths {
+        if !self.crates.contains_key(&krate) {
+            let cache = self.cache.get_or_insert_with(|| load_cache(tcx).unwrap_or_default());
+            let paths = match cache.remove(&tcx.stable_crate_id(krate).as_u64()) {
+                Some(paths) if paths.svh == tcx.crate_hash(krate) => paths,
+                _ => {
+                    self.dirty = true;
+                    CratePaths::build(tcx, krate)
+                }
+            };
+            self.crates.insert(krate, paths);
+        }
+        &self.crates[&krate]
+    }
+
+    /// Writes the paths of the crates used during this compilation to the incremental directory,
+    /// if they were not all read from there, along with the cached paths of the other crates.
+    pub(crate) fn save(&mut self, tcx: TyCtxt<'_>) {
+        if !self.dirty {
+            return;
+        }
+        self.dirty = false;
+        let Some(path) = cache_path(tcx) else { return };
+
+        // The cached paths of the crates that were not needed this time are kept for the next
+        // compilations.
+        let crates: Vec<(u64, &CratePaths)> = self
+            .crates
+            .iter()
+            .map(|(&krate, paths)| (tcx.stable_crate_id(krate).as_u64(), paths))
+            .chain(self.cache.iter().flatten().map(|(&id, paths)| (id, paths)))
+            .collect();
+
+        // The cache is shared by all the sessions of the crate, so it's written to a temporary
+        // file first, which is then moved in place.
+        let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+        let result = FileEncoder::new(&tmp_path).and_then(|mut e| {
+            e.emit_raw_bytes(&CACHE_FORMAT_VERSION.to_le_bytes());
+            emit_len(&mut e, tcx.sess.cfg_version.len());
+            e.emit_raw_bytes(tcx.sess.cfg_version.as_bytes());
+            emit_len(&mut e, crates.len());
+            for (stable_crate_id, paths) in crates {
+                e.emit_raw_bytes(&stable_crate_id.to_le_bytes());
+                paths.encode(&mut e);
+            }
+            // The length of the index is written last, so that truncated files are ignored.
+            let len = e.position() as u64;
+            e.emit_raw_bytes(&len.to_le_bytes());
+            e.finish().map_err(|(_, error)| error)?;
+            fs::rename(&tmp_path, &path)
+        });
+        if let Err(error) = result {
+            debug!("failed to write the extern path index to `{}`: {error}", path.display());
+            let _ = fs::remove_file(&tmp_path);
+        }
+    }
+}
+
+/// Returns the path of the cache, in the directory shared by the sessions of the crate.
+fn cache_path(tcx: TyCtxt<'_>) -> Option<PathBuf> {
+    let session_dir = tcx.sess.incr_comp_session_dir_opt()?;
+    Some(session_dir.parent()?.join(CACHE_FILE_NAME))
+}
+
+/// Reads the cache, unless it was written by another compiler or in another format, or is
+/// incomplete or corrupted.
+fn load_cache(tcx: TyCtxt<'_>) -> Option<FxHashMap<u64, CratePaths>> {
+    let data = fs::read(cache_path(tcx)?).ok()?;
+    let mut d = MemDecoder::new(&data, 0).ok()?;
+    let len_start = d.len().checked_sub(size_of::<u64>())?;
+    let len = u64::from_le_bytes(data[len_start..d.len()].try_into().unwrap());
+    let cache = if len == len_start as u64
+        && read_u64(&mut d) == Some(CACHE_FORMAT_VERSION)
+        && read_len(&mut d, 1).and_then(|len| read_bytes(&mut d, len))
+            == Some(tcx.sess.cfg_version.as_bytes())
+    {
+        read_len(&mut d, MIN_CRATE_SIZE).and_then(|len| {
+            (0..len).map(|_| Some((read_u64(&mut d)?, CratePaths::decode(&mut d)?))).collect()
+        })
+    } else {
+        None
+    };
+    if cache.is_none() {
+        debug!("ignoring the outdated, incomplete or corrupted extern path index cache");
+    }
+    cache
+}
+
+// The cache is decoded without trusting its contents, which may have been corrupted, so all the
+// integers are written with a fixed size, and all the reads are checked against the length of
+// the data left.
+
+fn emit_len(e: &mut FileEncoder, len: usize) {
+    e.emit_raw_bytes(&(len as u64).to_le_bytes());
+}
+
+fn read_bytes<'a>(d: &'a mut MemDecoder<'_>, len: usize) -> Option<&'a [u8]> {
+    (len <= d.remaining()).then(|| d.read_raw_bytes(len))
+}
+
+fn read_u64(d: &mut MemDecoder<'_>) -> Option<u64> {
+    Some(u64::from_le_bytes(read_bytes(d, 8)?.try_into().unwrap()))
+}
+
+fn read_fingerprint(d: &mut MemDecoder<'_>) -> Option<Fingerprint> {
+    Some(Fingerprint::from_le_bytes(read_bytes(d, 16)?.try_into().unwrap()))
+}
+
+fn read_bool(d: &mut MemDecoder<'_>) -> Option<bool> {
+    match read_bytes(d, 1)? {
+        [0] => Some(false),
+        [1] => Some(true),
+        _ => None,
+    }
+}
+
+/// Reads a number of elements, which is rejected if the data left is too short to hold them,
+/// given the smallest size of an element.
+fn read_len(d: &mut MemDecoder<'_>, min_element_size: usize) -> Option<usize> {
+    let len = usize::try_from(read_u64(d)?).ok()?;
+    (len.checked_mul(min_element_size)? <= d.remaining()).then_some(len)
+}
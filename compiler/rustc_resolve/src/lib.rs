@@ -33,6 +33,7 @@
 use errors::{
     ParamKindInEnumDiscriminant, ParamKindInNonTrivialAnonConst, ParamKindInTyOfConstParam,
 };
+use extern_path_index::ExternPathIndex;
 use imports::{Import, ImportData, ImportKind, NameResolution};
 use late::{HasGenericParams, PathSource, PatternSource, UnnecessaryQualification};
 use macros::{MacroRulesBinding, MacroRulesScope, MacroRulesScopeRef};
@@ -83,6 +84,7 @@
 mod diagnostics;
 mod effective_visibilities;
 mod errors;
+mod extern_path_index;
 mod ident;
 mod imports;
 mod late;
@@ -1096,6 +1098,8 @@ pub struct Resolver<'ra, 'tcx> {
     module_self_bindings: FxHashMap<Module<'ra>, NameBinding<'ra>>,
 
     used_extern_options: FxHashSet<Symbol>,
+    /// The public paths of the extern crates, for import suggestions.
+    extern_path_index: ExternPathIndex,
     macro_names: FxHashSet<Ident>,
     builtin_macros: FxHashMap<Symbol, BuiltinMacroState>,
     registered_tools: &'tcx RegisteredTools,
@@ -1494,6 +1498,7 @@ pub fn new(
             module_self_bindings,
 
             used_extern_options: Default::default(),
+            extern_path_index: Default::default(),
             macro_names: FxHashSet::default(),
             builtin_macros: Default::default(),
             registered_tools,
@@ -1,10 +1,12 @@
 use std::cmp;
+use std::time::Duration;
 
-use rustc_abi::{Align, Size};
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lock;
-use rustc_span::Symbol;
-use rustc_span::def_id::DefId;
+use rustc_span::def_id::{DefId, LocalDefId};
+use rustc_span::{Span, Symbol};
+
+use crate::config::PrintTypeSizesFormat;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -60,17 +62,217 @@ pub enum DataTypeKind {
     Coroutine,
 }
 
+impl DataTypeKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            DataTypeKind::Struct => "struct",
+            DataTypeKind::Union => "union",
+            DataTypeKind::Enum => "enum",
+            DataTypeKind::Closure => "closure",
+            DataTypeKind::Coroutine => "coroutine",
+        }
+    }
+}
+
+/// How the variant of an enum-like type is encoded.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum TagEncodingInfo {
+    /// The tag stores the discriminant of the variant.
+    Direct,
+    /// The tag is stored in a niche of the fields of `untagged_variant`, and only encodes the
+    /// `niche_variants`, starting at `niche_start`.
+    Niche { untagged_variant: Symbol, niche_variants: Vec<Symbol>, niche_start: u128 },
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TagInfo {
+    pub encoding: TagEncodingInfo,
+    pub offset: u64,
+    pub size: u64,
+}
+
+/// The largest niche of a type: invalid values of a scalar in the type, which enclosing enums can
+/// use to store their tag.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NicheInfo {
+    pub offset: u64,
+    pub size: u64,
+    /// The valid values of the scalar, from `valid_start` to `valid_end` inclusive, wrapping
+    /// around.
+    pub valid_start: u128,
+    pub valid_end: u128,
+    /// The number of invalid values.
+    pub available: u128,
+}
+
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub struct TypeSizeInfo {
     pub kind: DataTypeKind,
     pub type_description: String,
+    /// The generic arguments of the type, for instantiations of generic ADTs.
+    pub generic_args: Vec<String>,
     pub align: u64,
     pub overall_size: u64,
     pub packed: bool,
     pub opt_discr_size: Option<u64>,
+    pub tag: Option<TagInfo>,
+    pub niche: Option<NicheInfo>,
     pub variants: Vec<VariantInfo>,
 }
 
+/// The layout of a type, as emitted by `-Zprint-type-sizes-format=json`.
+#[derive(serde::Serialize)]
+struct JsonTypeLayout<'a> {
+    #[serde(rename = "type")]
+    type_description: &'a str,
+    kind: &'static str,
+    generic_args: &'a [String],
+    size: u64,
+    align: u64,
+    packed: bool,
+    tag: Option<JsonTag>,
+    niche: Option<JsonNiche>,
+    variants: Vec<JsonVariant>,
+    end_padding: u64,
+}
+
+#[derive(serde::Serialize)]
+struct JsonTag {
+    encoding: &'static str,
+    offset: u64,
+    size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    untagged_variant: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    niche_variants: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    niche_start: Option<u128>,
+}
+
+#[derive(serde::Serialize)]
+struct JsonNiche {
+    offset: u64,
+    size: u64,
+    valid_range: [u128; 2],
+    available: u128,
+}
+
+#[derive(serde::Serialize)]
+struct JsonVariant {
+    name: Option<String>,
+    size: u64,
+    align: u64,
+    /// Whether `size` is only the minimum size, for unsized types.
+    #[serde(rename = "unsized")]
+    is_unsized: bool,
+    fields: Vec<JsonField>,
+    padding: Vec<JsonPadding>,
+}
+
+#[derive(serde::Serialize)]
+struct JsonField {
+    name: String,
+    kind: String,
+    offset: u64,
+    size: u64,
+    align: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    type_name: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct JsonPadding {
+    offset: u64,
+    size: u64,
+}
+
+impl<'a> JsonTypeLayout<'a> {
+    fn new(info: &'a TypeSizeInfo) -> Self {
+        let discr_size = info.opt_discr_size.unwrap_or(0);
+        let mut max_variant_size = discr_size;
+        let variants = info
+            .variants
+            .iter()
+            .map(|variant| {
+                max_variant_size = cmp::max(max_variant_size, variant.size);
+
+                // Padding is computed like in the text output: fields by increasing offset,
+                // starting after the discriminant.
+                let mut fields = variant.fields.clone();
+                fields.sort_by_key(|f| (f.offset, f.size));
+                let mut padding = vec![];
+                let mut min_offset = discr_size;
+                for field in &fields {
+                    if field.offset > min_offset {
+                        padding.push(JsonPadding {
+                            offset: min_offset,
+                            size: field.offset - min_offset,
+                        });
+                    }
+                    min_offset = cmp::max(min_offset, field.offset + field.size);
+                }
+
+                JsonVariant {
+                    name: variant.name.map(|name| name.to_string()),
+                    size: variant.size,
+                    align: variant.align,
+                    is_unsized: variant.kind == SizeKind::Min,
+                    fields: fields
+                        .into_iter()
+                        .map(|field| JsonField {
+                            name: field.name.to_string(),
+                            kind: field.kind.to_string(),
+                            offset: field.offset,
+                            size: field.size,
+                            align: field.align,
+                            type_name: field.type_name.map(|name| name.to_string()),
+                        })
+                        .collect(),
+                    padding,
+                }
+            })
+            .collect();
+
+        let tag = info.tag.as_ref().map(|tag| {
+            let (encoding, untagged_variant, niche_variants, niche_start) = match &tag.encoding {
+                TagEncodingInfo::Direct => ("direct", None, None, None),
+                TagEncodingInfo::Niche { untagged_variant, niche_variants, niche_start } => (
+                    "niche",
+                    Some(untagged_variant.to_string()),
+                    Some(niche_variants.iter().map(|name| name.to_string()).collect()),
+                    Some(*niche_start),
+                ),
+            };
+            JsonTag {
+                encoding,
+                offset: tag.offset,
+                size: tag.size,
+                untagged_variant,
+                niche_variants,
+                niche_start,
+            }
+        });
+
+        JsonTypeLayout {
+            type_description: &info.type_description,
+            kind: info.kind.as_str(),
+            generic_args: &info.generic_args,
+            size: info.overall_size,
+            align: info.align,
+            packed: info.packed,
+            tag,
+            niche: info.niche.map(|niche| JsonNiche {
+                offset: niche.offset,
+                size: niche.size,
+                valid_range: [niche.valid_start, niche.valid_end],
+                available: niche.available,
+            }),
+            variants,
+            end_padding: info.overall_size.saturating_sub(max_variant_size),
+        }
+    }
+}
+
 pub struct VTableSizeInfo {
     pub trait_name: String,
 
@@ -90,40 +292,45 @@ pub struct VTableSizeInfo {
     pub upcasting_cost_percent: f64,
 }
 
+/// Something that took longer to compile than the budget set with `-Z compile-time-budget`,
+/// `-Z const-eval-step-budget` or `-Z trait-obligation-budget`.
+#[derive(Clone, Debug)]
+pub struct CompileTimeInfo {
+    /// The item the time is accounted to, at whose lint level it is reported.
+    pub def_id: LocalDefId,
+    pub span: Span,
+    pub elapsed: Duration,
+    pub kind: CompileTimeKind,
+}
+
+#[derive(Clone, Debug)]
+pub enum CompileTimeKind {
+    /// The type-checking of a body, with the time spent in each of the self-profile activities it
+    /// consists of, and the part of it spent solving trait obligations.
+    Typeck { activities: Vec<(&'static str, Duration)>, trait_solving: Duration },
+    /// The evaluation of a constant or static.
+    ConstEval { steps: usize },
+    /// The solving of a single trait obligation.
+    TraitObligation { predicate: String },
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
     vtable_sizes: Lock<FxHashMap<DefId, VTableSizeInfo>>,
+    compile_times: Lock<Vec<CompileTimeInfo>>,
 }
 
 impl CodeStats {
-    pub fn record_type_size<S: ToString>(
-        &self,
-        kind: DataTypeKind,
-        type_desc: S,
-        align: Align,
-        overall_size: Size,
-        packed: bool,
-        opt_discr_size: Option<Size>,
-        mut variants: Vec<VariantInfo>,
-    ) {
+    pub fn record_type_size(&self, mut info: TypeSizeInfo) {
         // Sort variants so the largest ones are shown first. A stable sort is
         // used here so that source code order is preserved for all variants
         // that have the same size.
         // Except for Coroutines, whose variants are already sorted according to
         // their yield points in `variant_info_for_coroutine`.
-        if kind != DataTypeKind::Coroutine {
-            variants.sort_by_key(|info| cmp::Reverse(info.size));
+        if info.kind != DataTypeKind::Coroutine {
+            info.variants.sort_by_key(|info| cmp::Reverse(info.size));
         }
-        let info = TypeSizeInfo {
-            kind,
-            type_description: type_desc.to_string(),
-            align: align.bytes(),
-            overall_size: overall_size.bytes(),
-            packed,
-            opt_discr_size: opt_discr_size.map(|s| s.bytes()),
-            variants,
-        };
         self.type_sizes.borrow_mut().insert(info);
     }
 
@@ -135,7 +342,16 @@ pub fn record_vtable_size(&self, trait_did: DefId, trait_name: &str, info: VTabl
         );
     }
 
-    pub fn print_type_sizes(&self) {
+    pub fn record_compile_time(&self, info: CompileTimeInfo) {
+        self.compile_times.lock().push(info);
+    }
+
+    /// Returns the compile times recorded since the last call, in the order they were recorded.
+    pub fn take_compile_times(&self) -> Vec<CompileTimeInfo> {
+        std::mem::take(&mut *self.compile_times.lock())
+    }
+
+    pub fn print_type_sizes(&self, format: PrintTypeSizesFormat) {
         let type_sizes = self.type_sizes.borrow();
         // We will soon sort, so the initial order does not matter.
         #[allow(rustc::potential_query_instability)]
@@ -145,6 +361,12 @@ pub fn print_type_sizes(&self) {
         // Secondary sort: description (dictionary order)
         sorted.sort_by_key(|info| (cmp::Reverse(info.overall_size), &info.type_description));
 
+        if format == PrintTypeSizesFormat::Json {
+            let layouts: Vec<_> = sorted.into_iter().map(JsonTypeLayout::new).collect();
+            println!("{}", serde_json::to_string_pretty(&layouts).unwrap());
+            return;
+        }
+
         for info in sorted {
             let TypeSizeInfo { type_description, overall_size, align, kind, variants, .. } = info;
             println!(
@@ -3176,6 +3176,15 @@ pub fn extension(self) -> &'static str {
     }
 }
 
+/// Which format to use for `-Z print-type-sizes`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum PrintTypeSizesFormat {
+    /// Print the layouts as indented text
+    Text,
+    /// Emit the layouts as structured JSON
+    Json,
+}
+
 /// `-Z patchable-function-entry` representation - how many nops to put before and after function
 /// entry.
 #[derive(Clone, Copy, PartialEq, Hash, Debug, Default)]
@@ -3216,7 +3225,8 @@ pub enum Polonius {
     /// Legacy version, using datalog and the `polonius-engine` crate. Historical value for `-Zpolonius`.
     Legacy,
 
-    /// In-tree prototype, extending the NLL infrastructure.
+    /// In-tree prototype, extending the NLL infrastructure with a location-sensitive computation of
+    /// live loans.
     Next,
 }
 
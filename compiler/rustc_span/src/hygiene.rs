@@ -640,6 +640,17 @@ pub fn update_dollar_crate_names(mut get_name: impl FnMut(SyntaxContext) -> Symb
     })
 }
 
+/// Returns the data of all the expansions of the local crate, except the root expansion.
+pub fn all_local_expn_data() -> Vec<(LocalExpnId, ExpnData)> {
+    HygieneData::with(|data| {
+        data.local_expn_data
+            .iter_enumerated()
+            .skip(1)
+            .filter_map(|(id, expn_data)| Some((id, expn_data.clone()?)))
+            .collect()
+    })
+}
+
 pub fn debug_hygiene_data(verbose: bool) -> String {
     HygieneData::with(|data| {
         if verbose {
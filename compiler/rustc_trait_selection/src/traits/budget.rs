@@ -0,0 +1,63 @@
+//! Accounting of the time spent solving trait obligations, for `-Z compile-time-budget` and
+//! `-Z trait-obligation-budget`.
+
+use std::time::{Duration, Instant};
+
+use rustc_infer::infer::InferCtxt;
+use rustc_infer::traits::PredicateObligation;
+use rustc_middle::ty;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_session::code_stats::{CompileTimeInfo, CompileTimeKind};
+use rustc_span::Span;
+use rustc_span::def_id::LocalDefId;
+
+/// Measures the solving of a single trait obligation, from its creation until it is dropped.
+///
+/// The obligations solved while solving another one are part of it, and not measured separately.
+pub(crate) struct ObligationTimer<'a, 'tcx> {
+    infcx: &'a InferCtxt<'tcx>,
+    obligation: Option<(Instant, LocalDefId, Span, ty::Predicate<'tcx>)>,
+}
+
+impl<'a, 'tcx> ObligationTimer<'a, 'tcx> {
+    pub(crate) fn start(
+        infcx: &'a InferCtxt<'tcx>,
+        obligation: &PredicateObligation<'tcx>,
+    ) -> ObligationTimer<'a, 'tcx> {
+        let opts = &infcx.tcx.sess.opts.unstable_opts;
+        let measure = (opts.compile_time_budget.is_some()
+            || opts.trait_obligation_budget.is_some())
+            && !infcx.solving_obligation.replace(true);
+        let obligation = measure.then(|| {
+            let cause = &obligation.cause;
+            (Instant::now(), cause.body_id, cause.span, obligation.predicate)
+        });
+        ObligationTimer { infcx, obligation }
+    }
+}
+
+impl Drop for ObligationTimer<'_, '_> {
+    /// Adds the time spent solving the obligation to the trait solving time of the inference
+    /// context, and records the obligation if it exceeds `-Z trait-obligation-budget`.
+    fn drop(&mut self) {
+        let Some((start, body_id, span, predicate)) = self.obligation else { return };
+        let elapsed = start.elapsed();
+        let infcx = self.infcx;
+        infcx.solving_obligation.set(false);
+        infcx.trait_solving_time.set(infcx.trait_solving_time.get() + elapsed);
+
+        let sess = infcx.tcx.sess;
+        let Some(budget) = sess.opts.unstable_opts.trait_obligation_budget else { return };
+        if elapsed > Duration::from_millis(budget) {
+            let predicate = infcx.resolve_vars_if_possible(predicate);
+            sess.code_stats.record_compile_time(CompileTimeInfo {
+                def_id: body_id,
+                span,
+                elapsed,
+                kind: CompileTimeKind::TraitObligation {
+                    predicate: with_no_trimmed_paths!(predicate.to_string()),
+                },
+            });
+        }
+    }
+}
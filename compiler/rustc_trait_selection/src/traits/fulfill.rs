@@ -16,6 +16,7 @@
 use thin_vec::ThinVec;
 use tracing::{debug, debug_span, instrument};
 
+use super::budget::ObligationTimer;
 use super::effects::{self, HostEffectObligation};
 use super::project::{self, ProjectAndUnifyResult};
 use super::select::SelectionContext;
@@ -316,6 +317,7 @@ fn process_obligation(
         &mut self,
         pending_obligation: &mut PendingPredicateObligation<'tcx>,
     ) -> ProcessResult<PendingPredicateObligation<'tcx>, FulfillmentErrorCode<'tcx>> {
+        let _timer = ObligationTimer::start(self.selcx.infcx, &pending_obligation.obligation);
         pending_obligation.stalled_on.truncate(0);
 
         let obligation = &mut pending_obligation.obligation;
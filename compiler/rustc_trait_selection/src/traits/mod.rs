@@ -3,6 +3,7 @@
 //! [rustc dev guide]: https://rustc-dev-guide.rust-lang.org/traits/resolution.html
 
 pub mod auto_trait;
+pub(crate) mod budget;
 pub(crate) mod coherence;
 pub mod const_evaluatable;
 mod dyn_compatibility;
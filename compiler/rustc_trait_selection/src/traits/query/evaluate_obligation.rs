@@ -3,6 +3,7 @@
 
 use crate::infer::InferCtxt;
 use crate::infer::canonical::OriginalQueryValues;
+use crate::traits::budget::ObligationTimer;
 use crate::traits::{
     EvaluationResult, ObligationCtxt, OverflowError, PredicateObligation, SelectionContext,
 };
@@ -63,6 +64,7 @@ fn evaluate_obligation(
         &self,
         obligation: &PredicateObligation<'tcx>,
     ) -> Result<EvaluationResult, OverflowError> {
+        let _timer = ObligationTimer::start(self, obligation);
         let mut _orig_values = OriginalQueryValues::default();
 
         let param_env = obligation.param_env;
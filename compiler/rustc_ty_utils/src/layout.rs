@@ -22,7 +22,10 @@
     self, AdtDef, CoroutineArgsExt, EarlyBinder, GenericArgsRef, PseudoCanonicalInput, Ty, TyCtxt,
     TypeVisitableExt,
 };
-use rustc_session::{DataTypeKind, FieldInfo, FieldKind, SizeKind, VariantInfo};
+use rustc_session::{
+    DataTypeKind, FieldInfo, FieldKind, NicheInfo, SizeKind, TagEncodingInfo, TagInfo,
+    TypeSizeInfo, VariantInfo,
+};
 use rustc_span::sym;
 use rustc_span::symbol::Symbol;
 use tracing::{debug, instrument, trace};
@@ -1029,17 +1032,35 @@ fn record_layout_for_printing<'tcx>(cx: &LayoutCx<'tcx>, layout: TyAndLayout<'tc
     }
 
     // (delay format until we actually need it)
-    let record = |kind, packed, opt_discr_size, variants| {
-        let type_desc = with_no_trimmed_paths!(format!("{}", layout.ty));
-        cx.tcx().sess.code_stats.record_type_size(
+    let record = |kind, packed, opt_discr_size: Option<Size>, tag, variants| {
+        let type_description = with_no_trimmed_paths!(format!("{}", layout.ty));
+        let generic_args = match *layout.ty.kind() {
+            ty::Adt(_, args) => args
+                .iter()
+                .filter(|arg| !matches!(arg.unpack(), ty::GenericArgKind::Lifetime(_)))
+                .map(|arg| with_no_trimmed_paths!(arg.to_string()))
+                .collect(),
+            _ => vec![],
+        };
+        let niche = layout.largest_niche.map(|niche| NicheInfo {
+            offset: niche.offset.bytes(),
+            size: niche.value.size(cx).bytes(),
+            valid_start: niche.valid_range.start,
+            valid_end: niche.valid_range.end,
+            available: niche.available(cx),
+        });
+        cx.tcx().sess.code_stats.record_type_size(TypeSizeInfo {
             kind,
-            type_desc,
-            layout.align.abi,
-            layout.size,
+            type_description,
+            generic_args,
+            align: layout.align.abi.bytes(),
+            overall_size: layout.size.bytes(),
             packed,
-            opt_discr_size,
+            opt_discr_size: opt_discr_size.map(|size| size.bytes()),
+            tag,
+            niche,
             variants,
-        );
+        });
     };
 
     match *layout.ty.kind() {
@@ -1048,7 +1069,8 @@ fn record_layout_for_printing<'tcx>(cx: &LayoutCx<'tcx>, layout: TyAndLayout<'tc
             let adt_kind = adt_def.adt_kind();
             let adt_packed = adt_def.repr().pack.is_some();
             let (variant_infos, opt_discr_size) = variant_info_for_adt(cx, layout, adt_def);
-            record(adt_kind.into(), adt_packed, opt_discr_size, variant_infos);
+            let tag = tag_info_for_printing(cx, layout, |idx| adt_def.variant(idx).name);
+            record(adt_kind.into(), adt_packed, opt_discr_size, tag, variant_infos);
         }
 
         ty::Coroutine(def_id, args) => {
@@ -1056,12 +1078,15 @@ fn record_layout_for_printing<'tcx>(cx: &LayoutCx<'tcx>, layout: TyAndLayout<'tc
             // Coroutines always have a begin/poisoned/end state with additional suspend points
             let (variant_infos, opt_discr_size) =
                 variant_info_for_coroutine(cx, layout, def_id, args);
-            record(DataTypeKind::Coroutine, false, opt_discr_size, variant_infos);
+            let tag = tag_info_for_printing(cx, layout, |idx| {
+                Symbol::intern(&ty::CoroutineArgs::variant_name(idx))
+            });
+            record(DataTypeKind::Coroutine, false, opt_discr_size, tag, variant_infos);
         }
 
         ty::Closure(..) => {
             debug!("print-type-size t: `{:?}` record closure", layout.ty);
-            record(DataTypeKind::Closure, false, None, vec![]);
+            record(DataTypeKind::Closure, false, None, None, vec![]);
         }
 
         _ => {
@@ -1070,6 +1095,34 @@ fn record_layout_for_printing<'tcx>(cx: &LayoutCx<'tcx>, layout: TyAndLayout<'tc
     };
 }
 
+/// Describes where the tag of a multi-variant layout is stored and how it encodes the variants.
+fn tag_info_for_printing<'tcx>(
+    cx: &LayoutCx<'tcx>,
+    layout: TyAndLayout<'tcx>,
+    variant_name: impl Fn(VariantIdx) -> Symbol,
+) -> Option<TagInfo> {
+    let Variants::Multiple { tag, ref tag_encoding, tag_field, .. } = layout.variants else {
+        return None;
+    };
+    let encoding = match *tag_encoding {
+        TagEncoding::Direct => TagEncodingInfo::Direct,
+        TagEncoding::Niche { untagged_variant, ref niche_variants, niche_start } => {
+            TagEncodingInfo::Niche {
+                untagged_variant: variant_name(untagged_variant),
+                niche_variants: (niche_variants.start().as_u32()..=niche_variants.end().as_u32())
+                    .map(|idx| variant_name(VariantIdx::from_u32(idx)))
+                    .collect(),
+                niche_start,
+            }
+        }
+    };
+    Some(TagInfo {
+        encoding,
+        offset: layout.fields.offset(tag_field).bytes(),
+        size: tag.size(cx).bytes(),
+    })
+}
+
 fn variant_info_for_adt<'tcx>(
     cx: &LayoutCx<'tcx>,
     layout: TyAndLayout<'tcx>,
@@ -44,6 +44,8 @@ impl Step for CrateBootstrap {
 
     fn should_run(run: ShouldRun<'_>) -> ShouldRun<'_> {
         run.path("src/tools/jsondoclint")
+            .path("src/tools/rustdoc-search")
+            .path("src/rustdoc-search-index")
             .path("src/tools/suggest-tests")
             .path("src/tools/replace-version-placeholder")
             .alias("tidyselftest")
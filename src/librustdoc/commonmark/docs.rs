@@ -0,0 +1,184 @@
+//! Rewriting the Markdown of doc comments for the CommonMark output.
+//!
+//! The documentation is kept as close as possible to what was written in the source. Only a few
+//! parts of it are edited in place:
+//!
+//! * Intra-doc links are replaced with inline links to the page of the item they resolved to.
+//!   Links that can't be resolved to a page (for instance, because the documentation of the
+//!   crate they point to was not generated) are replaced with their text.
+//! * Rust code blocks get an explicit `rust` language, and their hidden lines are removed.
+//! * Headings are demoted to fit below the headings of the page they're on.
+
+use std::ops::Range;
+
+use pulldown_cmark::{BrokenLink, CodeBlockKind, CowStr, Event, LinkType, Parser, Tag, TagEnd};
+
+use crate::clean::RenderedLink;
+use crate::html::markdown::{ErrorCodes, LangString, main_body_opts, map_line};
+
+#[cfg(test)]
+mod tests;
+
+/// Rewrites the documentation `md` so that it can be included in a page, under a heading of
+/// level `heading_offset`.
+///
+/// An empty `href` in `links` means that the link could not be resolved to a page.
+pub(super) fn render(md: &str, links: &[RenderedLink], heading_offset: u32) -> String {
+    if md.is_empty() {
+        return String::new();
+    }
+    let mut replacer = |broken_link: BrokenLink<'_>| {
+        links
+            .iter()
+            .find(|link| *link.original_text == *broken_link.reference)
+            .map(|link| (CowStr::from(link.original_text.to_string()), CowStr::Borrowed("")))
+    };
+    let parser = Parser::new_with_broken_link_callback(md, main_body_opts(), Some(&mut replacer));
+
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    // The link being visited, and the range of its text.
+    let mut current_link: Option<(Range<usize>, &RenderedLink)> = None;
+    let mut link_text: Option<Range<usize>> = None;
+    // The setext heading being visited, and the end of its text.
+    let mut current_setext_heading: Option<(Range<usize>, usize)> = None;
+    let mut code_block: Option<(Range<usize>, bool, String)> = None;
+
+    for (event, range) in parser.into_offset_iter() {
+        if current_link.is_some() && !matches!(event, Event::End(TagEnd::Link)) {
+            let text_range = link_text.get_or_insert(range.clone());
+            text_range.start = text_range.start.min(range.start);
+            text_range.end = text_range.end.max(range.end);
+        }
+        if let Some((_, text_end)) = &mut current_setext_heading
+            && !matches!(event, Event::End(TagEnd::Heading(_)))
+        {
+            *text_end = (*text_end).max(range.end);
+        }
+
+        match event {
+            Event::Start(Tag::Link { link_type, dest_url, .. }) => {
+                if let Some(link) = links.iter().find(|link| *link.original_text == *dest_url) {
+                    let mut range = range;
+                    // The range of collapsed links doesn't include their trailing `[]`.
+                    if matches!(link_type, LinkType::Collapsed | LinkType::CollapsedUnknown)
+                        && md[range.end..].starts_with("[]")
+                    {
+                        range.end += 2;
+                    }
+                    current_link = Some((range, link));
+                    link_text = None;
+                }
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some((range, link)) = current_link.take() {
+                    let text = link_text.take().map_or("", |r| &md[r]);
+                    edits.push((range, render_link(text, link)));
+                }
+            }
+            Event::Start(Tag::Heading { level, .. }) => {
+                let level = level as u32;
+                let new_level = (level + heading_offset).min(6);
+                let hashes = "#".repeat((new_level - level) as usize);
+                if md[range.clone()].starts_with('#') {
+                    edits.push((range.start..range.start, hashes));
+                } else {
+                    // Setext headings (underlined with `===` or `---`) can't be demoted, so they
+                    // are turned into ATX headings.
+                    edits.push((range.start..range.start, "#".repeat(new_level as usize) + " "));
+                    current_setext_heading = Some((range, 0));
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((range, text_end)) = current_setext_heading.take() {
+                    let newline = if md[range.clone()].ends_with('\n') { "\n" } else { "" };
+                    edits.push((text_end.max(range.start)..range.end, newline.to_owned()));
+                }
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let (is_rust, start) = match kind {
+                    CodeBlockKind::Fenced(lang) => (
+                        LangString::parse_without_check(&lang, ErrorCodes::No, false).rust,
+                        range.start,
+                    ),
+                    // The indentation of the first line is not part of the range of indented
+                    // code blocks, but it has to be replaced by the fence too.
+                    CodeBlockKind::Indented => {
+                        let indent = md[..range.start].len()
+                            - md[..range.start].trim_end_matches([' ', '\t']).len();
+                        (true, range.start - indent.min(4))
+                    }
+                };
+                code_block = Some((start..range.end, is_rust, String::new()));
+            }
+            Event::Text(text) => {
+                if let Some((_, _, code)) = &mut code_block {
+                    code.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((range, true, code)) = code_block.take() {
+                    let replacement = render_rust_code_block(md, range.clone(), &code);
+                    edits.push((range, replacement));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut out = String::with_capacity(md.len());
+    let mut last = 0;
+    for (range, replacement) in edits {
+        out.push_str(&md[last..range.start]);
+        out.push_str(&replacement);
+        last = range.end;
+    }
+    out.push_str(&md[last..]);
+    out
+}
+
+/// Renders an intra-doc link whose text is `text` in the source.
+fn render_link(text: &str, link: &RenderedLink) -> String {
+    // Remove the disambiguator from links like `[fn@f]` or ``[`fn@f`]``.
+    let text = if text == &*link.original_text {
+        if text.starts_with('`') {
+            format!("`{}`", link.new_text.trim_matches('`'))
+        } else {
+            link.new_text.to_string()
+        }
+    } else {
+        text.to_owned()
+    };
+    if link.href.is_empty() { text } else { format!("[{text}]({})", link.href) }
+}
+
+/// Renders a Rust code block found at `range` in `md`, without its hidden lines.
+fn render_rust_code_block(md: &str, range: Range<usize>, code: &str) -> String {
+    // Lines after the first one need to keep the indentation (and the `>` of block quotes) of the
+    // container the code block is in.
+    let line_start = md[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let prefix: String =
+        md[line_start..range.start].chars().map(|c| if c == '>' { '>' } else { ' ' }).collect();
+
+    let code = code.lines().filter_map(|line| map_line(line).for_html()).collect::<Vec<_>>();
+    // The fence needs to be longer than any run of backticks in the code.
+    let longest_run = code
+        .iter()
+        .flat_map(|line| line.split(|c| c != '`'))
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+
+    let mut out = format!("{fence}rust\n");
+    for line in &code {
+        out.push_str(&prefix);
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str(&prefix);
+    out.push_str(&fence);
+    if md[range].ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
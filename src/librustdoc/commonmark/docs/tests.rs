@@ -0,0 +1,71 @@
+use super::render;
+use crate::clean::RenderedLink;
+
+fn link(original_text: &str, new_text: &str, href: &str) -> RenderedLink {
+    RenderedLink {
+        original_text: original_text.into(),
+        new_text: new_text.into(),
+        href: href.to_owned(),
+        tooltip: String::new(),
+    }
+}
+
+#[test]
+fn test_intra_doc_links() {
+    fn t(input: &str, expect: &str) {
+        let links = [
+            link("Foo", "Foo", "struct.Foo.html"),
+            link("`Foo`", "Foo", "struct.Foo.html"),
+            link("fn@bar", "bar", "../fn.bar.md"),
+            link("`fn@bar`", "bar", "../fn.bar.md"),
+            link("Baz", "Baz", ""),
+        ];
+        assert_eq!(render(input, &links, 1), expect);
+    }
+
+    t("See [Foo].", "See [Foo](struct.Foo.html).");
+    t("See [`Foo`].", "See [`Foo`](struct.Foo.html).");
+    t("See [Foo][].", "See [Foo](struct.Foo.html).");
+    t("See [the struct](Foo).", "See [the struct](struct.Foo.html).");
+    t("See [the struct][Foo].\n\n[Foo]: Foo", "See [the struct](struct.Foo.html).\n\n[Foo]: Foo");
+    t("See [fn@bar] and [`fn@bar`].", "See [bar](../fn.bar.md) and [`bar`](../fn.bar.md).");
+    t("See [*the* function](fn@bar).", "See [*the* function](../fn.bar.md).");
+    // Links to pages that aren't documented are replaced with their text.
+    t("See [Baz].", "See Baz.");
+    // Links that aren't intra-doc links are kept as they are.
+    t(
+        "See [Quux] and [the book](https://doc.rust-lang.org/book/).",
+        "See [Quux] and [the book](https://doc.rust-lang.org/book/).",
+    );
+}
+
+#[test]
+fn test_headings() {
+    fn t(input: &str, heading_offset: u32, expect: &str) {
+        assert_eq!(render(input, &[], heading_offset), expect);
+    }
+
+    t("# Examples\n\nText", 1, "## Examples\n\nText");
+    t("## Panics [Foo]\n", 3, "##### Panics [Foo]\n");
+    t("##### Deep", 3, "###### Deep");
+    t("Examples\n========\n\nText", 2, "### Examples\n\nText");
+    t("Examples\n--------\n", 1, "### Examples\n");
+}
+
+#[test]
+fn test_code_blocks() {
+    fn t(input: &str, expect: &str) {
+        assert_eq!(render(input, &[], 1), expect);
+    }
+
+    t("```\n# fn main() {\nlet x = 1;\n# }\n```\n", "```rust\nlet x = 1;\n```\n");
+    t("```should_panic\npanic!();\n```", "```rust\npanic!();\n```");
+    t("```rust,ignore\n## not hidden\n```", "```rust\n# not hidden\n```");
+    t("```text\n# not rust\n```", "```text\n# not rust\n```");
+    t("    let x = 1;\n", "```rust\nlet x = 1;\n```\n");
+    t(
+        "- Item:\n  ```\n  # use std::io;\n  let x = 1;\n  ```\n",
+        "- Item:\n  ```rust\n  let x = 1;\n  ```\n",
+    );
+    t("> ```\n> let s = \"```\";\n> ```", "> ````rust\n> let s = \"```\";\n> ````");
+}
@@ -0,0 +1,452 @@
+//! Rustdoc's CommonMark backend
+//!
+//! This module renders a crate as a tree of Markdown files, laid out like the HTML output: one
+//! `index.md` file per module, in a directory named after it, and one `{type}.{name}.md` file
+//! per item. Intra-doc links are rewritten to relative links between these files, so that the
+//! output can be fed as is to static site generators and other tools working on Markdown.
+
+mod docs;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Symbol;
+use rustc_span::symbol::kw;
+use tracing::debug;
+
+use crate::clean::types::ExternalLocation;
+use crate::clean::{self, ItemLink, RenderedLink};
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::error::Error;
+use crate::formats::FormatRenderer;
+use crate::formats::cache::Cache;
+use crate::formats::item_type::ItemType;
+use crate::formats::print::{self, Printer};
+use crate::html::format::{href_relative_parts, join_with_double_colon};
+use crate::html::markdown::plain_text_summary;
+use crate::html::render::{ItemSection, compare_names, item_ty_to_section};
+use crate::try_err;
+
+pub(crate) struct MarkdownRenderer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    cache: Rc<Cache>,
+    /// The directory the pages of the current module are written to.
+    dst: PathBuf,
+    /// The path of the current module, which links are relative to.
+    current: Vec<Symbol>,
+    /// Whether the current module, or one of its ancestors, is stripped. No page is written for
+    /// anything inside such a module.
+    in_stripped_module: bool,
+}
+
+impl MarkdownRenderer<'_> {
+    /// Computes the link to the page of `did`, relative to the current module.
+    ///
+    /// Items of external crates link to their HTML documentation if it is hosted somewhere, and
+    /// to the Markdown output of the crate if it was documented in the same output directory.
+    fn href(&self, did: DefId) -> Option<String> {
+        if let Some((fqp, ty)) = self.cache.paths.get(&did) {
+            return Some(self.relative_href(fqp, *ty));
+        }
+        let (fqp, ty) = self.cache.external_paths.get(&did)?;
+        match self.cache.extern_locations.get(&did.krate)? {
+            ExternalLocation::Remote(root) => {
+                let mut href = root.trim_end_matches('/').to_owned();
+                for part in module_fqp(fqp, *ty) {
+                    href.push('/');
+                    href.push_str(part.as_str());
+                }
+                href.push('/');
+                href.push_str(&page_name(fqp, *ty, "html"));
+                Some(href)
+            }
+            ExternalLocation::Local => Some(self.relative_href(fqp, *ty)),
+            ExternalLocation::Unknown => None,
+        }
+    }
+
+    fn relative_href(&self, fqp: &[Symbol], ty: ItemType) -> String {
+        let mut href = String::new();
+        for part in href_relative_parts(module_fqp(fqp, ty), &self.current) {
+            href.push_str(part.as_str());
+            href.push('/');
+        }
+        href.push_str(&page_name(fqp, ty, "md"));
+        href
+    }
+
+    /// Resolves the intra-doc links of `item` to the pages they point to.
+    fn links(&self, item: &clean::Item) -> Vec<RenderedLink> {
+        let Some(links) = self.cache.intra_doc_links.get(&item.item_id) else { return vec![] };
+        links
+            .iter()
+            .map(|ItemLink { link, link_text, page_id, fragment }| {
+                let mut href = self.href(*page_id).unwrap_or_default();
+                if !href.is_empty()
+                    && let Some(fragment) = fragment
+                {
+                    fragment.render(&mut href, self.tcx);
+                }
+                RenderedLink {
+                    original_text: link.clone(),
+                    new_text: link_text.clone(),
+                    href,
+                    tooltip: String::new(),
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the documentation of `item`, with its headings demoted below `heading_level`.
+    fn write_docs(&self, page: &mut String, item: &clean::Item, heading_level: u32) {
+        let doc = item.doc_value();
+        if !doc.is_empty() {
+            page.push_str(docs::render(&doc, &self.links(item), heading_level).trim_end());
+            page.push_str("\n\n");
+        }
+    }
+
+    fn write_page(&self, file_name: &str, page: String) -> Result<(), Error> {
+        try_err!(fs::create_dir_all(&self.dst), &self.dst);
+        let path = self.dst.join(file_name);
+        try_err!(fs::write(&path, page), &path);
+        Ok(())
+    }
+
+    fn render_module(&self, item: &clean::Item, module: &clean::Module) -> String {
+        let mut page = String::new();
+        let title = if item.is_crate() { "Crate" } else { "Module" };
+        writeln!(page, "# {title} `{}`\n", join_with_double_colon(&self.current)).unwrap();
+        self.write_docs(&mut page, item, 1);
+
+        let mut items = module.items.iter().filter(|i| !i.is_stripped()).collect::<Vec<_>>();
+        items.sort_by(|a, b| {
+            let lhs = a.name.unwrap_or(kw::Empty);
+            let rhs = b.name.unwrap_or(kw::Empty);
+            compare_names(lhs.as_str(), rhs.as_str())
+        });
+        for &section in ItemSection::ALL {
+            let mut section_items =
+                items.iter().filter(|i| item_ty_to_section(i.type_()) == section).peekable();
+            if section_items.peek().is_none() {
+                continue;
+            }
+            writeln!(page, "## {}\n", section.name()).unwrap();
+            for item in section_items {
+                match item.kind {
+                    clean::ImportItem(ref import) => {
+                        let mut p = Printer::new(self.tcx);
+                        p.visibility(item);
+                        p.push("use ");
+                        p.path(&import.source.path);
+                        match import.kind {
+                            clean::ImportKind::Simple(name)
+                                if import.source.path.last_opt() != Some(name) =>
+                            {
+                                p.push(&format!(" as {name}"));
+                            }
+                            clean::ImportKind::Simple(_) => {}
+                            clean::ImportKind::Glob => p.push("::*"),
+                        }
+                        writeln!(page, "- `{};`", p.finish()).unwrap();
+                    }
+                    clean::ExternCrateItem { ref src } => {
+                        let name = item.name.unwrap();
+                        match src {
+                            Some(src) => writeln!(page, "- `extern crate {src} as {name};`"),
+                            None => writeln!(page, "- `extern crate {name};`"),
+                        }
+                        .unwrap();
+                    }
+                    _ => {
+                        let name = item.name.unwrap();
+                        let href = page_name(&[name], item.type_(), "md");
+                        let href = if item.is_mod() { format!("{name}/{href}") } else { href };
+                        write!(page, "- [`{name}`]({href})").unwrap();
+                        let summary = plain_text_summary(&item.doc_value(), &self.links(item));
+                        if !summary.is_empty() {
+                            write!(page, ": {summary}").unwrap();
+                        }
+                        page.push('\n');
+                    }
+                }
+            }
+            page.push('\n');
+        }
+        page
+    }
+
+    fn render_item(&self, item: &clean::Item) -> String {
+        let mut page = String::new();
+        let name = item.name.unwrap();
+        let path = join_with_double_colon(&[&self.current[..], &[name]].concat());
+        writeln!(page, "# {} `{path}`\n", title(item.type_())).unwrap();
+        if let Some(decl) = print::declaration(self.tcx, item) {
+            writeln!(page, "```rust\n{decl}\n```\n").unwrap();
+        }
+        self.write_docs(&mut page, item, 1);
+
+        match item.kind {
+            clean::StructItem(clean::Struct { ref fields, .. }) => {
+                self.write_fields(&mut page, fields, "structfield");
+            }
+            clean::UnionItem(clean::Union { ref fields, .. }) => {
+                self.write_fields(&mut page, fields, "structfield");
+            }
+            clean::EnumItem(ref enum_) => {
+                let mut variants = enum_.variants().peekable();
+                if variants.peek().is_some() {
+                    page.push_str("## Variants\n\n");
+                }
+                for variant in variants {
+                    let variant_name = variant.name.unwrap();
+                    writeln!(page, "<a id=\"variant.{variant_name}\"></a>").unwrap();
+                    writeln!(page, "### `{}`\n", print::variant(self.tcx, variant)).unwrap();
+                    self.write_docs(&mut page, variant, 3);
+                    if let clean::VariantItem(clean::Variant {
+                        kind: clean::VariantKind::Struct(ref s),
+                        ..
+                    }) = variant.kind
+                    {
+                        let anchor = format!("variant.{variant_name}.field");
+                        self.write_fields(&mut page, &s.fields, &anchor);
+                    }
+                }
+            }
+            clean::TraitItem(ref trait_) => {
+                let sections = [
+                    ("Associated Types", ItemType::AssocType),
+                    ("Associated Constants", ItemType::AssocConst),
+                    ("Required Methods", ItemType::TyMethod),
+                    ("Provided Methods", ItemType::Method),
+                ];
+                for (title, ty) in sections {
+                    let items = trait_.items.iter().filter(|i| i.type_() == ty).collect::<Vec<_>>();
+                    if !items.is_empty() {
+                        writeln!(page, "## {title}\n").unwrap();
+                        self.write_assoc_items(&mut page, items, 3);
+                    }
+                }
+                self.write_implementors(&mut page, trait_.def_id);
+            }
+            _ => {}
+        }
+        if matches!(
+            item.kind,
+            clean::StructItem(_)
+                | clean::UnionItem(_)
+                | clean::EnumItem(_)
+                | clean::PrimitiveItem(_)
+                | clean::ForeignTypeItem
+        ) {
+            self.write_impls(&mut page, item.item_id.expect_def_id());
+        }
+        page
+    }
+
+    /// Writes the non-stripped fields in `fields`, with their documentation. Struct fields get a
+    /// section of their own, while the fields of variants are listed under them.
+    fn write_fields(&self, page: &mut String, fields: &[clean::Item], anchor: &str) {
+        let fields = fields
+            .iter()
+            .filter_map(|f| match f.kind {
+                clean::StructFieldItem(ref ty) => Some((f, ty)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if fields.is_empty() {
+            return;
+        }
+        let level = if anchor == "structfield" {
+            page.push_str("## Fields\n\n");
+            3
+        } else {
+            4
+        };
+        let hashes = "#".repeat(level as usize);
+        for (field, ty) in fields {
+            let name = field.name.unwrap();
+            let mut p = Printer::new(self.tcx);
+            p.push(&format!("{name}: "));
+            p.ty(ty);
+            writeln!(page, "<a id=\"{anchor}.{name}\"></a>").unwrap();
+            writeln!(page, "{hashes} `{}`\n", p.finish()).unwrap();
+            self.write_docs(page, field, level);
+        }
+    }
+
+    /// Writes associated items, with their documentation.
+    fn write_assoc_items(&self, page: &mut String, items: Vec<&clean::Item>, level: u32) {
+        let hashes = "#".repeat(level as usize);
+        for item in items {
+            if item.is_stripped() {
+                continue;
+            }
+            let Some(sig) = print::assoc_item_signature(self.tcx, item) else { continue };
+            writeln!(page, "<a id=\"{}.{}\"></a>", item.type_(), item.name.unwrap()).unwrap();
+            writeln!(page, "{hashes} `{}`\n", print::one_line(&sig)).unwrap();
+            self.write_docs(page, item, level);
+        }
+    }
+
+    fn write_impls(&self, page: &mut String, did: DefId) {
+        let Some(impls) = self.cache.impls.get(&did) else { return };
+        let (inherent, trait_impls): (Vec<_>, Vec<_>) =
+            impls.iter().partition(|i| i.inner_impl().trait_.is_none());
+
+        if !inherent.is_empty() {
+            page.push_str("## Implementations\n\n");
+        }
+        for i in inherent {
+            writeln!(page, "### `{}`\n", print::impl_header(self.tcx, i.inner_impl())).unwrap();
+            self.write_docs(page, &i.impl_item, 3);
+            self.write_assoc_items(page, i.inner_impl().items.iter().collect(), 4);
+        }
+
+        let (auto, trait_impls): (Vec<_>, Vec<_>) =
+            trait_impls.into_iter().partition(|i| i.inner_impl().kind.is_auto());
+        let (blanket, trait_impls): (Vec<_>, Vec<_>) =
+            trait_impls.into_iter().partition(|i| i.inner_impl().kind.is_blanket());
+        for (title, impls) in [
+            ("Trait Implementations", trait_impls),
+            ("Auto Trait Implementations", auto),
+            ("Blanket Implementations", blanket),
+        ] {
+            if impls.is_empty() {
+                continue;
+            }
+            writeln!(page, "## {title}\n").unwrap();
+            for i in impls {
+                writeln!(page, "- `{}`", print::impl_header(self.tcx, i.inner_impl())).unwrap();
+            }
+            page.push('\n');
+        }
+    }
+
+    fn write_implementors(&self, page: &mut String, trait_did: DefId) {
+        let Some(implementors) = self.cache.implementors.get(&trait_did) else { return };
+        if implementors.is_empty() {
+            return;
+        }
+        page.push_str("## Implementors\n\n");
+        for i in implementors {
+            writeln!(page, "- `{}`", print::impl_header(self.tcx, i.inner_impl())).unwrap();
+        }
+        page.push('\n');
+    }
+}
+
+/// The path of the module in which the page of an item is.
+fn module_fqp(fqp: &[Symbol], ty: ItemType) -> &[Symbol] {
+    if ty == ItemType::Module { fqp } else { &fqp[..fqp.len() - 1] }
+}
+
+/// The name of the file of the page of an item, within its module's directory.
+fn page_name(fqp: &[Symbol], ty: ItemType, extension: &str) -> String {
+    match ty {
+        ItemType::Module => format!("index.{extension}"),
+        _ => format!("{ty}.{}.{extension}", fqp.last().unwrap()),
+    }
+}
+
+fn title(ty: ItemType) -> &'static str {
+    match ty {
+        ItemType::Module => "Module",
+        ItemType::Struct => "Struct",
+        ItemType::Union => "Union",
+        ItemType::Enum => "Enum",
+        ItemType::Function => "Function",
+        ItemType::TypeAlias => "Type Alias",
+        ItemType::Static => "Static",
+        ItemType::Constant => "Constant",
+        ItemType::Trait => "Trait",
+        ItemType::TraitAlias => "Trait Alias",
+        ItemType::Macro => "Macro",
+        ItemType::ProcAttribute => "Attribute Macro",
+        ItemType::ProcDerive => "Derive Macro",
+        ItemType::Primitive => "Primitive Type",
+        ItemType::Keyword => "Keyword",
+        ItemType::ForeignType => "Foreign Type",
+        _ => "Item",
+    }
+}
+
+impl<'tcx> FormatRenderer<'tcx> for MarkdownRenderer<'tcx> {
+    fn descr() -> &'static str {
+        "markdown"
+    }
+
+    const RUN_ON_MODULE: bool = true;
+    type ModuleData = bool;
+
+    fn init(
+        krate: clean::Crate,
+        options: RenderOptions,
+        cache: Cache,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<(Self, clean::Crate), Error> {
+        debug!("Initializing markdown renderer");
+        Ok((
+            MarkdownRenderer {
+                tcx,
+                cache: Rc::new(cache),
+                dst: options.output,
+                current: Vec::new(),
+                in_stripped_module: false,
+            },
+            krate,
+        ))
+    }
+
+    fn save_module_data(&mut self) -> Self::ModuleData {
+        self.in_stripped_module
+    }
+
+    fn restore_module_data(&mut self, in_stripped_module: Self::ModuleData) {
+        self.in_stripped_module = in_stripped_module;
+    }
+
+    fn item(&mut self, item: clean::Item) -> Result<(), Error> {
+        if self.in_stripped_module
+            || item.is_stripped()
+            || matches!(item.kind, clean::ImportItem(_) | clean::ExternCrateItem { .. })
+        {
+            return Ok(());
+        }
+        let page = self.render_item(&item);
+        self.write_page(&page_name(&[item.name.unwrap()], item.type_(), "md"), page)
+    }
+
+    fn mod_item_in(&mut self, item: &clean::Item) -> Result<(), Error> {
+        let name = item.name.unwrap();
+        self.dst.push(name.as_str());
+        self.current.push(name);
+        self.in_stripped_module |= item.is_stripped();
+        if !self.in_stripped_module
+            && let clean::ModuleItem(ref module) = item.kind
+        {
+            let page = self.render_module(item, module);
+            self.write_page("index.md", page)?;
+        }
+        Ok(())
+    }
+
+    fn mod_item_out(&mut self) -> Result<(), Error> {
+        self.dst.pop();
+        self.current.pop();
+        Ok(())
+    }
+
+    fn after_krate(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn cache(&self) -> &Cache {
+        &self.cache
+    }
+}
@@ -20,6 +20,7 @@
 use rustc_target::spec::TargetTuple;
 
 use crate::core::new_dcx;
+use crate::doctest::DocTestResults;
 use crate::externalfiles::ExternalHtml;
 use crate::html::markdown::IdMap;
 use crate::html::render::StylePath;
@@ -33,6 +34,8 @@ pub(crate) enum OutputFormat {
     Json,
     #[default]
     Html,
+    Markdown,
+    Man,
 }
 
 impl OutputFormat {
@@ -48,6 +51,8 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             "json" => Ok(OutputFormat::Json),
             "html" => Ok(OutputFormat::Html),
+            "markdown" => Ok(OutputFormat::Markdown),
+            "man" => Ok(OutputFormat::Man),
             _ => Err(format!("unknown output format `{value}`")),
         }
     }
@@ -119,6 +124,9 @@ pub(crate) struct Options {
     /// Optional path to persist the doctest executables to, defaults to a
     /// temporary directory if not set.
     pub(crate) persist_doctests: Option<PathBuf>,
+    /// Optional path to write the outcome and output of each doctest to, so that they can be
+    /// shown in the documentation.
+    pub(crate) write_doctest_results: Option<PathBuf>,
     /// Runtool to run doctests with
     pub(crate) runtool: Option<String>,
     /// Arguments to pass to the runtool
@@ -205,6 +213,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("test_args", &self.test_args)
             .field("test_run_directory", &self.test_run_directory)
             .field("persist_doctests", &self.persist_doctests)
+            .field("write_doctest_results", &self.write_doctest_results)
             .field("show_coverage", &self.show_coverage)
             .field("crate_version", &self.crate_version)
             .field("runtool", &self.runtool)
@@ -287,6 +296,8 @@ pub(crate) struct RenderOptions {
     pub(crate) generate_link_to_definition: bool,
     /// Set of function-call locations to include as examples
     pub(crate) call_locations: AllCallLocations,
+    /// Outcome of the doctests of a previous `--test` run, shown under each example.
+    pub(crate) doctest_results: DocTestResults,
     /// If `true`, Context::init will not emit shared files.
     pub(crate) no_emit_shared: bool,
     /// If `true`, HTML source code pages won't be generated.
@@ -300,6 +311,12 @@ pub(crate) struct RenderOptions {
     pub(crate) include_parts_dir: Vec<PathToParts>,
     /// Where to write crate-info
     pub(crate) parts_out_dir: Option<PathToParts>,
+    /// If `true`, write the search index of the crate in the format of the
+    /// `rustdoc-search-index` crate, next to the HTML files.
+    pub(crate) offline_search_index: bool,
+    /// If `true`, write pages with diagrams of the supertraits and implementors of each trait, and
+    /// of the module structure of the crate.
+    pub(crate) generate_diagrams: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -712,9 +729,9 @@ fn println_condition(condition: Condition) {
             Some(s) => match OutputFormat::try_from(s.as_str()) {
                 Ok(out_fmt) => {
                     if !out_fmt.is_json() && show_coverage {
-                        dcx.fatal(
-                            "html output format isn't supported for the --show-coverage option",
-                        );
+                        dcx.fatal(format!(
+                            "{s} output format isn't supported for the --show-coverage option"
+                        ));
                     }
                     out_fmt
                 }
@@ -740,6 +757,7 @@ fn println_condition(condition: Condition) {
         let static_root_path = matches.opt_str("static-root-path");
         let test_run_directory = matches.opt_str("test-run-directory").map(PathBuf::from);
         let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
+        let write_doctest_results = matches.opt_str("write-doctest-results").map(PathBuf::from);
         let test_builder = matches.opt_str("test-builder").map(PathBuf::from);
         let codegen_options_strs = matches.opt_strs("C");
         let unstable_opts_strs = matches.opt_strs("Z");
@@ -758,6 +776,8 @@ fn println_condition(condition: Condition) {
         let extern_html_root_takes_precedence =
             matches.opt_present("extern-html-root-takes-precedence");
         let html_no_source = matches.opt_present("html-no-source");
+        let offline_search_index = matches.opt_present("offline-search-index");
+        let generate_diagrams = matches.opt_present("generate-diagrams");
         let should_merge = match parse_merge(matches) {
             Ok(result) => result,
             Err(e) => dcx.fatal(format!("--merge option error: {e}")),
@@ -771,9 +791,29 @@ fn println_condition(condition: Condition) {
             .emit();
         }
 
+        if offline_search_index && (show_coverage || output_format != OutputFormat::Html) {
+            dcx.struct_warn(
+                "`--offline-search-index` option can only be used with HTML output format",
+            )
+            .with_note("`--offline-search-index` option will be ignored")
+            .emit();
+        }
+
+        if generate_diagrams && (show_coverage || output_format != OutputFormat::Html) {
+            dcx.struct_warn(
+                "`--generate-diagrams` option can only be used with HTML output format",
+            )
+            .with_note("`--generate-diagrams` option will be ignored")
+            .emit();
+        }
+
         let scrape_examples_options = ScrapeExamplesOptions::new(matches, dcx);
         let with_examples = matches.opt_strs("with-examples");
         let call_locations = crate::scrape_examples::load_call_locations(with_examples, dcx);
+        let doctest_results = match DocTestResults::load(&matches.opt_strs("doctest-results")) {
+            Ok(results) => results,
+            Err(e) => dcx.fatal(e),
+        };
 
         let unstable_features =
             rustc_feature::UnstableFeatures::from_environment(crate_name.as_deref());
@@ -804,6 +844,7 @@ fn println_condition(condition: Condition) {
             crate_version,
             test_run_directory,
             persist_doctests,
+            write_doctest_results,
             runtool,
             runtool_args,
             enable_per_target_ignores,
@@ -846,12 +887,15 @@ fn println_condition(condition: Condition) {
             emit,
             generate_link_to_definition,
             call_locations,
+            doctest_results,
             no_emit_shared: false,
             html_no_source,
             output_to_stdout,
             should_merge,
             include_parts_dir,
             parts_out_dir,
+            offline_search_index,
+            generate_diagrams,
         };
         Some((input, options, render_options))
     }
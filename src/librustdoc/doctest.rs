@@ -1,5 +1,6 @@
 mod make;
 mod markdown;
+mod results;
 mod runner;
 mod rust;
 
@@ -9,10 +10,11 @@
 use std::process::{self, Command, Stdio};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::{panic, str};
+use std::{fmt, panic, str};
 
 pub(crate) use make::DocTestBuilder;
 pub(crate) use markdown::test as test_markdown;
+pub(crate) use results::{DocTestOutcome, DocTestResult, DocTestResults, DocTestResultsWriter};
 use rustc_ast as ast;
 use rustc_data_structures::fx::{FxHashMap, FxIndexMap, FxIndexSet};
 use rustc_errors::emitter::HumanReadableErrorType;
@@ -48,6 +50,8 @@ pub(crate) struct GlobalTestOptions {
     pub(crate) attrs: Vec<String>,
     /// Path to file containing arguments for the invocation of rustc.
     pub(crate) args_file: PathBuf,
+    /// Where to record the outcome of each doctest, for `--write-doctest-results`.
+    pub(crate) results: Option<DocTestResultsWriter>,
 }
 
 pub(crate) fn generate_args_file(file_path: &Path, options: &RustdocOptions) -> Result<(), String> {
@@ -168,6 +172,10 @@ pub(crate) fn run(
     };
     let args_path = temp_dir.path().join("rustdoc-cfgs");
     crate::wrap_return(dcx, generate_args_file(&args_path, &options))?;
+    let results = match create_results_writer(&options) {
+        Ok(results) => results,
+        Err(error) => return crate::wrap_return(dcx, Err(error)),
+    };
 
     let CreateRunnableDocTests {
         standalone_tests,
@@ -182,7 +190,7 @@ pub(crate) fn run(
             let collector = queries.global_ctxt()?.enter(|tcx| {
                 let crate_name = tcx.crate_name(LOCAL_CRATE).to_string();
                 let crate_attrs = tcx.hir().attrs(CRATE_HIR_ID);
-                let opts = scrape_test_config(crate_name, crate_attrs, args_path);
+                let opts = scrape_test_config(crate_name, crate_attrs, args_path, results);
                 let enable_per_target_ignores = options.enable_per_target_ignores;
 
                 let mut collector = CreateRunnableDocTests::new(options, opts);
@@ -330,11 +338,21 @@ pub(crate) fn run_tests(
     }
 }
 
+pub(crate) fn create_results_writer(
+    options: &RustdocOptions,
+) -> Result<Option<DocTestResultsWriter>, String> {
+    let Some(path) = &options.write_doctest_results else { return Ok(None) };
+    DocTestResultsWriter::create(path).map(Some).map_err(|error| {
+        format!("failed to create doctest results file `{}`: {error}", path.display())
+    })
+}
+
 // Look for `#![doc(test(no_crate_inject))]`, used by crates in the std facade.
 fn scrape_test_config(
     crate_name: String,
     attrs: &[ast::Attribute],
     args_file: PathBuf,
+    results: Option<DocTestResultsWriter>,
 ) -> GlobalTestOptions {
     use rustc_ast_pretty::pprust;
 
@@ -344,6 +362,7 @@ fn scrape_test_config(
         attrs: Vec::new(),
         insert_indent_space: false,
         args_file,
+        results,
     };
 
     let test_attrs: Vec<_> = attrs
@@ -389,6 +408,33 @@ enum TestFailure {
     UnexpectedRunPass,
 }
 
+impl fmt::Display for TestFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestFailure::CompileError => write!(f, "Couldn't compile the test."),
+            TestFailure::UnexpectedCompilePass => {
+                write!(f, "Test compiled successfully, but it's marked `compile_fail`.")
+            }
+            TestFailure::UnexpectedRunPass => {
+                write!(f, "Test executable succeeded, but it's marked `should_panic`.")
+            }
+            TestFailure::MissingErrorCodes(codes) => {
+                write!(f, "Some expected error codes were not found: {codes:?}")
+            }
+            TestFailure::ExecutionError(err) => {
+                write!(f, "Couldn't run the test: {err}")?;
+                if err.kind() == io::ErrorKind::PermissionDenied {
+                    write!(f, " - maybe your tempdir is mounted with noexec?")?;
+                }
+                Ok(())
+            }
+            TestFailure::ExecutionFailure(out) => {
+                write!(f, "Test executable failed ({reason}).", reason = out.status)
+            }
+        }
+    }
+}
+
 enum DirState {
     Temp(tempfile::TempDir),
     Perm(PathBuf),
@@ -465,12 +511,15 @@ fn path_for_merged_doctest(&self) -> PathBuf {
 ///
 /// This is the function that calculates the compiler command line, invokes the compiler, then
 /// invokes the test or tests in a separate executable (if applicable).
+///
+/// Returns the output of the test executable if it was run. Its stdout and stderr are empty if
+/// they weren't captured.
 fn run_test(
     doctest: RunnableDocTest,
     rustdoc_options: &RustdocOptions,
     supports_color: bool,
     report_unused_externs: impl Fn(UnusedExterns),
-) -> Result<(), TestFailure> {
+) -> Result<Option<process::Output>, TestFailure> {
     let langstr = &doctest.langstr;
     // Make sure we emit well-formed executable names for our target.
     let rust_out = add_exe_suffix("rust_out".to_owned(), &rustdoc_options.target);
@@ -633,7 +682,7 @@ fn drop(&mut self) {
     }
 
     if doctest.no_run {
-        return Ok(());
+        return Ok(None);
     }
 
     // Run the code!
@@ -665,17 +714,17 @@ fn drop(&mut self) {
         cmd.output()
     };
     match result {
-        Err(e) => return Err(TestFailure::ExecutionError(e)),
+        Err(e) => Err(TestFailure::ExecutionError(e)),
         Ok(out) => {
             if langstr.should_panic && out.status.success() {
-                return Err(TestFailure::UnexpectedRunPass);
+                Err(TestFailure::UnexpectedRunPass)
             } else if !langstr.should_panic && !out.status.success() {
-                return Err(TestFailure::ExecutionFailure(out));
+                Err(TestFailure::ExecutionFailure(out))
+            } else {
+                Ok(Some(out))
             }
         }
     }
-
-    Ok(())
 }
 
 /// Converts a path intended to use as a command to absolute if it is
@@ -842,6 +891,8 @@ fn add_test(&mut self, scraped_test: ScrapedDocTest) {
             || scraped_test.langstr.test_harness
             || scraped_test.langstr.standalone_crate
             || self.rustdoc_options.nocapture
+            // Merged doctests don't capture the output of each test.
+            || self.opts.results.is_some()
             || self.rustdoc_options.test_args.iter().any(|arg| arg == "--show-output");
         if is_standalone {
             let test_desc = self.generate_test_desc_and_fn(doctest, scraped_test);
@@ -932,6 +983,7 @@ fn doctest_run_fn(
         &global_opts,
         Some(&global_opts.crate_name),
     );
+    let results = global_opts.results.clone();
     let runnable_test = RunnableDocTest {
         full_test_code,
         full_test_line_offset,
@@ -946,28 +998,14 @@ fn doctest_run_fn(
     let res =
         run_test(runnable_test, &rustdoc_options, doctest.supports_color, report_unused_externs);
 
+    if let Some(results) = results {
+        results.write(&DocTestResult::new(scraped_test.name, scraped_test.text, &res));
+    }
+
     if let Err(err) = res {
         match err {
-            TestFailure::CompileError => {
-                eprint!("Couldn't compile the test.");
-            }
-            TestFailure::UnexpectedCompilePass => {
-                eprint!("Test compiled successfully, but it's marked `compile_fail`.");
-            }
-            TestFailure::UnexpectedRunPass => {
-                eprint!("Test executable succeeded, but it's marked `should_panic`.");
-            }
-            TestFailure::MissingErrorCodes(codes) => {
-                eprint!("Some expected error codes were not found: {codes:?}");
-            }
-            TestFailure::ExecutionError(err) => {
-                eprint!("Couldn't run the test: {err}");
-                if err.kind() == io::ErrorKind::PermissionDenied {
-                    eprint!(" - maybe your tempdir is mounted with noexec?");
-                }
-            }
-            TestFailure::ExecutionFailure(out) => {
-                eprintln!("Test executable failed ({reason}).", reason = out.status);
+            TestFailure::ExecutionFailure(ref out) => {
+                eprintln!("{err}");
 
                 // FIXME(#12309): An unfortunate side-effect of capturing the test
                 // executable's output is that the relative ordering between the test's
@@ -993,6 +1031,7 @@ fn doctest_run_fn(
                     }
                 }
             }
+            _ => eprint!("{err}"),
         }
 
         panic::resume_unwind(Box::new(()));
@@ -8,7 +8,8 @@
 use tempfile::tempdir;
 
 use super::{
-    CreateRunnableDocTests, DocTestVisitor, GlobalTestOptions, ScrapedDocTest, generate_args_file,
+    CreateRunnableDocTests, DocTestVisitor, GlobalTestOptions, ScrapedDocTest,
+    create_results_writer, generate_args_file,
 };
 use crate::config::Options;
 use crate::html::markdown::{ErrorCodes, LangString, MdRelLine, find_testable_code};
@@ -91,6 +92,7 @@ pub(crate) fn test(input: &Input, options: Options) -> Result<(), String> {
         insert_indent_space: false,
         attrs: vec![],
         args_file,
+        results: create_results_writer(&options)?,
     };
 
     let mut md_collector = MdCollector {
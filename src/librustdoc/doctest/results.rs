@@ -0,0 +1,144 @@
+//! Recording the outcome of doctests with `--write-doctest-results`, so that the HTML
+//! documentation can show them under each code block with `--doctest-results`.
+//!
+//! The results file has one JSON object per line, one per doctest that was run. Doctests are
+//! matched to code blocks by their code, as it is passed to the compiler before being wrapped
+//! into a `main` function.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use std::sync::{Arc, Mutex};
+
+use rustc_data_structures::fx::FxHashMap;
+
+use super::TestFailure;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DocTestOutcome {
+    /// The doctest compiled and ran, and behaved as its attributes say it should.
+    Passed,
+    /// The doctest compiled (or failed to, for `compile_fail`) as expected, but was not run.
+    Compiled,
+    Failed,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct DocTestResult {
+    /// The name of the doctest, as shown by the test harness.
+    pub(crate) name: String,
+    pub(crate) code: String,
+    pub(crate) outcome: DocTestOutcome,
+    /// What the doctest printed to stdout, if it was run and its output was captured.
+    pub(crate) stdout: String,
+    /// The message the doctest panicked with, for `should_panic` doctests and doctests that
+    /// failed at runtime.
+    pub(crate) panic_message: Option<String>,
+    /// Why the doctest failed, if it did.
+    pub(crate) failure: Option<String>,
+}
+
+impl DocTestResult {
+    pub(super) fn new(
+        name: String,
+        code: String,
+        result: &Result<Option<process::Output>, TestFailure>,
+    ) -> Self {
+        let (outcome, output, failure) = match result {
+            Ok(Some(output)) => (DocTestOutcome::Passed, Some(output), None),
+            Ok(None) => (DocTestOutcome::Compiled, None, None),
+            Err(TestFailure::ExecutionFailure(output)) => (
+                DocTestOutcome::Failed,
+                Some(output),
+                Some(format!("Test executable failed ({}).", output.status)),
+            ),
+            Err(failure) => (DocTestOutcome::Failed, None, Some(failure.to_string())),
+        };
+        let stdout = output.map(|o| String::from_utf8_lossy(&o.stdout).into_owned());
+        let panic_message = output.and_then(|o| panic_message(&String::from_utf8_lossy(&o.stderr)));
+        DocTestResult {
+            name,
+            code,
+            outcome,
+            stdout: stdout.unwrap_or_default(),
+            panic_message,
+            failure,
+        }
+    }
+}
+
+/// Extracts the message of the first panic from the stderr of a doctest.
+pub(super) fn panic_message(stderr: &str) -> Option<String> {
+    let mut lines = stderr.lines();
+    let first = lines.find(|l| l.starts_with("thread '") && l.contains("' panicked at "))?;
+    // Since Rust 1.73 the message is on the lines after the location,
+    // which is followed by a `:`. Previously, it was on the same line.
+    let message = if let Some((_, message)) = first.split_once("' panicked at '") {
+        message.rsplit_once("', ").map_or(message, |(message, _)| message).to_owned()
+    } else {
+        lines
+            .take_while(|l| !l.starts_with("note: ") && !l.starts_with("stack backtrace:"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    Some(message)
+}
+
+/// Appends the results of doctests to a file, shared between the threads running them.
+#[derive(Clone)]
+pub(crate) struct DocTestResultsWriter(Arc<Mutex<File>>);
+
+impl DocTestResultsWriter {
+    pub(crate) fn create(path: &Path) -> io::Result<Self> {
+        Ok(DocTestResultsWriter(Arc::new(Mutex::new(File::create(path)?))))
+    }
+
+    pub(super) fn write(&self, result: &DocTestResult) {
+        let mut line = serde_json::to_string(result).unwrap();
+        line.push('\n');
+        if let Err(error) = self.0.lock().unwrap().write_all(line.as_bytes()) {
+            eprintln!("failed to write doctest results: {error}");
+        }
+    }
+}
+
+/// The results of a previous doctest run, looked up while rendering code blocks.
+#[derive(Clone, Debug, Default)]
+pub struct DocTestResults {
+    by_code: FxHashMap<String, DocTestResult>,
+}
+
+impl DocTestResults {
+    pub(crate) fn load(paths: &[String]) -> Result<Self, String> {
+        let mut by_code = FxHashMap::default();
+        for path in paths {
+            let contents = fs::read_to_string(path)
+                .map_err(|error| format!("failed to read doctest results `{path}`: {error}"))?;
+            for (i, line) in contents.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+                let result: DocTestResult = serde_json::from_str(line).map_err(|error| {
+                    format!("invalid doctest result at `{path}:{}`: {error}", i + 1)
+                })?;
+                // If the same code is tested several times, a failure is more interesting.
+                if by_code
+                    .get(&result.code)
+                    .is_none_or(|r: &DocTestResult| r.outcome != DocTestOutcome::Failed)
+                {
+                    by_code.insert(result.code.clone(), result);
+                }
+            }
+        }
+        Ok(DocTestResults { by_code })
+    }
+
+    pub(crate) fn get(&self, code: &str) -> Option<&DocTestResult> {
+        self.by_code.get(code)
+    }
+}
+
+impl FromIterator<DocTestResult> for DocTestResults {
+    fn from_iter<I: IntoIterator<Item = DocTestResult>>(iter: I) -> Self {
+        DocTestResults { by_code: iter.into_iter().map(|r| (r.code.clone(), r)).collect() }
+    }
+}
@@ -32,6 +32,7 @@ fn default_global_opts(crate_name: impl Into<String>) -> GlobalTestOptions {
         insert_indent_space: false,
         attrs: vec![],
         args_file: PathBuf::new(),
+        results: None,
     }
 }
 
@@ -379,3 +380,21 @@ fn main() {
     let (output, len) = make_test(input, None, false, &opts, None);
     assert_eq!((output, len), (expected, 1));
 }
+
+#[test]
+fn doctest_panic_message() {
+    let stderr = "\
+thread 'main' panicked at src/lib.rs:5:1:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+";
+    assert_eq!(
+        super::results::panic_message(stderr).as_deref(),
+        Some("assertion `left == right` failed\n  left: 1\n right: 2")
+    );
+    let stderr = "thread 'main' panicked at 'oh no', src/lib.rs:5:1\n";
+    assert_eq!(super::results::panic_message(stderr).as_deref(), Some("oh no"));
+    assert_eq!(super::results::panic_message("error: linking failed\n"), None);
+}
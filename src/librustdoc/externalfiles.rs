@@ -47,6 +47,7 @@ pub(crate) fn load(
                 edition,
                 playground,
                 heading_offset: HeadingOffset::H2,
+                doctest_results: None,
             }
             .into_string()
         );
@@ -62,6 +63,7 @@ pub(crate) fn load(
                 edition,
                 playground,
                 heading_offset: HeadingOffset::H2,
+                doctest_results: None,
             }
             .into_string()
         );
@@ -1,5 +1,6 @@
 pub(crate) mod cache;
 pub(crate) mod item_type;
+pub(crate) mod print;
 pub(crate) mod renderer;
 
 pub(crate) use renderer::{FormatRenderer, run_format};
@@ -0,0 +1,695 @@
+//! Printing item declarations as plain Rust source, for the renderers that don't output HTML.
+//!
+//! This mirrors what `html::format` does, without any of the links or HTML escaping.
+
+use rustc_abi::ExternAbi;
+use rustc_hir as hir;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::symbol::kw;
+
+use crate::clean::{self, PrimitiveType};
+use crate::formats::item_type::ItemType;
+
+pub(crate) struct Printer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    out: String,
+}
+
+impl<'tcx> Printer<'tcx> {
+    pub(crate) fn new(tcx: TyCtxt<'tcx>) -> Self {
+        Printer { tcx, out: String::new() }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.out
+    }
+
+    pub(crate) fn push(&mut self, s: &str) {
+        self.out.push_str(s);
+    }
+
+    fn comma_sep<T>(
+        &mut self,
+        items: impl IntoIterator<Item = T>,
+        mut f: impl FnMut(&mut Self, T),
+    ) {
+        for (i, item) in items.into_iter().enumerate() {
+            if i != 0 {
+                self.push(", ");
+            }
+            f(self, item);
+        }
+    }
+
+    pub(crate) fn visibility(&mut self, item: &clean::Item) {
+        match item.visibility(self.tcx) {
+            None => {}
+            Some(ty::Visibility::Public) => self.push("pub "),
+            Some(ty::Visibility::Restricted(did)) if did.is_crate_root() => {
+                self.push("pub(crate) ")
+            }
+            Some(ty::Visibility::Restricted(did)) => {
+                let path = self.tcx.def_path_str(did);
+                self.push(&format!("pub(in {path}) "));
+            }
+        }
+    }
+
+    pub(crate) fn fn_header(&mut self, header: hir::FnHeader) {
+        if header.is_const() {
+            self.push("const ");
+        }
+        if header.is_async() {
+            self.push("async ");
+        }
+        self.push(header.safety.prefix_str());
+        self.abi(header.abi);
+    }
+
+    fn abi(&mut self, abi: ExternAbi) {
+        if abi != ExternAbi::Rust {
+            self.push(&format!("extern \"{}\" ", abi.name()));
+        }
+    }
+
+    pub(crate) fn lifetime(&mut self, lt: &clean::Lifetime) {
+        self.push(lt.0.as_str());
+    }
+
+    pub(crate) fn ty(&mut self, t: &clean::Type) {
+        match t {
+            clean::Type::Path { path } => {
+                // Paths like `T::Output` and `Self::Output` are printed with all their segments.
+                if path.is_assoc_ty() {
+                    self.path(path);
+                } else if let Some(last) = path.segments.last() {
+                    self.path_segment(last);
+                }
+            }
+            clean::Type::DynTrait(bounds, lt) => {
+                self.push("dyn ");
+                for (i, bound) in bounds.iter().enumerate() {
+                    if i != 0 {
+                        self.push(" + ");
+                    }
+                    self.poly_trait(bound);
+                }
+                if let Some(lt) = lt {
+                    self.push(" + ");
+                    self.lifetime(lt);
+                }
+            }
+            clean::Type::Generic(name) => self.push(name.as_str()),
+            clean::Type::SelfTy => self.push("Self"),
+            clean::Type::Primitive(PrimitiveType::Never) => self.push("!"),
+            clean::Type::Primitive(prim) => self.push(prim.as_sym().as_str()),
+            clean::Type::BareFunction(decl) => {
+                self.higher_ranked_params(&decl.generic_params);
+                self.push(decl.safety.prefix_str());
+                self.abi(decl.abi);
+                self.push("fn");
+                self.fn_decl(&decl.decl);
+            }
+            clean::Type::Tuple(types) => {
+                self.push("(");
+                self.comma_sep(types, Self::ty);
+                if types.len() == 1 {
+                    self.push(",");
+                }
+                self.push(")");
+            }
+            clean::Type::Slice(t) => {
+                self.push("[");
+                self.ty(t);
+                self.push("]");
+            }
+            clean::Type::Array(t, n) => {
+                self.push("[");
+                self.ty(t);
+                self.push(&format!("; {n}]"));
+            }
+            clean::Type::Pat(t, pat) => {
+                self.ty(t);
+                self.push(&format!(" is {pat}"));
+            }
+            clean::Type::RawPointer(mutability, t) => {
+                self.push(match mutability {
+                    hir::Mutability::Mut => "*mut ",
+                    hir::Mutability::Not => "*const ",
+                });
+                self.ty(t);
+            }
+            clean::Type::BorrowedRef { lifetime, mutability, type_ } => {
+                self.push("&");
+                if let Some(lt) = lifetime {
+                    self.lifetime(lt);
+                    self.push(" ");
+                }
+                self.push(mutability.prefix_str());
+                self.ty(type_);
+            }
+            clean::Type::QPath(qpath) => {
+                let clean::QPathData { assoc, self_type, should_show_cast, trait_ } = &**qpath;
+                if let Some(trait_) = trait_
+                    && *should_show_cast
+                {
+                    self.push("<");
+                    self.ty(self_type);
+                    self.push(" as ");
+                    self.path(trait_);
+                    self.push(">");
+                } else {
+                    self.ty(self_type);
+                }
+                self.push("::");
+                self.path_segment(assoc);
+            }
+            clean::Type::Infer => self.push("_"),
+            clean::Type::ImplTrait(bounds) => {
+                self.push("impl ");
+                self.bounds(bounds);
+            }
+        }
+    }
+
+    pub(crate) fn path(&mut self, path: &clean::Path) {
+        for (i, segment) in path.segments.iter().enumerate() {
+            if i != 0 {
+                self.push("::");
+            }
+            if segment.name != kw::PathRoot {
+                self.path_segment(segment);
+            }
+        }
+    }
+
+    fn path_segment(&mut self, segment: &clean::PathSegment) {
+        self.push(segment.name.as_str());
+        self.generic_args(&segment.args);
+    }
+
+    fn generic_args(&mut self, args: &clean::GenericArgs) {
+        match args {
+            clean::GenericArgs::AngleBracketed { args, constraints } => {
+                if args.is_empty() && constraints.is_empty() {
+                    return;
+                }
+                self.push("<");
+                self.comma_sep(args.iter(), Self::generic_arg);
+                if !args.is_empty() && !constraints.is_empty() {
+                    self.push(", ");
+                }
+                self.comma_sep(constraints, Self::constraint);
+                self.push(">");
+            }
+            clean::GenericArgs::Parenthesized { inputs, output } => {
+                self.push("(");
+                self.comma_sep(inputs.iter(), Self::ty);
+                self.push(")");
+                if let Some(output) = output {
+                    self.push(" -> ");
+                    self.ty(output);
+                }
+            }
+        }
+    }
+
+    fn generic_arg(&mut self, arg: &clean::GenericArg) {
+        match arg {
+            clean::GenericArg::Lifetime(lt) => self.lifetime(lt),
+            clean::GenericArg::Type(t) => self.ty(t),
+            clean::GenericArg::Const(c) => {
+                let expr = c.expr(self.tcx);
+                self.push(&expr);
+            }
+            clean::GenericArg::Infer => self.push("_"),
+        }
+    }
+
+    fn constraint(&mut self, constraint: &clean::AssocItemConstraint) {
+        self.path_segment(&constraint.assoc);
+        match &constraint.kind {
+            clean::AssocItemConstraintKind::Equality { term } => {
+                self.push(" = ");
+                self.term(term);
+            }
+            clean::AssocItemConstraintKind::Bound { bounds } => {
+                if !bounds.is_empty() {
+                    self.push(": ");
+                    self.bounds(bounds);
+                }
+            }
+        }
+    }
+
+    fn term(&mut self, term: &clean::Term) {
+        match term {
+            clean::Term::Type(t) => self.ty(t),
+            clean::Term::Constant(c) => {
+                let expr = c.expr(self.tcx);
+                self.push(&expr);
+            }
+        }
+    }
+
+    pub(crate) fn bounds(&mut self, bounds: &[clean::GenericBound]) {
+        for (i, bound) in bounds.iter().enumerate() {
+            if i != 0 {
+                self.push(" + ");
+            }
+            self.bound(bound);
+        }
+    }
+
+    fn bound(&mut self, bound: &clean::GenericBound) {
+        match bound {
+            clean::GenericBound::Outlives(lt) => self.lifetime(lt),
+            clean::GenericBound::TraitBound(poly, modifiers) => {
+                self.push(match modifiers.constness {
+                    hir::BoundConstness::Never => "",
+                    hir::BoundConstness::Maybe(_) => "~const ",
+                    hir::BoundConstness::Always(_) => "const ",
+                });
+                self.push(match modifiers.polarity {
+                    hir::BoundPolarity::Positive => "",
+                    hir::BoundPolarity::Negative(_) => "!",
+                    hir::BoundPolarity::Maybe(_) => "?",
+                });
+                self.poly_trait(poly);
+            }
+            clean::GenericBound::Use(args) => {
+                self.push("use<");
+                self.comma_sep(args, |p, arg| p.push(arg.as_str()));
+                self.push(">");
+            }
+        }
+    }
+
+    fn poly_trait(&mut self, poly: &clean::PolyTrait) {
+        self.higher_ranked_params(&poly.generic_params);
+        self.path(&poly.trait_);
+    }
+
+    fn higher_ranked_params(&mut self, params: &[clean::GenericParamDef]) {
+        if !params.is_empty() {
+            self.push("for<");
+            self.comma_sep(params, Self::generic_param);
+            self.push("> ");
+        }
+    }
+
+    fn generic_param(&mut self, param: &clean::GenericParamDef) {
+        match &param.kind {
+            clean::GenericParamDefKind::Lifetime { outlives } => {
+                self.push(param.name.as_str());
+                if !outlives.is_empty() {
+                    self.push(": ");
+                    for (i, lt) in outlives.iter().enumerate() {
+                        if i != 0 {
+                            self.push(" + ");
+                        }
+                        self.lifetime(lt);
+                    }
+                }
+            }
+            clean::GenericParamDefKind::Type { bounds, default, .. } => {
+                self.push(param.name.as_str());
+                if !bounds.is_empty() {
+                    self.push(": ");
+                    self.bounds(bounds);
+                }
+                if let Some(default) = default {
+                    self.push(" = ");
+                    self.ty(default);
+                }
+            }
+            clean::GenericParamDefKind::Const { ty, default, .. } => {
+                self.push(&format!("const {}: ", param.name));
+                self.ty(ty);
+                if let Some(default) = default {
+                    self.push(&format!(" = {default}"));
+                }
+            }
+        }
+    }
+
+    /// Prints the generic parameters of an item, without its `where` clause.
+    pub(crate) fn generics(&mut self, generics: &clean::Generics) {
+        let mut params = generics.params.iter().filter(|p| !p.is_synthetic_param()).peekable();
+        if params.peek().is_some() {
+            self.push("<");
+            self.comma_sep(params, Self::generic_param);
+            self.push(">");
+        }
+    }
+
+    pub(crate) fn where_clause(&mut self, generics: &clean::Generics) {
+        if generics.where_predicates.is_empty() {
+            return;
+        }
+        self.push("\nwhere");
+        for predicate in &generics.where_predicates {
+            self.push("\n    ");
+            match predicate {
+                clean::WherePredicate::BoundPredicate { ty, bounds, bound_params } => {
+                    self.higher_ranked_params(bound_params);
+                    self.ty(ty);
+                    self.push(":");
+                    if !bounds.is_empty() {
+                        self.push(" ");
+                        self.bounds(bounds);
+                    }
+                }
+                clean::WherePredicate::RegionPredicate { lifetime, bounds } => {
+                    self.lifetime(lifetime);
+                    self.push(": ");
+                    self.bounds(bounds);
+                }
+                clean::WherePredicate::EqPredicate { lhs, rhs } => {
+                    self.ty(lhs);
+                    self.push(" == ");
+                    self.term(rhs);
+                }
+            }
+            self.push(",");
+        }
+    }
+
+    pub(crate) fn fn_decl(&mut self, decl: &clean::FnDecl) {
+        self.push("(");
+        self.comma_sep(&decl.inputs.values, |p, arg| {
+            if arg.is_const {
+                p.push("const ");
+            }
+            if let Some(self_ty) = arg.to_receiver() {
+                match self_ty {
+                    clean::SelfTy => p.push("self"),
+                    clean::BorrowedRef { lifetime, mutability, type_: box clean::SelfTy } => {
+                        p.push("&");
+                        if let Some(lt) = lifetime {
+                            p.lifetime(lt);
+                            p.push(" ");
+                        }
+                        p.push(mutability.prefix_str());
+                        p.push("self");
+                    }
+                    _ => {
+                        p.push("self: ");
+                        p.ty(self_ty);
+                    }
+                }
+            } else {
+                p.push(&format!("{}: ", arg.name));
+                p.ty(&arg.type_);
+            }
+        });
+        if decl.c_variadic {
+            self.push(if decl.inputs.values.is_empty() { "..." } else { ", ..." });
+        }
+        self.push(")");
+        if !matches!(decl.output, clean::Tuple(ref types) if types.is_empty()) {
+            self.push(" -> ");
+            self.ty(&decl.output);
+        }
+    }
+
+    pub(crate) fn impl_header(&mut self, impl_: &clean::Impl) {
+        self.push(impl_.safety.prefix_str());
+        self.push("impl");
+        self.generics(&impl_.generics);
+        self.push(" ");
+        if let Some(trait_) = &impl_.trait_ {
+            if impl_.polarity == ty::ImplPolarity::Negative {
+                self.push("!");
+            }
+            self.path(trait_);
+            self.push(" for ");
+        }
+        if let clean::ImplKind::Blanket(ty) = &impl_.kind {
+            self.ty(ty);
+        } else {
+            self.ty(&impl_.for_);
+        }
+        self.where_clause(&impl_.generics);
+    }
+}
+
+/// Prints the header of an impl block on a single line.
+pub(crate) fn impl_header(tcx: TyCtxt<'_>, impl_: &clean::Impl) -> String {
+    let mut p = Printer::new(tcx);
+    p.impl_header(impl_);
+    one_line(&p.finish())
+}
+
+/// Prints a variant as it is written in its enum.
+pub(crate) fn variant(tcx: TyCtxt<'_>, variant: &clean::Item) -> String {
+    let mut p = Printer::new(tcx);
+    p.push(variant.name.unwrap().as_str());
+    let clean::VariantItem(ref v) = variant.kind else { unreachable!() };
+    match v.kind {
+        clean::VariantKind::CLike => {}
+        clean::VariantKind::Tuple(ref fields) => {
+            p.push("(");
+            for (i, field) in fields.iter().enumerate() {
+                if i != 0 {
+                    p.push(", ");
+                }
+                match field.kind {
+                    clean::StructFieldItem(ref ty) => p.ty(ty),
+                    _ => p.push("_"),
+                }
+            }
+            p.push(")");
+        }
+        clean::VariantKind::Struct(ref s) => {
+            p.push(" {");
+            for (i, field) in s.fields.iter().enumerate() {
+                p.push(if i == 0 { " " } else { ", " });
+                match field.kind {
+                    clean::StructFieldItem(ref ty) => {
+                        p.push(&format!("{}: ", field.name.unwrap()));
+                        p.ty(ty);
+                    }
+                    _ => p.push("/* private field */"),
+                }
+            }
+            p.push(" }");
+        }
+    }
+    p.finish()
+}
+
+/// Prints the signature of an associated item, as it is written in a trait or an impl.
+pub(crate) fn assoc_item_signature(tcx: TyCtxt<'_>, item: &clean::Item) -> Option<String> {
+    let name = item.name?;
+    let mut p = Printer::new(tcx);
+    match item.kind {
+        clean::TyMethodItem(ref f) | clean::MethodItem(ref f, _) => {
+            p.visibility(item);
+            p.fn_header(item.fn_header(tcx)?);
+            p.push(&format!("fn {name}"));
+            p.generics(&f.generics);
+            p.fn_decl(&f.decl);
+            p.where_clause(&f.generics);
+        }
+        clean::TyAssocConstItem(ref generics, ref ty) => {
+            p.push(&format!("const {name}"));
+            p.generics(generics);
+            p.push(": ");
+            p.ty(ty);
+            p.where_clause(generics);
+        }
+        clean::AssocConstItem(ref c) => {
+            p.visibility(item);
+            p.push(&format!("const {name}"));
+            p.generics(&c.generics);
+            p.push(": ");
+            p.ty(&c.type_);
+            p.where_clause(&c.generics);
+        }
+        clean::TyAssocTypeItem(ref generics, ref bounds) => {
+            p.push(&format!("type {name}"));
+            p.generics(generics);
+            if !bounds.is_empty() {
+                p.push(": ");
+                p.bounds(bounds);
+            }
+            p.where_clause(generics);
+        }
+        clean::AssocTypeItem(ref alias, _) => {
+            p.push(&format!("type {name}"));
+            p.generics(&alias.generics);
+            p.where_clause(&alias.generics);
+            p.push(" = ");
+            p.ty(&alias.type_);
+        }
+        _ => return None,
+    }
+    Some(p.finish())
+}
+
+/// Prints the declaration of an item, as shown at the top of its page.
+pub(crate) fn declaration(tcx: TyCtxt<'_>, item: &clean::Item) -> Option<String> {
+    let name = item.name.unwrap();
+    let mut p = Printer::new(tcx);
+    match item.kind {
+        clean::StructItem(ref s) => {
+            p.visibility(item);
+            p.push(&format!("struct {name}"));
+            p.generics(&s.generics);
+            match s.ctor_kind {
+                Some(rustc_hir::def::CtorKind::Fn) => {
+                    p.push("(");
+                    for (i, field) in s.fields.iter().enumerate() {
+                        if i != 0 {
+                            p.push(", ");
+                        }
+                        match field.kind {
+                            clean::StructFieldItem(ref ty) => {
+                                p.visibility(field);
+                                p.ty(ty);
+                            }
+                            _ => p.push("/* private field */"),
+                        }
+                    }
+                    p.push(")");
+                    p.where_clause(&s.generics);
+                    p.push(";");
+                }
+                Some(rustc_hir::def::CtorKind::Const) => {
+                    p.where_clause(&s.generics);
+                    p.push(";");
+                }
+                None => {
+                    p.where_clause(&s.generics);
+                    fields_body(&mut p, &s.generics, &s.fields);
+                }
+            }
+        }
+        clean::UnionItem(ref u) => {
+            p.visibility(item);
+            p.push(&format!("union {name}"));
+            p.generics(&u.generics);
+            p.where_clause(&u.generics);
+            fields_body(&mut p, &u.generics, &u.fields);
+        }
+        clean::EnumItem(ref e) => {
+            p.visibility(item);
+            p.push(&format!("enum {name}"));
+            p.generics(&e.generics);
+            p.where_clause(&e.generics);
+            p.push(if e.generics.where_predicates.is_empty() { " {\n" } else { "\n{\n" });
+            for v in e.variants() {
+                p.push(&format!("    {},\n", variant(tcx, v)));
+            }
+            if e.has_stripped_entries() {
+                p.push("    // some variants omitted\n");
+            }
+            p.push("}");
+        }
+        clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f, _) => {
+            p.visibility(item);
+            p.fn_header(item.fn_header(tcx)?);
+            p.push(&format!("fn {name}"));
+            p.generics(&f.generics);
+            p.fn_decl(&f.decl);
+            p.where_clause(&f.generics);
+        }
+        clean::TypeAliasItem(ref alias) => {
+            p.visibility(item);
+            p.push(&format!("type {name}"));
+            p.generics(&alias.generics);
+            p.where_clause(&alias.generics);
+            p.push(" = ");
+            p.ty(&alias.type_);
+            p.push(";");
+        }
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s, _) => {
+            p.visibility(item);
+            if let clean::ForeignStaticItem(_, safety) = item.kind {
+                p.push(safety.prefix_str());
+            }
+            p.push("static ");
+            p.push(s.mutability.prefix_str());
+            p.push(&format!("{name}: "));
+            p.ty(&s.type_);
+            p.push(";");
+        }
+        clean::ConstantItem(ref c) => {
+            p.visibility(item);
+            p.push(&format!("const {name}"));
+            p.generics(&c.generics);
+            p.push(": ");
+            p.ty(&c.type_);
+            p.push(&format!(" = {};", c.expr(tcx)));
+        }
+        clean::TraitItem(ref t) => {
+            p.visibility(item);
+            p.push(t.safety(tcx).prefix_str());
+            if t.is_auto(tcx) {
+                p.push("auto ");
+            }
+            p.push(&format!("trait {name}"));
+            p.generics(&t.generics);
+            if !t.bounds.is_empty() {
+                p.push(": ");
+                p.bounds(&t.bounds);
+            }
+            p.where_clause(&t.generics);
+            p.push(if t.generics.where_predicates.is_empty() { " {" } else { "\n{" });
+            for assoc in t.items.iter().filter(|i| !i.is_stripped()) {
+                if let Some(sig) = assoc_item_signature(tcx, assoc) {
+                    let body = if assoc.type_() == ItemType::Method { " { ... }" } else { ";" };
+                    p.push(&format!("\n    {}{body}", sig.replace('\n', "\n    ")));
+                }
+            }
+            p.push(if t.items.is_empty() { "}" } else { "\n}" });
+        }
+        clean::TraitAliasItem(ref alias) => {
+            p.push(&format!("trait {name}"));
+            p.generics(&alias.generics);
+            p.push(" = ");
+            p.bounds(&alias.bounds);
+            p.where_clause(&alias.generics);
+            p.push(";");
+        }
+        clean::ForeignTypeItem => {
+            p.visibility(item);
+            p.push(&format!("type {name};"));
+        }
+        clean::MacroItem(ref m) => return Some(m.source.clone()),
+        clean::ProcMacroItem(ref m) => match m.kind {
+            rustc_span::hygiene::MacroKind::Bang => p.push(&format!("{name}!() {{ ... }}")),
+            rustc_span::hygiene::MacroKind::Attr => p.push(&format!("#[{name}]")),
+            rustc_span::hygiene::MacroKind::Derive => p.push(&format!("#[derive({name})]")),
+        },
+        _ => return None,
+    }
+    Some(p.finish())
+}
+
+fn fields_body(p: &mut Printer<'_>, generics: &clean::Generics, fields: &[clean::Item]) {
+    p.push(if generics.where_predicates.is_empty() { " {\n" } else { "\n{\n" });
+    let mut has_stripped_fields = false;
+    for field in fields {
+        match field.kind {
+            clean::StructFieldItem(ref ty) => {
+                p.push("    ");
+                p.visibility(field);
+                p.push(&format!("{}: ", field.name.unwrap()));
+                p.ty(ty);
+                p.push(",\n");
+            }
+            _ => has_stripped_fields = true,
+        }
+    }
+    if has_stripped_fields {
+        p.push("    /* private fields */\n");
+    }
+    p.push("}");
+}
+
+/// Collapses a multi-line declaration, for headings and lists.
+pub(crate) fn one_line(s: &str) -> String {
+    s.replace("\n    ", " ").replace('\n', " ")
+}
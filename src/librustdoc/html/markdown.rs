@@ -20,6 +20,7 @@
 //!     edition: Edition::Edition2015,
 //!     playground: &None,
 //!     heading_offset: HeadingOffset::H2,
+//!     doctest_results: None,
 //! };
 //! let html = md.into_string();
 //! // ... something using html
@@ -48,7 +49,7 @@
 
 use crate::clean::RenderedLink;
 use crate::doctest;
-use crate::doctest::GlobalTestOptions;
+use crate::doctest::{DocTestOutcome, DocTestResult, DocTestResults, GlobalTestOptions};
 use crate::html::escape::{Escape, EscapeBodyText};
 use crate::html::format::Buffer;
 use crate::html::highlight;
@@ -97,6 +98,8 @@ pub struct Markdown<'a> {
     /// Offset at which we render headings.
     /// E.g. if `heading_offset: HeadingOffset::H2`, then `# something` renders an `<h2>`.
     pub heading_offset: HeadingOffset,
+    /// The outcome of the doctests, to show under the code blocks they were written in.
+    pub doctest_results: Option<&'a DocTestResults>,
 }
 /// A struct like `Markdown` that renders the markdown with a table of contents.
 pub(crate) struct MarkdownWithToc<'a> {
@@ -138,13 +141,13 @@ pub(crate) fn as_bool(self) -> bool {
 /// Controls whether a line will be hidden or shown in HTML output.
 ///
 /// All lines are used in documentation tests.
-enum Line<'a> {
+pub(crate) enum Line<'a> {
     Hidden(&'a str),
     Shown(Cow<'a, str>),
 }
 
 impl<'a> Line<'a> {
-    fn for_html(self) -> Option<Cow<'a, str>> {
+    pub(crate) fn for_html(self) -> Option<Cow<'a, str>> {
         match self {
             Line::Shown(l) => Some(l),
             Line::Hidden(_) => None,
@@ -164,7 +167,7 @@ fn for_code(self) -> Cow<'a, str> {
 // is done in the single # case. This inconsistency seems okay, if non-ideal. In
 // order to fix it we'd have to iterate to find the first non-# character, and
 // then reallocate to remove it; which would make us return a String.
-fn map_line(s: &str) -> Line<'_> {
+pub(crate) fn map_line(s: &str) -> Line<'_> {
     let trimmed = s.trim();
     if trimmed.starts_with("##") {
         Line::Shown(Cow::Owned(s.replacen("##", "#", 1)))
@@ -206,6 +209,7 @@ struct CodeBlocks<'p, 'a, I: Iterator<Item = Event<'a>>> {
     // Information about the playground if a URL has been specified, containing an
     // optional crate name and the URL.
     playground: &'p Option<Playground>,
+    doctest_results: Option<&'p DocTestResults>,
 }
 
 impl<'p, 'a, I: Iterator<Item = Event<'a>>> CodeBlocks<'p, 'a, I> {
@@ -214,8 +218,15 @@ fn new(
         error_codes: ErrorCodes,
         edition: Edition,
         playground: &'p Option<Playground>,
+        doctest_results: Option<&'p DocTestResults>,
     ) -> Self {
-        CodeBlocks { inner: iter, check_error_codes: error_codes, edition, playground }
+        CodeBlocks {
+            inner: iter,
+            check_error_codes: error_codes,
+            edition,
+            playground,
+            doctest_results,
+        }
     }
 }
 
@@ -274,6 +285,13 @@ fn next(&mut self) -> Option<Self::Item> {
 
         let lines = original_text.lines().filter_map(|l| map_line(l).for_html());
         let text = lines.intersperse("\n".into()).collect::<String>();
+        let code = || {
+            original_text
+                .lines()
+                .map(|l| map_line(l).for_code())
+                .intersperse("\n".into())
+                .collect::<String>()
+        };
 
         let explicit_edition = edition.is_some();
         let edition = edition.unwrap_or(self.edition);
@@ -284,11 +302,7 @@ fn next(&mut self) -> Option<Self::Item> {
             if url.is_empty() {
                 return None;
             }
-            let test = original_text
-                .lines()
-                .map(|l| map_line(l).for_code())
-                .intersperse("\n".into())
-                .collect::<String>();
+            let test = code();
             let krate = krate.as_ref().map(|s| s.as_str());
 
             // FIXME: separate out the code to make a code block into runnable code
@@ -299,6 +313,7 @@ fn next(&mut self) -> Option<Self::Item> {
                 insert_indent_space: true,
                 attrs: vec![],
                 args_file: PathBuf::new(),
+                results: None,
             };
             let doctest = doctest::DocTestBuilder::new(&test, krate, edition, false, None, None);
             let (test, _) = doctest.generate_unique_doctest(&test, false, &opts, krate);
@@ -337,10 +352,41 @@ fn next(&mut self) -> Option<Self::Item> {
             playground_button.as_deref(),
             &added_classes,
         );
+        if let Some(result) = self.doctest_results.and_then(|results| results.get(&code())) {
+            render_doctest_result(&mut s, result);
+        }
         Some(Event::Html(s.into_inner().into()))
     }
 }
 
+/// Renders the outcome of a doctest, and what it printed, under its code block.
+fn render_doctest_result(s: &mut Buffer, result: &DocTestResult) {
+    let (class, badge) = match result.outcome {
+        DocTestOutcome::Passed => ("passed", "Test passed"),
+        DocTestOutcome::Compiled => ("compiled", "Test compiled"),
+        DocTestOutcome::Failed => ("failed", "Test failed"),
+    };
+    write!(s, "<div class=\"doctest-result {class}\"><span class=\"badge\">{badge}</span>");
+    if let Some(failure) = &result.failure {
+        write!(s, " <span class=\"failure\">{}</span>", Escape(failure));
+    }
+    if let Some(message) = &result.panic_message {
+        write!(
+            s,
+            "<div class=\"panic-message\">Panicked with: <code>{}</code></div>",
+            Escape(message)
+        );
+    }
+    if !result.stdout.is_empty() {
+        write!(
+            s,
+            "<pre class=\"doctest-output\"><code>{}</code></pre>",
+            Escape(result.stdout.strip_suffix('\n').unwrap_or(&result.stdout))
+        );
+    }
+    s.write_str("</div>");
+}
+
 /// Make headings links with anchor IDs and build up TOC.
 struct LinkReplacer<'a, I: Iterator<Item = Event<'a>>> {
     inner: I,
@@ -1125,7 +1171,7 @@ fn default() -> Self {
 }
 
 impl LangString {
-    fn parse_without_check(
+    pub(crate) fn parse_without_check(
         string: &str,
         allow_error_code_check: ErrorCodes,
         enable_per_target_ignores: bool,
@@ -1311,6 +1357,7 @@ pub fn into_string(self) -> String {
             edition,
             playground,
             heading_offset,
+            doctest_results,
         } = self;
 
         // This is actually common enough to special-case
@@ -1334,7 +1381,7 @@ pub fn into_string(self) -> String {
             let p = footnotes::Footnotes::new(p, existing_footnotes);
             let p = LinkReplacer::new(p.map(|(ev, _)| ev), links);
             let p = TableWrapper::new(p);
-            let p = CodeBlocks::new(p, codes, edition, playground);
+            let p = CodeBlocks::new(p, codes, edition, playground, doctest_results);
             html::push_html(&mut s, p);
         });
 
@@ -1369,7 +1416,7 @@ pub(crate) fn into_parts(self) -> (Toc, String) {
             let p = HeadingLinks::new(p, Some(&mut toc), ids, HeadingOffset::H1);
             let p = footnotes::Footnotes::new(p, existing_footnotes);
             let p = TableWrapper::new(p.map(|(ev, _)| ev));
-            let p = CodeBlocks::new(p, codes, edition, playground);
+            let p = CodeBlocks::new(p, codes, edition, playground, None);
             html::push_html(&mut s, p);
         });
 
@@ -1,8 +1,10 @@
+use rustc_span::create_default_session_globals_then;
 use rustc_span::edition::{DEFAULT_EDITION, Edition};
 
 use super::{
-    ErrorCodes, HeadingOffset, IdMap, Ignore, LangString, LangStringToken, Markdown,
-    MarkdownItemInfo, TagIterator, find_testable_code, plain_text_summary, short_markdown_summary,
+    DocTestOutcome, DocTestResult, DocTestResults, ErrorCodes, HeadingOffset, IdMap, Ignore,
+    LangString, LangStringToken, Markdown, MarkdownItemInfo, TagIterator, find_testable_code,
+    plain_text_summary, short_markdown_summary,
 };
 
 #[test]
@@ -305,6 +307,7 @@ fn t(input: &str, expect: &str) {
             edition: DEFAULT_EDITION,
             playground: &None,
             heading_offset: HeadingOffset::H2,
+            doctest_results: None,
         }
         .into_string();
         assert_eq!(output, expect, "original: {}", input);
@@ -356,6 +359,7 @@ fn t(map: &mut IdMap, input: &str, expect: &str) {
             edition: DEFAULT_EDITION,
             playground: &None,
             heading_offset: HeadingOffset::H2,
+            doctest_results: None,
         }
         .into_string();
         assert_eq!(output, expect, "original: {}", input);
@@ -504,6 +508,7 @@ fn t(input: &str, expect: &str) {
             edition: DEFAULT_EDITION,
             playground: &None,
             heading_offset: HeadingOffset::H2,
+            doctest_results: None,
         }
         .into_string();
         assert_eq!(output, expect, "original: {}", input);
@@ -534,3 +539,51 @@ fn t(input: &str, expect: &str) {
 # hello</code></pre></div>",
     );
 }
+
+#[test]
+fn test_doctest_results() {
+    create_default_session_globals_then(|| {
+        let result =
+            |code: &str, outcome, stdout: &str, panic_message: Option<&str>| DocTestResult {
+                name: String::new(),
+                code: code.to_owned(),
+                outcome,
+                stdout: stdout.to_owned(),
+                panic_message: panic_message.map(str::to_owned),
+                failure: None,
+            };
+        let results: DocTestResults = [
+            result("let x = 1;\nprintln!(\"{x}\");", DocTestOutcome::Passed, "1\n", None),
+            result("panic!(\"<oh no>\");", DocTestOutcome::Passed, "", Some("<oh no>")),
+        ]
+        .into_iter()
+        .collect();
+        let render = |input: &str| {
+            Markdown {
+                content: input,
+                links: &[],
+                ids: &mut IdMap::new(),
+                error_codes: ErrorCodes::Yes,
+                edition: DEFAULT_EDITION,
+                playground: &None,
+                heading_offset: HeadingOffset::H2,
+                doctest_results: Some(&results),
+            }
+            .into_string()
+        };
+
+        // Hidden lines are part of the tested code.
+        let output = render("```\n# let x = 1;\nprintln!(\"{x}\");\n```");
+        assert!(output.ends_with(
+            "<div class=\"doctest-result passed\"><span class=\"badge\">Test passed</span>\
+             <pre class=\"doctest-output\"><code>1</code></pre></div>"
+        ));
+        let output = render("```should_panic\npanic!(\"<oh no>\");\n```");
+        assert!(output.ends_with(
+            "<div class=\"doctest-result passed\"><span class=\"badge\">Test passed</span>\
+             <div class=\"panic-message\">Panicked with: <code>&lt;oh no&gt;</code></div></div>"
+        ));
+        let output = render("```\nprintln!(\"untested\");\n```");
+        assert!(!output.contains("doctest-result"));
+    });
+}
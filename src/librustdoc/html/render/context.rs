@@ -13,6 +13,7 @@
 use rustc_span::{FileName, Symbol, sym};
 use tracing::info;
 
+use super::diagrams::Diagrams;
 use super::print_item::{full_path, item_path, print_item};
 use super::sidebar::{ModuleLike, Sidebar, print_sidebar, sidebar_module_like};
 use super::{AllTypes, LinkFromSrc, StylePath, collect_spans_and_sources, scrape_examples_help};
@@ -21,6 +22,7 @@
 use crate::clean::{self, ExternalCrate};
 use crate::config::{ModuleSorting, RenderOptions, ShouldMerge};
 use crate::docfs::{DocFS, PathError};
+use crate::doctest::DocTestResults;
 use crate::error::Error;
 use crate::formats::FormatRenderer;
 use crate::formats::cache::Cache;
@@ -141,9 +143,13 @@ pub(crate) struct SharedContext<'tcx> {
     /// The [`Cache`] used during rendering.
     pub(crate) cache: Cache,
     pub(crate) call_locations: AllCallLocations,
+    /// The outcome of the doctests of a previous `--test` run, shown under each example.
+    pub(crate) doctest_results: DocTestResults,
     /// Controls whether we read / write to cci files in the doc root. Defaults read=true,
     /// write=true
     should_merge: ShouldMerge,
+    /// `Some` if the diagrams pages are generated (`--generate-diagrams`).
+    pub(super) diagrams: Option<Diagrams>,
 }
 
 impl SharedContext<'_> {
@@ -480,8 +486,10 @@ fn init(
             show_type_layout,
             generate_link_to_definition,
             call_locations,
+            doctest_results,
             no_emit_shared,
             html_no_source,
+            generate_diagrams,
             ..
         } = options;
 
@@ -567,7 +575,9 @@ fn init(
             span_correspondence_map: matches,
             cache,
             call_locations,
+            doctest_results,
             should_merge: options.should_merge,
+            diagrams: generate_diagrams.then(Diagrams::default),
         };
 
         let dst = output;
@@ -798,6 +808,12 @@ fn mod_item_in(&mut self, item: &clean::Item) -> Result<(), Error> {
                 let joint_dst = self.dst.join("index.html");
                 self.shared.fs.write(joint_dst, buf)?;
             }
+            if item.is_crate()
+                && let Some(ref diagrams) = self.shared.diagrams
+            {
+                self.shared.ensure_dir(&self.dst)?;
+                diagrams.render_crate(self, item)?;
+            }
         }
         if !self.info.is_inside_inlined_module {
             if let Some(def_id) = item.def_id()
@@ -858,6 +874,11 @@ fn item(&mut self, item: clean::Item) -> Result<(), Error> {
 
             if !self.info.render_redirect_pages {
                 self.shared.all.borrow_mut().append(full_path(self, &item), &item_type);
+                if let clean::TraitItem(_) = item.kind
+                    && let Some(ref diagrams) = self.shared.diagrams
+                {
+                    diagrams.render_trait(self, &item)?;
+                }
             }
             // If the item is a macro, redirect from the old macro URL (with !)
             // to the new one (without).
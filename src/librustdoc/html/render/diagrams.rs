@@ -0,0 +1,388 @@
+//! Graphviz diagrams of the traits and modules of a crate.
+//!
+//! When `--generate-diagrams` is passed, each trait gets a `trait.{name}.diagrams.html` page next
+//! to its own page, showing its supertraits and its implementors, and the crate gets a
+//! `diagrams.html` page showing its module structure. The graphs are built from the cache and
+//! written as DOT files with `rustc_graphviz`, and they are turned into SVG images embedded in the
+//! pages by running Graphviz's `dot` program. If it can't be run, the pages show the DOT sources
+//! instead.
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
+use rustc_graphviz as dot;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_span::sym;
+
+use super::Context;
+use crate::clean::utils::has_doc_flag;
+use crate::clean::{self, ImportSource};
+use crate::error::Error;
+use crate::formats::item_type::ItemType;
+use crate::formats::print::{self, Printer};
+use crate::html::escape::Escape;
+use crate::html::format::{Buffer, href, join_with_double_colon};
+use crate::html::layout;
+
+/// The state of the diagrams generation, which is only enabled by `--generate-diagrams`.
+#[derive(Default)]
+pub(crate) struct Diagrams {
+    /// Set when `dot` couldn't be run, so that it is reported only once.
+    dot_unavailable: Cell<bool>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum EdgeKind {
+    /// A supertrait bound, an implementation or a submodule.
+    Direct,
+    /// A blanket implementation or a re-export.
+    Indirect,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Edge {
+    source: usize,
+    target: usize,
+    kind: EdgeKind,
+}
+
+/// A graph of items, each node being labelled with the path of an item and linking to its page.
+struct Graph {
+    name: &'static str,
+    /// The labels of the nodes and the links to their pages.
+    nodes: FxIndexMap<String, Option<String>>,
+    edges: FxIndexSet<Edge>,
+    /// The node of the item the diagram is about.
+    root: usize,
+}
+
+impl Graph {
+    fn new(name: &'static str) -> Self {
+        Graph { name, nodes: FxIndexMap::default(), edges: FxIndexSet::default(), root: 0 }
+    }
+
+    fn node(&mut self, label: String, href: Option<String>) -> usize {
+        self.nodes.insert_full(label, href).0
+    }
+
+    /// Adds the node of the item `did`, linking to its page if it has one.
+    fn item(&mut self, cx: &Context<'_>, did: DefId) -> usize {
+        match href(did, cx) {
+            Ok((url, _, fqp)) => self.node(join_with_double_colon(&fqp), Some(url)),
+            Err(_) => self.node(cx.tcx().def_path_str(did), None),
+        }
+    }
+
+    fn edge(&mut self, source: usize, target: usize, kind: EdgeKind) {
+        if source != target {
+            self.edges.insert(Edge { source, target, kind });
+        }
+    }
+
+    fn to_dot(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        dot::render_opts(self, &mut out, &[dot::RenderOption::NoEdgeLabels]).unwrap();
+        out
+    }
+}
+
+impl<'a> dot::Labeller<'a> for Graph {
+    type Node = usize;
+    type Edge = Edge;
+
+    fn graph_id(&'a self) -> dot::Id<'a> {
+        dot::Id::new(self.name).unwrap()
+    }
+
+    fn node_id(&'a self, n: &usize) -> dot::Id<'a> {
+        dot::Id::new(format!("n{n}")).unwrap()
+    }
+
+    fn node_shape(&'a self, _: &usize) -> Option<dot::LabelText<'a>> {
+        Some(dot::LabelText::LabelStr("box".into()))
+    }
+
+    fn node_label(&'a self, n: &usize) -> dot::LabelText<'a> {
+        let (label, href) = self.nodes.get_index(*n).unwrap();
+        let label = dot::escape_html(label);
+        let label = if *n == self.root { format!("<B>{label}</B>") } else { label };
+        // Only HTML labels can link to pages in the SVG output.
+        dot::LabelText::HtmlStr(Cow::Owned(match href {
+            Some(href) => format!(
+                "<TABLE BORDER=\"0\" CELLPADDING=\"0\" HREF=\"{}\"><TR><TD>{label}</TD></TR></TABLE>",
+                dot::escape_html(href),
+            ),
+            None => label,
+        }))
+    }
+
+    fn node_style(&'a self, n: &usize) -> dot::Style {
+        if self.nodes[*n].is_some() { dot::Style::Rounded } else { dot::Style::Dashed }
+    }
+
+    fn edge_style(&'a self, e: &Edge) -> dot::Style {
+        match e.kind {
+            EdgeKind::Direct => dot::Style::Solid,
+            EdgeKind::Indirect => dot::Style::Dashed,
+        }
+    }
+}
+
+impl<'a> dot::GraphWalk<'a> for Graph {
+    type Node = usize;
+    type Edge = Edge;
+
+    fn nodes(&'a self) -> dot::Nodes<'a, usize> {
+        (0..self.nodes.len()).collect()
+    }
+
+    fn edges(&'a self) -> dot::Edges<'a, Edge> {
+        self.edges.iter().copied().collect()
+    }
+
+    fn source(&'a self, edge: &Edge) -> usize {
+        edge.source
+    }
+
+    fn target(&'a self, edge: &Edge) -> usize {
+        edge.target
+    }
+}
+
+/// The direct supertraits of the trait `did`.
+fn supertraits<'tcx>(cx: &Context<'tcx>, did: DefId) -> impl Iterator<Item = DefId> + 'tcx {
+    cx.tcx()
+        .explicit_super_predicates_of(did)
+        .iter_identity_copied()
+        .filter_map(|(pred, _)| Some(pred.as_trait_clause()?.def_id()))
+}
+
+/// The supertraits of the trait `did` and the traits of the crate it is a supertrait of, with
+/// edges going from each trait to its supertraits.
+fn supertrait_graph(cx: &Context<'_>, did: DefId) -> Graph {
+    let mut graph = Graph::new("supertraits");
+    graph.root = graph.item(cx, did);
+    let mut stack = vec![(did, graph.root)];
+    let mut seen = FxIndexSet::default();
+    while let Some((did, node)) = stack.pop() {
+        if !seen.insert(did) {
+            continue;
+        }
+        for supertrait in supertraits(cx, did) {
+            let target = graph.item(cx, supertrait);
+            graph.edge(node, target, EdgeKind::Direct);
+            stack.push((supertrait, target));
+        }
+    }
+    for &subtrait in cx.cache().traits.keys() {
+        if supertraits(cx, subtrait).any(|supertrait| supertrait == did) {
+            let source = graph.item(cx, subtrait);
+            graph.edge(source, graph.root, EdgeKind::Direct);
+        }
+    }
+    graph
+}
+
+/// The types implementing the trait `did`, with edges going from each type to the trait. Blanket
+/// implementations are shown with dashed edges, and auto trait implementations are left out.
+fn implementor_graph(cx: &Context<'_>, did: DefId) -> Graph {
+    let mut graph = Graph::new("implementors");
+    graph.root = graph.item(cx, did);
+    let Some(implementors) = cx.cache().implementors.get(&did) else { return graph };
+    for implementor in implementors {
+        let impl_ = implementor.inner_impl();
+        if impl_.kind.is_auto() {
+            continue;
+        }
+        let (source, kind) =
+            if impl_.kind.is_blanket() || matches!(impl_.for_, clean::Type::Generic(_)) {
+                // There is no type to show for blanket implementations, so show their header instead.
+                (graph.node(print::impl_header(cx.tcx(), impl_), None), EdgeKind::Indirect)
+            } else {
+                let mut p = Printer::new(cx.tcx());
+                p.ty(&impl_.for_);
+                let href = impl_.for_.def_id(cx.cache()).and_then(|did| href(did, cx).ok());
+                (graph.node(p.finish(), href.map(|(url, ..)| url)), EdgeKind::Direct)
+            };
+        graph.edge(source, graph.root, kind);
+    }
+    graph
+}
+
+/// The modules of the crate, with edges going from each module to its submodules, and dashed
+/// edges going from each module to the modules of the items it re-exports.
+fn module_graph(cx: &Context<'_>, krate: &clean::Item) -> Graph {
+    fn add_modules(cx: &Context<'_>, graph: &mut Graph, module: &clean::Item) -> usize {
+        let node = graph.item(cx, module.item_id.expect_def_id());
+        let clean::ModuleItem(ref m) = module.kind else { unreachable!() };
+        for item in &m.items {
+            if item.is_mod() && !item.is_stripped() {
+                let child = add_modules(cx, graph, item);
+                graph.edge(node, child, EdgeKind::Direct);
+            }
+        }
+        node
+    }
+
+    /// The module of the local item `did`, if it is documented.
+    fn module_of(cx: &Context<'_>, did: DefId) -> Option<DefId> {
+        let (_, ty) = cx.cache().paths.get(&did)?;
+        let module = if *ty == ItemType::Module {
+            did
+        } else {
+            cx.tcx().parent_module_from_def_id(did.as_local()?).to_def_id()
+        };
+        cx.cache().paths.contains_key(&module).then_some(module)
+    }
+
+    fn add_reexports(cx: &Context<'_>, graph: &mut Graph, module: &clean::Item) {
+        let did = module.item_id.expect_def_id();
+        let node = graph.item(cx, did);
+        let clean::ModuleItem(ref m) = module.kind else { unreachable!() };
+        for item in &m.items {
+            let target = match item.kind {
+                clean::ModuleItem(_) => {
+                    add_reexports(cx, graph, item);
+                    continue;
+                }
+                clean::ImportItem(clean::Import {
+                    source: ImportSource { did: Some(target), .. },
+                    ..
+                }) => target,
+                // Inlined re-exports.
+                _ => match item.item_id.as_def_id() {
+                    Some(target) if item.inline_stmt_id.is_some() => target,
+                    _ => continue,
+                },
+            };
+            if target.is_local()
+                && let Some(target_module) = module_of(cx, target)
+                && target_module != did
+            {
+                let target = graph.item(cx, target_module);
+                graph.edge(node, target, EdgeKind::Indirect);
+            }
+        }
+    }
+
+    let mut graph = Graph::new("modules");
+    graph.root = add_modules(cx, &mut graph, krate);
+    add_reexports(cx, &mut graph, krate);
+    graph
+}
+
+impl Diagrams {
+    /// Renders `dot` to an SVG image with Graphviz, reporting it if `dot` can't be run.
+    fn to_svg(&self, cx: &Context<'_>, dot: &[u8]) -> Option<String> {
+        let dcx = cx.tcx().dcx();
+        let child = Command::new("dot")
+            .arg("-Tsvg")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(err) => {
+                if !self.dot_unavailable.replace(true) {
+                    dcx.struct_warn(format!("could not run `dot` to render diagrams: {err}"))
+                        .with_note("the diagrams are written as DOT files only")
+                        .with_help("install Graphviz to render them as SVG images")
+                        .emit();
+                }
+                return None;
+            }
+        };
+        child.stdin.take().unwrap().write_all(dot).ok()?;
+        let output = child.wait_with_output().ok()?;
+        if !output.status.success() {
+            dcx.warn(format!(
+                "`dot` failed to render a diagram: {}",
+                String::from_utf8_lossy(&output.stderr).trim(),
+            ));
+            return None;
+        }
+        let svg = String::from_utf8(output.stdout).ok()?;
+        // Remove the XML declaration and doctype so that the image can be inlined in the page.
+        svg.find("<svg").map(|start| svg[start..].to_owned())
+    }
+
+    /// Writes the page `{prefix}diagrams.html` in the current directory, with a section for each
+    /// of the `graphs`, and their DOT sources next to it.
+    fn write_page(
+        &self,
+        cx: &Context<'_>,
+        prefix: &str,
+        title: &str,
+        heading: &str,
+        graphs: &[(&str, Graph)],
+    ) -> Result<(), Error> {
+        let mut content = Buffer::html();
+        write!(content, "<div class=\"main-heading\"><h1>{heading}</h1></div>");
+        for (section, graph) in graphs {
+            let source = graph.to_dot();
+            let file_name = format!("{prefix}{}.dot", graph.name);
+            cx.shared.fs.write(cx.dst.join(&file_name), source.clone())?;
+            super::write_section_heading(&mut content, section, graph.name, None, "");
+            match self.to_svg(cx, &source) {
+                Some(svg) => write!(content, "<div class=\"diagram\">{svg}</div>"),
+                None => write!(
+                    content,
+                    "<pre class=\"diagram\">{}</pre>",
+                    Escape(&String::from_utf8_lossy(&source)),
+                ),
+            }
+            write!(content, "<p><a href=\"{file_name}\">DOT source</a></p>");
+        }
+
+        let page = layout::Page {
+            title,
+            css_class: "mod sys",
+            root_path: &cx.root_path(),
+            static_root_path: cx.shared.static_root_path.as_deref(),
+            description: title,
+            resource_suffix: &cx.shared.resource_suffix,
+            rust_logo: has_doc_flag(cx.tcx(), LOCAL_CRATE.as_def_id(), sym::rust_logo),
+        };
+        let v = layout::render(
+            &cx.shared.layout,
+            &page,
+            "",
+            |buf: &mut Buffer| buf.push_buffer(content),
+            &cx.shared.style_files,
+        );
+        cx.shared.fs.write(cx.dst.join(format!("{prefix}diagrams.html")), v)
+    }
+
+    /// Writes the diagrams page of the trait `item`.
+    pub(super) fn render_trait(&self, cx: &Context<'_>, item: &clean::Item) -> Result<(), Error> {
+        let did = item.item_id.expect_def_id();
+        let name = item.name.unwrap();
+        let path = join_with_double_colon(&[&cx.current[..], &[name]].concat());
+        self.write_page(
+            cx,
+            &format!("trait.{name}."),
+            &format!("Diagrams of {path} - Rust"),
+            &format!("Diagrams of trait <a class=\"trait\" href=\"trait.{name}.html\">{name}</a>"),
+            &[
+                ("Supertraits", supertrait_graph(cx, did)),
+                ("Implementors", implementor_graph(cx, did)),
+            ],
+        )
+    }
+
+    /// Writes the diagrams page of the crate `krate`.
+    pub(super) fn render_crate(&self, cx: &Context<'_>, krate: &clean::Item) -> Result<(), Error> {
+        let name = krate.name.unwrap();
+        self.write_page(
+            cx,
+            "",
+            &format!("Diagrams of {name} - Rust"),
+            &format!("Diagrams of crate <a class=\"mod\" href=\"index.html\">{name}</a>"),
+            &[("Modules", module_graph(cx, krate))],
+        )
+    }
+}
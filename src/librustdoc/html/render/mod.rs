@@ -29,6 +29,7 @@
 mod tests;
 
 mod context;
+mod diagrams;
 mod ordered_json;
 mod print_item;
 pub(crate) mod sidebar;
@@ -59,6 +60,7 @@
 use tracing::{debug, info};
 
 pub(crate) use self::context::*;
+pub(crate) use self::print_item::compare_names;
 pub(crate) use self::span_map::{LinkFromSrc, collect_spans_and_sources};
 pub(crate) use self::write_shared::*;
 use crate::clean::{self, ItemId, RenderedLink};
@@ -497,6 +499,7 @@ fn scrape_examples_help(shared: &SharedContext<'_>) -> String {
             edition: shared.edition(),
             playground: &shared.playground,
             heading_offset: HeadingOffset::H1,
+            doctest_results: None,
         }
         .into_string()
     )
@@ -541,6 +544,7 @@ fn render_markdown<'a, 'cx: 'a>(
                 edition: cx.shared.edition(),
                 playground: &cx.shared.playground,
                 heading_offset,
+                doctest_results: Some(&cx.shared.doctest_results),
             }
             .into_string()
         )
@@ -1968,6 +1972,7 @@ fn render_default_items(
                     edition: cx.shared.edition(),
                     playground: &cx.shared.playground,
                     heading_offset: HeadingOffset::H4,
+                    doctest_results: Some(&cx.shared.doctest_results),
                 }
                 .into_string()
             );
@@ -2241,7 +2246,7 @@ pub(crate) enum ItemSection {
 }
 
 impl ItemSection {
-    const ALL: &'static [Self] = {
+    pub(crate) const ALL: &'static [Self] = {
         use ItemSection::*;
         // NOTE: The order here affects the order in the UI.
         // Keep this synchronized with addSidebarItems in main.js
@@ -2302,7 +2307,7 @@ fn id(self) -> &'static str {
         }
     }
 
-    fn name(self) -> &'static str {
+    pub(crate) fn name(self) -> &'static str {
         match self {
             Self::Reexports => "Re-exports",
             Self::Modules => "Modules",
@@ -2332,7 +2337,7 @@ fn name(self) -> &'static str {
     }
 }
 
-fn item_ty_to_section(ty: ItemType) -> ItemSection {
+pub(crate) fn item_ty_to_section(ty: ItemType) -> ItemSection {
     match ty {
         ItemType::ExternCrate | ItemType::Import => ItemSection::Reexports,
         ItemType::Module => ItemSection::Modules,
@@ -529,6 +529,16 @@ fn cmp(i1: &clean::Item, i2: &clean::Item, tcx: TyCtxt<'_>) -> Ordering {
     if last_section.is_some() {
         w.write_str(ITEM_TABLE_CLOSE);
     }
+
+    if item.is_crate() && cx.shared.diagrams.is_some() {
+        write_section_heading(
+            w,
+            "Diagrams",
+            &cx.derive_id("diagrams"),
+            None,
+            "<p><a href=\"diagrams.html\">Module structure</a></p>",
+        );
+    }
 }
 
 /// Render the stability, deprecation and portability tags that are displayed in the item's summary
@@ -1161,6 +1171,19 @@ fn trait_item(w: &mut Buffer, cx: &Context<'_>, m: &clean::Item, t: &clean::Item
         "<script src=\"{src}\"{extern_before}{extern_crates}{extern_after} async></script>",
         src = js_src_path.finish(),
     );
+
+    if cx.shared.diagrams.is_some() {
+        write_section_heading(
+            w,
+            "Diagrams",
+            &cx.derive_id("diagrams"),
+            None,
+            format_args!(
+                "<p><a href=\"trait.{name}.diagrams.html\">Supertraits and implementors</a></p>",
+                name = it.name.unwrap(),
+            ),
+        );
+    }
 }
 
 fn item_trait_alias(
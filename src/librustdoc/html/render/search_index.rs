@@ -1,4 +1,5 @@
 pub(crate) mod encode;
+mod offline;
 
 use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, VecDeque};
@@ -22,6 +23,10 @@
 use crate::html::render::ordered_json::OrderedJson;
 use crate::html::render::{self, IndexItem, IndexItemFunctionType, RenderType, RenderTypeId};
 
+/// An entry of the paths of the search index: the item type, the display path, the re-exported
+/// internal path, and whether the type is `#[doc(search_unbox)]`.
+type CratePath = (ItemType, Vec<Symbol>, Option<Vec<Symbol>>, bool);
+
 /// The serialized search description sharded version
 ///
 /// The `index` is a JSON-encoded list of names and other information.
@@ -50,6 +55,8 @@
 pub(crate) struct SerializedSearchIndex {
     pub(crate) index: OrderedJson,
     pub(crate) desc: Vec<(usize, String)>,
+    /// The same index in the format of the `rustdoc-search-index` crate, if requested.
+    pub(crate) offline: Option<rustdoc_search_index::Crate>,
 }
 
 const DESC_INDEX_SHARD_LEN: usize = 128 * 1024;
@@ -59,14 +66,14 @@ pub(crate) fn build_index(
     krate: &clean::Crate,
     cache: &mut Cache,
     tcx: TyCtxt<'_>,
+    build_offline_index: bool,
 ) -> SerializedSearchIndex {
     // Maps from ID to position in the `crate_paths` array.
     let mut itemid_to_pathid = FxHashMap::default();
     let mut primitives = FxHashMap::default();
     let mut associated_types = FxHashMap::default();
 
-    // item type, display path, re-exported internal path
-    let mut crate_paths: Vec<(ItemType, Vec<Symbol>, Option<Vec<Symbol>>, bool)> = vec![];
+    let mut crate_paths: Vec<CratePath> = vec![];
 
     // Attach all orphan items to the type's definition if the type
     // has since been learned.
@@ -132,7 +139,7 @@ fn insert_into_map<F: std::hash::Hash + Eq>(
             map: &mut FxHashMap<F, isize>,
             itemid: F,
             lastpathid: &mut isize,
-            crate_paths: &mut Vec<(ItemType, Vec<Symbol>, Option<Vec<Symbol>>, bool)>,
+            crate_paths: &mut Vec<CratePath>,
             item_type: ItemType,
             path: &[Symbol],
             exact_path: Option<&[Symbol]>,
@@ -162,7 +169,7 @@ fn convert_render_type_id(
             primitives: &mut FxHashMap<Symbol, isize>,
             associated_types: &mut FxHashMap<Symbol, isize>,
             lastpathid: &mut isize,
-            crate_paths: &mut Vec<(ItemType, Vec<Symbol>, Option<Vec<Symbol>>, bool)>,
+            crate_paths: &mut Vec<CratePath>,
             tcx: TyCtxt<'_>,
         ) -> Option<RenderTypeId> {
             use crate::clean::PrimitiveType;
@@ -250,7 +257,7 @@ fn convert_render_type(
             primitives: &mut FxHashMap<Symbol, isize>,
             associated_types: &mut FxHashMap<Symbol, isize>,
             lastpathid: &mut isize,
-            crate_paths: &mut Vec<(ItemType, Vec<Symbol>, Option<Vec<Symbol>>, bool)>,
+            crate_paths: &mut Vec<CratePath>,
             tcx: TyCtxt<'_>,
         ) {
             if let Some(generics) = &mut ty.generics {
@@ -462,7 +469,7 @@ fn convert_render_type(
 
     struct CrateData<'a> {
         items: Vec<&'a IndexItem>,
-        paths: Vec<(ItemType, Vec<Symbol>, Option<Vec<Symbol>>, bool)>,
+        paths: Vec<CratePath>,
         // The String is alias name and the vec is the list of the elements with this alias.
         //
         // To be noted: the `usize` elements are indexes to `items`.
@@ -773,10 +780,13 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         desc.iter().map(|(len, _)| *len).sum::<usize>() + empty_desc.len()
     );
 
+    let crate_name = krate.name(tcx);
+    let offline = build_offline_index
+        .then(|| offline::build_offline_index(crate_name, &crate_doc, &crate_items, &crate_paths));
+
     // The index, which is actually used to search, is JSON
     // It uses `JSON.parse(..)` to actually load, since JSON
     // parses faster than the full JavaScript syntax.
-    let crate_name = krate.name(tcx);
     let data = CrateData {
         items: crate_items,
         paths: crate_paths,
@@ -789,7 +799,7 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         OrderedJson::serialize(crate_name.as_str()).unwrap(),
         OrderedJson::serialize(data).unwrap(),
     ]);
-    SerializedSearchIndex { index, desc }
+    SerializedSearchIndex { index, desc, offline }
 }
 
 pub(crate) fn get_function_type_for_search(
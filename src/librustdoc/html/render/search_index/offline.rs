@@ -0,0 +1,123 @@
+//! Conversion of the search index into the standalone format defined by the
+//! `rustdoc-search-index` crate, which is written when `--offline-search-index` is passed.
+
+use rustc_span::Symbol;
+use rustdoc_search_index as offline;
+
+use super::CratePath;
+use crate::formats::item_type::ItemType;
+use crate::html::format::join_with_double_colon;
+use crate::html::render::{IndexItem, IndexItemFunctionType, RenderType, RenderTypeId};
+
+pub(super) fn build_offline_index(
+    crate_name: Symbol,
+    crate_doc: &str,
+    items: &[&IndexItem],
+    paths: &[CratePath],
+) -> offline::Crate {
+    let paths = paths
+        .iter()
+        .map(|&(ty, ref path, _, search_unbox)| {
+            let (name, module) = path.split_last().unwrap();
+            offline::Path {
+                kind: item_kind(ty),
+                name: name.to_string(),
+                module: join_with_double_colon(module),
+                search_unbox,
+            }
+        })
+        .collect();
+
+    let mut module = "";
+    let items = items
+        .iter()
+        .map(|item| {
+            // The JSON index leaves out the path of items that are in the same
+            // module as the item before them, so restore it here.
+            if !item.path.is_empty() {
+                module = &item.path;
+            }
+            offline::Item {
+                kind: item_kind(item.ty),
+                name: item.name.to_string(),
+                module: module.to_owned(),
+                desc: item.desc.clone(),
+                parent: item.parent_idx.map(|idx| u32::try_from(idx).unwrap()),
+                deprecated: item.deprecation.is_some(),
+                aliases: item.aliases.iter().map(|alias| alias.to_string()).collect(),
+                signature: item.search_type.as_ref().and_then(convert_function_type),
+            }
+        })
+        .collect();
+
+    offline::Crate { name: crate_name.to_string(), doc: crate_doc.to_owned(), paths, items }
+}
+
+fn convert_function_type(ty: &IndexItemFunctionType) -> Option<offline::FunctionSignature> {
+    // Like `IndexItemFunctionType::write_to_string`, drop signatures that mention a type
+    // we couldn't figure out, since they would match too many queries.
+    if ty.inputs.iter().chain(ty.output.iter()).any(|i| i.id.is_none() && i.generics.is_none()) {
+        return None;
+    }
+    Some(offline::FunctionSignature {
+        inputs: convert_types(&ty.inputs),
+        output: convert_types(&ty.output),
+        where_clause: ty.where_clause.iter().map(|bounds| convert_types(bounds)).collect(),
+        param_names: ty.param_names.iter().map(|name| name.to_string()).collect(),
+    })
+}
+
+fn convert_types(types: &[RenderType]) -> Vec<offline::TypeNode> {
+    types.iter().map(convert_type).collect()
+}
+
+fn convert_type(ty: &RenderType) -> offline::TypeNode {
+    offline::TypeNode {
+        id: ty.id.map_or(offline::TypeId::None, convert_type_id),
+        generics: ty.generics.as_deref().map(convert_types).unwrap_or_default(),
+        bindings: ty
+            .bindings
+            .iter()
+            .flatten()
+            .map(|(id, constraints)| (convert_type_id(*id), convert_types(constraints)))
+            .collect(),
+    }
+}
+
+fn convert_type_id(id: RenderTypeId) -> offline::TypeId {
+    match id {
+        RenderTypeId::Index(idx) if idx >= 0 => offline::TypeId::Path(idx.try_into().unwrap()),
+        RenderTypeId::Index(idx) => offline::TypeId::Generic((-idx - 1).try_into().unwrap()),
+        _ => panic!("must convert render types to indexes before serializing"),
+    }
+}
+
+fn item_kind(ty: ItemType) -> offline::ItemKind {
+    match ty {
+        ItemType::Keyword => offline::ItemKind::Keyword,
+        ItemType::Primitive => offline::ItemKind::Primitive,
+        ItemType::Module => offline::ItemKind::Module,
+        ItemType::ExternCrate => offline::ItemKind::ExternCrate,
+        ItemType::Import => offline::ItemKind::Import,
+        ItemType::Struct => offline::ItemKind::Struct,
+        ItemType::Enum => offline::ItemKind::Enum,
+        ItemType::Function => offline::ItemKind::Function,
+        ItemType::TypeAlias => offline::ItemKind::TypeAlias,
+        ItemType::Static => offline::ItemKind::Static,
+        ItemType::Trait => offline::ItemKind::Trait,
+        ItemType::Impl => offline::ItemKind::Impl,
+        ItemType::TyMethod => offline::ItemKind::TyMethod,
+        ItemType::Method => offline::ItemKind::Method,
+        ItemType::StructField => offline::ItemKind::StructField,
+        ItemType::Variant => offline::ItemKind::Variant,
+        ItemType::Macro => offline::ItemKind::Macro,
+        ItemType::AssocType => offline::ItemKind::AssocType,
+        ItemType::Constant => offline::ItemKind::Constant,
+        ItemType::AssocConst => offline::ItemKind::AssocConst,
+        ItemType::Union => offline::ItemKind::Union,
+        ItemType::ForeignType => offline::ItemKind::ForeignType,
+        ItemType::ProcAttribute => offline::ItemKind::ProcAttribute,
+        ItemType::ProcDerive => offline::ItemKind::ProcDerive,
+        ItemType::TraitAlias => offline::ItemKind::TraitAlias,
+    }
+}
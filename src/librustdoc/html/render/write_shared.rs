@@ -66,8 +66,12 @@ pub(crate) fn write_shared(
     // Write shared runs within a flock; disable thread dispatching of IO temporarily.
     let _lock = try_err!(flock::Lock::new(&lock_file, true, true, true), &lock_file);
 
-    let SerializedSearchIndex { index, desc } = build_index(krate, &mut cx.shared.cache, tcx);
+    let SerializedSearchIndex { index, desc, offline } =
+        build_index(krate, &mut cx.shared.cache, tcx, opt.offline_search_index);
     write_search_desc(cx, krate, &desc)?; // does not need to be merged
+    if let Some(offline) = offline {
+        write_offline_search_index(cx, krate, &offline)?; // does not need to be merged
+    }
 
     let crate_name = krate.name(cx.tcx());
     let crate_name = crate_name.as_str(); // rand
@@ -240,6 +244,23 @@ fn write_search_desc(
     Ok(())
 }
 
+/// Writes the search index read by `src/tools/rustdoc-search` into the crate's directory.
+fn write_offline_search_index(
+    cx: &mut Context<'_>,
+    krate: &Crate,
+    index: &rustdoc_search_index::Crate,
+) -> Result<(), Error> {
+    let crate_name = krate.name(cx.tcx()).to_string();
+    let path = PathBuf::from_iter([
+        &cx.dst,
+        Path::new(&crate_name),
+        Path::new(rustdoc_search_index::FILE_NAME),
+    ]);
+    create_parents(&path)?;
+    try_err!(fs::write(&path, index.encode()), &path);
+    Ok(())
+}
+
 /// Contains pre-rendered contents to insert into the CCI template
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub(crate) struct CrateInfo {
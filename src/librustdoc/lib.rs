@@ -42,6 +42,7 @@
 extern crate rustc_errors;
 extern crate rustc_expand;
 extern crate rustc_feature;
+extern crate rustc_graphviz;
 extern crate rustc_hir;
 extern crate rustc_hir_analysis;
 extern crate rustc_hir_pretty;
@@ -104,6 +105,7 @@ macro_rules! map {
 }
 
 mod clean;
+mod commonmark;
 mod config;
 mod core;
 mod docfs;
@@ -116,6 +118,7 @@ macro_rules! map {
 pub mod html;
 mod json;
 pub(crate) mod lint;
+mod man;
 mod markdown;
 mod passes;
 mod scrape_examples;
@@ -684,6 +687,38 @@ fn opts() -> Vec<RustcOptGroup> {
             "[rust]",
         ),
         opt(Unstable, Flag, "", "html-no-source", "Disable HTML source code pages generation", ""),
+        opt(
+            Unstable,
+            Flag,
+            "",
+            "offline-search-index",
+            "Write a binary search index for each documented crate, for use by `rustdoc-search`",
+            "",
+        ),
+        opt(
+            Unstable,
+            Flag,
+            "",
+            "generate-diagrams",
+            "Generate Graphviz diagrams of the traits and modules of the documented crate",
+            "",
+        ),
+        opt(
+            Unstable,
+            Opt,
+            "",
+            "write-doctest-results",
+            "When running doctests, write the outcome and output of each one to this file",
+            "PATH",
+        ),
+        opt(
+            Unstable,
+            Multi,
+            "",
+            "doctest-results",
+            "Show the doctest results written by `--write-doctest-results` under each example",
+            "PATH",
+        ),
     ]
 }
 
@@ -898,6 +933,17 @@ fn main_args(
                     config::OutputFormat::Json => sess.time("render_json", || {
                         run_renderer::<json::JsonRenderer<'_>>(krate, render_opts, cache, tcx)
                     }),
+                    config::OutputFormat::Markdown => sess.time("render_markdown", || {
+                        run_renderer::<commonmark::MarkdownRenderer<'_>>(
+                            krate,
+                            render_opts,
+                            cache,
+                            tcx,
+                        )
+                    }),
+                    config::OutputFormat::Man => sess.time("render_man", || {
+                        run_renderer::<man::ManRenderer<'_>>(krate, render_opts, cache, tcx)
+                    }),
                 }
             })
         })
@@ -0,0 +1,409 @@
+//! Rustdoc's man page backend
+//!
+//! This module renders the public API of a crate as `man 3` pages written in roff, for the
+//! people using a Rust library through bindings who expect its documentation to be available
+//! with `man`. All the pages are written to a `man3` directory in the output directory, with one
+//! page for each module, function, type and trait. Like the files of the HTML documentation, the
+//! pages are named after the path of the item and its kind, so that a module and a function with
+//! the same name don't overwrite each other: `man3/foo.struct.Bar.3` for the struct `foo::Bar`,
+//! `man3/foo.mod.bar.3` for the module `foo::bar`, and `man3/foo.3` for the crate root. The path
+//! is joined with `.` rather than `::`, which is not allowed in file names on Windows.
+//!
+//! The headings of the documentation of an item are mapped to the sections of its page, with
+//! the conventional sections of Rust documentation (`# Examples`, `# Errors`, `# Safety`, ...)
+//! becoming their man page counterpart.
+
+mod roff;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use rustc_data_structures::fx::FxIndexSet;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Symbol;
+use rustc_span::symbol::kw;
+use tracing::debug;
+
+use self::roff::{escape, quote};
+use crate::clean::{self, ItemLink, RenderedLink};
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::error::Error;
+use crate::formats::cache::Cache;
+use crate::formats::item_type::ItemType;
+use crate::formats::{FormatRenderer, print};
+use crate::html::format::join_with_double_colon;
+use crate::html::markdown::plain_text_summary;
+use crate::html::render::compare_names;
+use crate::try_err;
+
+/// The section of the manual the pages are written for: library calls.
+const SECTION: &str = "3";
+
+pub(crate) struct ManRenderer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    cache: Rc<Cache>,
+    /// The directory all the pages are written to.
+    dst: PathBuf,
+    /// The path of the current module.
+    current: Vec<Symbol>,
+    /// Set below a stripped module, where none of the items, submodules included, get a page.
+    in_stripped_module: bool,
+}
+
+/// Whether items of this type get a page of their own.
+fn has_page(ty: ItemType) -> bool {
+    matches!(
+        ty,
+        ItemType::Module
+            | ItemType::Function
+            | ItemType::Struct
+            | ItemType::Enum
+            | ItemType::Union
+            | ItemType::TypeAlias
+            | ItemType::Trait
+            | ItemType::TraitAlias
+            | ItemType::ForeignType
+    )
+}
+
+/// The name of the page of the item of type `ty` at the path `fqp`, without the section.
+fn page_name(fqp: &[Symbol], ty: ItemType) -> String {
+    let (name, parents) = fqp.split_last().unwrap();
+    if parents.is_empty() && ty == ItemType::Module {
+        return name.to_string();
+    }
+    let mut page = String::new();
+    for parent in parents {
+        write!(page, "{parent}.").unwrap();
+    }
+    write!(page, "{}.{name}", ty.as_str()).unwrap();
+    page
+}
+
+impl ManRenderer<'_> {
+    /// The name of the page of the local item `did`, if it has one.
+    fn page_name(&self, did: DefId) -> Option<String> {
+        let (fqp, ty) = self.cache.paths.get(&did)?;
+        has_page(*ty).then(|| page_name(fqp, *ty))
+    }
+
+    /// Resolves the intra-doc links of `item`. They can't be followed in a man page, but their
+    /// text has to be fixed up.
+    fn links(&self, item: &clean::Item) -> Vec<RenderedLink> {
+        let Some(links) = self.cache.intra_doc_links.get(&item.item_id) else { return vec![] };
+        links
+            .iter()
+            .map(|ItemLink { link, link_text, .. }| RenderedLink {
+                original_text: link.clone(),
+                new_text: link_text.clone(),
+                href: String::new(),
+                tooltip: String::new(),
+            })
+            .collect()
+    }
+
+    /// The first line of the documentation of `item`, with the backticks of inline code removed
+    /// as it can't be highlighted in the name of a page.
+    fn summary(&self, item: &clean::Item) -> String {
+        plain_text_summary(&item.doc_value(), &self.links(item)).replace('`', "")
+    }
+
+    /// Writes the documentation of a member of the item the page is about.
+    fn write_member_docs(&self, page: &mut String, item: &clean::Item) {
+        page.push_str(&roff::render(&item.doc_value(), &self.links(item), true));
+    }
+
+    fn render_page(&self, item: &clean::Item, path: &str, page_name: &str) -> String {
+        let mut page = String::new();
+        let source = match self.cache.crate_version {
+            Some(ref version) => format!("{} {version}", self.current[0]),
+            None => self.current[0].to_string(),
+        };
+        writeln!(
+            page,
+            ".TH {} {SECTION} \"\" {} \"Rust Library Functions\"",
+            quote(page_name),
+            quote(&source)
+        )
+        .unwrap();
+
+        page.push_str(".SH NAME\n");
+        let summary = self.summary(item);
+        if summary.is_empty() {
+            writeln!(page, "{}", escape(path)).unwrap();
+        } else {
+            writeln!(page, "{} \\- {}", escape(path), escape(&summary)).unwrap();
+        }
+
+        if let Some(decl) = print::declaration(self.tcx, item) {
+            page.push_str(".SH SYNOPSIS\n.nf\n");
+            writeln!(page, "{}", escape(&decl)).unwrap();
+            page.push_str(".fi\n");
+        }
+        let mut docs = roff::render(&item.doc_value(), &self.links(item), false);
+        // The pages linked to go in the `See also` section of the documentation if it has one,
+        // instead of a second section with the same name at the end of the page.
+        let mut see_also = self.see_also(item);
+        if let Some(pages) = &see_also
+            && let Some(end) = roff::section_end(&docs, "SEE ALSO")
+        {
+            docs.insert_str(end, &format!(".PP\n{pages}"));
+            see_also = None;
+        }
+        page.push_str(&docs);
+
+        match item.kind {
+            clean::ModuleItem(ref module) => self.write_module_items(&mut page, module),
+            clean::StructItem(clean::Struct { ref fields, .. }) => {
+                self.write_fields(&mut page, fields);
+            }
+            clean::UnionItem(clean::Union { ref fields, .. }) => {
+                self.write_fields(&mut page, fields);
+            }
+            clean::EnumItem(ref enum_) => {
+                let mut variants = enum_.variants().peekable();
+                if variants.peek().is_some() {
+                    page.push_str(".SH VARIANTS\n");
+                }
+                for variant in variants {
+                    let signature = print::variant(self.tcx, variant);
+                    writeln!(page, ".SS {}", quote(&signature)).unwrap();
+                    self.write_member_docs(&mut page, variant);
+                }
+            }
+            clean::TraitItem(ref trait_) => {
+                let sections = [
+                    ("ASSOCIATED TYPES", ItemType::AssocType),
+                    ("ASSOCIATED CONSTANTS", ItemType::AssocConst),
+                    ("REQUIRED METHODS", ItemType::TyMethod),
+                    ("PROVIDED METHODS", ItemType::Method),
+                ];
+                for (title, ty) in sections {
+                    let items = trait_.items.iter().filter(|i| i.type_() == ty).collect();
+                    self.write_assoc_items(&mut page, title, items);
+                }
+                if let Some(implementors) = self.cache.implementors.get(&trait_.def_id) {
+                    self.write_impl_list(&mut page, "IMPLEMENTORS", implementors.iter());
+                }
+            }
+            _ => {}
+        }
+        if matches!(
+            item.kind,
+            clean::StructItem(_)
+                | clean::UnionItem(_)
+                | clean::EnumItem(_)
+                | clean::ForeignTypeItem
+        ) {
+            self.write_impls(&mut page, item.item_id.expect_def_id());
+        }
+        if let Some(pages) = see_also {
+            page.push_str(".SH \"SEE ALSO\"\n");
+            page.push_str(&pages);
+        }
+        page
+    }
+
+    fn write_module_items(&self, page: &mut String, module: &clean::Module) {
+        let mut items = module
+            .items
+            .iter()
+            .filter(|i| !i.is_stripped() && has_page(i.type_()))
+            .filter_map(|i| Some((self.page_name(i.item_id.as_def_id()?)?, i)))
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            return;
+        }
+        items.sort_by(|(a, _), (b, _)| compare_names(a, b));
+        page.push_str(".SH ITEMS\n");
+        for (name, item) in items {
+            writeln!(page, ".TP\n\\fB{}\\fR({SECTION})", escape(&name)).unwrap();
+            let summary = self.summary(item);
+            if !summary.is_empty() {
+                writeln!(page, "{}", escape(&summary)).unwrap();
+            }
+        }
+    }
+
+    fn write_fields(&self, page: &mut String, fields: &[clean::Item]) {
+        let mut fields = fields
+            .iter()
+            .filter_map(|f| match f.kind {
+                clean::StructFieldItem(ref ty) => Some((f, ty)),
+                _ => None,
+            })
+            .peekable();
+        if fields.peek().is_none() {
+            return;
+        }
+        page.push_str(".SH FIELDS\n");
+        for (field, ty) in fields {
+            let mut p = print::Printer::new(self.tcx);
+            p.push(&format!("{}: ", field.name.unwrap()));
+            p.ty(ty);
+            writeln!(page, ".SS {}", quote(&p.finish())).unwrap();
+            self.write_member_docs(page, field);
+        }
+    }
+
+    fn write_assoc_items(&self, page: &mut String, title: &str, items: Vec<&clean::Item>) {
+        let mut items = items
+            .into_iter()
+            .filter(|i| !i.is_stripped())
+            .filter_map(|i| Some((i, print::assoc_item_signature(self.tcx, i)?)))
+            .peekable();
+        if items.peek().is_none() {
+            return;
+        }
+        writeln!(page, ".SH {}", quote(title)).unwrap();
+        for (item, signature) in items {
+            writeln!(page, ".SS {}", quote(&print::one_line(&signature))).unwrap();
+            self.write_member_docs(page, item);
+        }
+    }
+
+    fn write_impls(&self, page: &mut String, did: DefId) {
+        let Some(impls) = self.cache.impls.get(&did) else { return };
+        let inherent_items = impls
+            .iter()
+            .filter(|i| i.inner_impl().trait_.is_none())
+            .flat_map(|i| &i.inner_impl().items)
+            .collect::<Vec<_>>();
+        let sections = [
+            ("ASSOCIATED TYPES", ItemType::AssocType),
+            ("ASSOCIATED CONSTANTS", ItemType::AssocConst),
+            ("METHODS", ItemType::Method),
+        ];
+        for (title, ty) in sections {
+            let items = inherent_items.iter().copied().filter(|i| i.type_() == ty).collect();
+            self.write_assoc_items(page, title, items);
+        }
+        let trait_impls = impls.iter().filter(|i| {
+            let impl_ = i.inner_impl();
+            impl_.trait_.is_some() && !impl_.kind.is_auto() && !impl_.kind.is_blanket()
+        });
+        self.write_impl_list(page, "TRAIT IMPLEMENTATIONS", trait_impls);
+    }
+
+    fn write_impl_list<'a>(
+        &self,
+        page: &mut String,
+        title: &str,
+        impls: impl Iterator<Item = &'a crate::formats::Impl>,
+    ) {
+        let mut impls = impls.peekable();
+        if impls.peek().is_none() {
+            return;
+        }
+        writeln!(page, ".SH {}", quote(title)).unwrap();
+        for i in impls {
+            let header = print::impl_header(self.tcx, i.inner_impl());
+            writeln!(page, ".IP \\(bu 4\n{}", escape(&header)).unwrap();
+        }
+    }
+
+    /// Lists the pages of the items linked to from the documentation of `item`, and the page of
+    /// its module.
+    fn see_also(&self, item: &clean::Item) -> Option<String> {
+        let mut pages = FxIndexSet::default();
+        if let Some(links) = self.cache.intra_doc_links.get(&item.item_id) {
+            pages.extend(links.iter().filter_map(|link| self.page_name(link.page_id)));
+        }
+        if !item.is_mod() && !self.current.is_empty() {
+            pages.insert(page_name(&self.current, ItemType::Module));
+        }
+        if let Some(own_page) = item.item_id.as_def_id().and_then(|did| self.page_name(did)) {
+            pages.shift_remove(&own_page);
+        }
+        if pages.is_empty() {
+            return None;
+        }
+        let pages = pages
+            .iter()
+            .map(|page| format!("\\fB{}\\fR({SECTION})", escape(page)))
+            .collect::<Vec<_>>();
+        Some(format!("{}\n", pages.join(",\n")))
+    }
+
+    fn write_page(&self, page_name: &str, page: String) -> Result<(), Error> {
+        try_err!(fs::create_dir_all(&self.dst), &self.dst);
+        let file = self.dst.join(format!("{page_name}.{SECTION}"));
+        try_err!(fs::write(&file, page), &file);
+        Ok(())
+    }
+}
+
+impl<'tcx> FormatRenderer<'tcx> for ManRenderer<'tcx> {
+    fn descr() -> &'static str {
+        "man"
+    }
+
+    const RUN_ON_MODULE: bool = true;
+    type ModuleData = bool;
+
+    fn init(
+        krate: clean::Crate,
+        options: RenderOptions,
+        cache: Cache,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<(Self, clean::Crate), Error> {
+        debug!("Initializing man renderer");
+        Ok((
+            ManRenderer {
+                tcx,
+                cache: Rc::new(cache),
+                dst: options.output.join(format!("man{SECTION}")),
+                current: Vec::new(),
+                in_stripped_module: false,
+            },
+            krate,
+        ))
+    }
+
+    fn save_module_data(&mut self) -> Self::ModuleData {
+        self.in_stripped_module
+    }
+
+    fn restore_module_data(&mut self, in_stripped_module: Self::ModuleData) {
+        self.in_stripped_module = in_stripped_module;
+    }
+
+    fn item(&mut self, item: clean::Item) -> Result<(), Error> {
+        if self.in_stripped_module || item.is_stripped() || !has_page(item.type_()) {
+            return Ok(());
+        }
+        let name = item.name.unwrap_or(kw::Empty);
+        let fqp = [&self.current[..], &[name]].concat();
+        let page_name = page_name(&fqp, item.type_());
+        let page = self.render_page(&item, &join_with_double_colon(&fqp), &page_name);
+        self.write_page(&page_name, page)
+    }
+
+    fn mod_item_in(&mut self, item: &clean::Item) -> Result<(), Error> {
+        self.current.push(item.name.unwrap());
+        self.in_stripped_module |= item.is_stripped();
+        if !self.in_stripped_module {
+            let page_name = page_name(&self.current, ItemType::Module);
+            let page = self.render_page(item, &join_with_double_colon(&self.current), &page_name);
+            self.write_page(&page_name, page)?;
+        }
+        Ok(())
+    }
+
+    fn mod_item_out(&mut self) -> Result<(), Error> {
+        self.current.pop();
+        Ok(())
+    }
+
+    fn after_krate(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn cache(&self) -> &Cache {
+        &self.cache
+    }
+}
@@ -0,0 +1,333 @@
+//! Converting the Markdown of doc comments to roff, the language of man pages.
+//!
+//! Only the `man` macro package is used, so that the output can be read with any `man`.
+
+use pulldown_cmark::{BrokenLink, CodeBlockKind, CowStr, Event, Parser, Tag, TagEnd};
+
+use crate::clean::RenderedLink;
+use crate::html::markdown::{ErrorCodes, LangString, main_body_opts, map_line};
+
+#[cfg(test)]
+mod tests;
+
+/// Escapes `s` so that it is printed as is in running text.
+pub(super) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    push_escaped(&mut out, s);
+    out
+}
+
+/// Escapes `s` so that it can be used as a (quoted) argument of a request.
+pub(super) fn quote(s: &str) -> String {
+    format!("\"{}\"", escape(s).replace('"', "\\(dq"))
+}
+
+fn push_escaped(out: &mut String, s: &str) {
+    for c in s.chars() {
+        // A `.` or a `'` at the start of a line would be taken as a request.
+        if (c == '.' || c == '\'') && (out.is_empty() || out.ends_with('\n')) {
+            out.push_str("\\&");
+        }
+        match c {
+            '\\' => out.push_str("\\e"),
+            '-' => out.push_str("\\-"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// The name of the man page section for a top-level heading of the documentation.
+///
+/// The conventional sections of Rust documentation are mapped to their man page equivalent.
+pub(super) fn section_name(heading: &str) -> String {
+    match heading.trim().to_lowercase().as_str() {
+        "example" | "examples" => "EXAMPLES".to_owned(),
+        "error" | "errors" => "ERRORS".to_owned(),
+        "return" | "returns" | "return value" => "RETURN VALUE".to_owned(),
+        "note" | "notes" => "NOTES".to_owned(),
+        "see also" => "SEE ALSO".to_owned(),
+        heading => heading.to_uppercase(),
+    }
+}
+
+/// Returns the end of the top-level section `name` of `roff`, if it has one: the start of the
+/// following section, or the end of `roff`.
+pub(super) fn section_end(roff: &str, name: &str) -> Option<usize> {
+    let heading = format!(".SH {}\n", quote(name));
+    let start =
+        if roff.starts_with(&heading) { 0 } else { roff.find(&format!("\n{heading}"))? + 1 };
+    let body = start + heading.len();
+    Some(roff[body..].find("\n.SH ").map_or(roff.len(), |end| body + end + 1))
+}
+
+struct Writer {
+    out: String,
+    /// Whether the documentation is that of a member of the item the page is about (a field or
+    /// a method, for instance), in which case its headings don't start new sections.
+    nested: bool,
+    /// Whether a section was started by the documentation.
+    in_section: bool,
+    /// The next number of each list being written, or `None` if it is not ordered.
+    lists: Vec<Option<u64>>,
+    /// Whether the current list item has not started its text yet.
+    at_item_start: bool,
+    heading: Option<String>,
+    code_block: Option<(bool, String)>,
+    /// The fonts of the enclosing inline elements, like `I` in emphasized text. `\fP` only goes
+    /// back to the font used before the last change, so the font to restore at the end of an
+    /// element has to be tracked to handle nested elements.
+    fonts: Vec<char>,
+}
+
+enum Link<'a> {
+    IntraDoc(&'a RenderedLink),
+    Url(String),
+}
+
+impl Writer {
+    /// Makes sure that the next output starts on a new line.
+    fn newline(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn request(&mut self, request: &str) {
+        self.newline();
+        self.out.push_str(request);
+        self.out.push('\n');
+    }
+
+    fn push_font(&mut self, font: char) {
+        self.fonts.push(font);
+        self.out.push_str(&format!("\\f{font}"));
+    }
+
+    fn pop_font(&mut self) {
+        self.fonts.pop();
+        let font = self.fonts.last().copied().unwrap_or('R');
+        self.out.push_str(&format!("\\f{font}"));
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(heading) = &mut self.heading {
+            heading.push_str(text);
+        } else {
+            push_escaped(&mut self.out, text);
+        }
+    }
+
+    /// Starts a block of text, in the description section if the documentation doesn't start
+    /// with a heading.
+    fn block(&mut self) {
+        if !self.nested && !self.in_section {
+            self.request(".SH DESCRIPTION");
+            self.in_section = true;
+        }
+    }
+
+    fn paragraph(&mut self) {
+        self.block();
+        if self.at_item_start {
+            self.at_item_start = false;
+        } else if self.lists.is_empty() {
+            self.request(".PP");
+        } else {
+            self.request(".IP \"\" 4");
+        }
+    }
+
+    fn heading(&mut self, level: u32, title: &str) {
+        if self.nested {
+            self.request(".PP");
+            self.out.push_str(&format!("\\fB{}\\fR\n", escape(title.trim())));
+        } else if level == 1 {
+            self.request(&format!(".SH {}", quote(&section_name(title))));
+            self.in_section = true;
+        } else {
+            self.block();
+            self.request(&format!(".SS {}", quote(title.trim())));
+        }
+    }
+
+    fn code_block(&mut self, is_rust: bool, code: &str) {
+        self.block();
+        if self.at_item_start {
+            self.at_item_start = false;
+        } else {
+            self.request(".PP");
+        }
+        self.request(".RS 4");
+        self.request(".nf");
+        let lines: Vec<_> = if is_rust {
+            code.lines().filter_map(|line| map_line(line).for_html()).collect()
+        } else {
+            code.lines().map(Into::into).collect()
+        };
+        for line in lines {
+            push_escaped(&mut self.out, &line);
+            self.out.push('\n');
+        }
+        self.request(".fi");
+        self.request(".RE");
+    }
+}
+
+/// Converts the documentation `md` to roff.
+///
+/// The top-level headings of the documentation of the item a page is about start new sections of
+/// the page, and any text before them goes in the `DESCRIPTION` section. The documentation of
+/// members (`nested`) is written under the current section instead.
+pub(super) fn render(md: &str, links: &[RenderedLink], nested: bool) -> String {
+    let mut w = Writer {
+        out: String::new(),
+        nested,
+        in_section: false,
+        lists: Vec::new(),
+        at_item_start: false,
+        heading: None,
+        code_block: None,
+        fonts: Vec::new(),
+    };
+    if md.is_empty() {
+        return w.out;
+    }
+    let mut replacer = |broken_link: BrokenLink<'_>| {
+        links
+            .iter()
+            .find(|link| *link.original_text == *broken_link.reference)
+            .map(|link| (CowStr::from(link.original_text.to_string()), CowStr::Borrowed("")))
+    };
+    let parser = Parser::new_with_broken_link_callback(md, main_body_opts(), Some(&mut replacer));
+
+    let mut link = None;
+    for event in parser {
+        match event {
+            Event::Start(Tag::Paragraph) => w.paragraph(),
+            Event::Start(Tag::Heading { .. }) => w.heading = Some(String::new()),
+            Event::End(TagEnd::Heading(level)) => {
+                let title = w.heading.take().unwrap_or_default();
+                w.heading(level as u32, &title);
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                w.block();
+                w.request(".RS 4");
+            }
+            Event::End(TagEnd::BlockQuote) => w.request(".RE"),
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let is_rust = match kind {
+                    CodeBlockKind::Fenced(lang) => {
+                        LangString::parse_without_check(&lang, ErrorCodes::No, false).rust
+                    }
+                    CodeBlockKind::Indented => true,
+                };
+                w.code_block = Some((is_rust, String::new()));
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((is_rust, code)) = w.code_block.take() {
+                    w.code_block(is_rust, &code);
+                }
+            }
+            Event::Start(Tag::List(start)) => {
+                w.block();
+                if !w.lists.is_empty() {
+                    w.request(".RS 4");
+                }
+                w.lists.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                w.lists.pop();
+                if !w.lists.is_empty() {
+                    w.request(".RE");
+                }
+                w.at_item_start = false;
+            }
+            Event::Start(Tag::Item) => {
+                let bullet = match w.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}.", *n - 1)
+                    }
+                    _ => "\\(bu".to_owned(),
+                };
+                w.request(&format!(".IP {bullet} 4"));
+                w.at_item_start = true;
+            }
+            Event::Start(Tag::Table(_)) => {
+                w.block();
+                w.request(".PP");
+                w.request(".nf");
+            }
+            Event::End(TagEnd::Table) => w.request(".fi"),
+            Event::End(TagEnd::TableHead | TagEnd::TableRow) => w.newline(),
+            Event::End(TagEnd::TableCell) => w.out.push('\t'),
+            Event::Start(Tag::FootnoteDefinition(label)) => {
+                w.block();
+                w.request(&format!(".IP [{}] 4", escape(&label)));
+                w.at_item_start = true;
+            }
+            Event::Start(Tag::Emphasis) if w.heading.is_none() => w.push_font('I'),
+            Event::Start(Tag::Strong) if w.heading.is_none() => w.push_font('B'),
+            Event::End(TagEnd::Emphasis | TagEnd::Strong) if w.heading.is_none() => w.pop_font(),
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                link = Some(match links.iter().find(|l| *l.original_text == *dest_url) {
+                    Some(l) => Link::IntraDoc(l),
+                    None => Link::Url(dest_url.to_string()),
+                });
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some(Link::Url(url)) = link.take()
+                    && !url.starts_with('#')
+                {
+                    w.text(&format!(" <{url}>"));
+                }
+            }
+            Event::Text(text) => {
+                if let Some((_, code)) = &mut w.code_block {
+                    code.push_str(&text);
+                } else if let Some(Link::IntraDoc(l)) = link
+                    && *text == *l.original_text
+                {
+                    // Remove the disambiguator from links like `[fn@f]`.
+                    w.text(&l.new_text);
+                } else {
+                    w.text(&text);
+                }
+            }
+            Event::Code(code) => {
+                let code = match link {
+                    Some(Link::IntraDoc(l))
+                        if l.original_text.get(1..l.original_text.len() - 1) == Some(&*code) =>
+                    {
+                        CowStr::from(l.new_text.to_string())
+                    }
+                    _ => code,
+                };
+                if w.heading.is_some() {
+                    w.text(&code);
+                } else {
+                    w.push_font('B');
+                    w.text(&code);
+                    w.pop_font();
+                }
+            }
+            Event::SoftBreak => {
+                if w.heading.is_some() {
+                    w.text(" ");
+                } else {
+                    w.newline();
+                }
+            }
+            Event::HardBreak => w.request(".br"),
+            Event::Rule => {
+                w.block();
+                w.request(".sp");
+            }
+            Event::FootnoteReference(label) => w.text(&format!("[{label}]")),
+            Event::TaskListMarker(checked) => w.text(if checked { "[x] " } else { "[ ] " }),
+            _ => {}
+        }
+    }
+    w.newline();
+    w.out
+}
@@ -0,0 +1,76 @@
+use super::{escape, quote, render, section_end, section_name};
+use crate::clean::RenderedLink;
+
+#[test]
+fn test_escape() {
+    assert_eq!(escape("a-b\\c"), "a\\-b\\ec");
+    assert_eq!(escape(".hidden\n'quoted"), "\\&.hidden\n\\&'quoted");
+    assert_eq!(escape("a.b 'c'"), "a.b 'c'");
+    assert_eq!(quote("say \"hi\""), "\"say \\(dqhi\\(dq\"");
+}
+
+#[test]
+fn test_section_name() {
+    assert_eq!(section_name("Examples"), "EXAMPLES");
+    assert_eq!(section_name("Errors"), "ERRORS");
+    assert_eq!(section_name("Returns"), "RETURN VALUE");
+    assert_eq!(section_name("Safety"), "SAFETY");
+    assert_eq!(section_name("Panics"), "PANICS");
+}
+
+#[test]
+fn test_sections() {
+    assert_eq!(
+        render("Does things.\n\n# Errors\n\nSometimes.\n\n## Details\n\nMore.", &[], false),
+        ".SH DESCRIPTION\n.PP\nDoes things.\n.SH \"ERRORS\"\n.PP\nSometimes.\n.SS \"Details\"\n\
+         .PP\nMore.\n",
+    );
+    // The headings of members don't start sections.
+    assert_eq!(
+        render("Does things.\n\n# Safety\n\nBe careful.", &[], true),
+        ".PP\nDoes things.\n.PP\n\\fBSafety\\fR\n.PP\nBe careful.\n",
+    );
+}
+
+#[test]
+fn test_section_end() {
+    let roff =
+        render("Does things.\n\n# See also\n\nOther things.\n\n# Errors\n\nNever.", &[], false);
+    let end = section_end(&roff, "SEE ALSO").unwrap();
+    assert!(roff[..end].ends_with(".PP\nOther things.\n"));
+    assert!(roff[end..].starts_with(".SH \"ERRORS\"\n"));
+    assert_eq!(section_end(&roff, "ERRORS"), Some(roff.len()));
+    assert_eq!(section_end(&roff, "EXAMPLES"), None);
+}
+
+#[test]
+fn test_inline() {
+    let links = [RenderedLink {
+        original_text: "fn@bar".into(),
+        new_text: "bar".into(),
+        href: String::new(),
+        tooltip: String::new(),
+    }];
+    assert_eq!(
+        render("Use *one* `x-y` with [fn@bar] or [the book](https://example.com).", &links, true),
+        ".PP\nUse \\fIone\\fR \\fBx\\-y\\fR with bar or the book <https://example.com>.\n",
+    );
+    // The font of the enclosing text is restored after inline code.
+    assert_eq!(
+        render("*Use `x` or __`y` here__.*", &[], true),
+        ".PP\n\\fIUse \\fBx\\fI or \\fB\\fBy\\fB here\\fI.\\fR\n",
+    );
+}
+
+#[test]
+fn test_lists_and_code_blocks() {
+    assert_eq!(
+        render("- one\n- two\n  1. nested\n\n```\n# use std::io;\nlet x = 1;\n```", &[], true),
+        ".IP \\(bu 4\none\n.IP \\(bu 4\ntwo\n.RS 4\n.IP 1. 4\nnested\n.RE\n.PP\n.RS 4\n.nf\n\
+         let x = 1;\n.fi\n.RE\n",
+    );
+    assert_eq!(
+        render("```text\n# not rust\n.dot\n```", &[], true),
+        ".PP\n.RS 4\n.nf\n# not rust\n\\&.dot\n.fi\n.RE\n",
+    );
+}
@@ -98,6 +98,7 @@ pub(crate) fn render_and_write<P: AsRef<Path>>(
             edition,
             playground: &playground,
             heading_offset: HeadingOffset::H1,
+            doctest_results: None,
         }
         .into_string()
     };
@@ -0,0 +1,573 @@
+//! Rustdoc's offline search index
+//!
+//! These types describe the file that rustdoc writes to `<out-dir>/<crate>/search-index.rsidx`
+//! when it is passed `--offline-search-index`. It carries the same data as the crate's entry in
+//! `search-index.js`, but is meant to be read by tools running outside of a browser, such as
+//! `src/tools/rustdoc-search`.
+//!
+//! # Format
+//!
+//! The file is a sequence of the following productions. `uint` is an unsigned LEB128 integer,
+//! `sint` is a zigzag-encoded signed LEB128 integer, `u8` is a single byte and `str` is a `uint`
+//! byte length followed by that many bytes of UTF-8.
+//!
+//! ```text
+//! file      = b"RDSI" version:uint crate
+//! crate     = name:str doc:str count:uint path* count:uint item*
+//! path      = kind:u8 name:str module:str flags:u8
+//! item      = kind:u8 name:str module:str desc:str parent:uint flags:u8
+//!             count:uint alias:str* [signature]
+//! signature = count:uint param_name:str* types(inputs) types(output)
+//!             count:uint types(where_clause)*
+//! types     = count:uint type*
+//! type      = id:sint types(generics) count:uint binding*
+//! binding   = id:sint types(constraints)
+//! ```
+//!
+//! * `path` entries are the types, traits and parents referenced by items, with their
+//!   module path joined by `::`. Bit 0 of their `flags` is set if the type is marked
+//!   `#[doc(search_unbox)]`.
+//! * An item's `parent` is `0` if it has none, or one plus the index of its parent in the
+//!   `path` list. Bit 0 of its `flags` is set if it is deprecated, and bit 1 is set if a
+//!   `signature` follows.
+//! * A type `id` of `0` means the type is only described by its generics (for example an
+//!   `impl Trait` with several bounds). A positive `id` `n` refers to `paths[n - 1]`, and a
+//!   negative `id` `-n` refers to the generic parameter `n - 1` of the function, whose name
+//!   and trait bounds are stored at the same position in `param_name` and `where_clause`.
+//! * `type`s are nested at most [`MAX_TYPE_DEPTH`] levels deep, counting from the outermost
+//!   `types` of a signature.
+//! * Descriptions are the short summaries shown in search results, and may contain inline
+//!   HTML.
+//!
+//! All numbers in the `kind` fields are the discriminants of [`ItemKind`].
+
+use std::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// The bytes every offline search index starts with.
+pub const MAGIC: &[u8; 4] = b"RDSI";
+
+/// The version of the offline search index format that this crate represents.
+///
+/// This integer is incremented with every breaking change to the format.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The maximum nesting of the types of a signature, including their generics and the constraints
+/// of their bindings, that the decoder accepts.
+pub const MAX_TYPE_DEPTH: usize = 128;
+
+/// The name of the file rustdoc writes in each crate's documentation directory.
+pub const FILE_NAME: &str = "search-index.rsidx";
+
+/// The search index of a single crate.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Crate {
+    pub name: String,
+    /// The short summary of the crate's documentation.
+    pub doc: String,
+    /// Types and parents referenced from [`Item::parent`] and [`TypeId::Path`].
+    pub paths: Vec<Path>,
+    pub items: Vec<Item>,
+}
+
+/// A type, trait or parent item referenced by the index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path {
+    pub kind: ItemKind,
+    pub name: String,
+    /// The path of the module containing this item, joined by `::`. Empty for primitives.
+    pub module: String,
+    /// Whether type-based search should also match the generics of this type on their own.
+    pub search_unbox: bool,
+}
+
+/// A searchable item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub name: String,
+    /// The path of the module containing this item (or its parent), joined by `::`.
+    pub module: String,
+    pub desc: String,
+    /// The index in [`Crate::paths`] of the type or trait this item is associated with.
+    pub parent: Option<u32>,
+    pub deprecated: bool,
+    pub aliases: Vec<String>,
+    pub signature: Option<FunctionSignature>,
+}
+
+/// The simplified signature of a function, as used by type-based search.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FunctionSignature {
+    pub inputs: Vec<TypeNode>,
+    pub output: Vec<TypeNode>,
+    /// The trait bounds of every generic parameter, indexed like [`TypeId::Generic`].
+    pub where_clause: Vec<Vec<TypeNode>>,
+    pub param_names: Vec<String>,
+}
+
+/// A type in a [`FunctionSignature`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeNode {
+    pub id: TypeId,
+    pub generics: Vec<TypeNode>,
+    /// Associated type constraints, like the `Item = u8` in `Iterator<Item = u8>`.
+    pub bindings: Vec<(TypeId, Vec<TypeNode>)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TypeId {
+    /// The type has no name of its own and is only described by its generics.
+    None,
+    /// An index into [`Crate::paths`].
+    Path(u32),
+    /// An index into [`FunctionSignature::param_names`].
+    Generic(u32),
+}
+
+/// The kind of an item.
+///
+/// The discriminants are the same as the ones used by rustdoc's `ItemType` and by the
+/// `itemTypes` table in `search.js`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum ItemKind {
+    Keyword = 0,
+    Primitive = 1,
+    Module = 2,
+    ExternCrate = 3,
+    Import = 4,
+    Struct = 5,
+    Enum = 6,
+    Function = 7,
+    TypeAlias = 8,
+    Static = 9,
+    Trait = 10,
+    Impl = 11,
+    TyMethod = 12,
+    Method = 13,
+    StructField = 14,
+    Variant = 15,
+    Macro = 16,
+    AssocType = 17,
+    Constant = 18,
+    AssocConst = 19,
+    Union = 20,
+    ForeignType = 21,
+    ProcAttribute = 23,
+    ProcDerive = 24,
+    TraitAlias = 25,
+}
+
+impl ItemKind {
+    const ALL: [ItemKind; 25] = [
+        ItemKind::Keyword,
+        ItemKind::Primitive,
+        ItemKind::Module,
+        ItemKind::ExternCrate,
+        ItemKind::Import,
+        ItemKind::Struct,
+        ItemKind::Enum,
+        ItemKind::Function,
+        ItemKind::TypeAlias,
+        ItemKind::Static,
+        ItemKind::Trait,
+        ItemKind::Impl,
+        ItemKind::TyMethod,
+        ItemKind::Method,
+        ItemKind::StructField,
+        ItemKind::Variant,
+        ItemKind::Macro,
+        ItemKind::AssocType,
+        ItemKind::Constant,
+        ItemKind::AssocConst,
+        ItemKind::Union,
+        ItemKind::ForeignType,
+        ItemKind::ProcAttribute,
+        ItemKind::ProcDerive,
+        ItemKind::TraitAlias,
+    ];
+
+    pub fn from_u8(n: u8) -> Option<ItemKind> {
+        ItemKind::ALL.iter().copied().find(|kind| *kind as u8 == n)
+    }
+
+    /// Parses the name used by [`ItemKind::as_str`], which is also the name accepted by
+    /// type filters in rustdoc's search (`fn:`, `struct:`...).
+    pub fn from_name(name: &str) -> Option<ItemKind> {
+        ItemKind::ALL.iter().copied().find(|kind| kind.as_str() == name)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ItemKind::Module => "mod",
+            ItemKind::ExternCrate => "externcrate",
+            ItemKind::Import => "import",
+            ItemKind::Struct => "struct",
+            ItemKind::Union => "union",
+            ItemKind::Enum => "enum",
+            ItemKind::Function => "fn",
+            ItemKind::TypeAlias => "type",
+            ItemKind::Static => "static",
+            ItemKind::Trait => "trait",
+            ItemKind::Impl => "impl",
+            ItemKind::TyMethod => "tymethod",
+            ItemKind::Method => "method",
+            ItemKind::StructField => "structfield",
+            ItemKind::Variant => "variant",
+            ItemKind::Macro => "macro",
+            ItemKind::Primitive => "primitive",
+            ItemKind::AssocType => "associatedtype",
+            ItemKind::Constant => "constant",
+            ItemKind::AssocConst => "associatedconstant",
+            ItemKind::ForeignType => "foreigntype",
+            ItemKind::Keyword => "keyword",
+            ItemKind::ProcAttribute => "attr",
+            ItemKind::ProcDerive => "derive",
+            ItemKind::TraitAlias => "traitalias",
+        }
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    BadMagic,
+    UnsupportedVersion(u32),
+    UnexpectedEof,
+    IntegerOverflow,
+    InvalidUtf8,
+    InvalidItemKind(u8),
+    InvalidPathIndex(u32),
+    InvalidGenericIndex(u32),
+    TypeTooDeep,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::BadMagic => f.write_str("not a rustdoc search index"),
+            DecodeError::UnsupportedVersion(v) => write!(
+                f,
+                "search index format version {v} is not supported (expected {FORMAT_VERSION})"
+            ),
+            DecodeError::UnexpectedEof => f.write_str("unexpected end of search index"),
+            DecodeError::IntegerOverflow => f.write_str("integer too large in search index"),
+            DecodeError::InvalidUtf8 => f.write_str("invalid UTF-8 in search index"),
+            DecodeError::InvalidItemKind(k) => write!(f, "invalid item kind {k} in search index"),
+            DecodeError::InvalidPathIndex(i) => write!(f, "invalid path index {i} in search index"),
+            DecodeError::InvalidGenericIndex(i) => {
+                write!(f, "invalid generic parameter index {i} in search index")
+            }
+            DecodeError::TypeTooDeep => {
+                write!(f, "type nested more than {MAX_TYPE_DEPTH} levels deep in search index")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+const ITEM_DEPRECATED: u8 = 1 << 0;
+const ITEM_HAS_SIGNATURE: u8 = 1 << 1;
+const PATH_SEARCH_UNBOX: u8 = 1 << 0;
+
+impl Crate {
+    /// Encodes the index, including the magic bytes and format version.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut e = Encoder { buf: Vec::new() };
+        e.buf.extend_from_slice(MAGIC);
+        e.uint(FORMAT_VERSION.into());
+        e.str(&self.name);
+        e.str(&self.doc);
+        e.uint(self.paths.len() as u64);
+        for path in &self.paths {
+            e.buf.push(path.kind as u8);
+            e.str(&path.name);
+            e.str(&path.module);
+            e.buf.push(if path.search_unbox { PATH_SEARCH_UNBOX } else { 0 });
+        }
+        e.uint(self.items.len() as u64);
+        for item in &self.items {
+            e.buf.push(item.kind as u8);
+            e.str(&item.name);
+            e.str(&item.module);
+            e.str(&item.desc);
+            e.uint(item.parent.map_or(0, |p| u64::from(p) + 1));
+            let mut flags = 0;
+            if item.deprecated {
+                flags |= ITEM_DEPRECATED;
+            }
+            if item.signature.is_some() {
+                flags |= ITEM_HAS_SIGNATURE;
+            }
+            e.buf.push(flags);
+            e.uint(item.aliases.len() as u64);
+            for alias in &item.aliases {
+                e.str(alias);
+            }
+            if let Some(signature) = &item.signature {
+                e.uint(signature.param_names.len() as u64);
+                for name in &signature.param_names {
+                    e.str(name);
+                }
+                e.types(&signature.inputs);
+                e.types(&signature.output);
+                e.uint(signature.where_clause.len() as u64);
+                for bounds in &signature.where_clause {
+                    e.types(bounds);
+                }
+            }
+        }
+        e.buf
+    }
+
+    /// Decodes an index produced by [`Crate::encode`].
+    pub fn decode(bytes: &[u8]) -> Result<Crate, DecodeError> {
+        let Some(rest) = bytes.strip_prefix(MAGIC) else {
+            return Err(DecodeError::BadMagic);
+        };
+        let mut d = Decoder { bytes: rest };
+        let version = d.u32()?;
+        if version != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let name = d.str()?;
+        let doc = d.str()?;
+        let path_count = d.len()?;
+        let mut paths = Vec::with_capacity(path_count.min(d.bytes.len()));
+        for _ in 0..path_count {
+            let kind = d.kind()?;
+            let name = d.str()?;
+            let module = d.str()?;
+            let flags = d.u8()?;
+            paths.push(Path { kind, name, module, search_unbox: flags & PATH_SEARCH_UNBOX != 0 });
+        }
+        let item_count = d.len()?;
+        let mut items = Vec::with_capacity(item_count.min(d.bytes.len()));
+        for _ in 0..item_count {
+            let kind = d.kind()?;
+            let name = d.str()?;
+            let module = d.str()?;
+            let desc = d.str()?;
+            let parent = match d.u32()? {
+                0 => None,
+                n => Some(n - 1),
+            };
+            let flags = d.u8()?;
+            let alias_count = d.len()?;
+            let aliases = (0..alias_count).map(|_| d.str()).collect::<Result<_, _>>()?;
+            let signature = if flags & ITEM_HAS_SIGNATURE != 0 {
+                let param_count = d.len()?;
+                let param_names = (0..param_count).map(|_| d.str()).collect::<Result<_, _>>()?;
+                let inputs = d.types()?;
+                let output = d.types()?;
+                let where_count = d.len()?;
+                let where_clause = (0..where_count).map(|_| d.types()).collect::<Result<_, _>>()?;
+                Some(FunctionSignature { inputs, output, where_clause, param_names })
+            } else {
+                None
+            };
+            items.push(Item {
+                kind,
+                name,
+                module,
+                desc,
+                parent,
+                deprecated: flags & ITEM_DEPRECATED != 0,
+                aliases,
+                signature,
+            });
+        }
+        let krate = Crate { name, doc, paths, items };
+        krate.check_indices()?;
+        Ok(krate)
+    }
+
+    /// Checks that the [`TypeId`]s refer to existing paths and generic parameters.
+    fn check_indices(&self) -> Result<(), DecodeError> {
+        fn check_types(types: &[TypeNode], paths: usize, params: usize) -> Result<(), DecodeError> {
+            for ty in types {
+                check_id(ty.id, paths, params)?;
+                check_types(&ty.generics, paths, params)?;
+                for (id, constraints) in &ty.bindings {
+                    check_id(*id, paths, params)?;
+                    check_types(constraints, paths, params)?;
+                }
+            }
+            Ok(())
+        }
+        fn check_id(id: TypeId, paths: usize, params: usize) -> Result<(), DecodeError> {
+            match id {
+                TypeId::Path(i) if i as usize >= paths => Err(DecodeError::InvalidPathIndex(i)),
+                TypeId::Generic(i) if i as usize >= params => {
+                    Err(DecodeError::InvalidGenericIndex(i))
+                }
+                _ => Ok(()),
+            }
+        }
+
+        let paths = self.paths.len();
+        for item in &self.items {
+            if let Some(parent) = item.parent {
+                check_id(TypeId::Path(parent), paths, 0)?;
+            }
+            if let Some(signature) = &item.signature {
+                let params = signature.param_names.len();
+                check_types(&signature.inputs, paths, params)?;
+                check_types(&signature.output, paths, params)?;
+                for bounds in &signature.where_clause {
+                    check_types(bounds, paths, params)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    fn uint(&mut self, mut n: u64) {
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                self.buf.push(byte);
+                return;
+            }
+            self.buf.push(byte | 0x80);
+        }
+    }
+
+    fn sint(&mut self, n: i64) {
+        self.uint(((n << 1) ^ (n >> 63)) as u64);
+    }
+
+    fn str(&mut self, s: &str) {
+        self.uint(s.len() as u64);
+        self.buf.extend_from_slice(s.as_bytes());
+    }
+
+    fn type_id(&mut self, id: TypeId) {
+        self.sint(match id {
+            TypeId::None => 0,
+            TypeId::Path(i) => i64::from(i) + 1,
+            TypeId::Generic(i) => -(i64::from(i) + 1),
+        });
+    }
+
+    fn types(&mut self, types: &[TypeNode]) {
+        self.uint(types.len() as u64);
+        for ty in types {
+            self.type_id(ty.id);
+            self.types(&ty.generics);
+            self.uint(ty.bindings.len() as u64);
+            for (id, constraints) in &ty.bindings {
+                self.type_id(*id);
+                self.types(constraints);
+            }
+        }
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl Decoder<'_> {
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        let (&first, rest) = self.bytes.split_first().ok_or(DecodeError::UnexpectedEof)?;
+        self.bytes = rest;
+        Ok(first)
+    }
+
+    fn uint(&mut self) -> Result<u64, DecodeError> {
+        let mut n = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift >= 64 || (shift == 63 && byte > 1) {
+                return Err(DecodeError::IntegerOverflow);
+            }
+            n |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+            shift += 7;
+        }
+    }
+
+    fn sint(&mut self) -> Result<i64, DecodeError> {
+        let n = self.uint()?;
+        Ok(((n >> 1) as i64) ^ -((n & 1) as i64))
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        u32::try_from(self.uint()?).map_err(|_| DecodeError::IntegerOverflow)
+    }
+
+    fn len(&mut self) -> Result<usize, DecodeError> {
+        usize::try_from(self.uint()?).map_err(|_| DecodeError::IntegerOverflow)
+    }
+
+    fn str(&mut self) -> Result<String, DecodeError> {
+        let len = self.len()?;
+        if len > self.bytes.len() {
+            return Err(DecodeError::UnexpectedEof);
+        }
+        let (s, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        String::from_utf8(s.to_vec()).map_err(|_| DecodeError::InvalidUtf8)
+    }
+
+    fn kind(&mut self) -> Result<ItemKind, DecodeError> {
+        let n = self.u8()?;
+        ItemKind::from_u8(n).ok_or(DecodeError::InvalidItemKind(n))
+    }
+
+    fn type_id(&mut self) -> Result<TypeId, DecodeError> {
+        let n = self.sint()?;
+        let index = |n: i64| u32::try_from(n - 1).map_err(|_| DecodeError::IntegerOverflow);
+        Ok(match n {
+            0 => TypeId::None,
+            n if n > 0 => TypeId::Path(index(n)?),
+            n => TypeId::Generic(index(n.checked_neg().ok_or(DecodeError::IntegerOverflow)?)?),
+        })
+    }
+
+    fn types(&mut self) -> Result<Vec<TypeNode>, DecodeError> {
+        self.nested_types(0)
+    }
+
+    /// Decodes `types` contained in `depth` other types of a signature. The depth is limited so
+    /// that a corrupt index can't overflow the stack.
+    fn nested_types(&mut self, depth: usize) -> Result<Vec<TypeNode>, DecodeError> {
+        let count = self.len()?;
+        if count > 0 && depth == MAX_TYPE_DEPTH {
+            return Err(DecodeError::TypeTooDeep);
+        }
+        let mut types = Vec::with_capacity(count.min(self.bytes.len()));
+        for _ in 0..count {
+            let id = self.type_id()?;
+            let generics = self.nested_types(depth + 1)?;
+            let binding_count = self.len()?;
+            let mut bindings = Vec::with_capacity(binding_count.min(self.bytes.len()));
+            for _ in 0..binding_count {
+                let id = self.type_id()?;
+                bindings.push((id, self.nested_types(depth + 1)?));
+            }
+            types.push(TypeNode { id, generics, bindings });
+        }
+        Ok(types)
+    }
+}
@@ -0,0 +1,122 @@
+use super::*;
+
+fn sample() -> Crate {
+    let vec_of_t = TypeNode {
+        id: TypeId::Path(0),
+        generics: vec![TypeNode { id: TypeId::Generic(0), generics: vec![], bindings: vec![] }],
+        bindings: vec![],
+    };
+    let usize = TypeNode { id: TypeId::Path(1), generics: vec![], bindings: vec![] };
+    Crate {
+        name: "alloc".to_owned(),
+        doc: "The Rust core allocation and collections library".to_owned(),
+        paths: vec![
+            Path {
+                kind: ItemKind::Struct,
+                name: "Vec".to_owned(),
+                module: "alloc::vec".to_owned(),
+                search_unbox: false,
+            },
+            Path {
+                kind: ItemKind::Primitive,
+                name: "usize".to_owned(),
+                module: String::new(),
+                search_unbox: false,
+            },
+        ],
+        items: vec![
+            Item {
+                kind: ItemKind::Struct,
+                name: "Vec".to_owned(),
+                module: "alloc::vec".to_owned(),
+                desc: "A contiguous growable array type.".to_owned(),
+                parent: None,
+                deprecated: false,
+                aliases: vec![],
+                signature: None,
+            },
+            Item {
+                kind: ItemKind::Method,
+                name: "len".to_owned(),
+                module: "alloc::vec".to_owned(),
+                desc: "Returns the number of elements in the vector.".to_owned(),
+                parent: Some(0),
+                deprecated: true,
+                aliases: vec!["length".to_owned(), "size".to_owned()],
+                signature: Some(FunctionSignature {
+                    inputs: vec![vec_of_t],
+                    output: vec![usize],
+                    where_clause: vec![vec![]],
+                    param_names: vec!["T".to_owned()],
+                }),
+            },
+        ],
+    }
+}
+
+#[test]
+fn test_roundtrip() {
+    let krate = sample();
+    let bytes = krate.encode();
+    assert!(bytes.starts_with(MAGIC));
+    assert_eq!(Crate::decode(&bytes), Ok(krate));
+}
+
+#[test]
+fn test_item_kind_discriminants() {
+    for n in 0..=u8::MAX {
+        if let Some(kind) = ItemKind::from_u8(n) {
+            assert_eq!(kind as u8, n);
+            assert_eq!(ItemKind::from_name(kind.as_str()), Some(kind));
+        }
+    }
+    assert_eq!(ItemKind::from_u8(22), None);
+    assert_eq!(ItemKind::from_u8(26), None);
+}
+
+#[test]
+fn test_decode_errors() {
+    let bytes = sample().encode();
+    assert_eq!(Crate::decode(b"RDSJ"), Err(DecodeError::BadMagic));
+    assert_eq!(Crate::decode(&bytes[..bytes.len() - 1]), Err(DecodeError::UnexpectedEof));
+
+    let mut newer = bytes.clone();
+    newer[MAGIC.len()] = 2;
+    assert_eq!(Crate::decode(&newer), Err(DecodeError::UnsupportedVersion(2)));
+
+    let mut bad_parent = sample();
+    bad_parent.items[1].parent = Some(5);
+    assert_eq!(Crate::decode(&bad_parent.encode()), Err(DecodeError::InvalidPathIndex(5)));
+
+    let mut bad_generic = sample();
+    let signature = bad_generic.items[1].signature.as_mut().unwrap();
+    signature.inputs[0].generics[0].id = TypeId::Generic(1);
+    assert_eq!(Crate::decode(&bad_generic.encode()), Err(DecodeError::InvalidGenericIndex(1)));
+}
+
+#[test]
+fn test_decode_deeply_nested_types() {
+    fn nested(depth: usize) -> Crate {
+        let mut ty = TypeNode { id: TypeId::Path(1), generics: vec![], bindings: vec![] };
+        for _ in 1..depth {
+            ty = TypeNode { id: TypeId::Path(0), generics: vec![ty], bindings: vec![] };
+        }
+        let mut krate = sample();
+        krate.items[1].signature.as_mut().unwrap().output = vec![ty];
+        krate
+    }
+
+    let deepest = nested(MAX_TYPE_DEPTH);
+    assert_eq!(Crate::decode(&deepest.encode()), Ok(deepest));
+    assert_eq!(Crate::decode(&nested(MAX_TYPE_DEPTH + 1).encode()), Err(DecodeError::TypeTooDeep));
+
+    // A corrupt index nesting types far deeper than any real signature is rejected instead of
+    // overflowing the stack.
+    let mut bytes = sample().encode();
+    // The encoding ends with the bounds of the only generic parameter: an empty `types`.
+    assert_eq!(bytes.pop(), Some(0));
+    for _ in 0..1_000_000 {
+        bytes.extend([1, 2]);
+    }
+    assert_eq!(Crate::decode(&bytes), Err(DecodeError::TypeTooDeep));
+}
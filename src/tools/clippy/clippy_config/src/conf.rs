@@ -460,6 +460,25 @@ pub fn get_configuration_metadata() -> Vec<ClippyConfiguration> {
     /// The maximum cognitive complexity a function can have
     #[lints(cognitive_complexity)]
     cognitive_complexity_threshold: u64 = 25,
+    /// Configuration for the lints of the custom lint libraries, which they read from the
+    /// `custom_lint_config` field of the `Conf` they are passed.
+    ///
+    /// #### Example
+    ///
+    /// ```toml
+    /// [custom-lint-config]
+    /// db-handle-wrapper = "crate::db::Handle"
+    /// ```
+    custom_lint_config: toml::Table = toml::Table::new(),
+    /// Dynamic libraries with project-local lints to load, relative to the directory of the
+    /// configuration file. See the "Custom Lints" chapter of the book for how to write them.
+    ///
+    /// #### Example
+    ///
+    /// ```toml
+    /// custom-lint-libraries = ["target/debug/libour_lints.so"]
+    /// ```
+    custom_lint_libraries: Vec<String> = Vec::new(),
     /// DEPRECATED LINT: CYCLOMATIC_COMPLEXITY.
     ///
     /// Use the Cognitive Complexity lint instead.
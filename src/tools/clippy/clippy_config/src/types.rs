@@ -1,10 +1,13 @@
 use clippy_utils::def_path_def_ids;
-use rustc_hir::def_id::DefIdMap;
+use rustc_hir::HirId;
+use rustc_hir::def_id::{DefIdMap, LOCAL_CRATE, LocalModDefId};
 use rustc_middle::ty::TyCtxt;
+use rustc_span::{FileName, Span};
 use serde::de::{self, Deserializer, Visitor};
 use serde::{Deserialize, Serialize, ser};
 use std::collections::HashMap;
 use std::fmt;
+use std::path::Path;
 
 #[derive(Debug, Deserialize)]
 pub struct Rename {
@@ -16,14 +19,21 @@ pub struct Rename {
 #[serde(untagged)]
 pub enum DisallowedPath {
     Simple(String),
-    WithReason { path: String, reason: Option<String> },
+    WithReason {
+        path: String,
+        reason: Option<String>,
+        replacement: Option<String>,
+        #[serde(rename = "only-in", default)]
+        only_in: Vec<String>,
+    },
 }
 
 impl DisallowedPath {
+    /// The path of the disallowed item, without the argument patterns.
     pub fn path(&self) -> &str {
         let (Self::Simple(path) | Self::WithReason { path, .. }) = self;
 
-        path
+        path.split_once('(').map_or(path.as_str(), |(path, _)| path).trim()
     }
 
     pub fn reason(&self) -> Option<&str> {
@@ -32,18 +42,158 @@ pub fn reason(&self) -> Option<&str> {
             Self::Simple(_) => None,
         }
     }
+
+    /// The code to replace uses of the item with. Occurrences of `$name` are replaced with the
+    /// arguments bound by the argument patterns.
+    pub fn replacement(&self) -> Option<&str> {
+        match &self {
+            Self::WithReason { replacement, .. } => replacement.as_deref(),
+            Self::Simple(_) => None,
+        }
+    }
+
+    /// The argument patterns, if the path is followed by a parenthesized list of them, as in
+    /// `std::env::var($name)`. For methods, the receiver is the first argument.
+    ///
+    /// A pattern is either `$name`, which matches any argument and binds it to `name`, `_`, which
+    /// matches any argument, or code that the argument has to match exactly, ignoring whitespace.
+    pub fn arg_patterns(&self) -> Option<Vec<&str>> {
+        let (Self::Simple(path) | Self::WithReason { path, .. }) = self;
+        let (_, args) = path.split_once('(')?;
+        let args = args.trim_end().strip_suffix(')').unwrap_or(args).trim();
+        if args.is_empty() {
+            return Some(Vec::new());
+        }
+        Some(split_args(args))
+    }
+
+    /// Whether the item is disallowed at `span` in the module containing `hir_id`.
+    ///
+    /// Rules with an `only-in` list only apply in the listed modules (written as paths starting
+    /// with the crate name, like `my_crate::legacy`) and files or directories (written as paths
+    /// containing a `/`, relative to the directory Clippy is run in), including their submodules
+    /// and subdirectories.
+    pub fn applies_in(&self, tcx: TyCtxt<'_>, module_paths: &mut ModulePaths, hir_id: HirId, span: Span) -> bool {
+        let Self::WithReason { only_in, .. } = self else {
+            return true;
+        };
+        if only_in.is_empty() {
+            return true;
+        }
+        let module = module_paths.get(tcx, hir_id);
+        let file = match tcx.sess.source_map().span_to_filename(span) {
+            FileName::Real(name) => name.local_path().map(Path::to_path_buf),
+            _ => None,
+        };
+        only_in.iter().any(|scope| {
+            if scope.contains('/') {
+                file.as_ref().is_some_and(|file| file.starts_with(scope))
+            } else {
+                module
+                    .strip_prefix(scope.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            }
+        })
+    }
+}
+
+/// The paths of the modules that `only-in` lists are checked against, starting with the crate
+/// name. Each one is only computed once, the first time a rule with an `only-in` list is checked
+/// in that module.
+#[derive(Default)]
+pub struct ModulePaths(HashMap<LocalModDefId, String>);
+
+impl ModulePaths {
+    /// The path of the module containing `hir_id`.
+    fn get(&mut self, tcx: TyCtxt<'_>, hir_id: HirId) -> &str {
+        self.0.entry(tcx.parent_module(hir_id)).or_insert_with_key(|module| {
+            format!(
+                "{}{}",
+                tcx.crate_name(LOCAL_CRATE),
+                tcx.def_path(module.to_def_id()).to_string_no_crate_verbose()
+            )
+        })
+    }
+}
+
+/// Splits argument patterns at the commas that aren't nested in brackets or inside string or
+/// character literals.
+fn split_args(args: &str) -> Vec<&str> {
+    let bytes = args.as_bytes();
+    let is_ident = |i: usize| bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_';
+    let mut patterns = Vec::new();
+    let (mut depth, mut start, mut i) = (0usize, 0, 0);
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = str_end(bytes, i + 1, None),
+            // A raw string, `r"..."` or `br#"..."#`, unless the `r` ends an identifier.
+            b'r' if (i == 0 || !is_ident(i - 1) || (bytes[i - 1] == b'b' && (i == 1 || !is_ident(i - 2)))) => {
+                let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+                if bytes.get(i + 1 + hashes) == Some(&b'"') {
+                    i = str_end(bytes, i + 2 + hashes, Some(hashes));
+                }
+            },
+            b'\'' => {
+                // Either a character literal or a lifetime, which is left alone.
+                if bytes.get(i + 1) == Some(&b'\\') {
+                    i = bytes
+                        .get(i + 3..)
+                        .and_then(|rest| rest.iter().position(|&b| b == b'\''))
+                        .map_or(bytes.len(), |end| i + 3 + end);
+                } else if let Some(c) = args[i + 1..].chars().next()
+                    && bytes.get(i + 1 + c.len_utf8()) == Some(&b'\'')
+                {
+                    i += 1 + c.len_utf8();
+                }
+            },
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            b',' if depth == 0 => {
+                patterns.push(args[start..i].trim());
+                start = i + 1;
+            },
+            _ => {},
+        }
+        i += 1;
+    }
+    patterns.push(args[start..].trim());
+    patterns
 }
 
-/// Creates a map of disallowed items to the reason they were disallowed.
+/// The index of the closing quote of the string literal whose contents start at `start`, or the
+/// length of `bytes` if it isn't closed. Raw strings are closed by a quote followed by `hashes`
+/// hashes, and have no escapes.
+fn str_end(bytes: &[u8], start: usize, hashes: Option<usize>) -> usize {
+    let mut i = start;
+    while i < bytes.len() {
+        match (bytes[i], hashes) {
+            (b'\\', None) => i += 1,
+            (b'"', None) => return i,
+            (b'"', Some(hashes))
+                if bytes[i + 1..].iter().take(hashes).filter(|&&b| b == b'#').count() == hashes =>
+            {
+                return i + hashes;
+            },
+            _ => {},
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Creates a map of disallowed items to the rules that disallow them.
 pub fn create_disallowed_map(
     tcx: TyCtxt<'_>,
     disallowed: &'static [DisallowedPath],
-) -> DefIdMap<(&'static str, Option<&'static str>)> {
-    disallowed
-        .iter()
-        .map(|x| (x.path(), x.path().split("::").collect::<Vec<_>>(), x.reason()))
-        .flat_map(|(name, path, reason)| def_path_def_ids(tcx, &path).map(move |id| (id, (name, reason))))
-        .collect()
+) -> DefIdMap<Vec<&'static DisallowedPath>> {
+    let mut map: DefIdMap<Vec<_>> = DefIdMap::default();
+    for x in disallowed {
+        let path: Vec<_> = x.path().split("::").collect();
+        for id in def_path_def_ids(tcx, &path) {
+            map.entry(id).or_default().push(x);
+        }
+    }
+    map
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -1,5 +1,5 @@
 use clippy_config::Conf;
-use clippy_config::types::create_disallowed_map;
+use clippy_config::types::{DisallowedPath, ModulePaths, create_disallowed_map};
 use clippy_utils::diagnostics::span_lint_and_then;
 use clippy_utils::{match_def_path, paths};
 use rustc_hir as hir;
@@ -174,13 +174,15 @@
 impl_lint_pass!(AwaitHolding => [AWAIT_HOLDING_LOCK, AWAIT_HOLDING_REFCELL_REF, AWAIT_HOLDING_INVALID_TYPE]);
 
 pub struct AwaitHolding {
-    def_ids: DefIdMap<(&'static str, Option<&'static str>)>,
+    def_ids: DefIdMap<Vec<&'static DisallowedPath>>,
+    module_paths: ModulePaths,
 }
 
 impl AwaitHolding {
     pub(crate) fn new(tcx: TyCtxt<'_>, conf: &'static Conf) -> Self {
         Self {
             def_ids: create_disallowed_map(tcx, &conf.await_holding_invalid_types),
+            module_paths: ModulePaths::default(),
         }
     }
 }
@@ -194,14 +196,14 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx hir::Expr<'tcx>) {
         }) = expr.kind
         {
             if let Some(coroutine_layout) = cx.tcx.mir_coroutine_witnesses(*def_id) {
-                self.check_interior_types(cx, coroutine_layout);
+                self.check_interior_types(cx, expr.hir_id, coroutine_layout);
             }
         }
     }
 }
 
 impl AwaitHolding {
-    fn check_interior_types(&self, cx: &LateContext<'_>, coroutine: &CoroutineLayout<'_>) {
+    fn check_interior_types(&mut self, cx: &LateContext<'_>, hir_id: hir::HirId, coroutine: &CoroutineLayout<'_>) {
         for (ty_index, ty_cause) in coroutine.field_tys.iter_enumerated() {
             if let rustc_middle::ty::Adt(adt, _) = ty_cause.ty.kind() {
                 let await_points = || {
@@ -247,8 +249,12 @@ fn check_interior_types(&self, cx: &LateContext<'_>, coroutine: &CoroutineLayout
                             );
                         },
                     );
-                } else if let Some(&(path, reason)) = self.def_ids.get(&adt.did()) {
-                    emit_invalid_type(cx, ty_cause.source_info.span, path, reason);
+                } else if let Some(rules) = self.def_ids.get(&adt.did())
+                    && let Some(rule) = rules
+                        .iter()
+                        .find(|rule| rule.applies_in(cx.tcx, &mut self.module_paths, hir_id, ty_cause.source_info.span))
+                {
+                    emit_invalid_type(cx, ty_cause.source_info.span, rule.path(), rule.reason());
                 }
             }
         }
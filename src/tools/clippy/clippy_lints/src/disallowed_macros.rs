@@ -1,9 +1,10 @@
 use clippy_config::Conf;
-use clippy_config::types::create_disallowed_map;
+use clippy_config::types::{DisallowedPath, ModulePaths, create_disallowed_map};
 use clippy_utils::diagnostics::{span_lint_and_then, span_lint_hir_and_then};
 use clippy_utils::macros::macro_backtrace;
+use clippy_utils::source::snippet_opt;
 use rustc_data_structures::fx::FxHashSet;
-use rustc_errors::Diag;
+use rustc_errors::{Applicability, Diag};
 use rustc_hir::def_id::DefIdMap;
 use rustc_hir::{
     Expr, ExprKind, ForeignItem, HirId, ImplItem, Item, ItemKind, OwnerId, Pat, Path, Stmt, TraitItem, Ty,
@@ -11,7 +12,7 @@
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::impl_lint_pass;
-use rustc_span::{ExpnId, MacroKind, Span};
+use rustc_span::{BytePos, ExpnId, MacroKind, Pos, Span};
 
 use crate::utils::attr_collector::AttrStorage;
 
@@ -38,6 +39,10 @@
     ///     # When using an inline table, can add a `reason` for why the macro
     ///     # is disallowed.
     ///     { path = "serde::Serialize", reason = "no serializing" },
+    ///     # Can add a `replacement` to suggest for function-like macros, and
+    ///     # restrict the rule to modules (starting with the crate name) and to
+    ///     # files or directories (relative to where Clippy is run).
+    ///     { path = "std::eprintln", replacement = "log::error", only-in = ["my_crate::server"] },
     /// ]
     /// ```
     /// ```no_run
@@ -60,7 +65,8 @@
 }
 
 pub struct DisallowedMacros {
-    disallowed: DefIdMap<(&'static str, Option<&'static str>)>,
+    disallowed: DefIdMap<Vec<&'static DisallowedPath>>,
+    module_paths: ModulePaths,
     seen: FxHashSet<ExpnId>,
     // Track the most recently seen node that can have a `derive` attribute.
     // Needed to use the correct lint level.
@@ -75,6 +81,7 @@ impl DisallowedMacros {
     pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf, earlies: AttrStorage) -> Self {
         Self {
             disallowed: create_disallowed_map(tcx, &conf.disallowed_macros),
+            module_paths: ModulePaths::default(),
             seen: FxHashSet::default(),
             derive_src: None,
             earlies,
@@ -91,12 +98,34 @@ fn check(&mut self, cx: &LateContext<'_>, span: Span, derive_src: Option<OwnerId
                 return;
             }
 
-            if let Some(&(path, reason)) = self.disallowed.get(&mac.def_id) {
-                let msg = format!("use of a disallowed macro `{path}`");
+            let hir_id = derive_src.map_or(cx.last_node_with_lint_attrs, |src| {
+                cx.tcx.local_def_id_to_hir_id(src.def_id)
+            });
+            if let Some(rules) = self.disallowed.get(&mac.def_id)
+                && let Some(rule) = rules
+                    .iter()
+                    .find(|rule| rule.applies_in(cx.tcx, &mut self.module_paths, hir_id, mac.span))
+            {
+                let msg = format!("use of a disallowed macro `{}`", rule.path());
                 let add_note = |diag: &mut Diag<'_, _>| {
-                    if let Some(reason) = reason {
+                    if let Some(reason) = rule.reason() {
                         diag.note(reason);
                     }
+                    // Replace the name of a function-like macro, up to the `!`.
+                    if let Some(replacement) = rule.replacement()
+                        && matches!(mac.kind, MacroKind::Bang)
+                        && !mac.span.from_expansion()
+                        && let Some(snippet) = snippet_opt(cx, mac.span)
+                        && let Some(bang) = snippet.find('!')
+                    {
+                        let name_span = mac.span.with_hi(mac.span.lo() + BytePos::from_usize(bang));
+                        diag.span_suggestion(
+                            name_span,
+                            "use",
+                            replacement.to_owned(),
+                            Applicability::MachineApplicable,
+                        );
+                    }
                 };
                 if matches!(mac.kind, MacroKind::Derive)
                     && let Some(derive_src) = derive_src
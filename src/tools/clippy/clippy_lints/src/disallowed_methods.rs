@@ -1,12 +1,19 @@
 use clippy_config::Conf;
-use clippy_config::types::create_disallowed_map;
+use clippy_config::types::{DisallowedPath, ModulePaths, create_disallowed_map};
 use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::get_parent_expr;
+use clippy_utils::source::snippet_opt;
+use clippy_utils::sugg::{Sugg, make_unop};
+use rustc_errors::Applicability;
 use rustc_hir::def::{CtorKind, DefKind, Res};
 use rustc_hir::def_id::DefIdMap;
-use rustc_hir::{Expr, ExprKind};
+use rustc_hir::{Expr, ExprKind, Mutability};
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::adjustment::{Adjust, Adjustment, AutoBorrow};
 use rustc_session::impl_lint_pass;
+use rustc_span::Span;
+use std::iter;
 
 declare_clippy_lint! {
     /// ### What it does
@@ -31,9 +38,23 @@
     ///     # When using an inline table, can add a `reason` for why the method
     ///     # is disallowed.
     ///     { path = "std::vec::Vec::leak", reason = "no leaking memory" },
+    ///     # Can add a `replacement` to suggest, and restrict the rule to calls whose
+    ///     # arguments match patterns. `$name` matches any argument, `_` matches
+    ///     # any argument without binding it, and anything else has to match exactly.
+    ///     { path = "std::env::var($name)", replacement = "our_env::var($name)" },
+    ///     { path = "std::fs::read(\"secrets.toml\")", reason = "use the secret store" },
+    ///     # Can restrict a rule to modules (starting with the crate name) and to
+    ///     # files or directories (relative to where Clippy is run).
+    ///     { path = "std::thread::spawn", only-in = ["my_crate::handlers", "src/jobs/"] },
     /// ]
     /// ```
     ///
+    /// For methods, the receiver is the first argument, so `std::vec::Vec::push($v, 0)` matches
+    /// `v.push(0)`. Without argument patterns, a method call is replaced with a call of the
+    /// `replacement` that passes the receiver borrowed like the method borrows it, so
+    /// `s.len()` becomes `our_len(&s)`. Suggestions with a `replacement` are applied by
+    /// `cargo clippy --fix`, unless the receiver is adjusted in a way that can't be written out.
+    ///
     /// ```rust,ignore
     /// // Example code where clippy issues a warning
     /// let xs = vec![1, 2, 3, 4];
@@ -58,13 +79,15 @@
 }
 
 pub struct DisallowedMethods {
-    disallowed: DefIdMap<(&'static str, Option<&'static str>)>,
+    disallowed: DefIdMap<Vec<&'static DisallowedPath>>,
+    module_paths: ModulePaths,
 }
 
 impl DisallowedMethods {
     pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf) -> Self {
         Self {
             disallowed: create_disallowed_map(tcx, &conf.disallowed_methods),
+            module_paths: ModulePaths::default(),
         }
     }
 }
@@ -72,31 +95,201 @@ pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf) -> Self {
 impl_lint_pass!(DisallowedMethods => [DISALLOWED_METHODS]);
 
 impl<'tcx> LateLintPass<'tcx> for DisallowedMethods {
-    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
-        let (id, span) = match &expr.kind {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
+        let (id, span, call) = match &expr.kind {
             ExprKind::Path(path)
                 if let Res::Def(DefKind::Fn | DefKind::Ctor(_, CtorKind::Fn) | DefKind::AssocFn, id) =
                     cx.qpath_res(path, expr.hir_id) =>
             {
-                (id, expr.span)
+                let call = get_parent_expr(cx, expr).and_then(|parent| match parent.kind {
+                    ExprKind::Call(callee, args) if callee.hir_id == expr.hir_id => {
+                        Some(Call::Fn(parent, args.iter().collect()))
+                    },
+                    _ => None,
+                });
+                (id, expr.span, call)
             },
-            ExprKind::MethodCall(name, ..) if let Some(id) = cx.typeck_results().type_dependent_def_id(expr.hir_id) => {
-                (id, name.ident.span)
+            ExprKind::MethodCall(name, receiver, args, _)
+                if let Some(id) = cx.typeck_results().type_dependent_def_id(expr.hir_id) =>
+            {
+                let args = iter::once(*receiver).chain(args.iter()).collect();
+                (id, name.ident.span, Some(Call::Method(expr, args)))
             },
             _ => return,
         };
-        if let Some(&(path, reason)) = self.disallowed.get(&id) {
-            span_lint_and_then(
-                cx,
-                DISALLOWED_METHODS,
-                span,
-                format!("use of a disallowed method `{path}`"),
-                |diag| {
-                    if let Some(reason) = reason {
-                        diag.note(reason);
-                    }
-                },
-            );
+        let Some(rules) = self.disallowed.get(&id) else {
+            return;
+        };
+        let mut bindings = Vec::new();
+        let Some(rule) = rules.iter().find(|rule| {
+            bindings.clear();
+            rule.applies_in(cx.tcx, &mut self.module_paths, expr.hir_id, expr.span) && match_args(cx, rule, call.as_ref(), &mut bindings)
+        }) else {
+            return;
+        };
+        span_lint_and_then(
+            cx,
+            DISALLOWED_METHODS,
+            span,
+            format!("use of a disallowed method `{}`", rule.path()),
+            |diag| {
+                if let Some(reason) = rule.reason() {
+                    diag.note(reason);
+                }
+                if let Some(replacement) = rule.replacement()
+                    && let Some((span, sugg, app)) = suggestion(cx, rule, replacement, span, call.as_ref(), &bindings)
+                {
+                    diag.span_suggestion(span, "use", sugg, app);
+                }
+            },
+        );
+    }
+}
+
+/// A call of a disallowed method, with all of its arguments.
+enum Call<'tcx> {
+    /// A call of a path, like `Vec::push(v, 0)`.
+    Fn(&'tcx Expr<'tcx>, Vec<&'tcx Expr<'tcx>>),
+    /// A method call, like `v.push(0)`. The receiver is the first argument.
+    Method(&'tcx Expr<'tcx>, Vec<&'tcx Expr<'tcx>>),
+}
+
+impl<'tcx> Call<'tcx> {
+    fn expr(&self) -> &'tcx Expr<'tcx> {
+        let (Self::Fn(expr, _) | Self::Method(expr, _)) = self;
+        expr
+    }
+
+    fn args(&self) -> &[&'tcx Expr<'tcx>] {
+        let (Self::Fn(_, args) | Self::Method(_, args)) = self;
+        args
+    }
+}
+
+/// Checks the arguments of `call` against the argument patterns of `rule`, binding the arguments
+/// matched by `$name` patterns.
+fn match_args<'tcx>(
+    cx: &LateContext<'tcx>,
+    rule: &'static DisallowedPath,
+    call: Option<&Call<'tcx>>,
+    bindings: &mut Vec<(&'static str, &'tcx Expr<'tcx>)>,
+) -> bool {
+    let Some(patterns) = rule.arg_patterns() else {
+        return true;
+    };
+    let Some(call) = call else {
+        return false;
+    };
+    let without_whitespace = |s: &str| s.split_whitespace().collect::<String>();
+    patterns.len() == call.args().len()
+        && patterns.into_iter().zip(call.args()).all(|(pattern, arg)| {
+            if let Some(name) = pattern.strip_prefix('$') {
+                bindings.push((name, arg));
+                true
+            } else {
+                pattern == "_"
+                    || snippet_opt(cx, arg.span)
+                        .is_some_and(|arg| without_whitespace(&arg) == without_whitespace(pattern))
+            }
+        })
+}
+
+/// Builds the suggestion to replace the use of the disallowed method at `span` with
+/// `replacement`.
+///
+/// With argument patterns, the whole call is replaced, substituting the bound arguments into the
+/// replacement. Otherwise only the path is replaced, and method calls are turned into calls of
+/// the replacement with the receiver as the first argument, borrowed like the method call borrowed
+/// it.
+fn suggestion(
+    cx: &LateContext<'_>,
+    rule: &DisallowedPath,
+    replacement: &str,
+    span: Span,
+    call: Option<&Call<'_>>,
+    bindings: &[(&str, &Expr<'_>)],
+) -> Option<(Span, String, Applicability)> {
+    let mut app = Applicability::MachineApplicable;
+    if span.from_expansion() || call.is_some_and(|call| call.expr().span.from_expansion()) {
+        return None;
+    }
+    let sugg_arg = |arg: &Expr<'_>, call: &Call<'_>, app: &mut Applicability| {
+        Sugg::hir_with_context(cx, arg, call.expr().span.ctxt(), "..", app)
+    };
+    let (span, sugg) = match call {
+        Some(call) if rule.arg_patterns().is_some() => {
+            // Replace `$name` with the argument bound to `name`.
+            let mut sugg = String::new();
+            let mut rest = replacement;
+            while let Some((before, after)) = rest.split_once('$') {
+                sugg.push_str(before);
+                let end = after
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                let (_, arg) = bindings.iter().find(|(name, _)| *name == &after[..end])?;
+                // The replacement may use the argument as an operand, e.g. `&mut $v`.
+                sugg.push_str(&sugg_arg(arg, call, &mut app).maybe_par().to_string());
+                rest = &after[end..];
+            }
+            sugg.push_str(rest);
+            (call.expr().span, sugg)
+        },
+        Some(call @ Call::Method(expr, args)) if let [receiver, args @ ..] = &args[..] => {
+            let receiver = receiver_sugg(cx, receiver, sugg_arg(receiver, call, &mut app), &mut app);
+            let args: Vec<_> = iter::once(receiver)
+                .chain(args.iter().map(|arg| sugg_arg(arg, call, &mut app)))
+                .map(|arg| arg.to_string())
+                .collect();
+            (expr.span, format!("{replacement}({})", args.join(", ")))
+        },
+        _ => (span, replacement.to_owned()),
+    };
+    Some((span, sugg, app))
+}
+
+/// Applies the auto-borrow of the method call to its `receiver`, so that it can be passed to a
+/// function. Derefs before the borrow are made explicit, except for reborrows of references.
+/// Other adjustments lower the applicability, since the replacement may take a different type.
+fn receiver_sugg<'a>(cx: &LateContext<'_>, receiver: &Expr<'_>, sugg: Sugg<'a>, app: &mut Applicability) -> Sugg<'a> {
+    let adjustments = cx.typeck_results().expr_adjustments(receiver);
+    let (borrow, derefs) = match adjustments {
+        [
+            derefs @ ..,
+            Adjustment {
+                kind: Adjust::Borrow(AutoBorrow::Ref(mutbl)),
+                ..
+            },
+        ] => (Some(Mutability::from(*mutbl)), derefs),
+        derefs => (None, derefs),
+    };
+    if derefs
+        .iter()
+        .any(|adjustment| !matches!(adjustment.kind, Adjust::Deref(_)))
+    {
+        if *app == Applicability::MachineApplicable {
+            *app = Applicability::MaybeIncorrect;
         }
+        return sugg;
+    }
+    if borrow.is_some()
+        && let [
+            Adjustment {
+                kind: Adjust::Deref(None),
+                ..
+            },
+        ] = derefs
+        && cx.typeck_results().expr_ty(receiver).is_ref()
+    {
+        return sugg;
+    }
+    let prefix = match borrow {
+        Some(Mutability::Mut) => "&mut ",
+        Some(Mutability::Not) => "&",
+        None => "",
+    };
+    if prefix.is_empty() && derefs.is_empty() {
+        sugg
+    } else {
+        make_unop(&format!("{prefix}{}", "*".repeat(derefs.len())), sugg)
     }
 }
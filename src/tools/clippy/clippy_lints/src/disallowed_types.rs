@@ -1,9 +1,12 @@
 use clippy_config::Conf;
+use clippy_config::types::{DisallowedPath, ModulePaths};
 use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::source::snippet_opt;
 use rustc_data_structures::fx::FxHashMap;
+use rustc_errors::Applicability;
 use rustc_hir::def::Res;
 use rustc_hir::def_id::DefIdMap;
-use rustc_hir::{Item, ItemKind, PolyTraitRef, PrimTy, Ty, TyKind, UseKind};
+use rustc_hir::{Item, ItemKind, Path, PolyTraitRef, PrimTy, QPath, Ty, TyKind, UseKind};
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::impl_lint_pass;
@@ -31,6 +34,10 @@
     ///     # When using an inline table, can add a `reason` for why the type
     ///     # is disallowed.
     ///     { path = "std::net::Ipv4Addr", reason = "no IPv4 allowed" },
+    ///     # Can add a `replacement` to suggest, and restrict the rule to modules
+    ///     # (starting with the crate name) and to files or directories (relative
+    ///     # to where Clippy is run).
+    ///     { path = "std::collections::HashMap", replacement = "rustc_hash::FxHashMap", only-in = ["my_crate::hot"] },
     /// ]
     /// ```
     ///
@@ -51,8 +58,9 @@
 }
 
 pub struct DisallowedTypes {
-    def_ids: DefIdMap<(&'static str, Option<&'static str>)>,
-    prim_tys: FxHashMap<PrimTy, (&'static str, Option<&'static str>)>,
+    def_ids: DefIdMap<Vec<&'static DisallowedPath>>,
+    prim_tys: FxHashMap<PrimTy, Vec<&'static DisallowedPath>>,
+    module_paths: ModulePaths,
 }
 
 impl DisallowedTypes {
@@ -61,60 +69,96 @@ pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf) -> Self {
         let mut prim_tys = FxHashMap::default();
         for x in &conf.disallowed_types {
             let path: Vec<_> = x.path().split("::").collect::<Vec<_>>();
-            let reason = x.reason();
             for res in clippy_utils::def_path_res(tcx, &path) {
                 match res {
                     Res::Def(_, id) => {
-                        def_ids.insert(id, (x.path(), reason));
+                        def_ids.entry(id).or_insert_with(Vec::new).push(x);
                     },
                     Res::PrimTy(ty) => {
-                        prim_tys.insert(ty, (x.path(), reason));
+                        prim_tys.entry(ty).or_insert_with(Vec::new).push(x);
                     },
                     _ => {},
                 }
             }
         }
-        Self { def_ids, prim_tys }
+        Self {
+            def_ids,
+            prim_tys,
+            module_paths: ModulePaths::default(),
+        }
     }
 
-    fn check_res_emit(&self, cx: &LateContext<'_>, res: &Res, span: Span) {
-        let (path, reason) = match res {
-            Res::Def(_, did) if let Some(&x) = self.def_ids.get(did) => x,
-            Res::PrimTy(prim) if let Some(&x) = self.prim_tys.get(prim) => x,
+    /// Lints the use of `res` at `span`. A `replacement` of the type is suggested for
+    /// `replace_span`, if any.
+    fn check_res_emit(&mut self, cx: &LateContext<'_>, res: &Res, span: Span, replace_span: Option<Span>) {
+        let rules = match res {
+            Res::Def(_, did) if let Some(rules) = self.def_ids.get(did) => rules,
+            Res::PrimTy(prim) if let Some(rules) = self.prim_tys.get(prim) => rules,
             _ => return,
         };
+        let Some(rule) = rules
+            .iter()
+            .find(|rule| rule.applies_in(cx.tcx, &mut self.module_paths, cx.last_node_with_lint_attrs, span))
+        else {
+            return;
+        };
         span_lint_and_then(
             cx,
             DISALLOWED_TYPES,
             span,
-            format!("use of a disallowed type `{path}`"),
+            format!("use of a disallowed type `{}`", rule.path()),
             |diag| {
-                if let Some(reason) = reason {
+                if let Some(reason) = rule.reason() {
                     diag.note(reason);
                 }
+                if let Some(replacement) = rule.replacement()
+                    && let Some(replace_span) = replace_span
+                    && !replace_span.from_expansion()
+                {
+                    diag.span_suggestion(
+                        replace_span,
+                        "use",
+                        replacement.to_owned(),
+                        Applicability::MachineApplicable,
+                    );
+                }
             },
         );
     }
 }
 
+/// The span of `path` without the generic arguments of its last segment, which are kept when
+/// replacing the path.
+fn path_span_without_args(path: &Path<'_>) -> Option<Span> {
+    let last = path.segments.last()?;
+    Some(path.span.with_hi(last.ident.span.hi()))
+}
+
 impl_lint_pass!(DisallowedTypes => [DISALLOWED_TYPES]);
 
 impl<'tcx> LateLintPass<'tcx> for DisallowedTypes {
     fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx Item<'tcx>) {
         if let ItemKind::Use(path, UseKind::Single) = &item.kind {
+            // Only replace imports of a single path, not the paths in a `use` tree.
+            let replace_span = Some(path.span).filter(|&span| snippet_opt(cx, span).is_some_and(|s| !s.contains('{')));
             for res in &path.res {
-                self.check_res_emit(cx, res, item.span);
+                self.check_res_emit(cx, res, item.span, replace_span);
             }
         }
     }
 
     fn check_ty(&mut self, cx: &LateContext<'tcx>, ty: &'tcx Ty<'tcx>) {
-        if let TyKind::Path(path) = &ty.kind {
-            self.check_res_emit(cx, &cx.qpath_res(path, ty.hir_id), ty.span);
+        if let TyKind::Path(qpath) = &ty.kind {
+            let replace_span = match qpath {
+                QPath::Resolved(None, path) => path_span_without_args(path),
+                _ => None,
+            };
+            self.check_res_emit(cx, &cx.qpath_res(qpath, ty.hir_id), ty.span, replace_span);
         }
     }
 
     fn check_poly_trait_ref(&mut self, cx: &LateContext<'tcx>, poly: &'tcx PolyTraitRef<'tcx>) {
-        self.check_res_emit(cx, &poly.trait_ref.path.res, poly.trait_ref.path.span);
+        let path = poly.trait_ref.path;
+        self.check_res_emit(cx, &path.res, path.span, path_span_without_args(path));
     }
 }
@@ -11,13 +11,18 @@
 // (Currently there is no way to opt into sysroot crates without `extern crate`.)
 extern crate rustc_driver;
 extern crate rustc_interface;
+extern crate rustc_lint;
+extern crate rustc_metadata;
 extern crate rustc_session;
 extern crate rustc_span;
 
+use clippy_config::Conf;
 use rustc_interface::interface;
-use rustc_session::EarlyDiagCtxt;
+use rustc_lint::LintStore;
+use rustc_metadata::{DylibError, load_symbol_from_dylib};
 use rustc_session::config::ErrorOutputType;
 use rustc_session::parse::ParseSess;
+use rustc_session::{EarlyDiagCtxt, Session};
 use rustc_span::symbol::Symbol;
 
 use std::env;
@@ -127,6 +132,80 @@ fn config(&mut self, config: &mut interface::Config) {
     }
 }
 
+/// The function that custom lint libraries export as `clippy_register_lints`. It is passed the
+/// parsed configuration, whose `custom_lint_config` holds the `custom-lint-config` table.
+type RegisterLintsFn = fn(&Session, &mut LintStore, &'static Conf);
+
+/// Loads the libraries listed in `custom-lint-libraries` and lets them register their lints.
+fn register_custom_lints(sess: &Session, lint_store: &mut LintStore, conf: &'static Conf, conf_file: Option<&Path>) {
+    if conf.custom_lint_libraries.is_empty() {
+        return;
+    }
+    let conf_dir = conf_file.and_then(Path::parent).unwrap_or(Path::new("."));
+    for library in &conf.custom_lint_libraries {
+        let path = conf_dir.join(library);
+        // SAFETY: the library has to be built against the same `rustc_driver` as this driver, which
+        // `load_symbol_from_dylib` can't check. Loading one built by a different toolchain fails
+        // because the `rustc_driver` dylib it links against can't be found.
+        match unsafe { load_symbol_from_dylib::<RegisterLintsFn>(&path, "clippy_register_lints") } {
+            Ok(register_lints) => {
+                // The library registers into a store of its own, since registering a lint that already
+                // exists is a compiler bug, and its lints are moved over unless one of them collides.
+                let mut custom_store = LintStore::new();
+                register_lints(sess, &mut custom_store, conf);
+                merge_custom_lints(sess, lint_store, custom_store, &path);
+            },
+            Err(DylibError::DlOpen(path, err)) => {
+                sess.dcx()
+                    .err(format!("failed to load custom lint library `{path}`{err}"));
+            },
+            Err(DylibError::DlSym(path, err)) => {
+                sess.dcx().err(format!(
+                    "custom lint library `{path}` does not export `clippy_register_lints`{err}"
+                ));
+            },
+        }
+    }
+}
+
+/// Moves the lints, lint groups and lint passes registered by the custom lint library at `path`
+/// into `lint_store`. If any of its lints or groups has the name of an already registered lint or
+/// group, nothing is moved and an error is emitted instead.
+fn merge_custom_lints(sess: &Session, lint_store: &mut LintStore, custom_store: LintStore, path: &Path) {
+    let lints = custom_store.get_lints();
+    let groups: Vec<_> = custom_store.get_lint_groups().collect();
+    let mut collisions = lints
+        .iter()
+        .map(|lint| lint.name_lower())
+        .filter(|name| lint_store.find_lints(name).is_ok())
+        .chain(
+            groups
+                .iter()
+                .filter(|(name, ..)| lint_store.is_lint_group(Symbol::intern(name)))
+                .map(|(name, ..)| (*name).to_string()),
+        )
+        .peekable();
+    if collisions.peek().is_some() {
+        for name in collisions {
+            sess.dcx().err(format!(
+                "custom lint library `{}` registers `{name}`, which is already a lint or lint group",
+                path.display()
+            ));
+        }
+        return;
+    }
+    lint_store.register_lints(lints);
+    for (name, lint_ids, _) in groups {
+        lint_store.register_group(true, name, None, lint_ids);
+    }
+    lint_store
+        .pre_expansion_passes
+        .extend(custom_store.pre_expansion_passes);
+    lint_store.early_passes.extend(custom_store.early_passes);
+    lint_store.late_passes.extend(custom_store.late_passes);
+    lint_store.late_module_passes.extend(custom_store.late_module_passes);
+}
+
 struct ClippyCallbacks {
     clippy_args_var: Option<String>,
 }
@@ -156,9 +235,11 @@ fn config(&mut self, config: &mut interface::Config) {
                 (previous)(sess, lint_store);
             }
 
-            let conf = clippy_config::Conf::read(sess, &conf_path);
+            let conf = Conf::read(sess, &conf_path);
             clippy_lints::register_lints(lint_store, conf);
             clippy_lints::register_pre_expansion_lints(lint_store, conf);
+            let conf_file = conf_path.as_ref().ok().and_then(|(path, _)| path.as_deref());
+            register_custom_lints(sess, lint_store, conf, conf_file);
         }));
 
         // FIXME: #4825; This is required, because Clippy lints that are based on MIR have to be
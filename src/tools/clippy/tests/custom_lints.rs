@@ -0,0 +1,171 @@
+//! Tests loading lints from the libraries listed in `custom-lint-libraries`.
+
+#![feature(rustc_private)]
+#![allow(unused_extern_crates)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::LazyLock;
+
+// The lint library is built against these, so they have to show up in the depinfo file.
+extern crate clippy_config;
+extern crate clippy_utils;
+
+static CLIPPY_DRIVER_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
+    let mut path = std::env::current_exe().unwrap();
+    assert!(path.pop()); // deps
+    path.set_file_name("clippy-driver");
+    path
+});
+
+/// Creates an empty directory for one test, next to the test executable.
+fn test_dir(name: &str) -> PathBuf {
+    let mut dir = std::env::current_exe().unwrap();
+    assert!(dir.pop()); // deps
+    dir.set_file_name("custom_lints");
+    let dir = dir.join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// The `--extern` flags for `clippy_config` and `clippy_utils`, found in the depinfo file of this
+/// test like in `compile-test.rs`. This needs `-Z binary-dep-depinfo`.
+fn extern_flags() -> Vec<String> {
+    let mut depinfo = std::env::current_exe().unwrap();
+    depinfo.set_extension("d");
+    let depinfo = fs::read_to_string(depinfo).unwrap();
+    ["clippy_config", "clippy_utils"]
+        .into_iter()
+        .map(|name| {
+            let path = depinfo
+                .lines()
+                .filter_map(|line| line.strip_suffix(':'))
+                .find(|path| {
+                    Path::new(path)
+                        .file_name()
+                        .and_then(|file_name| file_name.to_str())
+                        .is_some_and(|file_name| {
+                            file_name.starts_with(&format!("lib{name}-")) && file_name.ends_with(".rlib")
+                        })
+                })
+                .unwrap_or_else(|| panic!("`{name}` not found in depinfo, is `-Z binary-dep-depinfo` enabled?"));
+            format!("--extern={name}={path}")
+        })
+        .collect()
+}
+
+/// Builds `tests/custom_lints/lints.rs` into `dir` as a dylib, with the given `--cfg`s.
+fn build_library(dir: &Path, cfgs: &[&str]) -> PathBuf {
+    let mut deps = std::env::current_exe().unwrap();
+    assert!(deps.pop());
+    let output = Command::new(&*CLIPPY_DRIVER_PATH)
+        .arg("--rustc")
+        .args(["--edition=2021", "--crate-type=dylib", "--crate-name=lints"])
+        .args(extern_flags())
+        .arg("-L")
+        .arg(format!("dependency={}", deps.display()))
+        .args(cfgs.iter().map(|cfg| format!("--cfg={cfg}")))
+        .arg("--out-dir")
+        .arg(dir)
+        .arg("tests/custom_lints/lints.rs")
+        .output()
+        .unwrap();
+    check_success(&output);
+    dir.join(format!(
+        "{}lints{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ))
+}
+
+/// Runs Clippy on `tests/custom_lints/linted.rs` with a `clippy.toml` in `dir` that loads
+/// `library`.
+fn run_clippy(dir: &Path, library: &str) -> Output {
+    fs::write(
+        dir.join("clippy.toml"),
+        format!("custom-lint-libraries = [{library:?}]\n\n[custom-lint-config]\nhelp = \"rename it\"\n"),
+    )
+    .unwrap();
+    Command::new(&*CLIPPY_DRIVER_PATH)
+        .env("CLIPPY_CONF_DIR", dir)
+        .env("CLIPPY_DISABLE_DOCS_LINKS", "true")
+        .args(["--edition=2021", "--crate-type=lib", "--emit=metadata", "--color=never"])
+        .arg("--out-dir")
+        .arg(dir)
+        .arg("tests/custom_lints/linted.rs")
+        .output()
+        .unwrap()
+}
+
+fn check_success(output: &Output) {
+    assert!(
+        output.status.success(),
+        "status: {}\nstderr: {}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().unwrap().to_str().unwrap()
+}
+
+#[test]
+fn reports_custom_lint() {
+    let dir = test_dir("reports_custom_lint");
+    let library = build_library(&dir, &[]);
+    let output = run_clippy(&dir, file_name(&library));
+    check_success(&output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning: function named `foo`"), "{stderr}");
+    assert!(stderr.contains("linted.rs:1:8"), "{stderr}");
+    // The help comes from `custom-lint-config`.
+    assert!(stderr.contains("= help: rename it"), "{stderr}");
+    assert!(
+        stderr.contains("`#[warn(clippy::fn_named_foo)]` on by default"),
+        "{stderr}"
+    );
+    // The second `foo` is in a module that allows the lint.
+    assert_eq!(stderr.matches("function named `foo`").count(), 1, "{stderr}");
+}
+
+#[test]
+fn missing_library() {
+    let dir = test_dir("missing_library");
+    let output = run_clippy(&dir, "libmissing.so");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("error: failed to load custom lint library `"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("libmissing.so"), "{stderr}");
+}
+
+#[test]
+fn missing_registration_function() {
+    let dir = test_dir("missing_registration_function");
+    let library = build_library(&dir, &["no_register"]);
+    let output = run_clippy(&dir, file_name(&library));
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("does not export `clippy_register_lints`"), "{stderr}");
+}
+
+#[test]
+fn lint_name_collision() {
+    let dir = test_dir("lint_name_collision");
+    let library = build_library(&dir, &["collide"]);
+    let output = run_clippy(&dir, file_name(&library));
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("registers `clippy::box_collection`, which is already a lint or lint group"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("internal compiler error"), "{stderr}");
+    // None of the library's lints are loaded.
+    assert!(!stderr.contains("function named `foo`"), "{stderr}");
+}
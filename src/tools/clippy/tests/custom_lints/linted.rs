@@ -0,0 +1,6 @@
+pub fn foo() {}
+
+#[allow(clippy::fn_named_foo)]
+pub mod allowed {
+    pub fn foo() {}
+}
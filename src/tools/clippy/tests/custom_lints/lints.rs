@@ -0,0 +1,63 @@
+//! A custom lint library for `tests/custom_lints.rs`.
+//!
+//! With `--cfg collide` it also registers a lint with the name of one of Clippy's own lints, and
+//! with `--cfg no_register` it does not export `clippy_register_lints`.
+
+#![feature(rustc_private, let_chains)]
+
+extern crate rustc_ast;
+extern crate rustc_driver;
+extern crate rustc_lint;
+extern crate rustc_session;
+
+use clippy_config::Conf;
+use clippy_utils::diagnostics::span_lint_and_help;
+use rustc_ast::{Item, ItemKind};
+use rustc_lint::{EarlyContext, EarlyLintPass, LintStore};
+use rustc_session::{Session, declare_tool_lint, impl_lint_pass};
+
+declare_tool_lint! {
+    pub clippy::FN_NAMED_FOO,
+    Warn,
+    "functions named `foo`"
+}
+
+#[cfg(collide)]
+declare_tool_lint! {
+    pub clippy::BOX_COLLECTION,
+    Warn,
+    "a lint with the name of one of Clippy's lints"
+}
+
+struct FnNamedFoo {
+    /// The `help` value of the `custom-lint-config` table.
+    help: &'static str,
+}
+
+impl_lint_pass!(FnNamedFoo => [FN_NAMED_FOO]);
+
+impl EarlyLintPass for FnNamedFoo {
+    fn check_item(&mut self, cx: &EarlyContext<'_>, item: &Item) {
+        if let ItemKind::Fn(_) = item.kind
+            && item.ident.name.as_str() == "foo"
+        {
+            span_lint_and_help(
+                cx,
+                FN_NAMED_FOO,
+                item.ident.span,
+                "function named `foo`",
+                None,
+                self.help,
+            );
+        }
+    }
+}
+
+#[cfg_attr(not(no_register), no_mangle)]
+pub fn clippy_register_lints(_sess: &Session, store: &mut LintStore, conf: &'static Conf) {
+    let help = conf.custom_lint_config["help"].as_str().unwrap();
+    store.register_lints(&[FN_NAMED_FOO]);
+    #[cfg(collide)]
+    store.register_lints(&[BOX_COLLECTION]);
+    store.register_early_pass(move || Box::new(FnNamedFoo { help }));
+}
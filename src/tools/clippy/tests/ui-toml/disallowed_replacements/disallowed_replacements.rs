@@ -0,0 +1,75 @@
+#![warn(clippy::disallowed_methods, clippy::disallowed_types, clippy::disallowed_macros)]
+#![allow(unused, clippy::explicit_auto_deref)]
+
+use std::collections::HashMap;
+//~^ disallowed_types
+
+mod our_env {
+    pub fn var(key: &str) -> Result<String, std::env::VarError> {
+        std::env::var(key)
+    }
+}
+
+fn push_zero(v: &mut Vec<i32>) {
+    v.extend([0]);
+}
+
+fn our_max(a: i32, b: i32) -> i32 {
+    if a < b { b } else { a }
+}
+
+fn our_len(s: &str) -> usize {
+    s.len()
+}
+
+fn our_repeat(s: &str, n: usize) -> String {
+    std::iter::repeat_n(s, n).collect()
+}
+
+fn split_commas(s: &str) -> std::str::Split<'_, [char; 1]> {
+    s.split([','])
+}
+
+mod app {
+    pub fn run() {
+        let _ = std::env::var("HOME");
+        //~^ disallowed_methods
+        let key = "PATH";
+        let _ = std::env::var(key);
+        //~^ disallowed_methods
+    }
+}
+
+fn main() {
+    let _ = std::env::var("HOME");
+
+    let mut v = vec![1];
+    v.push(0);
+    //~^ disallowed_methods
+    v.push(1);
+
+    let _ = std::cmp::max(1, 2);
+    //~^ disallowed_methods
+    let _ = [1, 2].into_iter().fold(0, std::cmp::max);
+    //~^ disallowed_methods
+
+    let s = String::from("abc");
+    let _ = s.len();
+    //~^ disallowed_methods
+    let _ = (s.clone() + "d").len();
+    //~^ disallowed_methods
+    let _ = "ab".repeat(1 + 2);
+    //~^ disallowed_methods
+    let _ = s.repeat(2 * 2);
+    //~^ disallowed_methods
+    let _ = "a,b".split(',');
+    //~^ disallowed_methods
+    let _ = "a b".split(' ');
+
+    let _map: HashMap<i32, i32> = HashMap::new();
+    //~^ disallowed_types
+    //~| disallowed_types
+
+    eprintln!("message");
+    //~^ disallowed_macros
+}
@@ -157,6 +157,7 @@ fn addr_from_alloc_id_uncached(
     ) -> InterpResult<'tcx, u64> {
         let this = self.eval_context_ref();
         let mut rng = this.machine.rng.borrow_mut();
+        let mut rng = this.machine.nondet.rng(NondetKind::Address, &mut *rng);
         let info = this.get_alloc_info(alloc_id);
         // This is either called immediately after allocation (and then cached), or when
         // adjusting `tcx` pointers (which never get freed). So assert that we are looking
@@ -204,7 +205,7 @@ fn addr_from_alloc_id_uncached(
         }
         // We are not in native lib mode, so we control the addresses ourselves.
         if let Some((reuse_addr, clock)) = global_state.reuse.take_addr(
-            &mut *rng,
+            &mut rng,
             info.size,
             info.align,
             memory_kind,
@@ -432,6 +433,7 @@ impl<'tcx> MiriMachine<'tcx> {
     pub fn free_alloc_id(&mut self, dead_id: AllocId, size: Size, align: Align, kind: MemoryKind) {
         let global_state = self.alloc_addresses.get_mut();
         let rng = self.rng.get_mut();
+        let mut rng = self.nondet.rng(NondetKind::Address, rng);
 
         // We can *not* remove this from `base_addr`, since the interpreter design requires that we
         // be able to retrieve an AllocId + offset for any memory access *before* we check if the
@@ -455,7 +457,7 @@ pub fn free_alloc_id(&mut self, dead_id: AllocId, size: Size, align: Align, kind
         global_state.exposed.remove(&dead_id);
         // Also remember this address for future reuse.
         let thread = self.threads.active_thread();
-        global_state.reuse.add_addr(rng, addr, size, align, kind, thread, || {
+        global_state.reuse.add_addr(&mut rng, addr, size, align, kind, thread, || {
             if let Some(data_race) = &self.data_race {
                 data_race.release_clock(&self.threads, |clock| clock.clone())
             } else {
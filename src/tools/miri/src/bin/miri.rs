@@ -591,6 +591,36 @@ fn main() {
         } else if let Some(param) = arg.strip_prefix("-Zmiri-preemption-rate=") {
             miri_config.preemption_rate =
                 parse_rate(param).unwrap_or_else(|err| show_error!("-Zmiri-preemption-rate {err}"));
+        } else if arg == "-Zmiri-explore" {
+            miri_config.explore.get_or_insert_default();
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-explore-preemption-bound=") {
+            let bound = param.parse::<u32>().unwrap_or_else(|err| {
+                show_error!("-Zmiri-explore-preemption-bound requires a `u32`: {}", err)
+            });
+            miri_config.explore.get_or_insert_default().preemption_bound = bound;
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-explore-max-runs=") {
+            let max_runs = match param.parse::<u32>() {
+                Ok(max_runs) if max_runs > 0 => max_runs,
+                _ => show_error!("-Zmiri-explore-max-runs requires a positive `u32`"),
+            };
+            miri_config.explore.get_or_insert_default().max_runs = max_runs;
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-replay-schedule=") {
+            let schedule = param
+                .parse::<miri::Schedule>()
+                .unwrap_or_else(|err| show_error!("-Zmiri-replay-schedule is invalid: {err}"));
+            miri_config.schedule = Some(schedule);
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-record=") {
+            miri_config.record = Some(PathBuf::from(param));
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-replay=") {
+            let recording = std::fs::read_to_string(param)
+                .map_err(|err| err.to_string())
+                .and_then(|input| miri::Recording::parse(&input))
+                .unwrap_or_else(|err| show_error!("-Zmiri-replay cannot read `{param}`: {err}"));
+            miri_config.replay = Some(recording);
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-leak-report=") {
+            miri_config.leak_report = Some(PathBuf::from(param));
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-unsafe-coverage=") {
+            miri_config.unsafe_coverage = Some(PathBuf::from(param));
         } else if arg == "-Zmiri-report-progress" {
             // This makes it take a few seconds between progress reports on my laptop.
             miri_config.report_progress = Some(1_000_000);
@@ -655,6 +685,22 @@ fn main() {
             "-Zmiri-unique-is-unique only has an effect when -Zmiri-tree-borrows is also used"
         );
     }
+    // Schedules are explored and replayed based on the vector clocks of the data race detector.
+    if (miri_config.explore.is_some() || miri_config.schedule.is_some())
+        && !miri_config.data_race_detector
+    {
+        show_error!(
+            "-Zmiri-explore and -Zmiri-replay-schedule cannot be used with -Zmiri-disable-data-race-detector"
+        );
+    }
+    if miri_config.explore.is_some() && miri_config.schedule.is_some() {
+        show_error!("-Zmiri-explore and -Zmiri-replay-schedule cannot be used together");
+    }
+    if miri_config.explore.is_some()
+        && (miri_config.record.is_some() || miri_config.replay.is_some())
+    {
+        show_error!("-Zmiri-explore cannot be used with -Zmiri-record or -Zmiri-replay");
+    }
     // Tree Borrows + permissive provenance does not work.
     if miri_config.provenance_mode == ProvenanceMode::Permissive
         && matches!(miri_config.borrow_tracker, Some(BorrowTrackerMethod::TreeBorrows))
@@ -1,5 +1,8 @@
 use std::cell::Cell;
-use std::time::{Duration, Instant as StdInstant};
+use std::rc::Rc;
+use std::time::{Duration, Instant as StdInstant, SystemTime};
+
+use crate::nondet::{NondetKind, NondetLog};
 
 /// When using a virtual clock, this defines how many nanoseconds we pretend are passing for each
 /// basic block.
@@ -64,6 +67,8 @@ pub fn duration_since(&self, earlier: Instant) -> Duration {
 #[derive(Debug)]
 pub struct Clock {
     kind: ClockKind,
+    /// Host clock reads are recorded to or replayed from this log.
+    nondet: Rc<NondetLog>,
 }
 
 #[derive(Debug)]
@@ -81,14 +86,14 @@ enum ClockKind {
 
 impl Clock {
     /// Create a new clock based on the availability of communication with the host.
-    pub fn new(communicate: bool) -> Self {
+    pub fn new(communicate: bool, nondet: Rc<NondetLog>) -> Self {
         let kind = if communicate {
             ClockKind::Host { epoch: StdInstant::now() }
         } else {
             ClockKind::Virtual { nanoseconds: 0.into() }
         };
 
-        Self { kind }
+        Self { kind, nondet }
     }
 
     /// Let the time pass for a small interval.
@@ -128,9 +133,26 @@ pub fn epoch(&self) -> Instant {
 
     pub fn now(&self) -> Instant {
         match &self.kind {
-            ClockKind::Host { .. } => Instant { kind: InstantKind::Host(StdInstant::now()) },
+            ClockKind::Host { epoch } => {
+                let nanos = StdInstant::now().duration_since(*epoch).as_nanos();
+                let nanos = u64::try_from(nanos).unwrap_or(u64::MAX);
+                let nanos = self.nondet.int(NondetKind::Clock, nanos);
+                Instant { kind: InstantKind::Host(*epoch + Duration::from_nanos(nanos)) }
+            }
             ClockKind::Virtual { nanoseconds } =>
                 Instant { kind: InstantKind::Virtual { nanoseconds: nanoseconds.get() } },
         }
     }
+
+    /// The current system time of the host. Must only be called when communication with the host
+    /// is enabled.
+    pub fn system_time(&self) -> SystemTime {
+        let nanos = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(duration) => u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX),
+            // Times before the Unix epoch are not supported anyway.
+            Err(_) => 0,
+        };
+        let nanos = self.nondet.int(NondetKind::SystemTime, nanos);
+        SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos)
+    }
 }
@@ -754,7 +754,10 @@ fn atomic_compare_exchange_scalar(
         let success_rate = 1.0 - this.machine.cmpxchg_weak_failure_rate;
         let cmpxchg_success = eq.to_scalar().to_bool()?
             && if can_fail_spuriously {
-                this.machine.rng.get_mut().gen_bool(success_rate)
+                this.machine
+                    .nondet
+                    .rng(NondetKind::WeakMemory, this.machine.rng.get_mut())
+                    .gen_bool(success_rate)
             } else {
                 true
             };
@@ -1820,6 +1823,13 @@ fn thread_state(&self, thread: ThreadId) -> (VectorIdx, Ref<'_, ThreadClockSet>)
         (index, clocks)
     }
 
+    /// Load the vector clock of the given thread.
+    #[inline]
+    pub(super) fn thread_clock(&self, thread: ThreadId) -> Ref<'_, VClock> {
+        let (_, clocks) = self.thread_state(thread);
+        Ref::map(clocks, |clocks| &clocks.clock)
+    }
+
     /// Load the current vector clock in use and the current set of thread clocks
     /// in use for the vector.
     #[inline]
@@ -0,0 +1,239 @@
+//! Systematic exploration of thread schedules.
+//!
+//! Normally, Miri follows a single schedule that is determined by `-Zmiri-seed` and
+//! `-Zmiri-preemption-rate`. With `-Zmiri-explore`, the program is instead executed repeatedly,
+//! and every execution follows a different schedule. The number of preemptions per schedule is
+//! bounded, and schedules with fewer preemptions are explored first (this is known as iterative
+//! context bounding): most concurrency bugs can be triggered with very few preemptions, so this
+//! finds them quickly while keeping the search space polynomial.
+//!
+//! A *scheduling point* is a point in the execution where more than one thread could run next.
+//! Switching threads because the active one blocked, terminated, or yielded is free; switching
+//! away from a thread that could keep running is a preemption and counts against the bound.
+//!
+//! Most interleavings differ only in the order of accesses that do not synchronize, and those
+//! interleavings are all equivalent as far as the data race detector is concerned: its vector
+//! clocks detect conflicting non-atomic accesses no matter in which order they happened. We
+//! therefore only consider preempting a thread after its vector clock changed, i.e., after it
+//! performed an atomic access or some other synchronizing operation.
+//!
+//! Every execution records the scheduling decisions it made as a [`Schedule`], which lists the
+//! scheduling points where a thread other than the default one was picked. Passing that schedule
+//! to `-Zmiri-replay-schedule` reproduces the execution deterministically.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use rustc_data_structures::fx::FxHashMap;
+
+use crate::concurrency::VClock;
+use crate::*;
+
+/// Configuration for `-Zmiri-explore`.
+#[derive(Clone, Debug)]
+pub struct ExploreConfig {
+    /// The maximal number of preemptions in each explored schedule.
+    pub preemption_bound: u32,
+    /// The maximal number of executions before the exploration is stopped.
+    pub max_runs: u32,
+}
+
+impl Default for ExploreConfig {
+    fn default() -> Self {
+        ExploreConfig { preemption_bound: 2, max_runs: 1000 }
+    }
+}
+
+/// A schedule, described by the scheduling points where it deviates from the default choice.
+///
+/// The textual representation is a comma-separated list of `<point>:<thread>` pairs, meaning that
+/// thread `<thread>` is picked at the `<point>`-th scheduling point.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schedule {
+    switches: BTreeMap<usize, ThreadId>,
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (point, thread)) in self.switches.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{point}:{}", thread.to_u32())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut switches = BTreeMap::new();
+        for switch in s.split(',').filter(|switch| !switch.is_empty()) {
+            let Some((point, thread)) = switch.split_once(':') else {
+                return Err(format!("`{switch}` is not of the form `<point>:<thread>`"));
+            };
+            let point = point.parse::<usize>().map_err(|err| format!("`{point}`: {err}"))?;
+            let thread = thread.parse::<u32>().map_err(|err| format!("`{thread}`: {err}"))?;
+            if switches.insert(point, ThreadId::new_unchecked(thread)).is_some() {
+                return Err(format!("scheduling point {point} is listed more than once"));
+            }
+        }
+        Ok(Schedule { switches })
+    }
+}
+
+/// A scheduling decision made during an execution.
+#[derive(Debug)]
+struct SchedulingPoint {
+    /// The threads that could run at this point, with the default choice first.
+    candidates: Vec<ThreadId>,
+    /// Whether the active thread could have kept running, i.e., whether picking any thread but
+    /// the first candidate is a preemption.
+    preemptible: bool,
+    /// The number of preemptions before this point.
+    preemptions: u32,
+}
+
+/// The scheduling decisions of a completed execution.
+#[derive(Debug)]
+pub struct ScheduleTrace {
+    /// The schedule the execution followed.
+    schedule: Schedule,
+    points: Vec<SchedulingPoint>,
+}
+
+impl ScheduleTrace {
+    /// The schedule that reproduces this execution.
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+}
+
+/// Enumerates the schedules of a program, with fewer preemptions first.
+///
+/// The schedules form a tree: the parent of a schedule is the same schedule without its last
+/// deviation from the default choices. After running a schedule, its children are all the
+/// schedules that deviate at one later scheduling point of that execution.
+#[derive(Debug)]
+pub struct Explorer {
+    preemption_bound: u32,
+    /// The schedules that still need to be run, indexed by their number of preemptions.
+    pending: Vec<VecDeque<Schedule>>,
+}
+
+impl Explorer {
+    pub fn new(preemption_bound: u32) -> Self {
+        let mut pending: Vec<_> = (0..=preemption_bound).map(|_| VecDeque::new()).collect();
+        pending[0].push_back(Schedule::default());
+        Explorer { preemption_bound, pending }
+    }
+
+    /// The next schedule to run, if any are left.
+    pub fn next_schedule(&mut self) -> Option<Schedule> {
+        self.pending.iter_mut().find_map(|schedules| schedules.pop_front())
+    }
+
+    /// Queues the children of the schedule that produced this trace.
+    pub fn add_children(&mut self, trace: &ScheduleTrace) {
+        let first = trace.schedule.switches.last_key_value().map_or(0, |(&point, _)| point + 1);
+        for (i, point) in trace.points.iter().enumerate().skip(first) {
+            // Every choice but the first one at a preemptible point is a preemption.
+            let preemptions = point.preemptions.strict_add(u32::from(point.preemptible));
+            if preemptions > self.preemption_bound {
+                continue;
+            }
+            for &thread in &point.candidates[1..] {
+                let mut schedule = trace.schedule.clone();
+                schedule.switches.insert(i, thread);
+                self.pending[usize::try_from(preemptions).unwrap()].push_back(schedule);
+            }
+        }
+    }
+}
+
+/// Makes the scheduling decisions of a single execution according to a given [`Schedule`], and
+/// records them.
+#[derive(Debug)]
+pub struct ScheduleController {
+    /// The schedule to follow.
+    schedule: Schedule,
+    /// The decisions made so far.
+    points: Vec<SchedulingPoint>,
+    /// The number of preemptions so far.
+    preemptions: u32,
+    /// The vector clock of each thread when it last passed a potential preemption point.
+    clocks: FxHashMap<ThreadId, VClock>,
+    /// Whether the active thread is being preempted (as opposed to having yielded).
+    preempting: bool,
+}
+
+impl ScheduleController {
+    pub fn new(schedule: Schedule) -> Self {
+        ScheduleController {
+            schedule,
+            points: Vec::new(),
+            preemptions: 0,
+            clocks: FxHashMap::default(),
+            preempting: false,
+        }
+    }
+
+    /// Called at the end of each basic block. Returns whether the active thread performed a
+    /// synchronizing operation since the last time it got here, and should thus be considered
+    /// for preemption.
+    pub(super) fn passed_sync_point(&mut self, thread: ThreadId, clock: &VClock) -> bool {
+        if self.clocks.get(&thread) == Some(clock) {
+            return false;
+        }
+        self.clocks.insert(thread, clock.clone());
+        self.preempting = true;
+        true
+    }
+
+    /// Returns whether the active thread is being preempted, and resets that state.
+    pub(super) fn take_preempting(&mut self) -> bool {
+        std::mem::take(&mut self.preempting)
+    }
+
+    /// Picks one of the candidate threads, the first of which is the default choice.
+    pub(super) fn choose<'tcx>(
+        &mut self,
+        candidates: Vec<ThreadId>,
+        preemptible: bool,
+    ) -> InterpResult<'tcx, ThreadId> {
+        if candidates.len() == 1 {
+            return interp_ok(candidates[0]);
+        }
+        let point = self.points.len();
+        let chosen = match self.schedule.switches.get(&point) {
+            None => 0,
+            Some(thread) => {
+                let Some(chosen) = candidates.iter().position(|candidate| candidate == thread)
+                else {
+                    throw_unsup_format!(
+                        "the schedule does not match this execution: thread {} cannot run at scheduling point {point}",
+                        thread.to_u32()
+                    );
+                };
+                chosen
+            }
+        };
+        let thread = candidates[chosen];
+        self.points.push(SchedulingPoint {
+            candidates,
+            preemptible,
+            preemptions: self.preemptions,
+        });
+        if preemptible && chosen != 0 {
+            self.preemptions = self.preemptions.strict_add(1);
+        }
+        interp_ok(thread)
+    }
+
+    pub fn into_trace(self) -> ScheduleTrace {
+        ScheduleTrace { schedule: self.schedule, points: self.points }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod cpu_affinity;
 pub mod data_race;
+pub mod explore;
 pub mod init_once;
 mod range_object_map;
 pub mod sync;
@@ -16,6 +16,7 @@
 use rustc_span::Span;
 
 use crate::concurrency::data_race;
+use crate::concurrency::explore::{ScheduleController, ScheduleTrace};
 use crate::shims::tls;
 use crate::*;
 
@@ -159,6 +160,8 @@ pub enum BlockReason {
     Epoll,
     /// Blocked on eventfd.
     Eventfd,
+    /// Blocked on a socket.
+    Socket,
 }
 
 /// The state of a thread.
@@ -401,7 +404,7 @@ fn get_wait_time(&self, clock: &Clock) -> Duration {
         match self {
             Timeout::Monotonic(instant) => instant.duration_since(clock.now()),
             Timeout::RealTime(time) =>
-                time.duration_since(SystemTime::now()).unwrap_or(Duration::ZERO),
+                time.duration_since(clock.system_time()).unwrap_or(Duration::ZERO),
         }
     }
 
@@ -451,6 +454,9 @@ pub struct ThreadManager<'tcx> {
     thread_local_allocs: FxHashMap<(DefId, ThreadId), StrictPointer>,
     /// A flag that indicates that we should change the active thread.
     yield_active_thread: bool,
+    /// If `Some`, scheduling decisions follow a given schedule (`-Zmiri-explore` and
+    /// `-Zmiri-replay-schedule`) instead of the random preemption rate.
+    schedule_controller: Option<ScheduleController>,
 }
 
 impl VisitProvenance for ThreadManager<'_> {
@@ -460,6 +466,7 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             thread_local_allocs,
             active_thread: _,
             yield_active_thread: _,
+            schedule_controller: _,
         } = self;
 
         for thread in threads {
@@ -481,11 +488,23 @@ fn default() -> Self {
             threads,
             thread_local_allocs: Default::default(),
             yield_active_thread: false,
+            schedule_controller: None,
         }
     }
 }
 
 impl<'tcx> ThreadManager<'tcx> {
+    pub(crate) fn new(config: &MiriConfig) -> Self {
+        let mut threads = Self::default();
+        threads.schedule_controller = config.schedule.clone().map(ScheduleController::new);
+        threads
+    }
+
+    /// Returns the scheduling decisions made so far, if they are being recorded.
+    pub fn take_schedule_trace(&mut self) -> Option<ScheduleTrace> {
+        self.schedule_controller.take().map(ScheduleController::into_trace)
+    }
+
     pub(crate) fn init(
         ecx: &mut MiriInterpCx<'tcx>,
         on_main_stack_empty: StackEmptyCallback<'tcx>,
@@ -775,22 +794,40 @@ fn schedule(&mut self, clock: &Clock) -> InterpResult<'tcx, SchedulingAction> {
         // `skip(N)` means we start iterating at thread N, so we skip 1 more to start just *after*
         // the active thread. Then after that we look at `take(N)`, i.e., the threads *before* the
         // active thread.
-        let threads = self
+        let mut threads = self
             .threads
             .iter_enumerated()
             .skip(self.active_thread.index() + 1)
-            .chain(self.threads.iter_enumerated().take(self.active_thread.index()));
-        for (id, thread) in threads {
-            debug_assert_ne!(self.active_thread, id);
-            if thread.state.is_enabled() {
-                info!(
-                    "---------- Now executing on thread `{}` (previous: `{}`) ----------------------------------------",
-                    self.get_thread_display_name(id),
-                    self.get_thread_display_name(self.active_thread)
-                );
-                self.active_thread = id;
-                break;
+            .chain(self.threads.iter_enumerated().take(self.active_thread.index()))
+            .filter(|(_, thread)| thread.state.is_enabled())
+            .map(|(id, _)| id);
+        let next = if let Some(controller) = &mut self.schedule_controller {
+            // Let the schedule decide among all threads that can run. When the active thread is
+            // being preempted, continuing with it is the default; when it yielded, it only runs
+            // again if no other thread can.
+            let active_enabled = self.threads[self.active_thread].state.is_enabled();
+            let preemptible = controller.take_preempting() && active_enabled;
+            let mut candidates: Vec<ThreadId> = threads.collect();
+            if preemptible || (active_enabled && candidates.is_empty()) {
+                candidates.insert(0, self.active_thread);
+            }
+            if candidates.is_empty() {
+                None
+            } else {
+                Some(controller.choose(candidates, preemptible)?)
             }
+        } else {
+            threads.next()
+        };
+        if let Some(id) = next
+            && id != self.active_thread
+        {
+            info!(
+                "---------- Now executing on thread `{}` (previous: `{}`) ----------------------------------------",
+                self.get_thread_display_name(id),
+                self.get_thread_display_name(self.active_thread)
+            );
+            self.active_thread = id;
         }
         self.yield_active_thread = false;
         if self.threads[self.active_thread].state.is_enabled() {
@@ -1050,7 +1087,7 @@ fn block_thread(
                     );
                     Timeout::RealTime(match anchor {
                         TimeoutAnchor::Absolute => SystemTime::UNIX_EPOCH,
-                        TimeoutAnchor::Relative => SystemTime::now(),
+                        TimeoutAnchor::Relative => this.machine.clock.system_time(),
                     })
                 }
                 TimeoutClock::Monotonic =>
@@ -1187,7 +1224,20 @@ fn maybe_preempt_active_thread(&mut self) {
         use rand::Rng as _;
 
         let this = self.eval_context_mut();
-        if this.machine.rng.get_mut().gen_bool(this.machine.preemption_rate) {
+        if let Some(controller) = &mut this.machine.threads.schedule_controller {
+            // Only consider a preemption if the active thread synchronized since the last time it
+            // got here. Otherwise, preempting it leads to an equivalent execution.
+            let active_thread = this.machine.threads.active_thread;
+            let data_race = this.machine.data_race.as_ref().unwrap();
+            if controller.passed_sync_point(active_thread, &data_race.thread_clock(active_thread)) {
+                this.yield_active_thread();
+            }
+        } else if this
+            .machine
+            .nondet
+            .rng(NondetKind::Schedule, this.machine.rng.get_mut())
+            .gen_bool(this.machine.preemption_rate)
+        {
             this.yield_active_thread();
         }
     }
@@ -488,6 +488,7 @@ fn buffered_atomic_read(
                         global.sc_read(&this.machine.threads);
                     }
                     let mut rng = this.machine.rng.borrow_mut();
+                    let mut rng = this.machine.nondet.rng(NondetKind::WeakMemory, &mut *rng);
                     let Some(buffer) = alloc_buffers
                         .get_store_buffer(alloc_range(base_offset, place.layout.size))?
                     else {
@@ -498,7 +499,7 @@ fn buffered_atomic_read(
                         global,
                         &this.machine.threads,
                         atomic == AtomicReadOrd::SeqCst,
-                        &mut *rng,
+                        &mut rng,
                         validate,
                     )?;
                     if global.track_outdated_loads && recency == LoadRecency::Outdated {
@@ -468,20 +468,41 @@ pub fn report_error<'tcx>(
     None
 }
 
-pub fn report_leaks<'tcx>(
-    ecx: &InterpCx<'tcx, MiriMachine<'tcx>>,
-    leaks: Vec<(AllocId, MemoryKind, Allocation<Provenance, AllocExtra<'tcx>, MiriAllocBytes>)>,
-) {
+pub fn report_leaks<'tcx>(ecx: &InterpCx<'tcx, MiriMachine<'tcx>>, sites: Vec<AllocSite<'tcx>>) {
     let mut any_pruned = false;
-    for (id, kind, alloc) in leaks {
-        let mut title = format!(
-            "memory leaked: {id:?} ({}, size: {:?}, align: {:?})",
-            kind,
-            alloc.size().bytes(),
-            alloc.align.bytes()
-        );
-        let Some(backtrace) = alloc.extra.backtrace else {
-            ecx.tcx.dcx().err(title);
+    for site in sites {
+        let mut title = match &site.allocs[..] {
+            &[(id, size, align)] =>
+                format!(
+                    "memory leaked: {id:?} ({}, size: {:?}, align: {:?})",
+                    site.kind,
+                    size.bytes(),
+                    align.bytes()
+                ),
+            &[(id, ..), ..] =>
+                format!(
+                    "memory leaked: {} allocations ({}, {} bytes in total), including {id:?}",
+                    site.allocs.len(),
+                    site.kind,
+                    site.bytes(),
+                ),
+            [] => unreachable!(),
+        };
+        let mut notes = vec![];
+        if site.reachability == Reachability::Indirect {
+            let msg = if site.allocs.len() == 1 {
+                "this allocation is only pointed to by other leaked memory"
+            } else {
+                "these allocations are only pointed to by other leaked memory"
+            };
+            notes.push((None, msg.to_string()));
+        }
+        let Some(backtrace) = site.backtrace else {
+            let mut diag = ecx.tcx.dcx().struct_err(title);
+            for (_, note) in notes {
+                diag.note(note);
+            }
+            diag.emit();
             continue;
         };
         title.push_str(", allocated here:");
@@ -491,7 +512,7 @@ pub fn report_leaks<'tcx>(
             DiagLevel::Error,
             title,
             vec![],
-            vec![],
+            notes,
             vec![],
             &backtrace,
             None, // we don't know the thread this is from
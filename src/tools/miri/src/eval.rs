@@ -8,14 +8,17 @@
 
 use rustc_abi::ExternAbi;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::pluralize;
 use rustc_hir::def::Namespace;
 use rustc_hir::def_id::DefId;
 use rustc_middle::ty::layout::{LayoutCx, LayoutOf};
 use rustc_middle::ty::{self, Ty, TyCtxt};
 use rustc_session::config::EntryFnType;
 
+use crate::concurrency::explore::{Explorer, ScheduleTrace};
 use crate::concurrency::thread::TlsAllocAction;
 use crate::diagnostics::report_leaks;
+use crate::leak_report::{leak_sites, write_leak_report};
 use crate::shims::tls;
 use crate::*;
 
@@ -157,6 +160,18 @@ pub struct MiriConfig {
     pub address_reuse_rate: f64,
     /// Probability for address reuse across threads.
     pub address_reuse_cross_thread_rate: f64,
+    /// If `Some`, run the program repeatedly to systematically explore its thread schedules.
+    pub explore: Option<ExploreConfig>,
+    /// If `Some`, follow the given thread schedule instead of preempting threads randomly.
+    pub schedule: Option<Schedule>,
+    /// If `Some`, record all nondeterministic values to this file.
+    pub record: Option<PathBuf>,
+    /// If `Some`, replay the nondeterministic values of a recorded execution.
+    pub replay: Option<Recording>,
+    /// If `Some`, write a report of the executed `unsafe` code to this file.
+    pub unsafe_coverage: Option<PathBuf>,
+    /// If `Some`, write a JSON report of the leaked allocations to this file.
+    pub leak_report: Option<PathBuf>,
 }
 
 impl Default for MiriConfig {
@@ -194,6 +209,12 @@ fn default() -> MiriConfig {
             collect_leak_backtraces: true,
             address_reuse_rate: 0.5,
             address_reuse_cross_thread_rate: 0.1,
+            explore: None,
+            schedule: None,
+            record: None,
+            replay: None,
+            unsafe_coverage: None,
+            leak_report: None,
         }
     }
 }
@@ -428,12 +449,58 @@ pub fn eval_entry<'tcx>(
     tcx: TyCtxt<'tcx>,
     entry_id: DefId,
     entry_type: EntryFnType,
-    config: MiriConfig,
+    mut config: MiriConfig,
 ) -> Option<i64> {
-    // Copy setting before we move `config`.
-    let ignore_leaks = config.ignore_leaks;
+    let Some(explore) = config.explore.clone() else {
+        return eval_entry_once(tcx, entry_id, entry_type, &config).0;
+    };
 
-    let mut ecx = match create_ecx(tcx, entry_id, entry_type, &config).report_err() {
+    // Run the program once per schedule, until one of them fails or we run out of schedules.
+    let mut explorer = Explorer::new(explore.preemption_bound);
+    let mut runs = 0u32;
+    while let Some(schedule) = explorer.next_schedule() {
+        if runs == explore.max_runs {
+            tcx.dcx().warn(format!(
+                "stopped exploring after {runs} schedules; not all schedules with at most {} \
+                preemption{} were explored",
+                explore.preemption_bound,
+                pluralize!(explore.preemption_bound)
+            ));
+            tcx.dcx().note("use `-Zmiri-explore-max-runs` to explore more schedules");
+            return Some(0);
+        }
+        runs = runs.strict_add(1);
+        config.schedule = Some(schedule);
+        let (return_code, trace) = eval_entry_once(tcx, entry_id, entry_type, &config);
+        let trace = trace.expect("schedule exploration did not record the schedule");
+        if return_code != Some(0) {
+            tcx.dcx().note(format!(
+                "the program failed on schedule {runs} of the exploration; \
+                use `-Zmiri-replay-schedule={}` to replay it",
+                trace.schedule()
+            ));
+            return return_code;
+        }
+        explorer.add_children(&trace);
+    }
+    tcx.dcx().note(format!(
+        "explored all {runs} schedule{} with at most {} preemption{}",
+        pluralize!(runs),
+        explore.preemption_bound,
+        pluralize!(explore.preemption_bound)
+    ));
+    Some(0)
+}
+
+/// Evaluates the entry function once, following `config.schedule` if it is set.
+/// Also returns the scheduling decisions of the execution, if they were recorded.
+fn eval_entry_once<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    entry_id: DefId,
+    entry_type: EntryFnType,
+    config: &MiriConfig,
+) -> (Option<i64>, Option<ScheduleTrace>) {
+    let mut ecx = match create_ecx(tcx, entry_id, entry_type, config).report_err() {
         Ok(v) => v,
         Err(err) => {
             let (kind, backtrace) = err.into_parts();
@@ -462,20 +529,74 @@ pub fn eval_entry<'tcx>(
         EnvVars::cleanup(&mut ecx).expect("error during env var cleanup");
     }
 
-    // Process the result.
-    let (return_code, leak_check) = report_error(&ecx, err)?;
-    if leak_check && !ignore_leaks {
-        // Check for thread leaks.
-        if !ecx.have_all_terminated() {
-            tcx.dcx().err("the main thread terminated without waiting for all remaining threads");
-            tcx.dcx().note("set `MIRIFLAGS=-Zmiri-ignore-leaks` to disable this check");
-            return None;
+    let trace = ecx.machine.threads.take_schedule_trace();
+    let mut return_code = process_result(&mut ecx, err, config);
+    if let Err(msg) = ecx.machine.nondet.write_recording() {
+        tcx.dcx().err(msg);
+        return_code = None;
+    }
+    if let Some(coverage) = &ecx.machine.unsafe_coverage {
+        if let Err(msg) = coverage.write_report(tcx) {
+            tcx.dcx().err(msg);
+            return_code = None;
         }
+    }
+    if let Some(msg) = ecx.machine.nondet.divergence() {
+        tcx.dcx().warn(format!("the replayed execution diverged from the recording: {msg}"));
+    }
+    (return_code, trace)
+}
+
+/// Reports the error that stopped the execution, and performs the leak checks.
+/// Returns `Some(return_code)` if the program completed without errors.
+fn process_result<'tcx>(
+    ecx: &mut MiriInterpCx<'tcx>,
+    err: InterpErrorInfo<'tcx>,
+    config: &MiriConfig,
+) -> Option<i64> {
+    let tcx = ecx.tcx.tcx;
+    let result = report_error(ecx, err);
+    let leak_check = !config.ignore_leaks && matches!(result, Some((_, true)));
+    // Check for thread leaks.
+    let threads_leaked = leak_check && !ecx.have_all_terminated();
+    if threads_leaked {
+        tcx.dcx().err("the main thread terminated without waiting for all remaining threads");
+        tcx.dcx().note("set `MIRIFLAGS=-Zmiri-ignore-leaks` to disable this check");
+    }
+    if (!leak_check || threads_leaked)
+        && !config.ignore_leaks
+        && let Some(path) = &config.leak_report
+    {
+        // There is no memory leak check if the execution stopped early, since memory that is
+        // still in use cannot be told apart from leaks. The report is written anyway, so that it
+        // is never missing or stale, but it only lists the memory reachable from statics.
+        let static_sites = ecx.static_sites();
+        let static_sites: Vec<_> = static_sites.iter().collect();
+        if let Err(msg) = write_leak_report(&ecx.machine, &static_sites, false, path) {
+            tcx.dcx().err(msg);
+        }
+    }
+    let (return_code, _) = result?;
+    if threads_leaked {
+        return None;
+    }
+    if leak_check {
         // Check for memory leaks.
         info!("Additional static roots: {:?}", ecx.machine.static_roots);
+        let static_sites =
+            if config.leak_report.is_some() { ecx.static_sites() } else { Vec::new() };
         let leaks = ecx.take_leaked_allocations(|ecx| &ecx.machine.static_roots);
-        if !leaks.is_empty() {
-            report_leaks(&ecx, leaks);
+        let leaks_found = !leaks.is_empty();
+        let sites = leak_sites(leaks);
+        if let Some(path) = &config.leak_report {
+            let all_sites: Vec<_> = sites.iter().chain(&static_sites).collect();
+            if let Err(msg) = write_leak_report(&ecx.machine, &all_sites, true, path) {
+                tcx.dcx().err(msg);
+                return None;
+            }
+        }
+        if leaks_found {
+            report_leaks(ecx, sites);
             tcx.dcx().note("set `MIRIFLAGS=-Zmiri-ignore-leaks` to disable this check");
             // Ignore the provided return code - let the reported error
             // determine the return code.
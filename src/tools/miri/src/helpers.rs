@@ -1,4 +1,5 @@
 use std::collections::BTreeSet;
+use std::fmt::Write as _;
 use std::num::NonZero;
 use std::sync::Mutex;
 use std::time::Duration;
@@ -421,6 +422,7 @@ fn gen_random(&mut self, ptr: Pointer, len: u64) -> InterpResult<'tcx> {
             let rng = this.machine.rng.get_mut();
             rng.fill_bytes(&mut data);
         }
+        let data = this.machine.nondet.bytes(NondetKind::Random, data);
 
         this.write_bytes_ptr(ptr, data.iter().copied())
     }
@@ -1221,6 +1223,24 @@ pub fn get_local_crates(tcx: TyCtxt<'_>) -> Vec<CrateNum> {
     local_crates
 }
 
+/// Appends `s` to `out` as a JSON string literal, for the reports Miri writes as JSON.
+pub(crate) fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => {
+                let code: u32 = c.into();
+                write!(out, "\\u{code:04x}").unwrap();
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
 pub(crate) fn bool_to_simd_element(b: bool, size: Size) -> Scalar {
     // SIMD uses all-1 as pattern for "true". In two's complement,
     // -1 has all its bits set to one and `from_int` will truncate or
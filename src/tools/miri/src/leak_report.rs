@@ -0,0 +1,223 @@
+//! Grouping and classification of leaked allocations, for the leak check and
+//! `-Zmiri-leak-report`.
+
+use std::cmp::Reverse;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use rustc_abi::{Align, Size};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_middle::ty;
+use rustc_span::Span;
+
+use crate::diagnostics::prune_stacktrace;
+use crate::helpers::write_json_string;
+use crate::*;
+
+/// An allocation that was removed from memory by the leak check.
+pub type LeakedAllocation<'tcx> =
+    (AllocId, MemoryKind, Allocation<Provenance, AllocExtra<'tcx>, MiriAllocBytes>);
+
+/// How an allocation that was not freed can still be reached at the end of the execution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Reachability {
+    /// Leaked: no pointer to the allocation is stored anywhere in memory.
+    Unreachable,
+    /// Leaked: the allocation is only pointed to by other leaked allocations.
+    Indirect,
+    /// Not a leak: the allocation is reachable from a `static`.
+    Static,
+}
+
+impl Reachability {
+    fn name(self) -> &'static str {
+        match self {
+            Reachability::Unreachable => "unreachable",
+            Reachability::Indirect => "indirect",
+            Reachability::Static => "static",
+        }
+    }
+}
+
+/// Allocations of the same kind and reachability that were allocated with the same backtrace.
+#[derive(Debug)]
+pub struct AllocSite<'tcx> {
+    pub kind: MemoryKind,
+    pub reachability: Reachability,
+    /// The backtrace of the allocation, if `-Zmiri-disable-leak-backtraces` was not set.
+    pub backtrace: Option<Vec<FrameInfo<'tcx>>>,
+    /// The allocations with their size and alignment.
+    pub allocs: Vec<(AllocId, Size, Align)>,
+}
+
+impl AllocSite<'_> {
+    pub fn bytes(&self) -> u64 {
+        self.allocs.iter().map(|(_, size, _)| size.bytes()).fold(0, u64::strict_add)
+    }
+}
+
+/// Groups allocations by their kind, reachability, and backtrace. The sites are returned in the
+/// order in which they first occur in `allocs`.
+pub fn group_by_site<'tcx>(
+    allocs: impl IntoIterator<
+        Item = (AllocId, MemoryKind, Reachability, Size, Align, Option<Vec<FrameInfo<'tcx>>>),
+    >,
+) -> Vec<AllocSite<'tcx>> {
+    type SiteKey<'tcx> = (String, Reachability, Option<Vec<(ty::Instance<'tcx>, Span)>>);
+    let mut sites: Vec<AllocSite<'tcx>> = Vec::new();
+    let mut index: FxHashMap<SiteKey<'tcx>, usize> = FxHashMap::default();
+    for (id, kind, reachability, size, align, backtrace) in allocs {
+        let frames = backtrace
+            .as_ref()
+            .map(|backtrace| backtrace.iter().map(|frame| (frame.instance, frame.span)).collect());
+        let i = *index.entry((kind.to_string(), reachability, frames)).or_insert_with(|| {
+            sites.push(AllocSite { kind, reachability, backtrace, allocs: Vec::new() });
+            sites.len().strict_sub(1)
+        });
+        sites[i].allocs.push((id, size, align));
+    }
+    sites
+}
+
+/// Groups the leaked allocations by site, and determines which of them are only pointed to by
+/// other leaked allocations.
+pub fn leak_sites<'tcx>(mut leaks: Vec<LeakedAllocation<'tcx>>) -> Vec<AllocSite<'tcx>> {
+    // Make the order independent of the hash map the leaks were taken from.
+    leaks.sort_by_key(|(id, ..)| *id);
+    let mut pointed_to = FxHashSet::default();
+    for (id, _, alloc) in &leaks {
+        alloc.visit_provenance(&mut |pointee, _| {
+            if let Some(pointee) = pointee
+                && pointee != *id
+            {
+                pointed_to.insert(pointee);
+            }
+        });
+    }
+    group_by_site(leaks.into_iter().map(|(id, kind, alloc)| {
+        let reachability = if pointed_to.contains(&id) {
+            Reachability::Indirect
+        } else {
+            Reachability::Unreachable
+        };
+        (id, kind, reachability, alloc.size(), alloc.align, alloc.extra.backtrace)
+    }))
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Groups the allocations that must not leak, but are reachable from a `static`, by site.
+    /// These are not reported as leaks, but they are often leaks in disguise, e.g. caches that
+    /// grow without bound.
+    fn static_sites(&self) -> Vec<AllocSite<'tcx>> {
+        let this = self.eval_context_ref();
+        // The same roots as the leak check: all globals and the static roots.
+        let globals = this.memory.alloc_map().filter_map_collect(|&id, &(kind, _)| {
+            (kind == MiriMemoryKind::Global.into()).then_some(id)
+        });
+        let reachable = this
+            .reachable_allocs(globals.into_iter().chain(this.machine.static_roots.iter().copied()));
+        let mut allocs = Vec::new();
+        this.memory.alloc_map().iter(|it| {
+            for (&id, (kind, alloc)) in it {
+                if !kind.may_leak() && reachable.contains(&id) {
+                    allocs.push((
+                        id,
+                        *kind,
+                        Reachability::Static,
+                        alloc.size(),
+                        alloc.align,
+                        alloc.extra.backtrace.clone(),
+                    ));
+                }
+            }
+        });
+        // Make the order independent of the hash map.
+        allocs.sort_by_key(|(id, ..)| *id);
+        group_by_site(allocs)
+    }
+}
+
+/// Writes the `-Zmiri-leak-report` file: a JSON object whose `sites` field lists all sites, with
+/// the sites that leaked the most bytes first, and whose `leak_check` field tells whether the leak
+/// check ran at all. Backtraces are pruned like in the leak check's diagnostics, according to
+/// `-Zmiri-backtrace`. A path of `-` prints the report to stdout.
+pub fn write_leak_report<'tcx>(
+    machine: &MiriMachine<'tcx>,
+    sites: &[&AllocSite<'tcx>],
+    leak_check: bool,
+    path: &Path,
+) -> Result<(), String> {
+    let source_map = machine.tcx.sess.source_map();
+    let mut sites = sites.to_vec();
+    sites.sort_by_key(|site| (site.reachability == Reachability::Static, Reverse(site.bytes())));
+    let (static_sites, leaked_sites): (Vec<_>, Vec<_>) =
+        sites.iter().partition(|site| site.reachability == Reachability::Static);
+    let total =
+        |sites: &[&&AllocSite<'_>]| sites.iter().map(|site| site.bytes()).fold(0, u64::strict_add);
+
+    let mut out = String::new();
+    write!(
+        out,
+        "{{\"leak_check\":{leak_check},\"leaked_bytes\":{},\"static_bytes\":{},\"sites\":[",
+        total(&leaked_sites),
+        total(&static_sites)
+    )
+    .unwrap();
+    for (i, site) in sites.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("\n{\"kind\":");
+        write_json_string(&mut out, &site.kind.to_string());
+        out.push_str(",\"reachability\":");
+        write_json_string(&mut out, site.reachability.name());
+        write!(
+            out,
+            ",\"allocations\":{},\"bytes\":{},\"alloc_ids\":[",
+            site.allocs.len(),
+            site.bytes()
+        )
+        .unwrap();
+        for (j, (id, ..)) in site.allocs.iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            write_json_string(&mut out, &format!("{id:?}"));
+        }
+        out.push_str("],\"backtrace\":");
+        match &site.backtrace {
+            None => out.push_str("null"),
+            Some(backtrace) => {
+                let (backtrace, _) = prune_stacktrace(backtrace.clone(), machine);
+                out.push('[');
+                for (j, frame) in backtrace.iter().enumerate() {
+                    if j > 0 {
+                        out.push(',');
+                    }
+                    let loc = source_map.lookup_char_pos(frame.span.lo());
+                    out.push_str("{\"function\":");
+                    write_json_string(&mut out, &frame.instance.to_string());
+                    out.push_str(",\"file\":");
+                    write_json_string(&mut out, &loc.file.name.prefer_local().to_string());
+                    write!(
+                        out,
+                        ",\"line\":{},\"column\":{}}}",
+                        loc.line,
+                        loc.col_display.strict_add(1)
+                    )
+                    .unwrap();
+                }
+                out.push(']');
+            }
+        }
+        out.push('}');
+    }
+    out.push_str("\n]}\n");
+    if path.as_os_str() == "-" {
+        print!("{out}");
+        return Ok(());
+    }
+    fs::write(path, out).map_err(|err| format!("failed to write `{}`: {err}", path.display()))
+}
@@ -82,13 +82,16 @@
 mod eval;
 mod helpers;
 mod intrinsics;
+mod leak_report;
 mod machine;
 mod math;
 mod mono_hash_map;
+mod nondet;
 mod operator;
 mod provenance_gc;
 mod range_map;
 mod shims;
+mod unsafe_coverage;
 
 // Establish a "crate-wide prelude": we often import `crate::*`.
 // Make all those symbols available in the same place as our own.
@@ -121,6 +124,7 @@
 pub use crate::concurrency::data_race::{
     AtomicFenceOrd, AtomicReadOrd, AtomicRwOrd, AtomicWriteOrd, EvalContextExt as _,
 };
+pub use crate::concurrency::explore::{ExploreConfig, Schedule};
 pub use crate::concurrency::init_once::{EvalContextExt as _, InitOnceId};
 pub use crate::concurrency::sync::{
     CondvarId, EvalContextExt as _, MutexRef, RwLockId, SynchronizationObjects,
@@ -138,11 +142,13 @@
 };
 pub use crate::helpers::{AccessKind, EvalContextExt as _};
 pub use crate::intrinsics::EvalContextExt as _;
+pub use crate::leak_report::{AllocSite, EvalContextExt as _, Reachability};
 pub use crate::machine::{
     AllocExtra, FrameExtra, MemoryKind, MiriInterpCx, MiriInterpCxExt, MiriMachine, MiriMemoryKind,
     PrimitiveLayouts, Provenance, ProvenanceExtra,
 };
 pub use crate::mono_hash_map::MonoHashMap;
+pub use crate::nondet::{NondetKind, Recording};
 pub use crate::operator::EvalContextExt as _;
 pub use crate::provenance_gc::{EvalContextExt as _, LiveAllocs, VisitProvenance, VisitWith};
 pub use crate::range_map::RangeMap;
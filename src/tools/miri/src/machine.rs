@@ -6,6 +6,7 @@
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::path::Path;
+use std::rc::Rc;
 use std::{fmt, process};
 
 use rand::rngs::StdRng;
@@ -28,6 +29,8 @@
 use crate::concurrency::cpu_affinity::{self, CpuAffinityMask};
 use crate::concurrency::data_race::{self, NaReadType, NaWriteType};
 use crate::concurrency::weak_memory;
+use crate::nondet::NondetLog;
+use crate::unsafe_coverage::UnsafeCoverage;
 use crate::*;
 
 /// First real-time signal.
@@ -479,6 +482,8 @@ pub struct MiriMachine<'tcx> {
 
     /// The list of all EpollEventInterest.
     pub(crate) epoll_interests: shims::EpollInterestTable,
+    /// The emulated loopback network that sockets are bound to.
+    pub(crate) network: shims::LoopbackNetwork,
 
     /// This machine's monotone clock.
     pub(crate) clock: Clock,
@@ -524,6 +529,12 @@ pub struct MiriMachine<'tcx> {
     /// Needs to be queried by ptr_to_int, hence needs interior mutability.
     pub(crate) rng: RefCell<StdRng>,
 
+    /// The log that nondeterministic values are recorded to or replayed from.
+    pub(crate) nondet: Rc<NondetLog>,
+
+    /// If `Some`, the executed basic blocks are counted to report the coverage of `unsafe` code.
+    pub(crate) unsafe_coverage: Option<UnsafeCoverage>,
+
     /// The allocation IDs to report when they are being allocated
     /// (helps for debugging memory leaks and use after free bugs).
     tracked_alloc_ids: FxHashSet<AllocId>,
@@ -612,6 +623,7 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx>) -> Self {
             measureme::Profiler::new(path).expect("Couldn't create `measureme` profiler")
         });
         let rng = StdRng::seed_from_u64(config.seed.unwrap_or(0));
+        let nondet = Rc::new(NondetLog::new(config.record.clone(), config.replay.clone()));
         let borrow_tracker = config.borrow_tracker.map(|bt| bt.instantiate_global_state(config));
         let data_race = config.data_race_detector.then(|| data_race::GlobalState::new(config));
         // Determine page size, stack address, and stack size.
@@ -646,7 +658,7 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx>) -> Self {
             cpu_affinity::MAX_CPUS,
             config.num_cpus
         );
-        let threads = ThreadManager::default();
+        let threads = ThreadManager::new(config);
         let mut thread_cpu_affinity = FxHashMap::default();
         if matches!(&*tcx.sess.target.os, "linux" | "freebsd" | "android") {
             thread_cpu_affinity
@@ -668,6 +680,7 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx>) -> Self {
             validation: config.validation,
             fds: shims::FdTable::init(config.mute_stdout_stderr),
             epoll_interests: shims::EpollInterestTable::new(),
+            network: Default::default(),
             dirs: Default::default(),
             layouts,
             threads,
@@ -681,6 +694,8 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx>) -> Self {
             local_crates,
             extern_statics: FxHashMap::default(),
             rng: RefCell::new(rng),
+            nondet: Rc::clone(&nondet),
+            unsafe_coverage: config.unsafe_coverage.clone().map(UnsafeCoverage::new),
             tracked_alloc_ids: config.tracked_alloc_ids.clone(),
             track_alloc_accesses: config.track_alloc_accesses,
             check_alignment: config.check_alignment,
@@ -690,7 +705,7 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx>) -> Self {
             preemption_rate: config.preemption_rate,
             report_progress: config.report_progress,
             basic_block_count: 0,
-            clock: Clock::new(config.isolated_op == IsolatedOp::Allow),
+            clock: Clock::new(config.isolated_op == IsolatedOp::Allow, nondet),
             #[cfg(unix)]
             native_lib: config.native_lib.as_ref().map(|lib_file_path| {
                 let target_triple = tcx.sess.opts.target_triple.tuple();
@@ -807,6 +822,7 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             alloc_addresses,
             fds,
             epoll_interests:_,
+            network: _,
             tcx: _,
             isolated_op: _,
             validation: _,
@@ -819,6 +835,8 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             backtrace_style: _,
             local_crates: _,
             rng: _,
+            nondet: _,
+            unsafe_coverage: _,
             tracked_alloc_ids: _,
             track_alloc_accesses: _,
             check_alignment: _,
@@ -1458,7 +1476,12 @@ fn init_frame(
             catch_unwind: None,
             timing,
             is_user_relevant: ecx.machine.is_user_relevant(&frame),
-            salt: ecx.machine.rng.borrow_mut().gen::<usize>() % ADDRS_PER_ANON_GLOBAL,
+            salt: ecx
+                .machine
+                .nondet
+                .rng(NondetKind::Address, &mut *ecx.machine.rng.borrow_mut())
+                .gen::<usize>()
+                % ADDRS_PER_ANON_GLOBAL,
             data_race: ecx.machine.data_race.as_ref().map(|_| data_race::FrameState::default()),
         };
 
@@ -1504,6 +1527,14 @@ fn before_terminator(ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
         // Make sure some time passes.
         ecx.machine.clock.tick();
 
+        if ecx.machine.unsafe_coverage.is_some() {
+            let frame = ecx.frame();
+            if let ty::InstanceKind::Item(def_id) = frame.instance().def {
+                let block = frame.current_loc().left().unwrap().block;
+                ecx.machine.unsafe_coverage.as_mut().unwrap().record(def_id, block);
+            }
+        }
+
         interp_ok(())
     }
 
@@ -1669,7 +1700,11 @@ fn get_global_alloc_salt(
         if unique {
             CTFE_ALLOC_SALT
         } else {
-            ecx.machine.rng.borrow_mut().gen::<usize>() % ADDRS_PER_ANON_GLOBAL
+            ecx.machine
+                .nondet
+                .rng(NondetKind::Address, &mut *ecx.machine.rng.borrow_mut())
+                .gen::<usize>()
+                % ADDRS_PER_ANON_GLOBAL
         }
     }
 
@@ -0,0 +1,370 @@
+//! Recording and replaying the nondeterministic values an execution depends on.
+//!
+//! With `-Zmiri-record=<file>`, the nondeterministic values that influence an execution are
+//! written to a log: reads of the host clocks, random bytes, the environment variables taken from
+//! the host, the bytes read from host files, and the values drawn from the RNG to decide on
+//! preemptions, allocation addresses and the behavior of weak memory. With
+//! `-Zmiri-replay=<file>`, those values are read back from the log instead, so the execution can
+//! be reproduced even if, for example, it depends on the time of day or on the contents of a file.
+//!
+//! Other interactions with the host file system, such as opening files, reading their metadata
+//! or listing directories, are not recorded: replaying an execution that reads files requires
+//! them to still exist, but not to have the same contents.
+//!
+//! Every kind of value has its own stream: replaying takes the next value of the right kind, so a
+//! small change to the program (such as an additional clock read) does not throw off all other
+//! values. Values are still obtained from the host (or the RNG) while replaying, and then replaced
+//! by the recorded ones. This keeps the RNG in the same state as during the recording, so that the
+//! values drawn from it for other purposes stay the same, too.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::{fmt, fs};
+
+use rand::RngCore;
+use rustc_data_structures::fx::FxHashMap;
+
+/// The kinds of nondeterministic values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NondetKind {
+    /// Reads of the host's monotonic clock, in nanoseconds since Miri's epoch.
+    Clock,
+    /// Reads of the host's system time, in nanoseconds since the Unix epoch.
+    SystemTime,
+    /// Random bytes requested by the program.
+    Random,
+    /// The environment variables taken from the host, as a `name\0value` string each. All of
+    /// them are replayed at once, so the environment is replaced by the recorded one.
+    Env,
+    /// The bytes of each successful read from a host file. Failed reads are not recorded.
+    FileRead,
+    /// Values drawn from the RNG to decide whether to preempt the active thread.
+    Schedule,
+    /// Values drawn from the RNG to pick the addresses of allocations.
+    Address,
+    /// Values drawn from the RNG to pick the store a weak memory load reads from, and to decide
+    /// whether a weak compare-exchange fails spuriously.
+    WeakMemory,
+}
+
+impl NondetKind {
+    const ALL: [NondetKind; 8] = [
+        NondetKind::Clock,
+        NondetKind::SystemTime,
+        NondetKind::Random,
+        NondetKind::Env,
+        NondetKind::FileRead,
+        NondetKind::Schedule,
+        NondetKind::Address,
+        NondetKind::WeakMemory,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            NondetKind::Clock => "clock",
+            NondetKind::SystemTime => "system-time",
+            NondetKind::Random => "random",
+            NondetKind::Env => "env",
+            NondetKind::FileRead => "file-read",
+            NondetKind::Schedule => "schedule",
+            NondetKind::Address => "address",
+            NondetKind::WeakMemory => "weak-memory",
+        }
+    }
+}
+
+impl fmt::Display for NondetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum NondetValue {
+    Int(u64),
+    Bytes(Vec<u8>),
+}
+
+fn write_hex(out: &mut String, bytes: &[u8]) {
+    for byte in bytes {
+        write!(out, "{byte:02x}").unwrap();
+    }
+}
+
+fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok()).collect()
+}
+
+/// The values of a recorded execution, in the format written by `-Zmiri-record`.
+///
+/// Each line holds one value: the kind of the value, followed by the value itself as a decimal
+/// integer or (for random bytes, environment variables and file contents) as hex-encoded bytes.
+/// Kinds of values that do not occur in a recording at all are not replayed.
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    values: FxHashMap<NondetKind, VecDeque<NondetValue>>,
+}
+
+impl Recording {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut values: FxHashMap<_, VecDeque<_>> = FxHashMap::default();
+        for (line_number, line) in input.lines().enumerate() {
+            let line_number = line_number.strict_add(1);
+            let mut words = line.split(' ');
+            let kind = words.next().unwrap();
+            let Some(kind) = NondetKind::ALL.into_iter().find(|k| k.name() == kind) else {
+                return Err(format!("line {line_number}: unknown kind of value `{kind}`"));
+            };
+            let value = match (kind, words.next(), words.next()) {
+                (
+                    NondetKind::Random | NondetKind::Env | NondetKind::FileRead,
+                    Some(bytes),
+                    None,
+                ) => parse_hex(bytes).map(NondetValue::Bytes),
+                (_, Some(int), None) => int.parse().ok().map(NondetValue::Int),
+                _ => None,
+            };
+            let Some(value) = value else {
+                return Err(format!("line {line_number}: invalid {kind} value"));
+            };
+            values.entry(kind).or_default().push_back(value);
+        }
+        Ok(Recording { values })
+    }
+}
+
+/// Records the nondeterministic values of an execution, or replays them from a [`Recording`].
+#[derive(Debug, Default)]
+pub struct NondetLog {
+    /// If `Some`, the file the values are recorded to.
+    record: Option<PathBuf>,
+    /// The values recorded so far, in the order in which they were obtained.
+    recorded: RefCell<Vec<(NondetKind, NondetValue)>>,
+    /// If `Some`, the values that are still to be replayed.
+    replay: Option<RefCell<Recording>>,
+    /// The first kind of value for which replaying failed because the execution asked for more
+    /// values than were recorded.
+    diverged: Cell<Option<NondetKind>>,
+}
+
+impl NondetLog {
+    pub fn new(record: Option<PathBuf>, replay: Option<Recording>) -> Self {
+        NondetLog { record, replay: replay.map(RefCell::new), ..Default::default() }
+    }
+
+    /// Whether values are recorded or replayed at all.
+    pub fn is_active(&self) -> bool {
+        self.record.is_some() || self.replay.is_some()
+    }
+
+    /// Records the `live` value, or replaces it by the next recorded value of the same kind.
+    fn value(&self, kind: NondetKind, live: NondetValue) -> NondetValue {
+        let value = match self.replay.as_ref().and_then(|replay| {
+            // Kinds of values that do not occur in the recording at all are not replayed.
+            replay.borrow_mut().values.get_mut(&kind).map(VecDeque::pop_front)
+        }) {
+            None => live,
+            Some(Some(value)) => value,
+            Some(None) => {
+                if self.diverged.get().is_none() {
+                    self.diverged.set(Some(kind));
+                }
+                live
+            }
+        };
+        if self.record.is_some() {
+            self.recorded.borrow_mut().push((kind, value.clone()));
+        }
+        value
+    }
+
+    pub fn int(&self, kind: NondetKind, live: u64) -> u64 {
+        if !self.is_active() {
+            return live;
+        }
+        match self.value(kind, NondetValue::Int(live)) {
+            NondetValue::Int(value) => value,
+            _ => live,
+        }
+    }
+
+    pub fn bytes(&self, kind: NondetKind, live: Vec<u8>) -> Vec<u8> {
+        if !self.is_active() {
+            return live;
+        }
+        match self.value(kind, NondetValue::Bytes(live.clone())) {
+            NondetValue::Bytes(value) => value,
+            _ => live,
+        }
+    }
+
+    /// Records the `live` values of `kind`, which are all obtained at once, or replaces them by
+    /// all the recorded values of that kind.
+    pub fn all_bytes(&self, kind: NondetKind, live: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+        if !self.is_active() {
+            return live;
+        }
+        let replayed = self.replay.as_ref().and_then(|replay| {
+            let values = replay.borrow_mut().values.remove(&kind)?;
+            Some(
+                values
+                    .into_iter()
+                    .filter_map(|value| {
+                        match value {
+                            NondetValue::Bytes(bytes) => Some(bytes),
+                            NondetValue::Int(_) => None,
+                        }
+                    })
+                    .collect(),
+            )
+        });
+        let values = replayed.unwrap_or(live);
+        if self.record.is_some() {
+            self.recorded
+                .borrow_mut()
+                .extend(values.iter().map(|bytes| (kind, NondetValue::Bytes(bytes.clone()))));
+        }
+        values
+    }
+
+    /// Wraps `rng` such that all values drawn from it are recorded or replayed as `kind`.
+    pub fn rng<'a, R: RngCore>(&'a self, kind: NondetKind, rng: &'a mut R) -> LoggedRng<'a, R> {
+        LoggedRng { log: self, kind, rng }
+    }
+
+    /// Writes the recorded values to the file given to `-Zmiri-record`, if any.
+    pub fn write_recording(&self) -> Result<(), String> {
+        if let Some(path) = &self.record {
+            let mut out = String::new();
+            for (kind, value) in self.recorded.borrow().iter() {
+                write!(out, "{kind} ").unwrap();
+                match value {
+                    NondetValue::Int(int) => write!(out, "{int}").unwrap(),
+                    NondetValue::Bytes(bytes) => write_hex(&mut out, bytes),
+                }
+                out.push('\n');
+            }
+            fs::write(path, out)
+                .map_err(|err| format!("failed to write `{}`: {err}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// If the replayed execution did not consume exactly the recorded values, describes how it
+    /// diverged from the recording.
+    pub fn divergence(&self) -> Option<String> {
+        let replay = self.replay.as_ref()?.borrow();
+        if let Some(kind) = self.diverged.get() {
+            return Some(format!("the execution needed more {kind} values than were recorded"));
+        }
+        let leftover = NondetKind::ALL
+            .into_iter()
+            .find(|kind| replay.values.get(kind).is_some_and(|values| !values.is_empty()))?;
+        Some(format!("the execution did not use all recorded {leftover} values"))
+    }
+}
+
+/// An RNG whose values are recorded or replayed by a [`NondetLog`].
+pub struct LoggedRng<'a, R> {
+    log: &'a NondetLog,
+    kind: NondetKind,
+    rng: &'a mut R,
+}
+
+impl<R: RngCore> RngCore for LoggedRng<'_, R> {
+    fn next_u32(&mut self) -> u32 {
+        let live = self.rng.next_u32();
+        u32::try_from(self.log.int(self.kind, live.into())).unwrap_or(live)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let live = self.rng.next_u64();
+        self.log.int(self.kind, live)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // The bytes are drawn at once, as without logging: drawing them in chunks could consume
+        // the RNG differently, and change the values drawn after them.
+        self.rng.fill_bytes(dest);
+        if !self.log.is_active() {
+            return;
+        }
+        // Log the bytes as integers, so that all values of this kind have the same format.
+        for chunk in dest.chunks_mut(8) {
+            let mut live = [0; 8];
+            live[..chunk.len()].copy_from_slice(chunk);
+            let value = self.log.int(self.kind, u64::from_le_bytes(live)).to_le_bytes();
+            chunk.copy_from_slice(&value[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    /// Draws random bytes and an address like the interpreter does, and then a value that is not
+    /// logged, which shows whether the RNG was left in the same state.
+    fn draw(log: &NondetLog, seed: u64) -> (Vec<u8>, Vec<u8>, u64, u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut random = vec![0; 13];
+        rng.fill_bytes(&mut random);
+        let random = log.bytes(NondetKind::Random, random);
+        let mut filled = vec![0; 12];
+        log.rng(NondetKind::Address, &mut rng).fill_bytes(&mut filled);
+        let address = log.rng(NondetKind::Address, &mut rng).gen_range(0..1 << 20);
+        (random, filled, address, rng.next_u64())
+    }
+
+    #[test]
+    fn recording_does_not_change_the_rng() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let recorded = draw(&NondetLog::new(Some(file.path().to_owned()), None), 0);
+        assert_eq!(recorded, draw(&NondetLog::default(), 0));
+    }
+
+    #[test]
+    fn record_then_replay() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let log = NondetLog::new(Some(file.path().to_owned()), None);
+        let (random, filled, address, _) = draw(&log, 0);
+        log.write_recording().unwrap();
+
+        // The recorded values are replayed, even though the RNG is seeded differently.
+        let recording = Recording::parse(&fs::read_to_string(file.path()).unwrap()).unwrap();
+        let log = NondetLog::new(None, Some(recording));
+        let (replayed_random, replayed_filled, replayed_address, _) = draw(&log, 1);
+        assert_eq!(replayed_random, random);
+        assert_eq!(replayed_filled, filled);
+        assert_eq!(replayed_address, address);
+        assert_eq!(log.divergence(), None);
+    }
+
+    #[test]
+    fn replay_all_bytes() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let log = NondetLog::new(Some(file.path().to_owned()), None);
+        let recorded = vec![b"A\0a".to_vec(), b"B\0b".to_vec()];
+        assert_eq!(log.all_bytes(NondetKind::Env, recorded.clone()), recorded);
+        log.write_recording().unwrap();
+
+        // All recorded values replace the live ones, however many there are of either.
+        let recording = Recording::parse(&fs::read_to_string(file.path()).unwrap()).unwrap();
+        let log = NondetLog::new(None, Some(recording));
+        assert_eq!(log.all_bytes(NondetKind::Env, vec![b"C\0c".to_vec()]), recorded);
+        assert_eq!(log.divergence(), None);
+    }
+}
@@ -239,4 +239,20 @@ fn run_provenance_gc(&mut self) {
         remove_unreachable_tags(this, tags);
         remove_unreachable_allocs(this, alloc_ids);
     }
+
+    /// Returns the live allocations that are transitively reachable from `roots` through the
+    /// provenance stored in allocations, including the roots themselves.
+    fn reachable_allocs(&self, roots: impl IntoIterator<Item = AllocId>) -> FxHashSet<AllocId> {
+        let this = self.eval_context_ref();
+        let mut todo: Vec<AllocId> = roots.into_iter().collect();
+        let mut reachable = FxHashSet::default();
+        while let Some(id) = todo.pop() {
+            if reachable.insert(id) {
+                if let Some((_, alloc)) = this.memory.alloc_map().get(id) {
+                    alloc.visit_provenance(&mut |pointee, _| todo.extend(pointee));
+                }
+            }
+        }
+        reachable
+    }
 }
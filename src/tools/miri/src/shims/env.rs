@@ -2,6 +2,7 @@
 
 use rustc_data_structures::fx::FxHashMap;
 
+use self::shims::os_str::bytes_to_os_str;
 use self::shims::unix::UnixEnvVars;
 use self::shims::windows::WindowsEnvVars;
 use crate::*;
@@ -42,6 +43,26 @@ pub(crate) fn init(
             }
         }
 
+        // The variables taken from the host are recorded, or replaced by the recorded ones.
+        if ecx.machine.nondet.is_active() {
+            let mut host_vars = env_vars
+                .drain()
+                .map(|(name, value)| {
+                    [name.as_encoded_bytes(), b"\0", value.as_encoded_bytes()].concat()
+                })
+                .collect::<Vec<_>>();
+            host_vars.sort();
+            for var in ecx.machine.nondet.all_bytes(NondetKind::Env, host_vars) {
+                let Some(separator) = var.iter().position(|&byte| byte == 0) else {
+                    throw_unsup_format!("invalid recorded environment variable {var:?}");
+                };
+                env_vars.insert(
+                    bytes_to_os_str(&var[..separator])?.to_owned(),
+                    bytes_to_os_str(&var[separator.strict_add(1)..])?.to_owned(),
+                );
+            }
+        }
+
         for (name, value) in &config.set_env_vars {
             env_vars.insert(OsString::from(name), OsString::from(value));
         }
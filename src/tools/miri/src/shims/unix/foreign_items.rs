@@ -146,6 +146,13 @@ fn emulate_foreign_item_inner(
                 let result = this.fcntl(args)?;
                 this.write_scalar(result, dest)?;
             }
+            "ioctl" => {
+                // `ioctl` is variadic. The argument count is checked based on the request
+                // in `this.ioctl()`, so we do not use `check_shim` here.
+                this.check_abi_and_shim_symbol_clash(abi, ExternAbi::C { unwind: false }, link_name)?;
+                let result = this.ioctl(args)?;
+                this.write_scalar(result, dest)?;
+            }
             "dup" => {
                 let [old_fd] = this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
                 let old_fd = this.read_scalar(old_fd)?.to_i32()?;
@@ -304,6 +311,99 @@ fn emulate_foreign_item_inner(
                 this.write_scalar(result, dest)?;
             }
 
+            // Sockets on the emulated loopback network
+            "socket" => {
+                let [domain, type_, protocol] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                let result = this.socket(domain, type_, protocol)?;
+                this.write_scalar(result, dest)?;
+            }
+            "bind" => {
+                let [socket, addr, addrlen] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                let result = this.bind(socket, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "listen" => {
+                let [socket, backlog] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                let result = this.listen(socket, backlog)?;
+                this.write_scalar(result, dest)?;
+            }
+            "connect" => {
+                let [socket, addr, addrlen] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                let result = this.connect(socket, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "accept" => {
+                let [socket, addr, addrlen] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                this.accept4(socket, addr, addrlen, /*flags*/ None, dest)?;
+            }
+            "accept4" => {
+                // Currently this function does not exist on all Unixes, e.g. on macOS.
+                if !matches!(&*this.tcx.sess.target.os, "linux" | "android" | "freebsd") {
+                    throw_unsup_format!(
+                        "`accept4` is not supported on {}",
+                        this.tcx.sess.target.os
+                    );
+                }
+                let [socket, addr, addrlen, flags] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                this.accept4(socket, addr, addrlen, Some(flags), dest)?;
+            }
+            "recv" => {
+                let [socket, buf, len, flags] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                this.recvfrom(socket, buf, len, flags, /*from*/ None, dest)?;
+            }
+            "recvfrom" => {
+                let [socket, buf, len, flags, addr, addrlen] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                this.recvfrom(socket, buf, len, flags, Some((addr, addrlen)), dest)?;
+            }
+            "send" => {
+                let [socket, buf, len, flags] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                this.sendto(socket, buf, len, flags, /*to*/ None, dest)?;
+            }
+            "sendto" => {
+                let [socket, buf, len, flags, addr, addrlen] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                this.sendto(socket, buf, len, flags, Some((addr, addrlen)), dest)?;
+            }
+            "shutdown" => {
+                let [socket, how] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                let result = this.shutdown(socket, how)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getsockname" => {
+                let [socket, addr, addrlen] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                let result = this.getsockname(socket, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getpeername" => {
+                let [socket, addr, addrlen] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                let result = this.getpeername(socket, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "setsockopt" => {
+                let [socket, level, name, value, len] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                let result = this.setsockopt(socket, level, name, value, len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getsockopt" => {
+                let [socket, level, name, value, len] =
+                    this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
+                let result = this.getsockopt(socket, level, name, value, len)?;
+                this.write_scalar(result, dest)?;
+            }
+
             // Time
             "gettimeofday" => {
                 let [tv, tz] = this.check_shim(abi, ExternAbi::C { unwind: false }, link_name, args)?;
@@ -25,6 +25,23 @@ struct FileHandle {
     writable: bool,
 }
 
+/// Like `return_read_success`, but the bytes read from the host file are recorded, or replaced by
+/// the recorded ones.
+fn return_file_read_success<'tcx>(
+    ecx: &mut MiriInterpCx<'tcx>,
+    ptr: Pointer,
+    mut bytes: Vec<u8>,
+    read_size: usize,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx> {
+    let len = bytes.len();
+    bytes.truncate(read_size);
+    let mut bytes = ecx.machine.nondet.bytes(NondetKind::FileRead, bytes);
+    // A replayed read never returns more than the buffer can hold.
+    bytes.truncate(len);
+    ecx.return_read_success(ptr, &bytes, bytes.len(), dest)
+}
+
 impl FileDescription for FileHandle {
     fn name(&self) -> &'static str {
         "file"
@@ -43,7 +60,7 @@ fn read<'tcx>(
         let mut bytes = vec![0; len];
         let result = (&mut &self.file).read(&mut bytes);
         match result {
-            Ok(read_size) => ecx.return_read_success(ptr, &bytes, read_size, dest),
+            Ok(read_size) => return_file_read_success(ecx, ptr, bytes, read_size, dest),
             Err(e) => ecx.set_last_error_and_return(e, dest),
         }
     }
@@ -92,7 +109,7 @@ fn pread<'tcx>(
         };
         let result = f();
         match result {
-            Ok(read_size) => ecx.return_read_success(ptr, &bytes, read_size, dest),
+            Ok(read_size) => return_file_read_success(ecx, ptr, bytes, read_size, dest),
             Err(e) => ecx.set_last_error_and_return(e, dest),
         }
     }
@@ -0,0 +1,1603 @@
+//! This implements TCP and UDP sockets on top of an emulated loopback network that lives entirely
+//! inside Miri. Nothing here talks to the host: connections and datagrams only ever travel between
+//! sockets created by the interpreted program, so this works with isolation enabled and behaves
+//! deterministically. Binding to any address other than a loopback or unspecified address fails
+//! with `EADDRNOTAVAIL` (as if the machine had no other network interfaces), and trying to reach a
+//! non-loopback address is unsupported.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::io::{ErrorKind, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use rustc_abi::Size;
+
+use crate::concurrency::VClock;
+use crate::helpers::check_min_arg_count;
+use crate::shims::unix::fd::{FileDescriptionRef, WeakFileDescriptionRef};
+use crate::shims::unix::linux::epoll::{EpollReadyEvents, EvalContextExt as _};
+use crate::shims::unix::*;
+use crate::*;
+
+/// The maximum number of bytes that can be buffered on the receiving end of a stream connection.
+/// Like for socketpair, this number is arbitrary as the value can always be configured in the real
+/// system.
+const MAX_STREAM_BUFFER_CAPACITY: usize = 212992;
+
+/// The maximum number of bytes that can be queued on a datagram socket. Datagrams that do not fit
+/// anymore are dropped, as they would be by a real loopback interface.
+const MAX_DATAGRAM_QUEUE_CAPACITY: usize = 212992;
+
+/// The largest payload of a single UDP datagram.
+const MAX_DATAGRAM_SIZE: usize = 65507;
+
+/// The ports handed out when binding to port 0, or when a socket is bound implicitly.
+/// This is the default `ip_local_port_range` on Linux.
+const EPHEMERAL_PORTS: RangeInclusive<u16> = 32768..=60999;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SocketType {
+    Stream,
+    Datagram,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AddressFamily {
+    Inet,
+    Inet6,
+}
+
+impl AddressFamily {
+    fn of(addr: &SocketAddr) -> Self {
+        if addr.is_ipv4() { AddressFamily::Inet } else { AddressFamily::Inet6 }
+    }
+
+    fn unspecified(self) -> IpAddr {
+        match self {
+            AddressFamily::Inet => Ipv4Addr::UNSPECIFIED.into(),
+            AddressFamily::Inet6 => Ipv6Addr::UNSPECIFIED.into(),
+        }
+    }
+
+    fn localhost(self) -> IpAddr {
+        match self {
+            AddressFamily::Inet => Ipv4Addr::LOCALHOST.into(),
+            AddressFamily::Inet6 => Ipv6Addr::LOCALHOST.into(),
+        }
+    }
+}
+
+/// The emulated loopback network, which keeps track of which addresses sockets are bound to.
+#[derive(Debug)]
+pub struct LoopbackNetwork {
+    /// All sockets that have been bound to an address, explicitly or implicitly. Closed sockets are
+    /// not removed eagerly; their weak reference simply cannot be upgraded anymore.
+    bound: BTreeMap<(SocketType, SocketAddr), WeakFileDescriptionRef>,
+    /// The next ephemeral port to try to hand out.
+    next_ephemeral_port: u16,
+}
+
+impl Default for LoopbackNetwork {
+    fn default() -> Self {
+        LoopbackNetwork { bound: BTreeMap::new(), next_ephemeral_port: *EPHEMERAL_PORTS.start() }
+    }
+}
+
+impl LoopbackNetwork {
+    /// Returns whether binding `addr` would conflict with a socket that is still open.
+    fn is_in_use(&self, ty: SocketType, addr: SocketAddr) -> bool {
+        self.bound.iter().any(|(&(bound_ty, bound_addr), fd)| {
+            bound_ty == ty
+                && bound_addr.is_ipv4() == addr.is_ipv4()
+                && bound_addr.port() == addr.port()
+                && (bound_addr.ip() == addr.ip()
+                    || bound_addr.ip().is_unspecified()
+                    || addr.ip().is_unspecified())
+                && fd.upgrade().is_some()
+        })
+    }
+
+    /// Bind `fd` to `addr`, picking an ephemeral port if the port is 0.
+    /// Returns the address that was actually bound.
+    fn bind(
+        &mut self,
+        ty: SocketType,
+        mut addr: SocketAddr,
+        fd: WeakFileDescriptionRef,
+    ) -> Result<SocketAddr, IoError> {
+        self.bound.retain(|_, fd| fd.upgrade().is_some());
+        if addr.port() == 0 {
+            let mut remaining = EPHEMERAL_PORTS.len();
+            loop {
+                if remaining == 0 {
+                    return Err(LibcError("EADDRINUSE"));
+                }
+                remaining = remaining.strict_sub(1);
+                let port = self.next_ephemeral_port;
+                self.next_ephemeral_port = if port == *EPHEMERAL_PORTS.end() {
+                    *EPHEMERAL_PORTS.start()
+                } else {
+                    port.strict_add(1)
+                };
+                addr.set_port(port);
+                if !self.is_in_use(ty, addr) {
+                    break;
+                }
+            }
+        } else if self.is_in_use(ty, addr) {
+            return Err(LibcError("EADDRINUSE"));
+        }
+        self.bound.insert((ty, addr), fd);
+        Ok(addr)
+    }
+
+    /// Find the open socket that traffic sent to `addr` is delivered to.
+    fn lookup(&self, ty: SocketType, addr: SocketAddr) -> Option<FileDescriptionRef> {
+        self.bound.iter().find_map(|(&(bound_ty, bound_addr), fd)| {
+            if bound_ty == ty
+                && bound_addr.is_ipv4() == addr.is_ipv4()
+                && bound_addr.port() == addr.port()
+                && (bound_addr.ip() == addr.ip() || bound_addr.ip().is_unspecified())
+            {
+                fd.upgrade()
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Buffer {
+    buf: VecDeque<u8>,
+    clock: VClock,
+}
+
+/// One end of an established stream connection.
+#[derive(Debug)]
+struct Connection {
+    /// The address of the other end of the connection.
+    peer_addr: SocketAddr,
+    /// The socket at the other end of the connection, which holds the buffer we are writing to.
+    /// This is a weak reference because the peer may be closed before us.
+    peer_fd: WeakFileDescriptionRef,
+    /// The data the peer sent to us that has not been read yet.
+    readbuf: Buffer,
+}
+
+#[derive(Debug)]
+struct Datagram {
+    from: SocketAddr,
+    data: Vec<u8>,
+    clock: VClock,
+}
+
+#[derive(Debug)]
+enum SocketState {
+    /// A stream socket that has neither been connected nor put in listening mode.
+    Unconnected,
+    /// A listening stream socket, with the connections that have not been accepted yet.
+    Listening(VecDeque<FileDescriptionRef>),
+    /// A connected stream socket.
+    Connected(Connection),
+    /// A datagram socket, with the address it is connected to (if any) and the datagrams that
+    /// have been received but not read yet.
+    Datagram { peer_addr: Option<SocketAddr>, queue: VecDeque<Datagram>, queued_bytes: usize },
+}
+
+/// A TCP or UDP socket on the emulated loopback network.
+#[derive(Debug)]
+struct Socket {
+    family: AddressFamily,
+    ty: SocketType,
+    is_nonblock: Cell<bool>,
+    /// The address this socket is bound to, if any.
+    local_addr: Cell<Option<SocketAddr>>,
+    state: RefCell<SocketState>,
+    /// Whether `shutdown` was called on this socket with `SHUT_RD` or `SHUT_RDWR`.
+    read_shutdown: Cell<bool>,
+    /// Whether `shutdown` was called on this socket with `SHUT_WR` or `SHUT_RDWR`.
+    write_shutdown: Cell<bool>,
+    /// Whether the peer was closed while there was still data it had not read, which resets the
+    /// connection.
+    reset: Cell<bool>,
+    /// The `SO_RCVTIMEO` and `SO_SNDTIMEO` socket options.
+    recv_timeout: Cell<Option<Duration>>,
+    send_timeout: Cell<Option<Duration>>,
+    /// Integer socket options that have no effect on the emulation, but whose values are
+    /// remembered so that `getsockopt` returns what `setsockopt` set.
+    options: RefCell<BTreeMap<(i32, i32), i32>>,
+    /// A list of thread ids blocked on an operation on this socket.
+    blocked_tid: RefCell<Vec<ThreadId>>,
+}
+
+impl Socket {
+    fn new(family: AddressFamily, ty: SocketType, is_nonblock: bool) -> Self {
+        let state = match ty {
+            SocketType::Stream => SocketState::Unconnected,
+            SocketType::Datagram =>
+                SocketState::Datagram { peer_addr: None, queue: VecDeque::new(), queued_bytes: 0 },
+        };
+        Socket {
+            family,
+            ty,
+            is_nonblock: Cell::new(is_nonblock),
+            local_addr: Cell::new(None),
+            state: RefCell::new(state),
+            read_shutdown: Cell::new(false),
+            write_shutdown: Cell::new(false),
+            reset: Cell::new(false),
+            recv_timeout: Cell::new(None),
+            send_timeout: Cell::new(None),
+            options: RefCell::new(BTreeMap::new()),
+            blocked_tid: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Whether the peer of this connection will not send any more data.
+    fn peer_done(conn: &Connection) -> bool {
+        match conn.peer_fd.upgrade() {
+            Some(peer_fd) => peer_fd.downcast::<Socket>().unwrap().write_shutdown.get(),
+            None => true,
+        }
+    }
+}
+
+impl FileDescription for Socket {
+    fn name(&self) -> &'static str {
+        "socket"
+    }
+
+    fn get_epoll_ready_events<'tcx>(&self) -> InterpResult<'tcx, EpollReadyEvents> {
+        // We only check the status of EPOLLIN, EPOLLOUT, EPOLLHUP, EPOLLRDHUP and EPOLLERR flags.
+        // If other event flags need to be supported in the future, the check should be added here.
+
+        let mut epoll_ready_events = EpollReadyEvents::new();
+        match &*self.state.borrow() {
+            SocketState::Unconnected => {
+                // Like on Linux, a stream socket that is not connected is writable (writes fail
+                // immediately) and hung up.
+                epoll_ready_events.epollout = true;
+                epoll_ready_events.epollhup = true;
+            }
+            SocketState::Listening(backlog) => {
+                epoll_ready_events.epollin = !backlog.is_empty();
+            }
+            SocketState::Connected(conn) => {
+                let peer_done = Socket::peer_done(conn);
+                let read_done = peer_done || self.read_shutdown.get();
+                epoll_ready_events.epollin = !conn.readbuf.buf.is_empty() || read_done;
+                epoll_ready_events.epollrdhup = read_done;
+                epoll_ready_events.epollerr = self.reset.get();
+                match conn.peer_fd.upgrade() {
+                    Some(peer_fd) if !self.write_shutdown.get() => {
+                        let peer = peer_fd.downcast::<Socket>().unwrap();
+                        if let SocketState::Connected(peer_conn) = &*peer.state.borrow() {
+                            epoll_ready_events.epollout =
+                                peer_conn.readbuf.buf.len() < MAX_STREAM_BUFFER_CAPACITY;
+                        }
+                        epoll_ready_events.epollhup = false;
+                    }
+                    peer_fd => {
+                        // Writing fails without blocking, so we are ready for writing.
+                        epoll_ready_events.epollout = true;
+                        epoll_ready_events.epollhup = peer_fd.is_none() || peer_done;
+                    }
+                }
+            }
+            SocketState::Datagram { queue, .. } => {
+                epoll_ready_events.epollin = !queue.is_empty();
+                epoll_ready_events.epollout = true;
+            }
+        }
+        interp_ok(epoll_ready_events)
+    }
+
+    fn close<'tcx>(
+        self: Box<Self>,
+        _communicate_allowed: bool,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<()>> {
+        // There is no need to unregister from the loopback network: the weak reference it holds
+        // to us cannot be upgraded anymore.
+        match self.state.into_inner() {
+            SocketState::Connected(conn) =>
+                if let Some(peer_fd) = conn.peer_fd.upgrade() {
+                    // Closing a socket with unread data resets the connection.
+                    if !conn.readbuf.buf.is_empty() {
+                        peer_fd.downcast::<Socket>().unwrap().reset.set(true);
+                    }
+                    // Notify the peer that close has happened, since that can unblock reads and
+                    // writes.
+                    notify_socket(&peer_fd, ecx)?;
+                },
+            SocketState::Listening(backlog) =>
+                for pending in backlog {
+                    // Connections that were never accepted get reset.
+                    let client_fd = match &*pending.downcast::<Socket>().unwrap().state.borrow() {
+                        SocketState::Connected(conn) => conn.peer_fd.upgrade(),
+                        _ => None,
+                    };
+                    drop(pending);
+                    if let Some(client_fd) = client_fd {
+                        client_fd.downcast::<Socket>().unwrap().reset.set(true);
+                        notify_socket(&client_fd, ecx)?;
+                    }
+                },
+            SocketState::Unconnected | SocketState::Datagram { .. } => {}
+        }
+        interp_ok(Ok(()))
+    }
+
+    fn read<'tcx>(
+        &self,
+        self_ref: &FileDescriptionRef,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        dest: &MPlaceTy<'tcx>,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let op = SocketOp::Recv { ptr, len, peek: false, dontwait: false, from: None };
+        socket_op(op, self_ref.downgrade(), dest, ecx)
+    }
+
+    fn write<'tcx>(
+        &self,
+        self_ref: &FileDescriptionRef,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        dest: &MPlaceTy<'tcx>,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let op = SocketOp::Send { ptr, len, dontwait: false, to: None };
+        socket_op(op, self_ref.downgrade(), dest, ecx)
+    }
+}
+
+/// An operation on a socket that may have to block until the socket is ready.
+#[derive(Debug, Clone, Copy)]
+enum SocketOp {
+    /// `accept`, where `addr` is the output location for the peer address and its length, and
+    /// `nonblock` says whether the new socket should be non-blocking.
+    Accept { addr: Option<(Pointer, Pointer)>, nonblock: bool },
+    /// `recv`, where `from` is the output location for the source address and its length.
+    Recv { ptr: Pointer, len: usize, peek: bool, dontwait: bool, from: Option<(Pointer, Pointer)> },
+    /// `send`, where `to` is the destination address of a datagram.
+    Send { ptr: Pointer, len: usize, dontwait: bool, to: Option<SocketAddr> },
+}
+
+impl VisitProvenance for SocketOp {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        match self {
+            SocketOp::Accept { addr, nonblock: _ } => addr.visit_provenance(visit),
+            SocketOp::Recv { ptr, len: _, peek: _, dontwait: _, from } => {
+                ptr.visit_provenance(visit);
+                from.visit_provenance(visit);
+            }
+            SocketOp::Send { ptr, len: _, dontwait: _, to: _ } => ptr.visit_provenance(visit),
+        }
+    }
+}
+
+/// Update the epoll readiness of the given socket, and wake up all threads blocked on it so that
+/// they retry their operation.
+fn notify_socket<'tcx>(
+    fd_ref: &FileDescriptionRef,
+    ecx: &mut MiriInterpCx<'tcx>,
+) -> InterpResult<'tcx> {
+    ecx.check_and_update_readiness(fd_ref)?;
+    // We need to take out the blocked thread ids and unblock them together,
+    // because `unblock_thread` may block them again and end up re-adding the
+    // thread to the blocked list.
+    let socket = fd_ref.downcast::<Socket>().unwrap();
+    let waiting_threads = std::mem::take(&mut *socket.blocked_tid.borrow_mut());
+    // FIXME: We can randomize the order of unblocking.
+    for thread_id in waiting_threads {
+        ecx.unblock_thread(thread_id, BlockReason::Socket)?;
+    }
+    interp_ok(())
+}
+
+/// Perform `op` on the socket, blocking the current thread if the socket is not ready yet (unless
+/// the socket or the operation is non-blocking).
+fn socket_op<'tcx>(
+    op: SocketOp,
+    weak_socket: WeakFileDescriptionRef,
+    dest: &MPlaceTy<'tcx>,
+    ecx: &mut MiriInterpCx<'tcx>,
+) -> InterpResult<'tcx> {
+    let Some(socket_ref) = weak_socket.upgrade() else {
+        throw_unsup_format!("socket got closed while blocking.")
+    };
+    // Since we only ever pass weak references to sockets, this cannot fail.
+    let socket = socket_ref.downcast::<Socket>().unwrap();
+
+    let done = match op {
+        SocketOp::Accept { addr, nonblock } => try_accept(socket, addr, nonblock, dest, ecx)?,
+        SocketOp::Recv { ptr, len, peek, dontwait: _, from } =>
+            try_recv(socket, ptr, len, peek, from, dest, ecx)?,
+        SocketOp::Send { ptr, len, dontwait: _, to } =>
+            try_send(socket, &socket_ref, ptr, len, to, dest, ecx)?,
+    };
+    if done {
+        return interp_ok(());
+    }
+
+    let dontwait = match op {
+        SocketOp::Accept { .. } => false,
+        SocketOp::Recv { dontwait, .. } | SocketOp::Send { dontwait, .. } => dontwait,
+    };
+    if socket.is_nonblock.get() || dontwait {
+        return ecx.set_last_error_and_return(ErrorKind::WouldBlock, dest);
+    }
+
+    let timeout = match op {
+        SocketOp::Send { .. } => socket.send_timeout.get(),
+        SocketOp::Accept { .. } | SocketOp::Recv { .. } => socket.recv_timeout.get(),
+    };
+    let timeout =
+        timeout.map(|duration| (TimeoutClock::Monotonic, TimeoutAnchor::Relative, duration));
+    socket.blocked_tid.borrow_mut().push(ecx.active_thread());
+    let dest = dest.clone();
+    ecx.block_thread(
+        BlockReason::Socket,
+        timeout,
+        callback!(
+            @capture<'tcx> {
+                op: SocketOp,
+                weak_socket: WeakFileDescriptionRef,
+                dest: MPlaceTy<'tcx>,
+            }
+            @unblock = |this| {
+                socket_op(op, weak_socket, &dest, this)
+            }
+            @timeout = |this| {
+                let Some(socket_ref) = weak_socket.upgrade() else {
+                    throw_unsup_format!("socket got closed while blocking.")
+                };
+                // Remove the current active thread_id from the blocked thread_id list.
+                socket_ref.downcast::<Socket>().unwrap()
+                    .blocked_tid.borrow_mut()
+                    .retain(|&id| id != this.active_thread());
+                this.set_last_error_and_return(ErrorKind::WouldBlock, &dest)
+            }
+        ),
+    );
+    interp_ok(())
+}
+
+/// Try to accept a pending connection. Returns `false` if there is none yet.
+fn try_accept<'tcx>(
+    socket: &Socket,
+    addr: Option<(Pointer, Pointer)>,
+    nonblock: bool,
+    dest: &MPlaceTy<'tcx>,
+    ecx: &mut MiriInterpCx<'tcx>,
+) -> InterpResult<'tcx, bool> {
+    let mut state = socket.state.borrow_mut();
+    let SocketState::Listening(backlog) = &mut *state else {
+        drop(state);
+        ecx.set_last_error_and_return(LibcError("EINVAL"), dest)?;
+        return interp_ok(true);
+    };
+    let Some(conn_ref) = backlog.pop_front() else {
+        return interp_ok(false);
+    };
+    drop(state);
+
+    let conn = conn_ref.downcast::<Socket>().unwrap();
+    conn.is_nonblock.set(nonblock);
+    let conn_state = conn.state.borrow();
+    let SocketState::Connected(conn_state) = &*conn_state else {
+        bug!("pending connection is not connected")
+    };
+    // Synchronize with the `connect` that created this connection, and with all writes to it
+    // that happened so far.
+    ecx.acquire_clock(&conn_state.readbuf.clock);
+    if let Some((addr, addrlen)) = addr {
+        ecx.write_socket_addr(conn_state.peer_addr, addr, addrlen)?;
+    }
+
+    let fd = ecx.machine.fds.insert(conn_ref.clone());
+    ecx.write_int(fd, dest)?;
+    interp_ok(true)
+}
+
+/// Try to receive data. Returns `false` if there is nothing to receive yet.
+fn try_recv<'tcx>(
+    socket: &Socket,
+    ptr: Pointer,
+    len: usize,
+    peek: bool,
+    from: Option<(Pointer, Pointer)>,
+    dest: &MPlaceTy<'tcx>,
+    ecx: &mut MiriInterpCx<'tcx>,
+) -> InterpResult<'tcx, bool> {
+    let mut state = socket.state.borrow_mut();
+    match &mut *state {
+        SocketState::Connected(conn) => {
+            let mut bytes = vec![0; len];
+            // Always succeed on read size 0.
+            if len == 0 {
+                drop(state);
+                ecx.return_read_success(ptr, &bytes, 0, dest)?;
+                return interp_ok(true);
+            }
+            if conn.readbuf.buf.is_empty() {
+                if socket.reset.replace(false) {
+                    drop(state);
+                    ecx.set_last_error_and_return(LibcError("ECONNRESET"), dest)?;
+                    return interp_ok(true);
+                }
+                if socket.read_shutdown.get() || Socket::peer_done(conn) {
+                    // 0 bytes successfully read indicates end-of-file.
+                    drop(state);
+                    ecx.return_read_success(ptr, &bytes, 0, dest)?;
+                    return interp_ok(true);
+                }
+                return interp_ok(false);
+            }
+
+            // Synchronize with all previous writes to this buffer.
+            // FIXME: this over-synchronizes; a more precise approach would be to
+            // only sync with the writes whose data we will read.
+            ecx.acquire_clock(&conn.readbuf.clock);
+            let actual_read_size = if peek {
+                let n = len.min(conn.readbuf.buf.len());
+                for (dst, src) in bytes.iter_mut().zip(conn.readbuf.buf.iter()) {
+                    *dst = *src;
+                }
+                n
+            } else {
+                // Conveniently, `read` exists on `VecDeque` and has exactly the desired behavior.
+                conn.readbuf.buf.read(&mut bytes).unwrap()
+            };
+            let peer_fd = conn.peer_fd.upgrade();
+            drop(state);
+
+            // The peer might be able to write again now.
+            if !peek && let Some(peer_fd) = peer_fd {
+                notify_socket(&peer_fd, ecx)?;
+            }
+            // Like Linux, we do not report a source address for stream sockets.
+            if let Some((_, addrlen)) = from {
+                let addrlen = ecx.ptr_to_mplace(addrlen, ecx.libc_ty_layout("socklen_t"));
+                ecx.write_null(&addrlen)?;
+            }
+            ecx.return_read_success(ptr, &bytes, actual_read_size, dest)?;
+            interp_ok(true)
+        }
+        SocketState::Datagram { queue, queued_bytes, .. } => {
+            let datagram = if peek {
+                queue.front().map(|datagram| {
+                    Datagram {
+                        from: datagram.from,
+                        data: datagram.data.clone(),
+                        clock: datagram.clock.clone(),
+                    }
+                })
+            } else {
+                queue.pop_front()
+            };
+            let Some(datagram) = datagram else {
+                return interp_ok(false);
+            };
+            if !peek {
+                *queued_bytes = queued_bytes.strict_sub(datagram.data.len());
+            }
+            drop(state);
+
+            // Synchronize with the `send` of this datagram.
+            ecx.acquire_clock(&datagram.clock);
+            if let Some((addr, addrlen)) = from {
+                ecx.write_socket_addr(datagram.from, addr, addrlen)?;
+            }
+            // The part of the datagram that does not fit into the buffer is discarded.
+            let actual_read_size = len.min(datagram.data.len());
+            ecx.return_read_success(ptr, &datagram.data, actual_read_size, dest)?;
+            interp_ok(true)
+        }
+        SocketState::Unconnected | SocketState::Listening(_) => {
+            drop(state);
+            ecx.set_last_error_and_return(LibcError("ENOTCONN"), dest)?;
+            interp_ok(true)
+        }
+    }
+}
+
+/// Try to send data. Returns `false` if there is no room for the data yet.
+fn try_send<'tcx>(
+    socket: &Socket,
+    socket_ref: &FileDescriptionRef,
+    ptr: Pointer,
+    len: usize,
+    to: Option<SocketAddr>,
+    dest: &MPlaceTy<'tcx>,
+    ecx: &mut MiriInterpCx<'tcx>,
+) -> InterpResult<'tcx, bool> {
+    let state = socket.state.borrow();
+    match &*state {
+        SocketState::Connected(conn) => {
+            // The destination address is ignored for connected stream sockets.
+            if socket.write_shutdown.get() {
+                drop(state);
+                ecx.set_last_error_and_return(ErrorKind::BrokenPipe, dest)?;
+                return interp_ok(true);
+            }
+            let Some(peer_fd) = conn.peer_fd.upgrade() else {
+                let err = if socket.reset.replace(false) {
+                    LibcError("ECONNRESET")
+                } else {
+                    ErrorKind::BrokenPipe.into()
+                };
+                drop(state);
+                ecx.set_last_error_and_return(err, dest)?;
+                return interp_ok(true);
+            };
+            drop(state);
+
+            // Always succeed on write size 0.
+            if len == 0 {
+                ecx.return_write_success(0, dest)?;
+                return interp_ok(true);
+            }
+
+            let peer = peer_fd.downcast::<Socket>().unwrap();
+            let mut peer_state = peer.state.borrow_mut();
+            let SocketState::Connected(peer_conn) = &mut *peer_state else {
+                bug!("peer of a connected socket is not connected")
+            };
+            let writebuf = &mut peer_conn.readbuf;
+            let available_space = MAX_STREAM_BUFFER_CAPACITY.strict_sub(writebuf.buf.len());
+            if available_space == 0 {
+                return interp_ok(false);
+            }
+            // Remember this clock so `recv` can synchronize with us.
+            ecx.release_clock(|clock| {
+                writebuf.clock.join(clock);
+            });
+            // Do full write / partial write based on the space available.
+            let actual_write_size = len.min(available_space);
+            let bytes = ecx.read_bytes_ptr_strip_provenance(ptr, Size::from_bytes(len))?;
+            writebuf.buf.extend(&bytes[..actual_write_size]);
+            drop(peer_state);
+
+            // The peer became readable.
+            notify_socket(&peer_fd, ecx)?;
+            ecx.return_write_success(actual_write_size, dest)?;
+            interp_ok(true)
+        }
+        SocketState::Datagram { peer_addr, .. } => {
+            let Some(to) = to.or(*peer_addr) else {
+                drop(state);
+                ecx.set_last_error_and_return(LibcError("EDESTADDRREQ"), dest)?;
+                return interp_ok(true);
+            };
+            drop(state);
+            if AddressFamily::of(&to) != socket.family {
+                ecx.set_last_error_and_return(LibcError("EAFNOSUPPORT"), dest)?;
+                return interp_ok(true);
+            }
+            if len > MAX_DATAGRAM_SIZE {
+                ecx.set_last_error_and_return(LibcError("EMSGSIZE"), dest)?;
+                return interp_ok(true);
+            }
+            let to = resolve_loopback(to)?;
+            // Sending on an unbound socket binds it to an ephemeral port.
+            let from = match ecx.bind_implicitly(socket, socket_ref)? {
+                Ok(from) => from,
+                Err(err) => {
+                    ecx.set_last_error_and_return(err, dest)?;
+                    return interp_ok(true);
+                }
+            };
+            let from = if from.ip().is_unspecified() {
+                SocketAddr::new(to.ip(), from.port())
+            } else {
+                from
+            };
+
+            // If nobody is listening at the destination, or its queue is full, the datagram is
+            // silently dropped.
+            if let Some(target_fd) = ecx.machine.network.lookup(SocketType::Datagram, to) {
+                let target = target_fd.downcast::<Socket>().unwrap();
+                let mut target_state = target.state.borrow_mut();
+                let SocketState::Datagram { peer_addr, queue, queued_bytes } = &mut *target_state
+                else {
+                    bug!("datagram address is bound to a stream socket")
+                };
+                // A connected datagram socket only receives datagrams from its peer.
+                if peer_addr.is_none_or(|peer_addr| peer_addr == from)
+                    && queued_bytes.strict_add(len) <= MAX_DATAGRAM_QUEUE_CAPACITY
+                {
+                    let mut clock = VClock::default();
+                    // Remember this clock so `recv` can synchronize with us.
+                    ecx.release_clock(|release_clock| clock.join(release_clock));
+                    let data = ecx.read_bytes_ptr_strip_provenance(ptr, Size::from_bytes(len))?;
+                    queue.push_back(Datagram { from, data: data.to_vec(), clock });
+                    *queued_bytes = queued_bytes.strict_add(len);
+                    drop(target_state);
+                    notify_socket(&target_fd, ecx)?;
+                }
+            }
+            ecx.return_write_success(len, dest)?;
+            interp_ok(true)
+        }
+        SocketState::Unconnected | SocketState::Listening(_) => {
+            drop(state);
+            ecx.set_last_error_and_return(LibcError("ENOTCONN"), dest)?;
+            interp_ok(true)
+        }
+    }
+}
+
+/// Map an address that traffic is sent to onto the loopback interface. The unspecified address
+/// means "this host", like on Linux; every other non-loopback address is unsupported.
+fn resolve_loopback<'tcx>(addr: SocketAddr) -> InterpResult<'tcx, SocketAddr> {
+    if addr.ip().is_unspecified() {
+        return interp_ok(SocketAddr::new(AddressFamily::of(&addr).localhost(), addr.port()));
+    }
+    if !addr.ip().is_loopback() {
+        throw_unsup_format!(
+            "cannot reach {addr}: only loopback addresses are supported by the emulated network"
+        );
+    }
+    interp_ok(addr)
+}
+
+impl<'tcx> EvalContextPrivExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextPrivExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Look up `fd_num` and make sure it refers to a socket. Sets the last error and returns
+    /// `None` otherwise.
+    fn socket_ref(&mut self, fd_num: i32) -> InterpResult<'tcx, Option<FileDescriptionRef>> {
+        let this = self.eval_context_mut();
+        let Some(fd) = this.machine.fds.get(fd_num) else {
+            this.set_last_error(LibcError("EBADF"))?;
+            return interp_ok(None);
+        };
+        if fd.downcast::<Socket>().is_none() {
+            this.set_last_error(LibcError("ENOTSOCK"))?;
+            return interp_ok(None);
+        }
+        interp_ok(Some(fd))
+    }
+
+    /// Read a `sockaddr_in` or `sockaddr_in6`.
+    fn read_socket_addr(
+        &mut self,
+        addr_op: &OpTy<'tcx>,
+        addrlen_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Result<SocketAddr, IoError>> {
+        let this = self.eval_context_mut();
+
+        let addrlen = this.read_scalar(addrlen_op)?.to_u32()?;
+        let sockaddr = this.deref_pointer_as(addr_op, this.libc_ty_layout("sockaddr"))?;
+        let family = this.project_field_named(&sockaddr, "sa_family")?;
+        let family = this.read_scalar(&family)?.to_uint(family.layout.size)?;
+        let af_inet = u128::try_from(this.eval_libc_i32("AF_INET")).unwrap();
+        let af_inet6 = u128::try_from(this.eval_libc_i32("AF_INET6")).unwrap();
+
+        let addr = if family == af_inet {
+            let layout = this.libc_ty_layout("sockaddr_in");
+            if u64::from(addrlen) < layout.size.bytes() {
+                return interp_ok(Err(LibcError("EINVAL")));
+            }
+            let sockaddr = this.deref_pointer_as(addr_op, layout)?;
+            let port = this.project_field_named(&sockaddr, "sin_port")?;
+            let ip = this.project_field_named(&sockaddr, "sin_addr")?;
+            // Both are stored in network byte order, so we read their bytes as they are.
+            let port = this.read_bytes_ptr_strip_provenance(port.ptr(), Size::from_bytes(2))?;
+            let port = u16::from_be_bytes(port.try_into().unwrap());
+            let ip = this.read_bytes_ptr_strip_provenance(ip.ptr(), Size::from_bytes(4))?;
+            let ip = <[u8; 4]>::try_from(ip).unwrap();
+            SocketAddr::V4(SocketAddrV4::new(ip.into(), port))
+        } else if family == af_inet6 {
+            let layout = this.libc_ty_layout("sockaddr_in6");
+            if u64::from(addrlen) < layout.size.bytes() {
+                return interp_ok(Err(LibcError("EINVAL")));
+            }
+            let sockaddr = this.deref_pointer_as(addr_op, layout)?;
+            let port = this.project_field_named(&sockaddr, "sin6_port")?;
+            let ip = this.project_field_named(&sockaddr, "sin6_addr")?;
+            let flowinfo = this.project_field_named(&sockaddr, "sin6_flowinfo")?;
+            let scope_id = this.project_field_named(&sockaddr, "sin6_scope_id")?;
+            let port = this.read_bytes_ptr_strip_provenance(port.ptr(), Size::from_bytes(2))?;
+            let port = u16::from_be_bytes(port.try_into().unwrap());
+            let ip = this.read_bytes_ptr_strip_provenance(ip.ptr(), Size::from_bytes(16))?;
+            let ip = <[u8; 16]>::try_from(ip).unwrap();
+            let flowinfo = this.read_scalar(&flowinfo)?.to_u32()?;
+            let scope_id = this.read_scalar(&scope_id)?.to_u32()?;
+            SocketAddr::V6(SocketAddrV6::new(ip.into(), port, flowinfo, scope_id))
+        } else {
+            return interp_ok(Err(LibcError("EAFNOSUPPORT")));
+        };
+        interp_ok(Ok(addr))
+    }
+
+    /// Write `addr` as a `sockaddr_in` or `sockaddr_in6` to `addr_ptr`, and its length to
+    /// `addrlen_ptr`. Like on Linux, the address is truncated if the buffer is too small for it,
+    /// and the length written is that of the whole address.
+    fn write_socket_addr(
+        &mut self,
+        addr: SocketAddr,
+        addr_ptr: Pointer,
+        addrlen_ptr: Pointer,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let addrlen = this.ptr_to_mplace(addrlen_ptr, this.libc_ty_layout("socklen_t"));
+        let layout = match addr {
+            SocketAddr::V4(_) => this.libc_ty_layout("sockaddr_in"),
+            SocketAddr::V6(_) => this.libc_ty_layout("sockaddr_in6"),
+        };
+        let buf_len = u64::from(this.read_scalar(&addrlen)?.to_u32()?);
+        // A truncated address is built in a temporary allocation, and then copied to the buffer.
+        let truncated = buf_len < layout.size.bytes();
+        let sockaddr = if truncated {
+            this.allocate(layout, MiriMemoryKind::Machine.into())?
+        } else {
+            this.ptr_to_mplace(addr_ptr, layout)
+        };
+        this.write_bytes_ptr(sockaddr.ptr(), std::iter::repeat_n(0, layout.size.bytes_usize()))?;
+
+        let (family, len_field, port_field, ip_field) = match addr {
+            SocketAddr::V4(_) => ("AF_INET", "sin_len", "sin_port", "sin_addr"),
+            SocketAddr::V6(_) => ("AF_INET6", "sin6_len", "sin6_port", "sin6_addr"),
+        };
+        let family_field = if addr.is_ipv4() { "sin_family" } else { "sin6_family" };
+        let family_field = this.project_field_named(&sockaddr, family_field)?;
+        this.write_int(this.eval_libc_i32(family), &family_field)?;
+        // BSDs store the length of the address in the address itself.
+        if this.projectable_has_field(&sockaddr, len_field) {
+            let len_field = this.project_field_named(&sockaddr, len_field)?;
+            this.write_int(layout.size.bytes(), &len_field)?;
+        }
+        // Port and address are stored in network byte order.
+        let port_field = this.project_field_named(&sockaddr, port_field)?;
+        this.write_bytes_ptr(port_field.ptr(), addr.port().to_be_bytes())?;
+        let ip_field = this.project_field_named(&sockaddr, ip_field)?;
+        match addr {
+            SocketAddr::V4(addr) => this.write_bytes_ptr(ip_field.ptr(), addr.ip().octets())?,
+            SocketAddr::V6(addr) => {
+                this.write_bytes_ptr(ip_field.ptr(), addr.ip().octets())?;
+                this.write_int_fields_named(
+                    &[
+                        ("sin6_flowinfo", addr.flowinfo().into()),
+                        ("sin6_scope_id", addr.scope_id().into()),
+                    ],
+                    &sockaddr,
+                )?;
+            }
+        }
+
+        if truncated {
+            this.mem_copy(sockaddr.ptr(), addr_ptr, Size::from_bytes(buf_len), true)?;
+            this.deallocate_ptr(sockaddr.ptr(), None, MiriMemoryKind::Machine.into())?;
+        }
+        this.write_int(layout.size.bytes(), &addrlen)?;
+        interp_ok(())
+    }
+
+    /// Bind `socket` to an ephemeral port on the unspecified address if it is not bound yet.
+    /// Returns the address the socket is bound to.
+    fn bind_implicitly(
+        &mut self,
+        socket: &Socket,
+        socket_ref: &FileDescriptionRef,
+    ) -> InterpResult<'tcx, Result<SocketAddr, IoError>> {
+        let this = self.eval_context_mut();
+        if let Some(local_addr) = socket.local_addr.get() {
+            return interp_ok(Ok(local_addr));
+        }
+        let addr = SocketAddr::new(socket.family.unspecified(), 0);
+        let result = this.machine.network.bind(socket.ty, addr, socket_ref.downgrade());
+        if let Ok(local_addr) = result {
+            socket.local_addr.set(Some(local_addr));
+        }
+        interp_ok(result)
+    }
+
+    /// Parse the `flags` argument of `send` and `recv`. Returns whether `MSG_PEEK` and
+    /// `MSG_DONTWAIT` were set.
+    fn parse_msg_flags(&self, name: &str, flags: &OpTy<'tcx>) -> InterpResult<'tcx, (bool, bool)> {
+        let this = self.eval_context_ref();
+        let mut flags = this.read_scalar(flags)?.to_i32()?;
+
+        let mut peek = false;
+        let mut dontwait = false;
+        // Interpret the flag. Every flag we recognize is "subtracted" from `flags`, so
+        // if there is anything left at the end, that's an unsupported flag.
+        let msg_peek = this.eval_libc_i32("MSG_PEEK");
+        if flags & msg_peek == msg_peek {
+            peek = true;
+            flags &= !msg_peek;
+        }
+        let msg_dontwait = this.eval_libc_i32("MSG_DONTWAIT");
+        if flags & msg_dontwait == msg_dontwait {
+            dontwait = true;
+            flags &= !msg_dontwait;
+        }
+        if matches!(&*this.tcx.sess.target.os, "linux" | "android" | "freebsd") {
+            // We never raise `SIGPIPE`, so `MSG_NOSIGNAL` does not need to do anything.
+            let msg_nosignal = this.eval_libc_i32("MSG_NOSIGNAL");
+            if flags & msg_nosignal == msg_nosignal {
+                flags &= !msg_nosignal;
+            }
+        }
+        if flags != 0 {
+            throw_unsup_format!("{name}: encountered unknown unsupported flags {flags:#x}");
+        }
+        interp_ok((peek, dontwait))
+    }
+
+    /// Read the optional output location for a socket address, which may be null.
+    fn read_socket_addr_out(
+        &self,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Option<(Pointer, Pointer)>> {
+        let this = self.eval_context_ref();
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+        interp_ok(if this.ptr_is_null(addr)? { None } else { Some((addr, addrlen)) })
+    }
+
+    /// Read a `timeval` socket option. A zero timeout means "no timeout".
+    fn read_timeval_option(
+        &mut self,
+        value: Pointer,
+        len: u32,
+    ) -> InterpResult<'tcx, Result<Option<Duration>, IoError>> {
+        let this = self.eval_context_mut();
+        let layout = this.libc_ty_layout("timeval");
+        if u64::from(len) < layout.size.bytes() {
+            return interp_ok(Err(LibcError("EINVAL")));
+        }
+        let timeval = this.ptr_to_mplace(value, layout);
+        let sec = this.project_field_named(&timeval, "tv_sec")?;
+        let sec = this.read_scalar(&sec)?.to_int(sec.layout.size)?;
+        let usec = this.project_field_named(&timeval, "tv_usec")?;
+        let usec = this.read_scalar(&usec)?.to_int(usec.layout.size)?;
+        let (Ok(sec), Ok(usec)) = (u64::try_from(sec), u32::try_from(usec)) else {
+            return interp_ok(Err(LibcError("EDOM")));
+        };
+        if usec >= 1_000_000 {
+            return interp_ok(Err(LibcError("EDOM")));
+        }
+        let duration = Duration::new(sec, usec.strict_mul(1000));
+        interp_ok(Ok(if duration.is_zero() { None } else { Some(duration) }))
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// For more information on the arguments see the socket manpage:
+    /// <https://man7.org/linux/man-pages/man2/socket.2.html>
+    fn socket(
+        &mut self,
+        domain: &OpTy<'tcx>,
+        type_: &OpTy<'tcx>,
+        protocol: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let domain = this.read_scalar(domain)?.to_i32()?;
+        let mut flags = this.read_scalar(type_)?.to_i32()?;
+        let protocol = this.read_scalar(protocol)?.to_i32()?;
+
+        let mut is_sock_nonblock = false;
+
+        // Interpret the flag. Every flag we recognize is "subtracted" from `flags`, so
+        // if there is anything left at the end, that's an unsupported flag.
+        if matches!(&*this.tcx.sess.target.os, "linux" | "android" | "freebsd") {
+            // SOCK_NONBLOCK and SOCK_CLOEXEC only exist on some targets.
+            let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+            let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+            if flags & sock_nonblock == sock_nonblock {
+                is_sock_nonblock = true;
+                flags &= !sock_nonblock;
+            }
+            if flags & sock_cloexec == sock_cloexec {
+                flags &= !sock_cloexec;
+            }
+        }
+
+        let family = if domain == this.eval_libc_i32("AF_INET") {
+            AddressFamily::Inet
+        } else if domain == this.eval_libc_i32("AF_INET6") {
+            AddressFamily::Inet6
+        } else {
+            throw_unsup_format!(
+                "socket: domain {:#x} is unsupported, only AF_INET and AF_INET6 are allowed",
+                domain
+            );
+        };
+        let ty = if flags == this.eval_libc_i32("SOCK_STREAM") {
+            SocketType::Stream
+        } else if flags == this.eval_libc_i32("SOCK_DGRAM") {
+            SocketType::Datagram
+        } else {
+            throw_unsup_format!(
+                "socket: type {:#x} is unsupported, only SOCK_STREAM, SOCK_DGRAM, \
+                                 SOCK_CLOEXEC and SOCK_NONBLOCK are allowed",
+                flags
+            );
+        };
+        let default_protocol = match ty {
+            SocketType::Stream => this.eval_libc_i32("IPPROTO_TCP"),
+            SocketType::Datagram => this.eval_libc_i32("IPPROTO_UDP"),
+        };
+        if protocol != 0 && protocol != default_protocol {
+            throw_unsup_format!(
+                "socket: socket protocol {protocol} is unsupported, \
+                                 only 0 and the default protocol of the socket type are allowed",
+            );
+        }
+
+        let fd = this.machine.fds.insert_new(Socket::new(family, ty, is_sock_nonblock));
+        interp_ok(Scalar::from_i32(fd))
+    }
+
+    fn bind(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        let socket = socket_ref.downcast::<Socket>().unwrap();
+        let addr = match this.read_socket_addr(addr, addrlen)? {
+            Ok(addr) => addr,
+            Err(err) => return this.set_last_error_and_return_i32(err),
+        };
+
+        if socket.local_addr.get().is_some() || AddressFamily::of(&addr) != socket.family {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        // The loopback interface is the only network interface we have.
+        if !addr.ip().is_loopback() && !addr.ip().is_unspecified() {
+            return this.set_last_error_and_return_i32(LibcError("EADDRNOTAVAIL"));
+        }
+        match this.machine.network.bind(socket.ty, addr, socket_ref.downgrade()) {
+            Ok(addr) => socket.local_addr.set(Some(addr)),
+            Err(err) => return this.set_last_error_and_return_i32(err),
+        }
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn listen(&mut self, socket: &OpTy<'tcx>, backlog: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        // We never refuse connections because the backlog is full, so the backlog size does not
+        // matter.
+        let _backlog = this.read_scalar(backlog)?.to_i32()?;
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        let socket = socket_ref.downcast::<Socket>().unwrap();
+
+        let mut state = socket.state.borrow_mut();
+        match &*state {
+            SocketState::Unconnected => {}
+            SocketState::Listening(_) => return interp_ok(Scalar::from_i32(0)),
+            SocketState::Connected(_) => {
+                drop(state);
+                return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+            }
+            SocketState::Datagram { .. } => {
+                drop(state);
+                return this.set_last_error_and_return_i32(LibcError("EOPNOTSUPP"));
+            }
+        }
+        // Listening on an unbound socket binds it to an ephemeral port.
+        if let Err(err) = this.bind_implicitly(socket, &socket_ref)? {
+            drop(state);
+            return this.set_last_error_and_return_i32(err);
+        }
+        *state = SocketState::Listening(VecDeque::new());
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn connect(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        let socket = socket_ref.downcast::<Socket>().unwrap();
+        let addr = match this.read_socket_addr(addr, addrlen)? {
+            Ok(addr) => addr,
+            Err(err) => return this.set_last_error_and_return_i32(err),
+        };
+        if AddressFamily::of(&addr) != socket.family {
+            return this.set_last_error_and_return_i32(LibcError("EAFNOSUPPORT"));
+        }
+        let addr = resolve_loopback(addr)?;
+
+        match &*socket.state.borrow() {
+            SocketState::Unconnected | SocketState::Datagram { .. } => {}
+            SocketState::Listening(_) =>
+                return this.set_last_error_and_return_i32(LibcError("EINVAL")),
+            SocketState::Connected(_) =>
+                return this.set_last_error_and_return_i32(LibcError("EISCONN")),
+        }
+
+        if socket.ty == SocketType::Datagram {
+            // Connecting a datagram socket just sets its default destination.
+            if let Err(err) = this.bind_implicitly(socket, &socket_ref)? {
+                return this.set_last_error_and_return_i32(err);
+            }
+            if let SocketState::Datagram { peer_addr, .. } = &mut *socket.state.borrow_mut() {
+                *peer_addr = Some(addr);
+            }
+            return interp_ok(Scalar::from_i32(0));
+        }
+
+        let Some(listener_ref) = this.machine.network.lookup(SocketType::Stream, addr) else {
+            return this.set_last_error_and_return_i32(LibcError("ECONNREFUSED"));
+        };
+        let listener = listener_ref.downcast::<Socket>().unwrap();
+        if !matches!(&*listener.state.borrow(), SocketState::Listening(_)) {
+            return this.set_last_error_and_return_i32(LibcError("ECONNREFUSED"));
+        }
+
+        // Connecting an unbound socket binds it to an ephemeral port.
+        let local_addr = match this.bind_implicitly(socket, &socket_ref)? {
+            Ok(local_addr) => local_addr,
+            Err(err) => return this.set_last_error_and_return_i32(err),
+        };
+        let local_addr = if local_addr.ip().is_unspecified() {
+            SocketAddr::new(addr.ip(), local_addr.port())
+        } else {
+            local_addr
+        };
+        socket.local_addr.set(Some(local_addr));
+
+        // Create the server end of the connection, and queue it on the listener. Since the
+        // handshake happens entirely inside Miri, the connection is established immediately,
+        // even for non-blocking sockets.
+        let server = Socket::new(socket.family, SocketType::Stream, false);
+        server.local_addr.set(Some(addr));
+        let mut readbuf = Buffer { buf: VecDeque::new(), clock: VClock::default() };
+        // Remember this clock so `accept` can synchronize with us.
+        this.release_clock(|clock| readbuf.clock.join(clock));
+        *server.state.borrow_mut() = SocketState::Connected(Connection {
+            peer_addr: local_addr,
+            peer_fd: socket_ref.downgrade(),
+            readbuf,
+        });
+        let server_ref = this.machine.fds.new_ref(server);
+        *socket.state.borrow_mut() = SocketState::Connected(Connection {
+            peer_addr: addr,
+            peer_fd: server_ref.downgrade(),
+            readbuf: Buffer { buf: VecDeque::new(), clock: VClock::default() },
+        });
+        if let SocketState::Listening(backlog) = &mut *listener.state.borrow_mut() {
+            backlog.push_back(server_ref);
+        }
+
+        notify_socket(&listener_ref, this)?;
+        this.check_and_update_readiness(&socket_ref)?;
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    /// Implements `accept` and `accept4`. `flags` is `None` for `accept`.
+    fn accept4(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+        flags: Option<&OpTy<'tcx>>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let addr = this.read_socket_addr_out(addr, addrlen)?;
+        let mut flags = match flags {
+            Some(flags) => this.read_scalar(flags)?.to_i32()?,
+            None => 0,
+        };
+
+        let mut nonblock = false;
+        if flags != 0 {
+            let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+            let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+            if flags & sock_nonblock == sock_nonblock {
+                nonblock = true;
+                flags &= !sock_nonblock;
+            }
+            if flags & sock_cloexec == sock_cloexec {
+                flags &= !sock_cloexec;
+            }
+        }
+        if flags != 0 {
+            throw_unsup_format!("accept4: encountered unknown unsupported flags {:#x}", flags);
+        }
+
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return this.write_int(-1, dest);
+        };
+        socket_op(SocketOp::Accept { addr, nonblock }, socket_ref.downgrade(), dest, this)
+    }
+
+    /// Implements `recv` and `recvfrom`. `from` is `None` for `recv`.
+    fn recvfrom(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        from: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let ptr = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let (peek, dontwait) = this.parse_msg_flags("recv", flags)?;
+        let from = match from {
+            Some((addr, addrlen)) => this.read_socket_addr_out(addr, addrlen)?,
+            None => None,
+        };
+
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(ptr, Size::from_bytes(len), CheckInAllocMsg::MemoryAccessTest)?;
+        // We cap the number of read bytes to the largest value that we are able to fit in both the
+        // host's and target's `isize`.
+        let len = len
+            .min(u64::try_from(this.target_isize_max()).unwrap())
+            .min(u64::try_from(isize::MAX).unwrap());
+        let len = usize::try_from(len).unwrap();
+
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return this.write_int(-1, dest);
+        };
+        let op = SocketOp::Recv { ptr, len, peek, dontwait, from };
+        socket_op(op, socket_ref.downgrade(), dest, this)
+    }
+
+    /// Implements `send` and `sendto`. `to` is `None` for `send`.
+    fn sendto(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        to: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let ptr = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let (peek, dontwait) = this.parse_msg_flags("send", flags)?;
+        if peek {
+            throw_unsup_format!("send: MSG_PEEK is unsupported");
+        }
+        let to = match to {
+            Some((addr, addrlen)) if !this.ptr_is_null(this.read_pointer(addr)?)? =>
+                match this.read_socket_addr(addr, addrlen)? {
+                    Ok(addr) => Some(addr),
+                    Err(err) => return this.set_last_error_and_return(err, dest),
+                },
+            _ => None,
+        };
+
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(ptr, Size::from_bytes(len), CheckInAllocMsg::MemoryAccessTest)?;
+        // We cap the number of written bytes to the largest value that we are able to fit in both
+        // the host's and target's `isize`.
+        let len = len
+            .min(u64::try_from(this.target_isize_max()).unwrap())
+            .min(u64::try_from(isize::MAX).unwrap());
+        let len = usize::try_from(len).unwrap();
+
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return this.write_int(-1, dest);
+        };
+        let op = SocketOp::Send { ptr, len, dontwait, to };
+        socket_op(op, socket_ref.downgrade(), dest, this)
+    }
+
+    fn shutdown(&mut self, socket: &OpTy<'tcx>, how: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let how = this.read_scalar(how)?.to_i32()?;
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        let socket = socket_ref.downcast::<Socket>().unwrap();
+
+        let (read, write) = if how == this.eval_libc_i32("SHUT_RD") {
+            (true, false)
+        } else if how == this.eval_libc_i32("SHUT_WR") {
+            (false, true)
+        } else if how == this.eval_libc_i32("SHUT_RDWR") {
+            (true, true)
+        } else {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        };
+        let peer_fd = match &*socket.state.borrow() {
+            SocketState::Connected(conn) => conn.peer_fd.upgrade(),
+            _ => return this.set_last_error_and_return_i32(LibcError("ENOTCONN")),
+        };
+
+        if read {
+            socket.read_shutdown.set(true);
+        }
+        if write {
+            socket.write_shutdown.set(true);
+        }
+        // Our own reads and writes, as well as the peer's reads, might not block anymore.
+        notify_socket(&socket_ref, this)?;
+        if let Some(peer_fd) = peer_fd {
+            notify_socket(&peer_fd, this)?;
+        }
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getsockname(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let addr_ptr = this.read_pointer(addr)?;
+        let addrlen_ptr = this.read_pointer(addrlen)?;
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        let socket = socket_ref.downcast::<Socket>().unwrap();
+
+        // An unbound socket reports the unspecified address.
+        let local_addr =
+            socket.local_addr.get().unwrap_or(SocketAddr::new(socket.family.unspecified(), 0));
+        this.write_socket_addr(local_addr, addr_ptr, addrlen_ptr)?;
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getpeername(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let addr_ptr = this.read_pointer(addr)?;
+        let addrlen_ptr = this.read_pointer(addrlen)?;
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        let socket = socket_ref.downcast::<Socket>().unwrap();
+
+        let peer_addr = match &*socket.state.borrow() {
+            SocketState::Connected(conn) => Some(conn.peer_addr),
+            SocketState::Datagram { peer_addr, .. } => *peer_addr,
+            SocketState::Unconnected | SocketState::Listening(_) => None,
+        };
+        let Some(peer_addr) = peer_addr else {
+            return this.set_last_error_and_return_i32(LibcError("ENOTCONN"));
+        };
+        this.write_socket_addr(peer_addr, addr_ptr, addrlen_ptr)?;
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn setsockopt(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        name: &OpTy<'tcx>,
+        value: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let name = this.read_scalar(name)?.to_i32()?;
+        let value = this.read_pointer(value)?;
+        let len = this.read_scalar(len)?.to_u32()?;
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        let socket = socket_ref.downcast::<Socket>().unwrap();
+
+        if level == this.eval_libc_i32("SOL_SOCKET")
+            && (name == this.eval_libc_i32("SO_RCVTIMEO")
+                || name == this.eval_libc_i32("SO_SNDTIMEO"))
+        {
+            let timeout = match this.read_timeval_option(value, len)? {
+                Ok(timeout) => timeout,
+                Err(err) => return this.set_last_error_and_return_i32(err),
+            };
+            if name == this.eval_libc_i32("SO_RCVTIMEO") {
+                socket.recv_timeout.set(timeout);
+            } else {
+                socket.send_timeout.set(timeout);
+            }
+            return interp_ok(Scalar::from_i32(0));
+        }
+
+        if !this.is_int_socket_option(level, name) {
+            throw_unsup_format!("setsockopt: option {name:#x} at level {level:#x} is unsupported");
+        }
+        let int_layout = this.machine.layouts.i32;
+        if u64::from(len) < int_layout.size.bytes() {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        let value = this.ptr_to_mplace(value, int_layout);
+        let value = this.read_scalar(&value)?.to_i32()?;
+        socket.options.borrow_mut().insert((level, name), value);
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getsockopt(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        name: &OpTy<'tcx>,
+        value: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let name = this.read_scalar(name)?.to_i32()?;
+        let value = this.read_pointer(value)?;
+        let len = this.deref_pointer_as(len, this.libc_ty_layout("socklen_t"))?;
+        let Some(socket_ref) = this.socket_ref(socket)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        let socket = socket_ref.downcast::<Socket>().unwrap();
+
+        if level == this.eval_libc_i32("SOL_SOCKET")
+            && (name == this.eval_libc_i32("SO_RCVTIMEO")
+                || name == this.eval_libc_i32("SO_SNDTIMEO"))
+        {
+            let timeout = if name == this.eval_libc_i32("SO_RCVTIMEO") {
+                socket.recv_timeout.get()
+            } else {
+                socket.send_timeout.get()
+            };
+            let timeout = timeout.unwrap_or_default();
+            let layout = this.libc_ty_layout("timeval");
+            if u64::from(this.read_scalar(&len)?.to_u32()?) < layout.size.bytes() {
+                return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+            }
+            let timeval = this.ptr_to_mplace(value, layout);
+            this.write_int_fields_named(
+                &[
+                    ("tv_sec", timeout.as_secs().into()),
+                    ("tv_usec", timeout.subsec_micros().into()),
+                ],
+                &timeval,
+            )?;
+            this.write_int(layout.size.bytes(), &len)?;
+            return interp_ok(Scalar::from_i32(0));
+        }
+
+        let option = if level == this.eval_libc_i32("SOL_SOCKET")
+            && name == this.eval_libc_i32("SO_ERROR")
+        {
+            // Errors are always reported by the operation that caused them, so there never is a
+            // pending error.
+            0
+        } else if level == this.eval_libc_i32("SOL_SOCKET") && name == this.eval_libc_i32("SO_TYPE")
+        {
+            match socket.ty {
+                SocketType::Stream => this.eval_libc_i32("SOCK_STREAM"),
+                SocketType::Datagram => this.eval_libc_i32("SOCK_DGRAM"),
+            }
+        } else if this.is_int_socket_option(level, name) {
+            let default = if level == this.eval_libc_i32("IPPROTO_IP")
+                && name == this.eval_libc_i32("IP_TTL")
+            {
+                64
+            } else if level == this.eval_libc_i32("SOL_SOCKET")
+                && (name == this.eval_libc_i32("SO_SNDBUF")
+                    || name == this.eval_libc_i32("SO_RCVBUF"))
+            {
+                i32::try_from(MAX_STREAM_BUFFER_CAPACITY).unwrap()
+            } else {
+                0
+            };
+            socket.options.borrow().get(&(level, name)).copied().unwrap_or(default)
+        } else {
+            throw_unsup_format!("getsockopt: option {name:#x} at level {level:#x} is unsupported");
+        };
+
+        let int_layout = this.machine.layouts.i32;
+        if u64::from(this.read_scalar(&len)?.to_u32()?) < int_layout.size.bytes() {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        let value = this.ptr_to_mplace(value, int_layout);
+        this.write_int(option, &value)?;
+        this.write_int(int_layout.size.bytes(), &len)?;
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    /// Returns whether the given option is an integer option that we accept but that has no effect
+    /// on the emulated network.
+    fn is_int_socket_option(&self, level: i32, name: i32) -> bool {
+        let this = self.eval_context_ref();
+        let mut sol_socket =
+            vec!["SO_REUSEADDR", "SO_KEEPALIVE", "SO_BROADCAST", "SO_SNDBUF", "SO_RCVBUF"];
+        if matches!(&*this.tcx.sess.target.os, "linux" | "android" | "freebsd" | "macos") {
+            sol_socket.push("SO_REUSEPORT");
+        }
+        if matches!(&*this.tcx.sess.target.os, "macos" | "freebsd") {
+            // We never raise `SIGPIPE`, so this does not need to do anything.
+            sol_socket.push("SO_NOSIGPIPE");
+        }
+        let options: &[(&str, &[&str])] = &[
+            ("SOL_SOCKET", &sol_socket),
+            ("IPPROTO_TCP", &["TCP_NODELAY"]),
+            ("IPPROTO_IP", &["IP_TTL"]),
+            ("IPPROTO_IPV6", &["IPV6_V6ONLY"]),
+        ];
+        options.iter().any(|(level_name, names)| {
+            level == this.eval_libc_i32(level_name)
+                && names.iter().any(|option| name == this.eval_libc_i32(option))
+        })
+    }
+
+    /// Implements the `ioctl` requests that make sense for sockets.
+    fn ioctl(&mut self, args: &[OpTy<'tcx>]) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let [fd_num, request] = check_min_arg_count("ioctl", args)?;
+        let fd_num = this.read_scalar(fd_num)?.to_i32()?;
+        let request = this.read_scalar(request)?.to_uint(request.layout.size)?;
+
+        let fionbio = this.eval_libc("FIONBIO");
+        let fionbio = fionbio.to_uint(fionbio.size())?;
+        let fioclex = this.eval_libc("FIOCLEX");
+        let fioclex = fioclex.to_uint(fioclex.size())?;
+
+        if request == fioclex {
+            // As usual we ignore CLOEXEC, but we still need to check that the file itself is open.
+            if !this.machine.fds.is_fd_num(fd_num) {
+                return this.set_last_error_and_return_i32(LibcError("EBADF"));
+            }
+            return interp_ok(Scalar::from_i32(0));
+        }
+        if request == fionbio {
+            let [_, _, value] = check_min_arg_count("ioctl(fd, FIONBIO, ...)", args)?;
+            let value = this.deref_pointer_as(value, this.machine.layouts.i32)?;
+            let value = this.read_scalar(&value)?.to_i32()?;
+            let Some(socket_ref) = this.socket_ref(fd_num)? else {
+                return interp_ok(Scalar::from_i32(-1));
+            };
+            socket_ref.downcast::<Socket>().unwrap().is_nonblock.set(value != 0);
+            return interp_ok(Scalar::from_i32(0));
+        }
+        throw_unsup_format!("ioctl: unsupported request {request:#x}");
+    }
+}
@@ -0,0 +1,413 @@
+//! Coverage of `unsafe` code, for `-Zmiri-unsafe-coverage`.
+//!
+//! While interpreting, we count how often each basic block of the local crate is executed. At the
+//! end, every `unsafe` block and `unsafe fn` body of the local crate is mapped to the basic blocks
+//! whose statements lie inside of it, and we report how many of those were executed. MIR no longer
+//! knows which scopes are `unsafe`, so this mapping is done by source spans: a basic block belongs
+//! to an `unsafe` region if one of its statements (or its terminator) was written inside of it.
+//! `unsafe` blocks that were produced by macro expansion are not reported.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::{fs, iter};
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def::DefKind;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_hir::{self as hir, BlockCheckMode, Safety, UnsafeSource};
+use rustc_middle::mir;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+use rustc_span::def_id::{DefId, LOCAL_CRATE, LocalDefId};
+
+use crate::helpers::write_json_string;
+
+/// The number of times each basic block of the local crate was executed.
+#[derive(Debug)]
+pub struct UnsafeCoverage {
+    /// The file the report is written to, or `-` for the standard output. If it ends in `.json`,
+    /// the report is written as JSON, otherwise in the lcov tracefile format. `{crate}` in the
+    /// file name is replaced by the name of the local crate and a hash that tells apart the crates
+    /// with the same name, so that each of the test binaries of `cargo miri test` gets its own
+    /// report.
+    path: PathBuf,
+    executed: FxHashMap<(DefId, mir::BasicBlock), u64>,
+}
+
+impl UnsafeCoverage {
+    pub fn new(path: PathBuf) -> Self {
+        UnsafeCoverage { path, executed: FxHashMap::default() }
+    }
+
+    /// Called whenever the terminator of `block` in the body of `def_id` is about to be executed.
+    pub fn record(&mut self, def_id: DefId, block: mir::BasicBlock) {
+        if def_id.is_local() {
+            let count = self.executed.entry((def_id, block)).or_default();
+            *count = count.strict_add(1);
+        }
+    }
+
+    /// Maps the executed basic blocks to the `unsafe` regions of the local crate and writes the
+    /// report.
+    pub fn write_report(&self, tcx: TyCtxt<'_>) -> Result<(), String> {
+        let regions = self.regions(tcx);
+        let report = if self.path.extension().is_some_and(|ext| ext == "json") {
+            to_json(&regions)
+        } else {
+            to_lcov(&regions)
+        };
+        if self.path.as_os_str() == "-" {
+            print!("{report}");
+            return Ok(());
+        }
+        let path = self.report_path(tcx);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("failed to create `{}`: {err}", dir.display()))?;
+        }
+        fs::write(&path, report)
+            .map_err(|err| format!("failed to write `{}`: {err}", path.display()))
+    }
+
+    fn report_path(&self, tcx: TyCtxt<'_>) -> PathBuf {
+        let Some(path) = self.path.to_str().filter(|path| path.contains("{crate}")) else {
+            return self.path.clone();
+        };
+        let krate = format!(
+            "{}-{:016x}",
+            tcx.crate_name(LOCAL_CRATE),
+            tcx.stable_crate_id(LOCAL_CRATE).as_u64()
+        );
+        PathBuf::from(path.replace("{crate}", &krate))
+    }
+
+    fn regions(&self, tcx: TyCtxt<'_>) -> Vec<UnsafeRegion> {
+        let source_map = tcx.sess.source_map();
+        let mut regions = Vec::new();
+        for def_id in tcx.hir().body_owners() {
+            if !tcx.def_kind(def_id).is_fn_like() {
+                continue;
+            }
+            let function = tcx.def_path_str(tcx.typeck_root_def_id(def_id.to_def_id()));
+            let body = tcx.hir().body_owned_by(def_id);
+            if matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+                && tcx.fn_sig(def_id).skip_binder().safety() == Safety::Unsafe
+                && !body.value.span.from_expansion()
+            {
+                regions.push((RegionKind::UnsafeFn, function.clone(), body.value.span));
+            }
+            let mut finder = UnsafeBlockFinder { blocks: Vec::new() };
+            finder.visit_body(body);
+            regions.extend(
+                finder
+                    .blocks
+                    .into_iter()
+                    .map(|span| (RegionKind::UnsafeBlock, function.clone(), span)),
+            );
+        }
+
+        let mut regions: Vec<_> = regions
+            .into_iter()
+            .map(|(kind, function, span)| {
+                let lo = source_map.lookup_char_pos(span.lo());
+                let hi = source_map.lookup_char_pos(span.hi());
+                UnsafeRegion {
+                    kind,
+                    function,
+                    file: lo.file.name.prefer_local().to_string(),
+                    start: (lo.line, lo.col_display.strict_add(1)),
+                    end: (hi.line, hi.col_display.strict_add(1)),
+                    span,
+                    blocks: 0,
+                    executed_blocks: 0,
+                    lines: BTreeMap::new(),
+                }
+            })
+            .collect();
+
+        // Map the basic blocks of all bodies to the regions that they lie in. Each basic block is
+        // visited once, and each of its spans is only compared against the regions containing it.
+        let nesting = RegionNesting::new(&regions);
+        for def_id in tcx.hir().body_owners() {
+            if !tcx.def_kind(def_id).is_fn_like() || !tcx.is_mir_available(def_id) {
+                continue;
+            }
+            let body = tcx.optimized_mir(def_id);
+            for (block, data) in body.basic_blocks.iter_enumerated() {
+                let count = self.executed_count(def_id, block);
+                let spans =
+                    data.statements.iter().map(|statement| statement.source_info.span).chain(
+                        data.terminator.iter().map(|terminator| terminator.source_info.span),
+                    );
+                let mut block_regions = Vec::new();
+                for span in spans {
+                    // Like `Span::find_ancestor_inside`: a region gets the first span on the way
+                    // out of the macro expansions that lies inside of it.
+                    let mut span_regions = Vec::new();
+                    let mut ancestor = Some(span);
+                    while let Some(span) = ancestor {
+                        for index in nesting.containing(&regions, span) {
+                            if span_regions.contains(&index)
+                                || !body.span.overlaps(regions[index].span)
+                            {
+                                continue;
+                            }
+                            span_regions.push(index);
+                            if span.is_dummy() {
+                                continue;
+                            }
+                            let line = source_map.lookup_char_pos(span.lo()).line;
+                            let line_count = regions[index].lines.entry(line).or_default();
+                            *line_count = (*line_count).max(count);
+                            if !block_regions.contains(&index) {
+                                block_regions.push(index);
+                            }
+                        }
+                        ancestor = span.parent_callsite();
+                    }
+                }
+                for index in block_regions {
+                    let region = &mut regions[index];
+                    region.blocks = region.blocks.strict_add(1);
+                    if count > 0 {
+                        region.executed_blocks = region.executed_blocks.strict_add(1);
+                    }
+                }
+            }
+        }
+
+        regions.sort_by(|a, b| (&a.file, a.start, a.end).cmp(&(&b.file, b.start, b.end)));
+        regions
+    }
+
+    fn executed_count(&self, def_id: LocalDefId, block: mir::BasicBlock) -> u64 {
+        self.executed.get(&(def_id.to_def_id(), block)).copied().unwrap_or(0)
+    }
+}
+
+/// How the `unsafe` regions are nested, to quickly find the regions containing a span. Regions
+/// are written in the source, so two of them are either disjoint or one contains the other.
+struct RegionNesting {
+    /// The indices of the regions, ordered by where they start, outer regions first.
+    order: Vec<usize>,
+    /// For each region, the innermost other region containing it.
+    parent: Vec<Option<usize>>,
+}
+
+impl RegionNesting {
+    fn new(regions: &[UnsafeRegion]) -> Self {
+        let mut order: Vec<_> = (0..regions.len()).collect();
+        order.sort_by_key(|&index| (regions[index].span.lo(), Reverse(regions[index].span.hi())));
+        let mut parent = vec![None; regions.len()];
+        let mut open: Vec<usize> = Vec::new();
+        for &index in &order {
+            while open
+                .last()
+                .is_some_and(|&outer| !regions[outer].span.contains(regions[index].span))
+            {
+                open.pop();
+            }
+            parent[index] = open.last().copied();
+            open.push(index);
+        }
+        RegionNesting { order, parent }
+    }
+
+    /// The indices of the regions containing `span`, innermost first.
+    fn containing(&self, regions: &[UnsafeRegion], span: Span) -> impl Iterator<Item = usize> + '_ {
+        // The last region starting before `span` is either inside of the innermost region
+        // containing `span`, or that region itself.
+        let starting_before =
+            self.order.partition_point(|&index| regions[index].span.lo() <= span.lo());
+        let mut index = starting_before.checked_sub(1).map(|position| self.order[position]);
+        while let Some(outer) = index.filter(|&outer| !regions[outer].span.contains(span)) {
+            index = self.parent[outer];
+        }
+        iter::successors(index, |&index| self.parent[index])
+    }
+}
+
+/// Collects the spans of the `unsafe` blocks written in a body.
+struct UnsafeBlockFinder {
+    blocks: Vec<Span>,
+}
+
+impl<'tcx> Visitor<'tcx> for UnsafeBlockFinder {
+    fn visit_block(&mut self, block: &'tcx hir::Block<'tcx>) {
+        if block.rules == BlockCheckMode::UnsafeBlock(UnsafeSource::UserProvided)
+            && !block.span.from_expansion()
+        {
+            self.blocks.push(block.span);
+        }
+        intravisit::walk_block(self, block);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RegionKind {
+    UnsafeBlock,
+    UnsafeFn,
+}
+
+impl RegionKind {
+    fn name(self) -> &'static str {
+        match self {
+            RegionKind::UnsafeBlock => "unsafe block",
+            RegionKind::UnsafeFn => "unsafe fn",
+        }
+    }
+}
+
+/// The coverage of an `unsafe` block or `unsafe fn` body.
+#[derive(Debug)]
+struct UnsafeRegion {
+    kind: RegionKind,
+    /// The path of the function the region is part of.
+    function: String,
+    file: String,
+    /// The (1-based) line and column where the region starts.
+    start: (usize, usize),
+    /// The (1-based) line and column where the region ends.
+    end: (usize, usize),
+    span: Span,
+    /// The number of basic blocks in the region.
+    blocks: u64,
+    /// The number of basic blocks in the region that were executed.
+    executed_blocks: u64,
+    /// For each line of the region that has code on it, how often the most frequently executed
+    /// basic block with code on that line was executed.
+    lines: BTreeMap<usize, u64>,
+}
+
+impl UnsafeRegion {
+    /// How often the region was executed, i.e., how often its most frequently executed line was
+    /// executed.
+    fn count(&self) -> u64 {
+        self.lines.values().copied().max().unwrap_or(0)
+    }
+
+    fn name(&self) -> String {
+        format!("{} in {} at {}:{}", self.kind.name(), self.function, self.start.0, self.start.1)
+    }
+}
+
+/// Writes the report in the lcov tracefile format. Each region is reported as a function, and the
+/// lines of all regions are reported as lines.
+fn to_lcov(regions: &[UnsafeRegion]) -> String {
+    let mut out = String::from("TN:\n");
+    for file_regions in regions.chunk_by(|a, b| a.file == b.file) {
+        writeln!(out, "SF:{}", file_regions[0].file).unwrap();
+        for region in file_regions {
+            writeln!(out, "FN:{},{}", region.start.0, region.name()).unwrap();
+        }
+        for region in file_regions {
+            writeln!(out, "FNDA:{},{}", region.count(), region.name()).unwrap();
+        }
+        let hit = file_regions.iter().filter(|region| region.count() > 0).count();
+        writeln!(out, "FNF:{}", file_regions.len()).unwrap();
+        writeln!(out, "FNH:{hit}").unwrap();
+        // Regions can be nested, so merge their lines.
+        let mut lines = BTreeMap::<usize, u64>::new();
+        for (&line, &count) in file_regions.iter().flat_map(|region| &region.lines) {
+            let line_count = lines.entry(line).or_default();
+            *line_count = (*line_count).max(count);
+        }
+        for (line, count) in &lines {
+            writeln!(out, "DA:{line},{count}").unwrap();
+        }
+        writeln!(out, "LF:{}", lines.len()).unwrap();
+        writeln!(out, "LH:{}", lines.values().filter(|&&count| count > 0).count()).unwrap();
+        out.push_str("end_of_record\n");
+    }
+    out
+}
+
+/// Writes the report as a JSON object with a single field `regions`, which lists all regions.
+fn to_json(regions: &[UnsafeRegion]) -> String {
+    let mut out = String::from("{\"regions\":[");
+    for (i, region) in regions.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("\n{\"kind\":");
+        write_json_string(&mut out, region.kind.name());
+        out.push_str(",\"function\":");
+        write_json_string(&mut out, &region.function);
+        out.push_str(",\"file\":");
+        write_json_string(&mut out, &region.file);
+        write!(
+            out,
+            ",\"start_line\":{},\"start_column\":{},\"end_line\":{},\"end_column\":{}",
+            region.start.0, region.start.1, region.end.0, region.end.1
+        )
+        .unwrap();
+        write!(
+            out,
+            ",\"count\":{},\"blocks\":{},\"executed_blocks\":{},\"lines\":[",
+            region.count(),
+            region.blocks,
+            region.executed_blocks
+        )
+        .unwrap();
+        for (j, (line, count)) in region.lines.iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            write!(out, "{{\"line\":{line},\"count\":{count}}}").unwrap();
+        }
+        out.push_str("]}");
+    }
+    out.push_str("\n]}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_span::DUMMY_SP;
+
+    use super::*;
+
+    fn region(kind: RegionKind, start: usize, lines: &[(usize, u64)]) -> UnsafeRegion {
+        UnsafeRegion {
+            kind,
+            function: "demo::f".to_string(),
+            file: "src/lib.rs".to_string(),
+            start: (start, 5),
+            end: (start.strict_add(2), 6),
+            span: DUMMY_SP,
+            blocks: 2,
+            executed_blocks: lines.iter().filter(|(_, count)| *count > 0).count() as u64,
+            lines: lines.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn lcov() {
+        let regions = [
+            region(RegionKind::UnsafeFn, 3, &[(4, 2), (5, 2)]),
+            region(RegionKind::UnsafeBlock, 5, &[(5, 1), (6, 0)]),
+        ];
+        assert_eq!(
+            to_lcov(&regions),
+            "TN:\nSF:src/lib.rs\n\
+             FN:3,unsafe fn in demo::f at 3:5\nFN:5,unsafe block in demo::f at 5:5\n\
+             FNDA:2,unsafe fn in demo::f at 3:5\nFNDA:1,unsafe block in demo::f at 5:5\n\
+             FNF:2\nFNH:2\nDA:4,2\nDA:5,2\nDA:6,0\nLF:3\nLH:2\nend_of_record\n"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let mut unexecuted = region(RegionKind::UnsafeBlock, 1, &[(2, 0)]);
+        unexecuted.function = "demo::\"quoted\"".to_string();
+        assert_eq!(
+            to_json(&[unexecuted]),
+            "{\"regions\":[\n{\"kind\":\"unsafe block\",\"function\":\"demo::\\\"quoted\\\"\",\
+             \"file\":\"src/lib.rs\",\"start_line\":1,\"start_column\":5,\"end_line\":3,\
+             \"end_column\":6,\"count\":0,\"blocks\":2,\"executed_blocks\":0,\
+             \"lines\":[{\"line\":2,\"count\":0}]}\n]}\n"
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//@compile-flags: -Zmiri-explore
+//@error-in-other-file: deadlock
+//@error-in-other-file: deadlock
+
+// Two threads that take the same locks in opposite order. The default schedule runs the main
+// thread's critical section to completion before the other thread gets to run, so only
+// exploring other schedules finds the deadlock.
+
+use std::sync::Mutex;
+use std::thread;
+
+static A: Mutex<()> = Mutex::new(());
+static B: Mutex<()> = Mutex::new(());
+
+fn main() {
+    let t = thread::spawn(|| {
+        let _b = B.lock().unwrap();
+        let _a = A.lock().unwrap();
+    });
+    {
+        let _a = A.lock().unwrap();
+        let _b = B.lock().unwrap();
+    }
+    t.join().unwrap();
+}
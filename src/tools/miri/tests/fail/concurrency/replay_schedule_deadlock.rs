@@ -0,0 +1,23 @@
+//@compile-flags: -Zmiri-replay-schedule=1:1
+//@error-in-other-file: deadlock
+//@error-in-other-file: deadlock
+
+// Replay the schedule that `-Zmiri-explore` reports for the deadlock in `explore_deadlock.rs`.
+
+use std::sync::Mutex;
+use std::thread;
+
+static A: Mutex<()> = Mutex::new(());
+static B: Mutex<()> = Mutex::new(());
+
+fn main() {
+    let t = thread::spawn(|| {
+        let _b = B.lock().unwrap();
+        let _a = A.lock().unwrap();
+    });
+    {
+        let _a = A.lock().unwrap();
+        let _b = B.lock().unwrap();
+    }
+    t.join().unwrap();
+}
@@ -0,0 +1,13 @@
+//@normalize-stderr-test: ".*│.*" -> "$$stripped$$"
+
+fn make() -> Box<Box<i32>> {
+    Box::new(Box::new(42))
+    //~^ ERROR: memory leaked: 3 allocations
+    //~| ERROR: memory leaked: 3 allocations
+}
+
+fn main() {
+    for _ in 0..3 {
+        std::mem::forget(make());
+    }
+}
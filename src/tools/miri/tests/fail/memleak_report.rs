@@ -0,0 +1,15 @@
+//@compile-flags: -Zmiri-leak-report=-
+//@normalize-stdout-test: "alloc[0-9]+" -> "ALLOC"
+//@normalize-stderr-test: ".*│.*" -> "$$stripped$$"
+
+fn make() -> Box<Box<i32>> {
+    Box::new(Box::new(42))
+    //~^ ERROR: memory leaked: 2 allocations
+    //~| ERROR: memory leaked: 2 allocations
+}
+
+fn main() {
+    for _ in 0..2 {
+        std::mem::forget(make());
+    }
+}
@@ -0,0 +1,14 @@
+//@compile-flags: -Zmiri-leak-report=-
+//@normalize-stdout-test: "alloc[0-9]+" -> "ALLOC"
+
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+static CACHE: AtomicPtr<[u64; 3]> = AtomicPtr::new(ptr::null_mut());
+
+// The execution stops with an error, so there is no leak check, but the report is still written.
+fn main() {
+    CACHE.store(Box::into_raw(Box::new([1, 2, 3])), Ordering::SeqCst);
+    std::mem::forget(Box::new(0u8));
+    unsafe { ptr::null::<i32>().read() }; //~ ERROR: null pointer
+}
@@ -0,0 +1,14 @@
+//@compile-flags: -Zmiri-leak-report=-
+//@normalize-stdout-test: "alloc[0-9]+" -> "ALLOC"
+//@normalize-stderr-test: ".*│.*" -> "$$stripped$$"
+
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+// Memory that is reachable from a static is not a leak, but it is listed in the report.
+static CACHE: AtomicPtr<[u64; 3]> = AtomicPtr::new(ptr::null_mut());
+
+fn main() {
+    CACHE.store(Box::into_raw(Box::new([1, 2, 3])), Ordering::SeqCst);
+    std::mem::forget(Box::new(0u8)); //~ ERROR: memory leaked
+}
@@ -0,0 +1,25 @@
+//@compile-flags: -Zmiri-explore
+
+// Both threads read the counter and then write back the incremented value. The update of one
+// thread is lost if the other one runs between the read and the write, which the default
+// schedule never does.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn main() {
+    let threads: Vec<_> = (0..2)
+        .map(|_| {
+            thread::spawn(|| {
+                let value = COUNTER.load(Ordering::SeqCst);
+                COUNTER.store(value + 1, Ordering::SeqCst);
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(COUNTER.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,321 @@
+//@only-target: linux # uses accept4, SOCK_NONBLOCK and epoll
+// test_blocking_accept depends on a deterministic schedule.
+//@compile-flags: -Zmiri-preemption-rate=0
+
+use std::mem::{size_of, zeroed};
+use std::thread;
+
+fn main() {
+    test_socket_errors();
+    test_bind_and_getsockname();
+    test_connect_accept();
+    test_blocking_accept();
+    test_socket_options();
+    test_epoll_readiness();
+    test_udp_sendto_recvfrom();
+}
+
+fn errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap()
+}
+
+fn loopback(port: u16) -> libc::sockaddr_in {
+    let mut addr: libc::sockaddr_in = unsafe { zeroed() };
+    addr.sin_family = libc::AF_INET as libc::sa_family_t;
+    addr.sin_port = port.to_be();
+    addr.sin_addr.s_addr = u32::from_be_bytes([127, 0, 0, 1]).to_be();
+    addr
+}
+
+fn new_socket(ty: i32) -> i32 {
+    let fd = unsafe { libc::socket(libc::AF_INET, ty, 0) };
+    assert!(fd >= 0);
+    fd
+}
+
+fn bind(fd: i32, addr: &libc::sockaddr_in) -> i32 {
+    unsafe {
+        libc::bind(
+            fd,
+            (addr as *const libc::sockaddr_in).cast(),
+            size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    }
+}
+
+fn connect(fd: i32, addr: &libc::sockaddr_in) -> i32 {
+    unsafe {
+        libc::connect(
+            fd,
+            (addr as *const libc::sockaddr_in).cast(),
+            size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    }
+}
+
+fn local_addr(fd: i32) -> libc::sockaddr_in {
+    let mut addr: libc::sockaddr_in = unsafe { zeroed() };
+    let mut len = size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    let res = unsafe { libc::getsockname(fd, (&raw mut addr).cast(), &mut len) };
+    assert_eq!(res, 0);
+    assert_eq!(len as usize, size_of::<libc::sockaddr_in>());
+    addr
+}
+
+/// Returns a listening socket and its address.
+fn listener() -> (i32, libc::sockaddr_in) {
+    let fd = new_socket(libc::SOCK_STREAM);
+    assert_eq!(bind(fd, &loopback(0)), 0);
+    assert_eq!(unsafe { libc::listen(fd, 16) }, 0);
+    (fd, local_addr(fd))
+}
+
+fn test_socket_errors() {
+    let fd = new_socket(libc::SOCK_STREAM);
+    let mut buf = [0u8; 4];
+    // Reading from or writing to an unconnected stream socket fails.
+    let res = unsafe { libc::recv(fd, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, -1);
+    assert_eq!(errno(), libc::ENOTCONN);
+    let res = unsafe { libc::send(fd, buf.as_ptr().cast(), buf.len(), libc::MSG_NOSIGNAL) };
+    assert_eq!(res, -1);
+    assert_eq!(errno(), libc::ENOTCONN);
+    // Nobody is listening.
+    assert_eq!(connect(fd, &loopback(1)), -1);
+    assert_eq!(errno(), libc::ECONNREFUSED);
+    // Socket functions only work on sockets.
+    let mut fds = [-1, -1];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    assert_eq!(unsafe { libc::listen(fds[0], 1) }, -1);
+    assert_eq!(errno(), libc::ENOTSOCK);
+    assert_eq!(unsafe { libc::listen(-1, 1) }, -1);
+    assert_eq!(errno(), libc::EBADF);
+}
+
+fn test_bind_and_getsockname() {
+    let fd = new_socket(libc::SOCK_STREAM);
+    // An unbound socket reports the unspecified address.
+    let addr = local_addr(fd);
+    assert_eq!(addr.sin_family, libc::AF_INET as libc::sa_family_t);
+    assert_eq!(addr.sin_port, 0);
+    assert_eq!(addr.sin_addr.s_addr, 0);
+
+    let mut addr = loopback(0);
+    addr.sin_addr.s_addr = u32::from_be_bytes([10, 0, 0, 1]).to_be();
+    assert_eq!(bind(fd, &addr), -1);
+    assert_eq!(errno(), libc::EADDRNOTAVAIL);
+
+    assert_eq!(bind(fd, &loopback(0)), 0);
+    let addr = local_addr(fd);
+    assert_ne!(addr.sin_port, 0);
+    assert_eq!(addr.sin_addr.s_addr, loopback(0).sin_addr.s_addr);
+    // The address is truncated to the size of a smaller buffer, and its full size is returned.
+    let mut buf = [0xffu8; 8];
+    let mut len = 4;
+    let res = unsafe { libc::getsockname(fd, buf.as_mut_ptr().cast(), &mut len) };
+    assert_eq!(res, 0);
+    assert_eq!(len as usize, size_of::<libc::sockaddr_in>());
+    assert_eq!(buf[..2], (libc::AF_INET as libc::sa_family_t).to_ne_bytes());
+    assert_eq!(buf[2..4], addr.sin_port.to_ne_bytes());
+    assert_eq!(buf[4..], [0xff; 4]);
+    // A socket can only be bound once.
+    assert_eq!(bind(fd, &loopback(0)), -1);
+    assert_eq!(errno(), libc::EINVAL);
+
+    let other = new_socket(libc::SOCK_STREAM);
+    assert_eq!(bind(other, &addr), -1);
+    assert_eq!(errno(), libc::EADDRINUSE);
+    // The address becomes available again once the socket is closed.
+    assert_eq!(unsafe { libc::close(fd) }, 0);
+    assert_eq!(bind(other, &addr), 0);
+}
+
+fn test_connect_accept() {
+    let (listener, addr) = listener();
+    let client = new_socket(libc::SOCK_STREAM | libc::SOCK_NONBLOCK);
+    assert_eq!(connect(client, &addr), 0);
+    assert_eq!(connect(client, &addr), -1);
+    assert_eq!(errno(), libc::EISCONN);
+
+    let mut peer: libc::sockaddr_in = unsafe { zeroed() };
+    let mut len = size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    let server =
+        unsafe { libc::accept4(listener, (&raw mut peer).cast(), &mut len, libc::SOCK_NONBLOCK) };
+    assert!(server >= 0);
+    assert_eq!(peer.sin_port, local_addr(client).sin_port);
+
+    let mut buf = [0u8; 8];
+    // Both ends are non-blocking.
+    let res = unsafe { libc::recv(server, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, -1);
+    assert_eq!(errno(), libc::EAGAIN);
+
+    // `read` and `write` work on sockets, too.
+    let res = unsafe { libc::write(client, b"abcdef".as_ptr().cast(), 6) };
+    assert_eq!(res, 6);
+    let res = unsafe { libc::recv(server, buf.as_mut_ptr().cast(), 3, libc::MSG_PEEK) };
+    assert_eq!(res, 3);
+    assert_eq!(&buf[..3], b"abc");
+    let res = unsafe { libc::read(server, buf.as_mut_ptr().cast(), buf.len()) };
+    assert_eq!(res, 6);
+    assert_eq!(&buf[..6], b"abcdef");
+
+    // After closing one end, the other one reads EOF and cannot write anymore.
+    assert_eq!(unsafe { libc::close(server) }, 0);
+    let res = unsafe { libc::recv(client, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, 0);
+    let res = unsafe { libc::send(client, buf.as_ptr().cast(), buf.len(), libc::MSG_NOSIGNAL) };
+    assert_eq!(res, -1);
+    assert_eq!(errno(), libc::EPIPE);
+}
+
+fn test_blocking_accept() {
+    let (listener, addr) = listener();
+    let server = thread::spawn(move || {
+        // This blocks until the main thread connects.
+        let fd = unsafe { libc::accept(listener, std::ptr::null_mut(), std::ptr::null_mut()) };
+        assert!(fd >= 0);
+        let mut buf = [0u8; 5];
+        // This blocks until the main thread writes.
+        let res = unsafe { libc::recv(fd, buf.as_mut_ptr().cast(), buf.len(), 0) };
+        assert_eq!(res, 5);
+        assert_eq!(&buf, b"hello");
+    });
+    thread::yield_now();
+    let client = new_socket(libc::SOCK_STREAM);
+    assert_eq!(connect(client, &addr), 0);
+    thread::yield_now();
+    let res = unsafe { libc::send(client, b"hello".as_ptr().cast(), 5, 0) };
+    assert_eq!(res, 5);
+    server.join().unwrap();
+}
+
+fn test_socket_options() {
+    let fd = new_socket(libc::SOCK_STREAM);
+    let one: libc::c_int = 1;
+    let res = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_REUSEADDR,
+            (&raw const one).cast(),
+            size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    assert_eq!(res, 0);
+
+    let get = |level, name| {
+        let mut value: libc::c_int = -1;
+        let mut len = size_of::<libc::c_int>() as libc::socklen_t;
+        let res = unsafe { libc::getsockopt(fd, level, name, (&raw mut value).cast(), &mut len) };
+        assert_eq!(res, 0);
+        value
+    };
+    assert_eq!(get(libc::SOL_SOCKET, libc::SO_REUSEADDR), 1);
+    assert_eq!(get(libc::IPPROTO_TCP, libc::TCP_NODELAY), 0);
+    assert_eq!(get(libc::SOL_SOCKET, libc::SO_ERROR), 0);
+    assert_eq!(get(libc::SOL_SOCKET, libc::SO_TYPE), libc::SOCK_STREAM);
+
+    // `ioctl(FIONBIO)` makes the socket non-blocking.
+    let (listener, _) = listener();
+    let mut on: libc::c_int = 1;
+    assert_eq!(unsafe { libc::ioctl(listener, libc::FIONBIO, &mut on) }, 0);
+    let res = unsafe { libc::accept(listener, std::ptr::null_mut(), std::ptr::null_mut()) };
+    assert_eq!(res, -1);
+    assert_eq!(errno(), libc::EWOULDBLOCK);
+}
+
+fn test_epoll_readiness() {
+    fn wait(epfd: i32) -> Vec<(u32, u64)> {
+        let mut events = [libc::epoll_event { events: 0, u64: 0 }; 8];
+        let n = unsafe { libc::epoll_wait(epfd, events.as_mut_ptr(), 8, 0) };
+        assert!(n >= 0);
+        events[..n as usize].iter().map(|e| (e.events, e.u64)).collect()
+    }
+    fn add(epfd: i32, fd: i32) {
+        let mut ev = libc::epoll_event {
+            events: (libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLRDHUP | libc::EPOLLET) as u32,
+            u64: fd as u64,
+        };
+        assert_eq!(unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, &mut ev) }, 0);
+    }
+
+    let epfd = unsafe { libc::epoll_create1(0) };
+    let (listener, addr) = listener();
+    add(epfd, listener);
+    assert_eq!(wait(epfd), vec![]);
+
+    // A pending connection makes the listener readable.
+    let client = new_socket(libc::SOCK_STREAM | libc::SOCK_NONBLOCK);
+    assert_eq!(connect(client, &addr), 0);
+    assert_eq!(wait(epfd), vec![(libc::EPOLLIN as u32, listener as u64)]);
+    let server = unsafe {
+        libc::accept4(listener, std::ptr::null_mut(), std::ptr::null_mut(), libc::SOCK_NONBLOCK)
+    };
+    assert!(server >= 0);
+
+    // A connected socket is writable.
+    add(epfd, server);
+    assert_eq!(wait(epfd), vec![(libc::EPOLLOUT as u32, server as u64)]);
+
+    // Incoming data makes it readable.
+    let res = unsafe { libc::send(client, b"x".as_ptr().cast(), 1, 0) };
+    assert_eq!(res, 1);
+    assert_eq!(wait(epfd), vec![((libc::EPOLLIN | libc::EPOLLOUT) as u32, server as u64)]);
+
+    // Shutting down the peer's writing side is reported as EPOLLRDHUP.
+    assert_eq!(unsafe { libc::shutdown(client, libc::SHUT_WR) }, 0);
+    assert_eq!(wait(epfd), vec![(
+        (libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLRDHUP) as u32,
+        server as u64
+    )]);
+
+    // Closing the peer is reported as EPOLLHUP.
+    assert_eq!(unsafe { libc::close(client) }, 0);
+    assert_eq!(wait(epfd), vec![(
+        (libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLRDHUP | libc::EPOLLHUP) as u32,
+        server as u64
+    )]);
+}
+
+fn test_udp_sendto_recvfrom() {
+    let a = new_socket(libc::SOCK_DGRAM);
+    let b = new_socket(libc::SOCK_DGRAM | libc::SOCK_NONBLOCK);
+    assert_eq!(bind(b, &loopback(0)), 0);
+    let b_addr = local_addr(b);
+
+    let mut buf = [0u8; 8];
+    let res = unsafe { libc::recv(b, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, -1);
+    assert_eq!(errno(), libc::EAGAIN);
+
+    // Sending without a destination fails.
+    let res = unsafe { libc::send(a, b"x".as_ptr().cast(), 1, 0) };
+    assert_eq!(res, -1);
+    assert_eq!(errno(), libc::EDESTADDRREQ);
+
+    // `a` is bound implicitly by sending.
+    let res = unsafe {
+        libc::sendto(
+            a,
+            b"datagram".as_ptr().cast(),
+            8,
+            0,
+            (&raw const b_addr).cast(),
+            size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    };
+    assert_eq!(res, 8);
+    let a_addr = local_addr(a);
+    assert_ne!(a_addr.sin_port, 0);
+
+    let mut from: libc::sockaddr_in = unsafe { zeroed() };
+    let mut len = size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    let res = unsafe {
+        libc::recvfrom(b, buf.as_mut_ptr().cast(), buf.len(), 0, (&raw mut from).cast(), &mut len)
+    };
+    assert_eq!(res, 8);
+    assert_eq!(&buf, b"datagram");
+    assert_eq!(from.sin_port, a_addr.sin_port);
+    assert_eq!(from.sin_addr.s_addr, loopback(0).sin_addr.s_addr);
+}
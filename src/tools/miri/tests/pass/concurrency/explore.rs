@@ -0,0 +1,20 @@
+//@compile-flags: -Zmiri-explore -Zmiri-explore-preemption-bound=0
+
+use std::sync::Mutex;
+use std::thread;
+
+static COUNTER: Mutex<usize> = Mutex::new(0);
+
+fn main() {
+    let threads: Vec<_> = (0..2)
+        .map(|_| {
+            thread::spawn(|| {
+                *COUNTER.lock().unwrap() += 1;
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(*COUNTER.lock().unwrap(), 2);
+}
@@ -0,0 +1,9 @@
+//@compile-flags: -Zmiri-replay=tests/pass/shims/env-replay.log -Zmiri-env-forward=MIRI_ENV_VAR_TEST
+
+use std::env;
+
+fn main() {
+    // The environment is replaced by the recorded one as a whole.
+    assert_eq!(env::var("MIRI_ENV_VAR_TEST"), Ok("recorded".to_owned()));
+    assert_eq!(env::var("MIRI_REPLAYED"), Ok("1".to_owned()));
+}
@@ -0,0 +1,20 @@
+//@ignore-target: windows # File handling is not implemented yet
+//@compile-flags: -Zmiri-disable-isolation -Zmiri-replay=tests/pass/shims/fs-replay.log
+
+use std::fs;
+use std::io::Read;
+
+#[path = "../../utils/mod.rs"]
+mod utils;
+
+fn main() {
+    let path = utils::prepare_with_content("miri_test_fs_replay.txt", b"live contents");
+    let mut file = fs::File::open(&path).unwrap();
+    // Each read returns the recorded bytes, not the ones in the file.
+    let mut buf = [0; 8];
+    assert_eq!(file.read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+    assert_eq!(file.read(&mut buf).unwrap(), 0);
+    drop(file);
+    fs::remove_file(&path).unwrap();
+}
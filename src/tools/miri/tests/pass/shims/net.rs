@@ -0,0 +1,229 @@
+//@ignore-target: windows # No socket support on Windows
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    test_tcp_echo();
+    test_tcp_addrs();
+    test_tcp_shutdown();
+    test_tcp_nonblocking();
+    test_tcp_read_timeout();
+    test_tcp_close_with_unread_data();
+    test_connection_refused();
+    test_addr_in_use();
+    test_addr_not_available();
+    test_udp();
+    test_udp_connected();
+    test_ipv6();
+}
+
+fn test_tcp_echo() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0; 5];
+        stream.read_exact(&mut buf).unwrap();
+        stream.write_all(&buf).unwrap();
+        // Read until the client closes the connection.
+        assert_eq!(stream.read(&mut buf).unwrap(), 0);
+    });
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.set_nodelay(true).unwrap();
+    assert!(stream.nodelay().unwrap());
+    stream.write_all(b"hello").unwrap();
+    let mut buf = [0; 5];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+    drop(stream);
+    server.join().unwrap();
+}
+
+fn test_tcp_addrs() {
+    let listener = TcpListener::bind("0.0.0.0:0").unwrap();
+    let listen_addr = listener.local_addr().unwrap();
+    assert!(listen_addr.ip().is_unspecified());
+    assert_ne!(listen_addr.port(), 0);
+
+    let client = TcpStream::connect((Ipv4Addr::LOCALHOST, listen_addr.port())).unwrap();
+    let (server, peer_addr) = listener.accept().unwrap();
+    assert_eq!(client.local_addr().unwrap(), peer_addr);
+    assert_eq!(server.peer_addr().unwrap(), peer_addr);
+    assert_eq!(client.peer_addr().unwrap(), server.local_addr().unwrap());
+    assert_eq!(server.local_addr().unwrap().ip(), Ipv4Addr::LOCALHOST);
+    assert_eq!(server.local_addr().unwrap().port(), listen_addr.port());
+    assert_eq!(client.take_error().unwrap().map(|e| e.kind()), None);
+}
+
+fn test_tcp_shutdown() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+
+    client.write_all(b"abc").unwrap();
+    client.shutdown(Shutdown::Write).unwrap();
+    // Writing after shutdown fails.
+    assert_eq!(client.write(b"d").unwrap_err().kind(), ErrorKind::BrokenPipe);
+    // The server can still read the data that was sent before the shutdown, and then sees EOF.
+    let mut buf = Vec::new();
+    server.read_to_end(&mut buf).unwrap();
+    assert_eq!(buf, b"abc");
+    // The other direction still works.
+    server.write_all(b"xyz").unwrap();
+    let mut buf = [0; 3];
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"xyz");
+}
+
+fn test_tcp_nonblocking() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    assert_eq!(listener.accept().unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    server.set_nonblocking(true).unwrap();
+    let mut buf = [0; 4];
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    client.write_all(b"ping").unwrap();
+    assert_eq!(server.peek(&mut buf).unwrap(), 4);
+    assert_eq!(server.read(&mut buf).unwrap(), 4);
+    assert_eq!(&buf, b"ping");
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+fn test_tcp_read_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let _client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+
+    let timeout = Duration::from_millis(100);
+    server.set_read_timeout(Some(timeout)).unwrap();
+    assert_eq!(server.read_timeout().unwrap(), Some(timeout));
+    let mut buf = [0; 1];
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+fn test_tcp_close_with_unread_data() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+
+    // Closing a socket with data that was never read resets the connection.
+    client.write_all(b"lost").unwrap();
+    drop(server);
+    let mut buf = [0; 1];
+    assert_eq!(client.read(&mut buf).unwrap_err().kind(), ErrorKind::ConnectionReset);
+    assert_eq!(client.read(&mut buf).unwrap(), 0);
+}
+
+fn test_connection_refused() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    assert_eq!(TcpStream::connect(addr).unwrap_err().kind(), ErrorKind::ConnectionRefused);
+}
+
+fn test_addr_in_use() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_eq!(TcpListener::bind(addr).unwrap_err().kind(), ErrorKind::AddrInUse);
+    assert_eq!(
+        TcpListener::bind((Ipv4Addr::UNSPECIFIED, addr.port())).unwrap_err().kind(),
+        ErrorKind::AddrInUse
+    );
+    // UDP and TCP ports are independent.
+    UdpSocket::bind(addr).unwrap();
+    // Once the listener is closed, the address can be used again.
+    drop(listener);
+    TcpListener::bind(addr).unwrap();
+}
+
+fn test_addr_not_available() {
+    // The loopback interface is the only network interface.
+    assert_eq!(TcpListener::bind("192.0.2.1:0").unwrap_err().kind(), ErrorKind::AddrNotAvailable);
+}
+
+fn test_udp() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+    let b_addr = b.local_addr().unwrap();
+
+    assert_eq!(a.send_to(b"first", b_addr).unwrap(), 5);
+    assert_eq!(a.send_to(b"second", b_addr).unwrap(), 6);
+    let mut buf = [0; 16];
+    // Datagram boundaries are preserved.
+    assert_eq!(b.peek_from(&mut buf).unwrap(), (5, a_addr));
+    assert_eq!(b.recv_from(&mut buf).unwrap(), (5, a_addr));
+    assert_eq!(&buf[..5], b"first");
+    // The part of a datagram that does not fit into the buffer is discarded.
+    let mut small = [0; 3];
+    assert_eq!(b.recv_from(&mut small).unwrap(), (3, a_addr));
+    assert_eq!(&small, b"sec");
+
+    // Blocking receive from another thread.
+    let receiver = thread::spawn(move || {
+        let mut buf = [0; 16];
+        let (n, from) = b.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"third");
+        from
+    });
+    a.send_to(b"third", b_addr).unwrap();
+    assert_eq!(receiver.join().unwrap(), a_addr);
+
+    // Datagrams from a socket bound to the unspecified address come from the loopback address.
+    let c = UdpSocket::bind("0.0.0.0:0").unwrap();
+    c.send_to(b"x", a_addr).unwrap();
+    let (_, from) = a.recv_from(&mut buf).unwrap();
+    assert_eq!(from.ip(), Ipv4Addr::LOCALHOST);
+    assert_eq!(from.port(), c.local_addr().unwrap().port());
+
+    a.set_nonblocking(true).unwrap();
+    assert_eq!(a.recv_from(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+fn test_udp_connected() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let c = UdpSocket::bind("127.0.0.1:0").unwrap();
+    a.connect(b.local_addr().unwrap()).unwrap();
+    assert_eq!(a.peer_addr().unwrap(), b.local_addr().unwrap());
+    assert_eq!(b.peer_addr().unwrap_err().kind(), ErrorKind::NotConnected);
+
+    a.send(b"hi").unwrap();
+    let mut buf = [0; 2];
+    assert_eq!(b.recv(&mut buf).unwrap(), 2);
+    assert_eq!(&buf, b"hi");
+
+    // A connected socket only receives datagrams from its peer.
+    c.send_to(b"no", a.local_addr().unwrap()).unwrap();
+    b.send_to(b"ok", a.local_addr().unwrap()).unwrap();
+    assert_eq!(a.recv(&mut buf).unwrap(), 2);
+    assert_eq!(&buf, b"ok");
+}
+
+fn test_ipv6() {
+    let listener = TcpListener::bind("[::1]:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_eq!(addr.ip(), Ipv6Addr::LOCALHOST);
+    let mut client = TcpStream::connect(addr).unwrap();
+    let (mut server, peer_addr) = listener.accept().unwrap();
+    assert!(matches!(peer_addr, SocketAddr::V6(_)));
+    client.write_all(b"v6").unwrap();
+    let mut buf = [0; 2];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"v6");
+
+    // IPv4 and IPv6 sockets do not talk to each other.
+    assert_eq!(
+        TcpStream::connect((Ipv4Addr::LOCALHOST, addr.port())).unwrap_err().kind(),
+        ErrorKind::ConnectionRefused
+    );
+}
@@ -0,0 +1,20 @@
+//@only-target: linux
+//@compile-flags: -Zmiri-replay=tests/pass/shims/random-replay.log
+
+use std::ffi::c_void;
+
+extern "C" {
+    fn getrandom(buf: *mut c_void, buflen: usize, flags: u32) -> isize;
+}
+
+fn random<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    assert_eq!(unsafe { getrandom(bytes.as_mut_ptr().cast(), N, 0) }, N as isize);
+    bytes
+}
+
+fn main() {
+    // The bytes of each request are replayed from the log.
+    assert_eq!(random::<4>(), [0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(random::<3>(), [0x01, 0x02, 0x03]);
+}
@@ -0,0 +1,15 @@
+//@compile-flags: -Zmiri-disable-isolation -Zmiri-replay=tests/pass/shims/time-replay.log
+
+use std::time::{Duration, Instant, SystemTime};
+
+fn main() {
+    // All host clock reads are replayed from the log.
+    let now = SystemTime::now();
+    assert_eq!(
+        now.duration_since(SystemTime::UNIX_EPOCH).unwrap(),
+        Duration::new(1_000_000_000, 123)
+    );
+    let before = Instant::now();
+    let after = Instant::now();
+    assert_eq!(after - before, Duration::from_secs(1));
+}
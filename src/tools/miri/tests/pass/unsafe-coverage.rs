@@ -0,0 +1,30 @@
+//@compile-flags: -Zmiri-unsafe-coverage=-
+// The lcov report of which `unsafe` code was executed is printed after the output of the program.
+
+unsafe fn read(ptr: *const i32) -> i32 {
+    unsafe { *ptr }
+}
+
+unsafe fn clear(ptr: *mut i32) {
+    unsafe { *ptr = 0 };
+}
+
+macro_rules! deref {
+    ($ptr:expr) => {
+        *$ptr
+    };
+}
+
+fn main() {
+    let mut x = 1;
+    // Code expanded from a macro counts for the `unsafe` block it is invoked in.
+    x += unsafe { deref!(&raw const x) };
+    for _ in 0..3 {
+        x += unsafe { read(&x) };
+    }
+    if x == 0 {
+        // Never executed.
+        unsafe { clear(&mut x) };
+    }
+    println!("{x}");
+}
@@ -0,0 +1,153 @@
+//! Searches the offline search indexes written by `rustdoc --offline-search-index` from the
+//! command line, using the same query syntax as the search bar of the HTML documentation:
+//!
+//! ```text
+//! $ rustdoc-search 'Vec<T> -> usize'
+//! $ rustdoc-search --doc-dir target/doc fn:from_utf8
+//! ```
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use clap::Parser;
+use rustdoc_search_index::{Crate, FILE_NAME, ItemKind};
+
+mod query;
+mod search;
+#[cfg(test)]
+mod tests;
+
+#[derive(Parser)]
+struct Cli {
+    /// The query, like `Vec::len`, `fn:len` or `Vec<T> -> usize`
+    #[arg(required = true)]
+    query: Vec<String>,
+
+    /// A documentation directory (like `target/doc`), a crate's documentation directory,
+    /// or an index file. Can be passed multiple times.
+    #[arg(long, default_value = "target/doc")]
+    doc_dir: Vec<PathBuf>,
+
+    /// Only search the crates with these names
+    #[arg(long = "crate")]
+    crates: Vec<String>,
+
+    /// The maximum number of results to show
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+}
+
+fn main() -> Result<()> {
+    let Cli { query, doc_dir, crates: crate_filter, limit } = Cli::parse();
+
+    let mut crates = Vec::new();
+    for dir in &doc_dir {
+        load_indexes(dir, &mut crates)?;
+    }
+    if !crate_filter.is_empty() {
+        crates.retain(|krate| crate_filter.contains(&krate.name));
+    }
+    if crates.is_empty() {
+        bail!(
+            "no search index found; document your crates with \
+             `RUSTDOCFLAGS=\"-Z unstable-options --offline-search-index\" cargo doc`"
+        );
+    }
+
+    let query = query::parse(&query.join(" "), &known_types(&crates))?;
+    let results = search::search(&crates, &query);
+    for result in results.iter().take(limit) {
+        let desc = strip_html(&result.item.desc);
+        let deprecated = if result.item.deprecated { " (deprecated)" } else { "" };
+        println!("{:<8} {}{deprecated}", result.item.kind.as_str(), result.path());
+        if !desc.is_empty() {
+            println!("         {desc}");
+        }
+    }
+    if results.len() > limit {
+        println!("... and {} more results", results.len() - limit);
+    } else if results.is_empty() {
+        println!("no results");
+    }
+    Ok(())
+}
+
+/// Loads `path` if it is an index, or the indexes of the crates documented in the directory
+/// `path` otherwise.
+fn load_indexes(path: &Path, crates: &mut Vec<Crate>) -> Result<()> {
+    if path.is_file() {
+        crates.push(load_index(path)?);
+        return Ok(());
+    }
+    let own_index = path.join(FILE_NAME);
+    if own_index.is_file() {
+        crates.push(load_index(&own_index)?);
+        return Ok(());
+    }
+    let entries = fs::read_dir(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let index = entry?.path().join(FILE_NAME);
+        if index.is_file() {
+            paths.push(index);
+        }
+    }
+    // Sort to get the same order of results for equally-ranked items across runs.
+    paths.sort();
+    for index in paths {
+        crates.push(load_index(&index)?);
+    }
+    Ok(())
+}
+
+fn load_index(path: &Path) -> Result<Crate> {
+    let bytes = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    Crate::decode(&bytes).with_context(|| format!("decoding {}", path.display()))
+}
+
+/// The lowercased names of all types, used to tell generic parameters apart in queries.
+fn known_types(crates: &[Crate]) -> HashSet<String> {
+    let mut known = HashSet::new();
+    for krate in crates {
+        for path in &krate.paths {
+            known.insert(path.name.to_lowercase());
+        }
+        for item in &krate.items {
+            if matches!(
+                item.kind,
+                ItemKind::Struct
+                    | ItemKind::Enum
+                    | ItemKind::Union
+                    | ItemKind::Trait
+                    | ItemKind::TraitAlias
+                    | ItemKind::TypeAlias
+                    | ItemKind::Primitive
+                    | ItemKind::ForeignType
+            ) {
+                known.insert(item.name.to_lowercase());
+            }
+        }
+    }
+    known
+}
+
+/// Removes the inline HTML tags and entities of a search result description.
+fn strip_html(desc: &str) -> String {
+    let mut text = String::with_capacity(desc.len());
+    let mut in_tag = false;
+    for c in desc.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,238 @@
+//! Parser for the subset of rustdoc's search syntax supported by this tool.
+//!
+//! ```text
+//! query     = [kind ":"] (types "->" [types] | "->" types | types)
+//! types     = type ("," type)*
+//! type      = path ["<" arg ("," arg)* ">"] | "[" [type] "]" | "(" [types] ")"
+//!           | "&" ["mut"] type
+//! arg       = ident "=" type | type
+//! path      = ident ("::" ident)*
+//! ```
+//!
+//! A query that is a single path without generics is a search by name. Anything else is
+//! a search by type signature. Single-segment names that aren't the name of any type in the
+//! loaded indexes are treated as generic parameters, like `T` in `Vec<T> -> usize`.
+
+use std::collections::HashSet;
+
+use anyhow::{Result, bail};
+use rustdoc_search_index::ItemKind;
+
+/// The names the parser gives to the built-in type syntax, which are never generics.
+const BUILTIN_TYPES: &[&str] = &["mut", "reference", "slice", "tuple", "unit"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Query {
+    pub(crate) kind: Option<ItemKind>,
+    pub(crate) search: Search,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Search {
+    /// Search items by name, optionally qualified by part of their path.
+    Name { path: Vec<String> },
+    /// Search functions by the types in their signature. Without an `->`, `output` is `None`
+    /// and the types may appear anywhere in the signature.
+    Types { inputs: Vec<QueryType>, output: Option<Vec<QueryType>> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum QueryType {
+    Generic(String),
+    Named { path: Vec<String>, generics: Vec<QueryType>, bindings: Vec<(String, Vec<QueryType>)> },
+}
+
+impl QueryType {
+    fn named(name: &str, generics: Vec<QueryType>) -> QueryType {
+        QueryType::Named { path: vec![name.to_owned()], generics, bindings: Vec::new() }
+    }
+
+    /// Turns the names that aren't in `known_types` into generic parameters.
+    fn resolve_generics(&mut self, known_types: &HashSet<String>) {
+        let QueryType::Named { path, generics, bindings } = self else { return };
+        if let [name] = &path[..] {
+            if generics.is_empty()
+                && bindings.is_empty()
+                && !BUILTIN_TYPES.contains(&name.as_str())
+                && !known_types.contains(&name.to_lowercase())
+            {
+                *self = QueryType::Generic(name.clone());
+                return;
+            }
+        }
+        for ty in generics.iter_mut().chain(bindings.iter_mut().flat_map(|(_, tys)| tys)) {
+            ty.resolve_generics(known_types);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Ident(&'a str),
+    PathSep,
+    Arrow,
+    Colon,
+    Punct(char),
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut rest = query;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c.is_alphanumeric() || c == '_' {
+            let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            tokens.push(Token::Ident(&rest[..end]));
+            rest = &rest[end..];
+        } else if let Some(after) = rest.strip_prefix("::") {
+            tokens.push(Token::PathSep);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("->") {
+            tokens.push(Token::Arrow);
+            rest = after;
+        } else if c == ':' {
+            tokens.push(Token::Colon);
+            rest = &rest[1..];
+        } else if "<>,=[]()&".contains(c) {
+            tokens.push(Token::Punct(c));
+            rest = &rest[1..];
+        } else {
+            bail!("unexpected character `{c}` in query");
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, token: Token<'_>) -> bool {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if !self.eat(Token::Punct(c)) {
+            bail!("expected `{c}` in query");
+        }
+        Ok(())
+    }
+
+    fn ident(&mut self) -> Result<&'a str> {
+        match self.peek() {
+            Some(&Token::Ident(ident)) => {
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => bail!("expected a name in query"),
+        }
+    }
+
+    fn path(&mut self) -> Result<Vec<String>> {
+        let mut path = vec![self.ident()?.to_owned()];
+        while self.eat(Token::PathSep) {
+            path.push(self.ident()?.to_owned());
+        }
+        Ok(path)
+    }
+
+    /// Parses a comma-separated list of types, stopping before `end` or the end of the query.
+    fn types(&mut self, end: Option<Token<'_>>) -> Result<Vec<QueryType>> {
+        let mut types = Vec::new();
+        while self.peek().is_some() && self.peek() != end.as_ref() {
+            types.push(self.ty()?);
+            if !self.eat(Token::Punct(',')) {
+                break;
+            }
+        }
+        Ok(types)
+    }
+
+    fn ty(&mut self) -> Result<QueryType> {
+        if self.eat(Token::Punct('&')) {
+            let mut generics = Vec::new();
+            if self.eat(Token::Ident("mut")) {
+                generics.push(QueryType::named("mut", Vec::new()));
+            }
+            generics.push(self.ty()?);
+            return Ok(QueryType::named("reference", generics));
+        }
+        if self.eat(Token::Punct('[')) {
+            let generics = self.types(Some(Token::Punct(']')))?;
+            self.expect(']')?;
+            return Ok(QueryType::named("slice", generics));
+        }
+        if self.eat(Token::Punct('(')) {
+            let generics = self.types(Some(Token::Punct(')')))?;
+            self.expect(')')?;
+            let name = if generics.is_empty() { "unit" } else { "tuple" };
+            return Ok(QueryType::named(name, generics));
+        }
+        let path = self.path()?;
+        let mut generics = Vec::new();
+        let mut bindings = Vec::new();
+        if self.eat(Token::Punct('<')) {
+            loop {
+                if let (Some(&Token::Ident(name)), Some(Token::Punct('='))) =
+                    (self.peek(), self.tokens.get(self.pos + 1))
+                {
+                    self.pos += 2;
+                    bindings.push((name.to_owned(), vec![self.ty()?]));
+                } else {
+                    generics.push(self.ty()?);
+                }
+                if !self.eat(Token::Punct(',')) {
+                    break;
+                }
+            }
+            self.expect('>')?;
+        }
+        Ok(QueryType::Named { path, generics, bindings })
+    }
+}
+
+/// Parses `query`. `known_types` contains the lowercased names of all types in the loaded
+/// indexes, and is used to tell generic parameters apart from types.
+pub(crate) fn parse(query: &str, known_types: &HashSet<String>) -> Result<Query> {
+    let mut parser = Parser { tokens: tokenize(query)?, pos: 0 };
+
+    let mut kind = None;
+    if let (Some(&Token::Ident(name)), Some(Token::Colon)) = (parser.peek(), parser.tokens.get(1)) {
+        let Some(k) = ItemKind::from_name(name) else {
+            bail!("unknown item kind `{name}`");
+        };
+        kind = Some(k);
+        parser.pos += 2;
+    }
+
+    let mut inputs = parser.types(Some(Token::Arrow))?;
+    let mut output = if parser.eat(Token::Arrow) { Some(parser.types(None)?) } else { None };
+    if parser.pos != parser.tokens.len() {
+        bail!("unexpected `{:?}` in query", parser.tokens[parser.pos]);
+    }
+
+    if let (None, [QueryType::Named { path, generics, bindings }]) = (&output, &mut inputs[..]) {
+        if generics.is_empty() && bindings.is_empty() {
+            return Ok(Query { kind, search: Search::Name { path: std::mem::take(path) } });
+        }
+    }
+    if inputs.is_empty() && output.as_ref().is_none_or(|output| output.is_empty()) {
+        bail!("empty query");
+    }
+    for ty in inputs.iter_mut().chain(output.iter_mut().flatten()) {
+        ty.resolve_generics(known_types);
+    }
+    Ok(Query { kind, search: Search::Types { inputs, output } })
+}
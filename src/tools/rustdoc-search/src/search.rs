@@ -0,0 +1,233 @@
+//! Matching of queries against the loaded search indexes.
+//!
+//! This follows the rules of the type-based search in `search.js` in spirit, but not in every
+//! detail: a query type matches a function type with the same name whose generics match the
+//! query's generics, a query generic matches a function generic (consistently across the whole
+//! signature), and types marked `#[doc(search_unbox)]` (like references) as well as the trait
+//! bounds of generic parameters can be looked through.
+
+use std::collections::HashMap;
+
+use rustdoc_search_index::{Crate, FunctionSignature, Item, TypeId, TypeNode};
+
+use crate::query::{Query, QueryType, Search};
+
+/// How deep unboxing is allowed to go, to avoid looping on recursive trait bounds.
+const MAX_UNBOX_DEPTH: usize = 8;
+
+pub(crate) struct SearchResult<'a> {
+    pub(crate) krate: &'a Crate,
+    pub(crate) item: &'a Item,
+    /// Lower is better.
+    rank: (usize, usize),
+}
+
+impl SearchResult<'_> {
+    /// The full path of the item, including its parent for associated items.
+    pub(crate) fn path(&self) -> String {
+        let mut path = self.item.module.clone();
+        if let Some(parent) = self.item.parent {
+            path.push_str("::");
+            path.push_str(&self.krate.paths[parent as usize].name);
+        }
+        if !path.is_empty() {
+            path.push_str("::");
+        }
+        path.push_str(&self.item.name);
+        path
+    }
+}
+
+pub(crate) fn search<'a>(crates: &'a [Crate], query: &Query) -> Vec<SearchResult<'a>> {
+    let mut results = Vec::new();
+    for krate in crates {
+        for item in &krate.items {
+            if query.kind.is_some_and(|kind| kind != item.kind) {
+                continue;
+            }
+            let rank = match &query.search {
+                Search::Name { path } => rank_by_name(krate, item, path),
+                Search::Types { inputs, output } => item.signature.as_ref().and_then(|signature| {
+                    rank_by_type(krate, signature, inputs, output.as_deref())
+                }),
+            };
+            if let Some(rank) = rank {
+                results.push(SearchResult { krate, item, rank });
+            }
+        }
+    }
+    results.sort_by(|a, b| {
+        (a.rank, a.item.module.len(), &a.item.name).cmp(&(
+            b.rank,
+            b.item.module.len(),
+            &b.item.name,
+        ))
+    });
+    results
+}
+
+fn rank_by_name(krate: &Crate, item: &Item, path: &[String]) -> Option<(usize, usize)> {
+    let (name, parents) = path.split_last().unwrap();
+    let name = name.to_lowercase();
+    let item_name = item.name.to_lowercase();
+    let rank = if item_name == name {
+        0
+    } else if item.aliases.iter().any(|alias| alias.to_lowercase() == name) {
+        1
+    } else if item_name.starts_with(&name) {
+        2
+    } else if item_name.contains(&name) {
+        3
+    } else {
+        return None;
+    };
+
+    // Every other segment of the query must appear, in order, in the path of the item.
+    let mut item_path = item.module.split("::").map(str::to_lowercase).collect::<Vec<_>>();
+    if let Some(parent) = item.parent {
+        item_path.push(krate.paths[parent as usize].name.to_lowercase());
+    }
+    let mut item_path = item_path.iter();
+    for parent in parents {
+        let parent = parent.to_lowercase();
+        item_path.find(|segment| **segment == parent)?;
+    }
+    Some((rank, item_name.len().abs_diff(name.len())))
+}
+
+fn rank_by_type(
+    krate: &Crate,
+    signature: &FunctionSignature,
+    inputs: &[QueryType],
+    output: Option<&[QueryType]>,
+) -> Option<(usize, usize)> {
+    let cx = MatchCx { krate, signature };
+    let mut generics = HashMap::new();
+    let matched = match output {
+        Some(output) => {
+            cx.unify_all(inputs, &signature.inputs, &mut generics, 0)
+                && cx.unify_all(output, &signature.output, &mut generics, 0)
+        }
+        None => {
+            let all = signature.inputs.iter().chain(&signature.output).cloned().collect::<Vec<_>>();
+            cx.unify_all(inputs, &all, &mut generics, 0)
+        }
+    };
+    if !matched {
+        return None;
+    }
+    // Prefer functions whose signature doesn't have much more than what was asked for.
+    let asked = inputs.len() + output.map_or(0, |output| output.len());
+    let unasked = (signature.inputs.len() + signature.output.len()).saturating_sub(asked);
+    Some((unasked, 0))
+}
+
+struct MatchCx<'a> {
+    krate: &'a Crate,
+    signature: &'a FunctionSignature,
+}
+
+/// Maps the names of generics in the query to the generic parameters of the function.
+type GenericMap = HashMap<String, u32>;
+
+impl MatchCx<'_> {
+    /// Returns whether every type in `query` matches a distinct type in `types`,
+    /// or in the types they can be unboxed into.
+    fn unify_all(
+        &self,
+        query: &[QueryType],
+        types: &[TypeNode],
+        generics: &mut GenericMap,
+        depth: usize,
+    ) -> bool {
+        let Some((first, rest)) = query.split_first() else {
+            return true;
+        };
+        for (i, ty) in types.iter().enumerate() {
+            let mut remaining = types.to_vec();
+            remaining.remove(i);
+
+            let saved = generics.clone();
+            if self.unify(first, ty, generics, depth)
+                && self.unify_all(rest, &remaining, generics, depth)
+            {
+                return true;
+            }
+            *generics = saved.clone();
+
+            if depth < MAX_UNBOX_DEPTH {
+                let unboxed = self.unbox(ty);
+                if !unboxed.is_empty() {
+                    remaining.extend(unboxed);
+                    if self.unify_all(query, &remaining, generics, depth + 1) {
+                        return true;
+                    }
+                    *generics = saved;
+                }
+            }
+        }
+        false
+    }
+
+    fn unify(
+        &self,
+        query: &QueryType,
+        ty: &TypeNode,
+        generics: &mut GenericMap,
+        depth: usize,
+    ) -> bool {
+        match (query, ty.id) {
+            (QueryType::Generic(name), TypeId::Generic(param)) => match generics.get(name) {
+                Some(&mapped) => mapped == param,
+                None if generics.values().any(|&mapped| mapped == param) => false,
+                None => {
+                    generics.insert(name.clone(), param);
+                    true
+                }
+            },
+            (QueryType::Generic(_), _) => false,
+            (QueryType::Named { path, generics: query_generics, bindings }, TypeId::Path(id)) => {
+                let entry = &self.krate.paths[id as usize];
+                let (name, parents) = path.split_last().unwrap();
+                if !entry.name.eq_ignore_ascii_case(name) {
+                    return false;
+                }
+                let module = entry.module.to_lowercase();
+                let mut module = module.split("::");
+                if !parents.iter().all(|parent| {
+                    let parent = parent.to_lowercase();
+                    module.any(|segment| segment == parent)
+                }) {
+                    return false;
+                }
+                // Constraints on associated types can also be asked for as generics,
+                // like `Iterator<u8>` for `Iterator<Item = u8>`.
+                let mut type_generics = ty.generics.clone();
+                type_generics.extend(ty.bindings.iter().flat_map(|(_, tys)| tys.iter().cloned()));
+                if !self.unify_all(query_generics, &type_generics, generics, depth) {
+                    return false;
+                }
+                bindings.iter().all(|(assoc, query_constraints)| {
+                    ty.bindings.iter().any(|(id, constraints)| {
+                        matches!(*id, TypeId::Path(id)
+                            if self.krate.paths[id as usize].name.eq_ignore_ascii_case(assoc))
+                            && self.unify_all(query_constraints, constraints, generics, depth)
+                    })
+                })
+            }
+            (QueryType::Named { .. }, TypeId::Generic(_) | TypeId::None) => false,
+        }
+    }
+
+    /// The types that can stand in for `ty` when it doesn't match on its own.
+    fn unbox(&self, ty: &TypeNode) -> Vec<TypeNode> {
+        match ty.id {
+            TypeId::Path(id) if self.krate.paths[id as usize].search_unbox => ty.generics.clone(),
+            TypeId::Path(_) => Vec::new(),
+            TypeId::Generic(param) => {
+                self.signature.where_clause.get(param as usize).cloned().unwrap_or_default()
+            }
+            TypeId::None => ty.generics.clone(),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use rustdoc_search_index::{Crate, FunctionSignature, Item, ItemKind, Path, TypeId, TypeNode};
+
+use crate::known_types;
+use crate::query::{self, QueryType, Search};
+use crate::search::search;
+
+fn ty(id: TypeId, generics: Vec<TypeNode>) -> TypeNode {
+    TypeNode { id, generics, bindings: vec![] }
+}
+
+fn path(kind: ItemKind, name: &str, module: &str, search_unbox: bool) -> Path {
+    Path { kind, name: name.to_owned(), module: module.to_owned(), search_unbox }
+}
+
+fn item(
+    kind: ItemKind,
+    name: &str,
+    module: &str,
+    parent: Option<u32>,
+    signature: Option<FunctionSignature>,
+) -> Item {
+    Item {
+        kind,
+        name: name.to_owned(),
+        module: module.to_owned(),
+        desc: String::new(),
+        parent,
+        deprecated: false,
+        aliases: vec![],
+        signature,
+    }
+}
+
+/// A tiny `alloc`: `Vec<T>`, `Vec::len(&Vec<T>) -> usize`, `Vec::new() -> Vec<T>`
+/// and `count<I: Iterator<Item = u8>>(I) -> usize`.
+fn sample() -> Vec<Crate> {
+    const VEC: u32 = 0;
+    const USIZE: u32 = 1;
+    const REFERENCE: u32 = 2;
+    const ITERATOR: u32 = 3;
+    const ITEM: u32 = 4;
+    const U8: u32 = 5;
+
+    let vec_of_t = ty(TypeId::Path(VEC), vec![ty(TypeId::Generic(0), vec![])]);
+    let usize = ty(TypeId::Path(USIZE), vec![]);
+    let iterator_of_u8 = TypeNode {
+        id: TypeId::Path(ITERATOR),
+        generics: vec![],
+        bindings: vec![(TypeId::Path(ITEM), vec![ty(TypeId::Path(U8), vec![])])],
+    };
+    vec![Crate {
+        name: "alloc".to_owned(),
+        doc: String::new(),
+        paths: vec![
+            path(ItemKind::Struct, "Vec", "alloc::vec", false),
+            path(ItemKind::Primitive, "usize", "", false),
+            path(ItemKind::Primitive, "reference", "", true),
+            path(ItemKind::Trait, "Iterator", "core::iter", false),
+            path(ItemKind::AssocType, "Item", "", false),
+            path(ItemKind::Primitive, "u8", "", false),
+        ],
+        items: vec![
+            item(ItemKind::Struct, "Vec", "alloc::vec", None, None),
+            item(
+                ItemKind::Method,
+                "len",
+                "alloc::vec",
+                Some(VEC),
+                Some(FunctionSignature {
+                    inputs: vec![ty(TypeId::Path(REFERENCE), vec![vec_of_t.clone()])],
+                    output: vec![usize.clone()],
+                    where_clause: vec![vec![]],
+                    param_names: vec!["T".to_owned()],
+                }),
+            ),
+            item(
+                ItemKind::Method,
+                "new",
+                "alloc::vec",
+                Some(VEC),
+                Some(FunctionSignature {
+                    inputs: vec![],
+                    output: vec![vec_of_t],
+                    where_clause: vec![vec![]],
+                    param_names: vec!["T".to_owned()],
+                }),
+            ),
+            item(
+                ItemKind::Function,
+                "count",
+                "alloc",
+                None,
+                Some(FunctionSignature {
+                    inputs: vec![ty(TypeId::Generic(0), vec![])],
+                    output: vec![usize],
+                    where_clause: vec![vec![iterator_of_u8]],
+                    param_names: vec!["I".to_owned()],
+                }),
+            ),
+        ],
+    }]
+}
+
+fn run(query: &str) -> Vec<String> {
+    run_in(&sample(), query)
+}
+
+fn run_in(crates: &[Crate], query: &str) -> Vec<String> {
+    let query = query::parse(query, &known_types(crates)).unwrap();
+    search(crates, &query).iter().map(|result| result.path()).collect()
+}
+
+#[test]
+fn test_parse() {
+    let crates = sample();
+    let known = known_types(&crates);
+    let query = query::parse("fn:Vec<T>, &mut u8 -> usize", &known).unwrap();
+    assert_eq!(query.kind, Some(ItemKind::Function));
+    let named = |name: &str, generics| QueryType::Named {
+        path: vec![name.to_owned()],
+        generics,
+        bindings: vec![],
+    };
+    assert_eq!(query.search, Search::Types {
+        inputs: vec![
+            named("Vec", vec![QueryType::Generic("T".to_owned())]),
+            named("reference", vec![named("mut", vec![]), named("u8", vec![])]),
+        ],
+        output: Some(vec![named("usize", vec![])]),
+    });
+    assert_eq!(query::parse("vec::Vec", &known).unwrap().search, Search::Name {
+        path: vec!["vec".to_owned(), "Vec".to_owned()]
+    });
+    assert!(query::parse("Vec<T", &known).is_err());
+    assert!(query::parse("nonsense:Vec", &known).is_err());
+}
+
+#[test]
+fn test_search_by_name() {
+    assert_eq!(run("len"), ["alloc::vec::Vec::len"]);
+    assert_eq!(run("Vec::new"), ["alloc::vec::Vec::new"]);
+    assert_eq!(run("iter::new"), Vec::<String>::new());
+    assert_eq!(run("struct:vec"), ["alloc::vec::Vec"]);
+}
+
+#[test]
+fn test_search_by_alias() {
+    let mut crates = sample();
+    let len = crates[0].items.iter_mut().find(|item| item.name == "len").unwrap();
+    len.aliases = vec!["length".to_owned(), "size".to_owned()];
+    // Aliases longer than the name of the item.
+    assert_eq!(run_in(&crates, "length"), ["alloc::vec::Vec::len"]);
+    assert_eq!(run_in(&crates, "size"), ["alloc::vec::Vec::len"]);
+    // The exact matches of the name come before the alias matches.
+    crates[0].items[0].aliases = vec!["new".to_owned()];
+    assert_eq!(run_in(&crates, "new"), ["alloc::vec::Vec::new", "alloc::vec::Vec"]);
+}
+
+#[test]
+fn test_search_by_type() {
+    assert_eq!(run("Vec<T> -> usize"), ["alloc::vec::Vec::len"]);
+    assert_eq!(run("&Vec<T> -> usize"), ["alloc::vec::Vec::len"]);
+    assert_eq!(run("-> Vec<T>"), ["alloc::vec::Vec::new"]);
+    assert_eq!(run("Vec<u8> -> usize"), Vec::<String>::new());
+    assert_eq!(run("Iterator<Item = u8> -> usize"), ["alloc::count"]);
+    assert_eq!(run("Iterator<u8> -> usize"), ["alloc::count"]);
+    assert_eq!(run("Iterator<usize> -> usize"), Vec::<String>::new());
+    // Without an arrow, the types can be anywhere in the signature.
+    assert_eq!(run("Vec<T>"), ["alloc::vec::Vec::new", "alloc::vec::Vec::len"]);
+}
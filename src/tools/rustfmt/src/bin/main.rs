@@ -12,6 +12,7 @@
 use std::env;
 use std::fs::File;
 use std::io::{self, Read, Write, stdout};
+use std::ops;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -19,7 +20,8 @@
 
 use crate::rustfmt::{
     CliOptions, Color, Config, Edition, EmitMode, FileLines, FileName,
-    FormatReportFormatterBuilder, Input, Session, StyleEdition, Verbosity, Version, load_config,
+    FormatReportFormatterBuilder, Input, Session, StyleEdition, Verbosity, Version,
+    enclosing_item_lines, load_config,
 };
 
 const BUG_REPORT_URL: &str = "https://github.com/rust-lang/rustfmt/issues/new?labels=bug";
@@ -90,6 +92,9 @@ pub enum OperationError {
     /// supported with standard input.
     #[error("Emit mode {0} not supported with standard output.")]
     StdinBadEmit(EmitMode),
+    /// Attempt to format byte ranges of files.
+    #[error("The `--range` option only works with standard input.")]
+    RangeWithFiles,
 }
 
 impl From<IoError> for OperationError {
@@ -116,9 +121,9 @@ fn make_opts() -> Options {
     );
     let is_nightly = is_nightly();
     let emit_opts = if is_nightly {
-        "[files|stdout|coverage|checkstyle|json]"
+        "[files|stdout|edits|coverage|checkstyle|json]"
     } else {
-        "[files|stdout]"
+        "[files|stdout|edits]"
     };
     opts.optopt("", "emit", "What data to emit and how", emit_opts);
     opts.optflag("", "backup", "Backup any modified files.");
@@ -155,6 +160,13 @@ fn make_opts() -> Options {
         "Prints the names of mismatched files that were formatted. Prints the names of \
          files that would be formatted when used with `--check` mode. ",
     );
+    opts.optmulti(
+        "",
+        "range",
+        "Format only the items enclosing the given byte range of standard input, and emit \
+         the resulting edits as JSON unless `--emit` is given. Can be repeated.",
+        "START..END",
+    );
     opts.optmulti(
         "",
         "config",
@@ -278,13 +290,19 @@ fn format_string(input: String, options: GetOptsOptions) -> Result<i32> {
     } else {
         match options.emit_mode {
             // Emit modes which work with standard input
-            // None means default, which is Stdout.
+            // None means default, which is Edits for byte ranges and Stdout otherwise.
+            None if !options.byte_ranges.is_empty() => {
+                config.set().emit_mode(EmitMode::Edits);
+            }
             None => {
                 config
                     .set()
                     .emit_mode(options.emit_mode.unwrap_or(EmitMode::Stdout));
             }
-            Some(EmitMode::Stdout) | Some(EmitMode::Checkstyle) | Some(EmitMode::Json) => {
+            Some(EmitMode::Stdout)
+            | Some(EmitMode::Checkstyle)
+            | Some(EmitMode::Json)
+            | Some(EmitMode::Edits) => {
                 config
                     .set_cli()
                     .emit_mode(options.emit_mode.unwrap_or(EmitMode::Stdout));
@@ -297,7 +315,12 @@ fn format_string(input: String, options: GetOptsOptions) -> Result<i32> {
     config.set().verbose(Verbosity::Quiet);
 
     // parse file_lines
-    if options.file_lines.is_all() {
+    if !options.byte_ranges.is_empty() {
+        let ranges = enclosing_item_lines(&input, &config, &options.byte_ranges)?
+            .ok_or_else(|| format_err!("Invalid `--range`: out of bounds of the input"))?;
+        let file_lines = FileLines::from_ranges(HashMap::from([(FileName::Stdin, ranges)]));
+        config.set_cli().file_lines(file_lines);
+    } else if options.file_lines.is_all() {
         config.set().file_lines(options.file_lines);
     } else {
         config.set_cli().file_lines(options.file_lines);
@@ -505,6 +528,10 @@ fn determine_operation(matches: &Matches) -> Result<Operation, OperationError> {
         })
         .collect();
 
+    if !files.is_empty() && matches.opt_present("range") {
+        return Err(OperationError::RangeWithFiles);
+    }
+
     // if no file argument is supplied, read from stdin
     if files.is_empty() {
         if minimal_config_path.is_some() {
@@ -522,7 +549,12 @@ fn determine_operation(matches: &Matches) -> Result<Operation, OperationError> {
     })
 }
 
-const STABLE_EMIT_MODES: [EmitMode; 3] = [EmitMode::Files, EmitMode::Stdout, EmitMode::Diff];
+const STABLE_EMIT_MODES: [EmitMode; 4] = [
+    EmitMode::Files,
+    EmitMode::Stdout,
+    EmitMode::Diff,
+    EmitMode::Edits,
+];
 
 /// Parsed command line options.
 #[derive(Clone, Debug, Default)]
@@ -539,6 +571,7 @@ struct GetOptsOptions {
     style_edition: Option<StyleEdition>,
     color: Option<Color>,
     file_lines: FileLines, // Default is all lines in all files.
+    byte_ranges: Vec<ops::Range<usize>>,
     unstable_features: bool,
     error_on_unformatted: Option<bool>,
     print_misformatted_file_names: bool,
@@ -622,6 +655,12 @@ pub fn from_matches(matches: &Matches) -> Result<GetOptsOptions> {
             )
             .collect::<Result<HashMap<_, _>, _>>()?;
 
+        options.byte_ranges = matches
+            .opt_strs("range")
+            .iter()
+            .map(|range| byte_range_from_range_str(range))
+            .collect::<Result<_>>()?;
+
         options.check = matches.opt_present("check");
         if let Some(ref emit_str) = matches.opt_str("emit") {
             if options.check {
@@ -774,10 +813,21 @@ fn style_edition_from_style_edition_str(edition_str: &str) -> Result<StyleEditio
     }
 }
 
+fn byte_range_from_range_str(range_str: &str) -> Result<ops::Range<usize>> {
+    let (start, end) = range_str
+        .split_once("..")
+        .ok_or_else(|| format_err!("Invalid `--range`: expected `START..END`"))?;
+    match (start.parse(), end.parse()) {
+        (Ok(start), Ok(end)) if start <= end => Ok(start..end),
+        _ => Err(format_err!("Invalid `--range`: `{}`", range_str)),
+    }
+}
+
 fn emit_mode_from_emit_str(emit_str: &str) -> Result<EmitMode> {
     match emit_str {
         "files" => Ok(EmitMode::Files),
         "stdout" => Ok(EmitMode::Stdout),
+        "edits" => Ok(EmitMode::Edits),
         "coverage" => Ok(EmitMode::Coverage),
         "checkstyle" => Ok(EmitMode::Checkstyle),
         "json" => Ok(EmitMode::Json),
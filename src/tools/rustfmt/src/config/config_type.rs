@@ -1,5 +1,6 @@
 use crate::config::file_lines::FileLines;
-use crate::config::macro_names::MacroSelectors;
+use crate::config::import_groups::ImportGroups;
+use crate::config::macro_names::{MacroGrammars, MacroSelectors};
 use crate::config::options::{IgnoreList, WidthHeuristics};
 
 /// Trait for types that can be used in `Config`.
@@ -53,6 +54,18 @@ fn doc_hint() -> String {
     }
 }
 
+impl ConfigType for MacroGrammars {
+    fn doc_hint() -> String {
+        String::from("{<string> = <grammar>, ...}")
+    }
+}
+
+impl ConfigType for ImportGroups {
+    fn doc_hint() -> String {
+        String::from("[[<pattern>, ...], ...]")
+    }
+}
+
 impl ConfigType for WidthHeuristics {
     fn doc_hint() -> String {
         String::new()
@@ -3,7 +3,7 @@
 use itertools::Itertools;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::{cmp, fmt, iter, str};
+use std::{cmp, fmt, iter, ops, str};
 
 use rustc_data_structures::sync::Lrc;
 use rustc_span::SourceFile;
@@ -104,6 +104,27 @@ pub fn new(lo: usize, hi: usize) -> Range {
         Range { lo, hi }
     }
 
+    /// Returns the lines of `text` which the byte range `bytes` touches, or `None` if `bytes` is
+    /// out of bounds. An empty byte range touches the line it is on.
+    pub fn from_byte_range(text: &str, bytes: ops::Range<usize>) -> Option<Range> {
+        if bytes.start > bytes.end || bytes.end > text.len() {
+            return None;
+        }
+        let line_of = |offset: usize| {
+            text.as_bytes()[..offset]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count()
+                + 1
+        };
+        let last = if bytes.is_empty() {
+            bytes.start
+        } else {
+            bytes.end - 1
+        };
+        Some(Range::new(line_of(bytes.start), line_of(last)))
+    }
+
     fn is_empty(self) -> bool {
         self.lo > self.hi
     }
@@ -382,6 +403,19 @@ fn test_range_contains() {
         assert!(!Range::new(1, 2).contains(Range::new(3, 10)));
     }
 
+    #[test]
+    fn test_range_from_byte_range() {
+        let text = "fn a() {}\nfn b() {}\n\nfn c() {}\n";
+        assert_eq!(Range::from_byte_range(text, 0..0), Some(Range::new(1, 1)));
+        assert_eq!(Range::from_byte_range(text, 3..10), Some(Range::new(1, 1)));
+        assert_eq!(Range::from_byte_range(text, 3..11), Some(Range::new(1, 2)));
+        assert_eq!(Range::from_byte_range(text, 10..10), Some(Range::new(2, 2)));
+        assert_eq!(Range::from_byte_range(text, 20..31), Some(Range::new(3, 4)));
+        assert_eq!(Range::from_byte_range(text, 31..31), Some(Range::new(5, 5)));
+        assert_eq!(Range::from_byte_range(text, 31..32), None);
+        assert_eq!(Range::from_byte_range(text, 5..4), None);
+    }
+
     #[test]
     fn test_range_merge() {
         assert_eq!(None, Range::new(1, 3).merge(Range::new(5, 5)));
@@ -0,0 +1,176 @@
+//! This module contains types and functions to support user-defined import groups.
+
+use std::{fmt, str};
+
+use itertools::Itertools;
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json as json;
+use thiserror::Error;
+
+/// User-defined import groups, in order. Each group is a list of patterns matched against the
+/// paths of imports. A pattern between slashes, like `/^company_[a-z]+$/`, is a regular
+/// expression matched anywhere in the path. Any other pattern is a glob matched against the path
+/// or a leading part of it: `std` matches `std::sync::Arc`, and `company_*` matches
+/// `company_core::Id`. The pattern `*` matches the imports that no other pattern matches.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ImportGroups(Vec<Vec<String>>);
+
+impl ImportGroups {
+    fn from_raw(raw: Vec<Vec<String>>) -> Result<Self, ImportGroupsError> {
+        for pattern in raw.iter().flatten() {
+            if let Err(err) = pattern_regex(pattern) {
+                return Err(ImportGroupsError::Pattern(pattern.clone(), err));
+            }
+        }
+        Ok(Self(raw))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Compiles the patterns of the groups. The patterns have been validated when the
+    /// configuration was parsed.
+    pub(crate) fn matcher(&self) -> ImportGroupMatcher {
+        let groups = self
+            .0
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .filter(|pattern| *pattern != "*")
+                    .map(|pattern| pattern_regex(pattern).expect("invalid import group pattern"))
+                    .collect()
+            })
+            .collect();
+        let fallback = self
+            .0
+            .iter()
+            .position(|group| group.iter().any(|pattern| pattern == "*"))
+            .unwrap_or(self.0.len());
+        ImportGroupMatcher { groups, fallback }
+    }
+}
+
+/// Compiles a pattern of `import_groups` into a regular expression.
+fn pattern_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    match pattern
+        .strip_prefix('/')
+        .and_then(|pattern| pattern.strip_suffix('/'))
+    {
+        Some(regex) => Regex::new(regex),
+        None => {
+            let glob = pattern.split('*').map(regex::escape).join(".*");
+            Regex::new(&format!("^{glob}(::|$)"))
+        }
+    }
+}
+
+/// The compiled patterns of `import_groups`.
+pub(crate) struct ImportGroupMatcher {
+    groups: Vec<Vec<Regex>>,
+    /// The group of the imports that match no pattern.
+    fallback: usize,
+}
+
+impl ImportGroupMatcher {
+    /// The number of groups, including the group of unmatched imports if no group contains `*`.
+    pub(crate) fn len(&self) -> usize {
+        self.groups.len().max(self.fallback + 1)
+    }
+
+    /// The index of the first group with a pattern matching the import `path`.
+    pub(crate) fn group(&self, path: &str) -> usize {
+        self.groups
+            .iter()
+            .position(|group| group.iter().any(|regex| regex.is_match(path)))
+            .unwrap_or(self.fallback)
+    }
+}
+
+impl fmt::Display for ImportGroups {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}]",
+            self.0.iter().format_with(", ", |group, f| f(&format_args!(
+                "[{}]",
+                group.iter().format(", ")
+            )))
+        )
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ImportGroupsError {
+    #[error("{0}")]
+    Json(json::Error),
+    #[error("invalid import group pattern `{0}`: {1}")]
+    Pattern(String, regex::Error),
+}
+
+impl<'de> Deserialize<'de> for ImportGroups {
+    fn deserialize<D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = Vec::deserialize(de)?;
+        Self::from_raw(raw).map_err(serde::de::Error::custom)
+    }
+}
+
+// This impl is needed for `Config::override_value` to work for use in tests.
+impl str::FromStr for ImportGroups {
+    type Err = ImportGroupsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = json::from_str(s).map_err(ImportGroupsError::Json)?;
+        Self::from_raw(raw)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn import_groups_match_paths() {
+        let groups = ImportGroups::from_str(
+            r#"[["std", "core", "alloc"], ["company_*"], ["*"], ["/^(crate|super|self)$/"]]"#,
+        )
+        .unwrap();
+        let matcher = groups.matcher();
+        assert_eq!(matcher.len(), 4);
+        assert_eq!(matcher.group("std::sync::Arc"), 0);
+        assert_eq!(matcher.group("core"), 0);
+        assert_eq!(matcher.group("stdx::Foo"), 2);
+        assert_eq!(matcher.group("company_core::Id"), 1);
+        assert_eq!(matcher.group("serde::Serialize"), 2);
+        assert_eq!(matcher.group("crate::models::Event"), 2);
+        assert_eq!(matcher.group("crate"), 3);
+    }
+
+    #[test]
+    fn unmatched_imports_go_last_without_fallback_group() {
+        let groups = ImportGroups::from_str(r#"[["std"], ["crate::*"]]"#).unwrap();
+        let matcher = groups.matcher();
+        assert_eq!(matcher.len(), 3);
+        assert_eq!(matcher.group("crate::models"), 1);
+        assert_eq!(matcher.group("crate"), 2);
+        assert_eq!(matcher.group("serde"), 2);
+    }
+
+    #[test]
+    fn import_groups_reject_invalid_regex() {
+        assert!(ImportGroups::from_str(r#"[["/company_(/"]]"#).is_err());
+        assert_eq!(
+            format!(
+                "{}",
+                ImportGroups::from_str(r#"[["std", "core"], ["*"]]"#).unwrap()
+            ),
+            "[[std, core], [*]]"
+        );
+    }
+}
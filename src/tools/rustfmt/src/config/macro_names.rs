@@ -1,8 +1,10 @@
 //! This module contains types and functions to support formatting specific macros.
 
 use itertools::Itertools;
+use std::collections::BTreeMap;
 use std::{fmt, str};
 
+use rustfmt_config_proc_macro::config_type;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json as json;
 use thiserror::Error;
@@ -98,6 +100,73 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
     }
 }
 
+#[config_type]
+/// How the arguments of a macro are parsed so that they can be formatted.
+pub enum MacroGrammar {
+    /// Comma-separated expressions, like the arguments of a function call.
+    Exprs,
+    /// A list of items, like the body of a module.
+    Items,
+    /// Arms like those of a `match` expression, optionally binding the value of an expression to
+    /// the pattern, like the arms of `tokio::select!`: `pattern [= expr] [, if guard] => body`.
+    MatchArms,
+}
+
+/// The grammars declared for macros, by the names of the macros.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct MacroGrammars(pub BTreeMap<String, MacroGrammar>);
+
+impl MacroGrammars {
+    /// The grammar declared for the macro at `path`. A name with a path, like `tokio::select`,
+    /// only matches invocations with that path, while a plain name also matches invocations
+    /// through any path ending in that name.
+    pub(crate) fn grammar(&self, path: &str) -> Option<MacroGrammar> {
+        let path = path.trim_start_matches("::");
+        self.0
+            .get(path)
+            .or_else(|| self.0.get(path.rsplit("::").next()?))
+            .copied()
+    }
+}
+
+impl fmt::Display for MacroGrammars {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.0
+                .iter()
+                .format_with(", ", |(name, grammar), f| f(&format_args!(
+                    "{name}: {grammar}"
+                )))
+        )
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum MacroGrammarsError {
+    #[error("{0}")]
+    Json(json::Error),
+    #[error("invalid grammar `{0}`")]
+    Grammar(String),
+}
+
+// This impl is needed for `Config::override_value` to work for use in tests.
+impl str::FromStr for MacroGrammars {
+    type Err = MacroGrammarsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw: BTreeMap<String, String> = json::from_str(s).map_err(MacroGrammarsError::Json)?;
+        raw.into_iter()
+            .map(|(name, grammar)| match MacroGrammar::from_str(&grammar) {
+                Ok(grammar) => Ok((name, grammar)),
+                Err(_) => Err(MacroGrammarsError::Grammar(grammar)),
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -120,6 +189,28 @@ fn macro_names_from_str() {
         );
     }
 
+    #[test]
+    fn macro_grammars_from_str() {
+        let grammars =
+            MacroGrammars::from_str(r#"{"tokio::select": "MatchArms", "html": "items"}"#).unwrap();
+        assert_eq!(
+            grammars.grammar("tokio::select"),
+            Some(MacroGrammar::MatchArms)
+        );
+        assert_eq!(
+            grammars.grammar("::tokio::select"),
+            Some(MacroGrammar::MatchArms)
+        );
+        assert_eq!(grammars.grammar("select"), None);
+        assert_eq!(grammars.grammar("html"), Some(MacroGrammar::Items));
+        assert_eq!(grammars.grammar("yew::html"), Some(MacroGrammar::Items));
+        assert_eq!(
+            format!("{grammars}"),
+            "html: Items, tokio::select: MatchArms"
+        );
+        assert!(MacroGrammars::from_str(r#"{"html": "Html"}"#).is_err());
+    }
+
     #[test]
     fn macro_names_display() {
         let macro_names = MacroSelectors::from_str(r#"["foo", "*", "bar"]"#).unwrap();
@@ -10,7 +10,7 @@
 #[allow(unreachable_pub)]
 pub use crate::config::file_lines::{FileLines, FileName, Range};
 #[allow(unreachable_pub)]
-pub use crate::config::macro_names::MacroSelector;
+pub use crate::config::macro_names::{MacroGrammar, MacroSelector};
 #[allow(unreachable_pub)]
 pub use crate::config::options::*;
 
@@ -21,6 +21,7 @@
 pub(crate) mod options;
 
 pub(crate) mod file_lines;
+pub(crate) mod import_groups;
 #[allow(unreachable_pub)]
 pub(crate) mod lists;
 pub(crate) mod macro_names;
@@ -78,6 +79,8 @@
         "Format the bodies of declarative macro definitions";
     skip_macro_invocations: SkipMacroInvocations, false,
         "Skip formatting the bodies of macros invoked with the following names.";
+    macro_grammars: MacroGrammarsConfig, false,
+        "Format the arguments of macros invoked with the following names using the given grammar.";
     hex_literal_case: HexLiteralCaseConfig, false, "Format hexadecimal integer literals";
 
     // Single line expressions and items
@@ -95,6 +98,8 @@
         "Merge or split imports to the provided granularity";
     group_imports: GroupImportsTacticConfig, false,
         "Controls the strategy for how imports are grouped together";
+    import_groups: ImportGroupsConfig, false,
+        "Groups imports by patterns matching their paths, overriding group_imports";
     merge_imports: MergeImports, false, "(deprecated: use imports_granularity instead)";
 
     // Ordering
@@ -747,6 +752,7 @@ fn test_dump_default_config() {
 imports_layout = "Mixed"
 imports_granularity = "Preserve"
 group_imports = "Preserve"
+import_groups = []
 reorder_imports = true
 reorder_modules = true
 reorder_impl_items = false
@@ -794,6 +800,8 @@ fn test_dump_default_config() {
 ignore = []
 emit_mode = "Files"
 make_backup = false
+
+[macro_grammars]
 "#,
             env!("CARGO_PKG_VERSION")
         );
@@ -837,6 +845,7 @@ fn test_dump_style_edition_2024_config() {
 imports_layout = "Mixed"
 imports_granularity = "Preserve"
 group_imports = "Preserve"
+import_groups = []
 reorder_imports = true
 reorder_modules = true
 reorder_impl_items = false
@@ -884,6 +893,8 @@ fn test_dump_style_edition_2024_config() {
 ignore = []
 emit_mode = "Files"
 make_backup = false
+
+[macro_grammars]
 "#,
             env!("CARGO_PKG_VERSION")
         );
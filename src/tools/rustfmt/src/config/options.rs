@@ -13,8 +13,9 @@
 
 use crate::config::Config;
 use crate::config::file_lines::FileLines;
+use crate::config::import_groups::ImportGroups;
 use crate::config::lists::*;
-use crate::config::macro_names::MacroSelectors;
+use crate::config::macro_names::{MacroGrammars, MacroSelectors};
 
 #[config_type]
 pub enum NewlineStyle {
@@ -168,6 +169,10 @@ pub enum EmitMode {
     /// Writes the resulting diffs in a JSON format. Returns an empty array
     /// `[]` if there were no diffs.
     Json,
+    /// Writes the edits which turn the input into the formatted output in a JSON format, as byte
+    /// ranges of the input and their replacements. Returns an empty array `[]` if there were no
+    /// edits.
+    Edits,
     /// Output the changed lines (for internal value only)
     ModifiedLines,
     /// Checks if a diff can be generated. If so, rustfmt outputs a diff and
@@ -597,6 +602,7 @@ macro_rules! config_option_with_style_edition_default {
     FormatMacroMatchers, bool, _ => false;
     FormatMacroBodies, bool, _ => true;
     SkipMacroInvocations, MacroSelectors, _ => MacroSelectors::default();
+    MacroGrammarsConfig, MacroGrammars, _ => MacroGrammars::default();
     HexLiteralCaseConfig, HexLiteralCase, _ => HexLiteralCase::Preserve;
 
     // Single line expressions and items
@@ -610,6 +616,7 @@ macro_rules! config_option_with_style_edition_default {
     ImportsLayout, ListTactic, _ => ListTactic::Mixed;
     ImportsGranularityConfig, ImportGranularity, _ => ImportGranularity::Preserve;
     GroupImportsTacticConfig, GroupImportsTactic, _ => GroupImportsTactic::Preserve;
+    ImportGroupsConfig, ImportGroups, _ => ImportGroups::default();
     MergeImports, bool, _ => false;
 
     // Ordering
@@ -1,5 +1,6 @@
 pub(crate) use self::checkstyle::*;
 pub(crate) use self::diff::*;
+pub(crate) use self::edits::*;
 pub(crate) use self::files::*;
 pub(crate) use self::files_with_backup::*;
 pub(crate) use self::json::*;
@@ -11,6 +12,7 @@
 
 mod checkstyle;
 mod diff;
+mod edits;
 mod files;
 mod files_with_backup;
 mod json;
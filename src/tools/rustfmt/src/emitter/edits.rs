@@ -0,0 +1,179 @@
+use super::{Emitter, EmitterResult, FormattedFile};
+use serde::Serialize;
+use serde_json::to_string as to_json_string;
+use std::io::{self, Write};
+
+/// Emits the edits which turn the original text of each file into the formatted text, as byte
+/// ranges of the original text and their replacements.
+#[derive(Debug, Default)]
+pub(crate) struct EditsEmitter {
+    edited_files: Vec<EditedFile>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct TextEdit {
+    /// The byte offset of the start of the replaced text in the original text.
+    start: usize,
+    /// The byte offset of the end of the replaced text in the original text, exclusive.
+    end: usize,
+    new_text: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct EditedFile {
+    name: String,
+    edits: Vec<TextEdit>,
+}
+
+impl Emitter for EditsEmitter {
+    fn emit_footer(&self, output: &mut dyn Write) -> Result<(), io::Error> {
+        writeln!(output, "{}", &to_json_string(&self.edited_files)?)
+    }
+
+    fn emit_formatted_file(
+        &mut self,
+        _output: &mut dyn Write,
+        FormattedFile {
+            filename,
+            original_text,
+            formatted_text,
+        }: FormattedFile<'_>,
+    ) -> Result<EmitterResult, io::Error> {
+        let edits = text_edits(original_text, formatted_text);
+        let has_diff = !edits.is_empty();
+
+        if has_diff {
+            self.edited_files.push(EditedFile {
+                name: format!("{filename}"),
+                edits,
+            });
+        }
+
+        Ok(EmitterResult { has_diff })
+    }
+}
+
+/// Computes the edits which turn `original` into `formatted`, replacing whole lines. Lines are
+/// compared with their line endings, so that changes to line endings are edits as well.
+fn text_edits(original: &str, formatted: &str) -> Vec<TextEdit> {
+    let original_lines: Vec<_> = original.split_inclusive('\n').collect();
+    let formatted_lines: Vec<_> = formatted.split_inclusive('\n').collect();
+
+    let mut edits = vec![];
+    let mut current: Option<TextEdit> = None;
+    let mut offset = 0;
+    for result in diff::slice(&original_lines, &formatted_lines) {
+        match result {
+            diff::Result::Left(line) => {
+                let edit = current.get_or_insert_with(|| TextEdit {
+                    start: offset,
+                    end: offset,
+                    new_text: String::new(),
+                });
+                offset += line.len();
+                edit.end = offset;
+            }
+            diff::Result::Right(line) => {
+                let edit = current.get_or_insert_with(|| TextEdit {
+                    start: offset,
+                    end: offset,
+                    new_text: String::new(),
+                });
+                edit.new_text.push_str(line);
+            }
+            diff::Result::Both(line, _) => {
+                edits.extend(current.take());
+                offset += line.len();
+            }
+        }
+    }
+    edits.extend(current);
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileName;
+
+    fn apply(original: &str, edits: &[TextEdit]) -> String {
+        let mut result = original.to_owned();
+        for edit in edits.iter().rev() {
+            result.replace_range(edit.start..edit.end, &edit.new_text);
+        }
+        result
+    }
+
+    #[test]
+    fn replaces_changed_lines() {
+        let original = "fn a() {}\nfn   b( ) {}\nfn c() {}\nfn d()\n{\n}\n";
+        let formatted = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n";
+        let edits = text_edits(original, formatted);
+        assert_eq!(
+            edits,
+            vec![
+                TextEdit {
+                    start: 10,
+                    end: 23,
+                    new_text: "fn b() {}\n".to_owned(),
+                },
+                TextEdit {
+                    start: 33,
+                    end: 44,
+                    new_text: "fn d() {}\n".to_owned(),
+                },
+            ]
+        );
+        assert_eq!(apply(original, &edits), formatted);
+    }
+
+    #[test]
+    fn inserts_and_deletes_lines() {
+        let original = "use a;\n\n\n\nuse b;\nfn main() {}";
+        let formatted = "use a;\n\nuse b;\nfn main() {}\n";
+        let edits = text_edits(original, formatted);
+        assert_eq!(edits.len(), 2);
+        assert_eq!(apply(original, &edits), formatted);
+    }
+
+    #[test]
+    fn no_edits_without_changes() {
+        let text = "fn main() {}\r\n";
+        assert!(text_edits(text, text).is_empty());
+        let edits = text_edits(text, "fn main() {}\n");
+        assert_eq!(apply(text, &edits), "fn main() {}\n");
+    }
+
+    #[test]
+    fn emits_edits_of_files_with_changes() {
+        let mut emitter = EditsEmitter::default();
+        let mut writer = Vec::new();
+        let result = emitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: &FileName::Stdin,
+                    original_text: "fn main( ) {}\n",
+                    formatted_text: "fn main() {}\n",
+                },
+            )
+            .unwrap();
+        assert!(result.has_diff);
+        let _ = emitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: &FileName::Stdin,
+                    original_text: "fn main() {}\n",
+                    formatted_text: "fn main() {}\n",
+                },
+            )
+            .unwrap();
+        let _ = emitter.emit_footer(&mut writer);
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "[{\"name\":\"<stdin>\",\"edits\":[{\"start\":0,\"end\":14,\
+             \"new_text\":\"fn main() {}\\n\"}]}]\n"
+        );
+    }
+}
@@ -53,6 +53,8 @@
 
 pub use crate::rustfmt_diff::{ModifiedChunk, ModifiedLines};
 
+pub use crate::selection::enclosing_item_lines;
+
 #[macro_use]
 mod utils;
 
@@ -89,6 +91,7 @@ macro_rules! static_regex {
 mod reorder;
 mod rewrite;
 pub(crate) mod rustfmt_diff;
+mod selection;
 mod shape;
 mod skip;
 mod sort;
@@ -528,6 +531,7 @@ pub(crate) fn create_emitter<'a>(config: &Config) -> Box<dyn Emitter + 'a> {
             Box::new(emitter::StdoutEmitter::new(config.verbose()))
         }
         EmitMode::Json => Box::new(emitter::JsonEmitter::default()),
+        EmitMode::Edits => Box::new(emitter::EditsEmitter::default()),
         EmitMode::ModifiedLines => Box::new(emitter::ModifiedLinesEmitter::default()),
         EmitMode::Checkstyle => Box::new(emitter::CheckstyleEmitter::default()),
         EmitMode::Diff => Box::new(emitter::DiffEmitter::new(config.clone())),
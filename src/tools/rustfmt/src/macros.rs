@@ -25,11 +25,13 @@
 use crate::comment::{
     CharClasses, FindUncommented, FullCodeCharKind, LineClasses, contains_comment,
 };
-use crate::config::StyleEdition;
 use crate::config::lists::*;
+use crate::config::{MacroGrammar, StyleEdition};
 use crate::expr::{RhsAssignKind, rewrite_array, rewrite_assign_rhs};
 use crate::lists::{ListFormatting, itemize_list, write_list};
+use crate::matches::{rewrite_guard, rewrite_match_body};
 use crate::overflow;
+use crate::parse::macros::grammar::{MacroArm, parse_exprs, parse_items, parse_match_arms};
 use crate::parse::macros::lazy_static::parse_lazy_static;
 use crate::parse::macros::{ParsedMacroArgs, parse_expr, parse_macro_args};
 use crate::rewrite::{
@@ -260,6 +262,23 @@ fn rewrite_macro_inner(
         }
     }
 
+    // Format macros with a declared grammar, or fall back to the usual heuristics if their
+    // arguments don't match the grammar.
+    let grammar = context
+        .config
+        .macro_grammars()
+        .grammar(context.snippet(mac.path.span));
+    if let Some(grammar) = grammar.filter(|_| !has_comment) {
+        match format_with_grammar(context, mac, grammar, &macro_name, shape, style, position) {
+            Ok(rw) => return Ok(rw),
+            Err(RewriteError::MacroFailure {
+                kind: MacroErrorKind::ParseFailure,
+                ..
+            }) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
     let ParsedMacroArgs {
         args: arg_vec,
         vec_with_semi,
@@ -1461,6 +1480,183 @@ fn format_lazy_static(
     Ok(result)
 }
 
+/// Formats a macro invocation according to the grammar declared for the macro in
+/// `macro_grammars`. Fails with `MacroErrorKind::ParseFailure` if the arguments don't match the
+/// grammar.
+fn format_with_grammar(
+    context: &RewriteContext<'_>,
+    mac: &ast::MacCall,
+    grammar: MacroGrammar,
+    macro_name: &str,
+    shape: Shape,
+    style: Delimiter,
+    position: MacroPosition,
+) -> RewriteResult {
+    let ts = mac.args.tokens.clone();
+    let span = mac.span();
+    match grammar {
+        MacroGrammar::Items => {
+            let items: Vec<_> = parse_items(context, ts)
+                .macro_error(MacroErrorKind::ParseFailure, span)?
+                .into_iter()
+                .map(MacroArg::Item)
+                .collect();
+            rewrite_macro_with_items(
+                context, &items, macro_name, shape, style, style, position, span,
+            )
+        }
+        MacroGrammar::Exprs => {
+            let (exprs, trailing_comma) =
+                parse_exprs(context, ts).macro_error(MacroErrorKind::ParseFailure, span)?;
+            let args: Vec<_> = exprs.into_iter().map(MacroArg::Expr).collect();
+            let separator = if trailing_comma {
+                Some(SeparatorTactic::Always)
+            } else {
+                Some(SeparatorTactic::Never)
+            };
+            let semicolon = match position {
+                MacroPosition::Item if style != Delimiter::Brace => ";",
+                _ => "",
+            };
+            let rewrite = match style {
+                Delimiter::Parenthesis => overflow::rewrite_with_parens(
+                    context,
+                    macro_name,
+                    args.iter(),
+                    shape,
+                    span,
+                    context.config.fn_call_width(),
+                    separator,
+                )?,
+                Delimiter::Bracket => rewrite_array(
+                    macro_name,
+                    args.iter(),
+                    span,
+                    context,
+                    shape,
+                    separator,
+                    Some(style),
+                )?,
+                _ => rewrite_exprs_in_braces(context, &args, macro_name, shape, trailing_comma)?,
+            };
+            Ok(format!("{rewrite}{semicolon}"))
+        }
+        MacroGrammar::MatchArms => {
+            let arms =
+                parse_match_arms(context, ts).macro_error(MacroErrorKind::ParseFailure, span)?;
+            rewrite_macro_arms(context, &arms, macro_name, shape, style, position)
+        }
+    }
+}
+
+/// Formats comma-separated expressions in braces on a single line if they fit, and one per line
+/// otherwise.
+fn rewrite_exprs_in_braces(
+    context: &RewriteContext<'_>,
+    args: &[MacroArg],
+    macro_name: &str,
+    shape: Shape,
+    trailing_comma: bool,
+) -> RewriteResult {
+    let nested_shape = shape
+        .block_indent(context.config.tab_spaces())
+        .with_max_width(context.config);
+    // 1 = `,`
+    let arg_shape = nested_shape
+        .sub_width(1)
+        .max_width_error(nested_shape.width, DUMMY_SP)?;
+    let args = args
+        .iter()
+        .map(|arg| arg.rewrite_result(context, arg_shape))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let one_line = format!("{macro_name} {{ {} }}", args.join(", "));
+    if !one_line.contains('\n') && one_line.len() <= shape.width {
+        return Ok(one_line);
+    }
+    let mut result = format!("{macro_name} {{");
+    for (i, arg) in args.iter().enumerate() {
+        result.push_str(&nested_shape.indent.to_string_with_newline(context.config));
+        result.push_str(arg);
+        if i + 1 < args.len() || trailing_comma {
+            result.push(',');
+        }
+    }
+    result.push_str(&shape.indent.to_string_with_newline(context.config));
+    result.push('}');
+    Ok(result)
+}
+
+/// Formats the arms of a macro with the `MatchArms` grammar like the arms of a `match`, one per
+/// line.
+fn rewrite_macro_arms(
+    context: &RewriteContext<'_>,
+    arms: &[MacroArm],
+    macro_name: &str,
+    shape: Shape,
+    style: Delimiter,
+    position: MacroPosition,
+) -> RewriteResult {
+    let (opener, closer) = match style {
+        Delimiter::Parenthesis => ("(", ")"),
+        Delimiter::Bracket => ("[", "]"),
+        _ => (" {", "}"),
+    };
+    let nested_shape = shape
+        .block_indent(context.config.tab_spaces())
+        .with_max_width(context.config);
+
+    let mut result = format!("{macro_name}{opener}");
+    for (i, arm) in arms.iter().enumerate() {
+        // 5 = ` => {`
+        let lhs_shape = nested_shape
+            .sub_width(5)
+            .max_width_error(nested_shape.width, arm.body.span)?;
+        let mut lhs = match &arm.pat {
+            Some(pat) => pat.rewrite_result(context, lhs_shape)?,
+            None => "else".to_owned(),
+        };
+        if let Some(expr) = &arm.expr {
+            lhs = rewrite_assign_rhs(
+                context,
+                format!("{lhs} ="),
+                &**expr,
+                &RhsAssignKind::Expr(&expr.kind, expr.span),
+                lhs_shape,
+            )?;
+        }
+        let last_line_width = lhs.lines().last().map_or(0, |line| line.trim().len());
+        if arm.guard_after_comma {
+            lhs.push(',');
+        }
+        let guard = rewrite_guard(
+            context,
+            &arm.guard,
+            nested_shape,
+            last_line_width,
+            lhs.contains('\n') && last_line_width > context.config.tab_spaces(),
+        )?;
+        lhs.push_str(&guard);
+
+        result.push_str(&nested_shape.indent.to_string_with_newline(context.config));
+        result.push_str(&rewrite_match_body(
+            context,
+            &arm.body,
+            &lhs,
+            nested_shape,
+            guard.contains('\n'),
+            arm.arrow_span,
+            i + 1 == arms.len(),
+        )?);
+    }
+    result.push_str(&shape.indent.to_string_with_newline(context.config));
+    result.push_str(closer);
+    if position == MacroPosition::Item && style != Delimiter::Brace {
+        result.push(';');
+    }
+    Ok(result)
+}
+
 fn rewrite_macro_with_items(
     context: &RewriteContext<'_>,
     items: &[MacroArg],
@@ -392,7 +392,7 @@ fn flatten_arm_body<'a>(
     }
 }
 
-fn rewrite_match_body(
+pub(crate) fn rewrite_match_body(
     context: &RewriteContext<'_>,
     body: &ptr::P<ast::Expr>,
     pats_str: &str,
@@ -567,7 +567,7 @@ fn rewrite_match_body(
 }
 
 // The `if ...` guard on a match arm.
-fn rewrite_guard(
+pub(crate) fn rewrite_guard(
     context: &RewriteContext<'_>,
     guard: &Option<ptr::P<ast::Expr>>,
     shape: Shape,
@@ -0,0 +1,131 @@
+//! Parsing the arguments of macros according to the grammars declared in `macro_grammars`.
+
+use rustc_ast::ast;
+use rustc_ast::ptr::P;
+use rustc_ast::token::TokenKind;
+use rustc_ast::tokenstream::TokenStream;
+use rustc_parse::parser::{CommaRecoveryMode, ForceCollect, RecoverColon, RecoverComma};
+use rustc_span::Span;
+use rustc_span::symbol::kw;
+
+use crate::rewrite::RewriteContext;
+use crate::utils::mk_sp;
+
+/// An arm of a macro with the `MatchArms` grammar: `pattern [= expr] [, if guard] => body`.
+pub(crate) struct MacroArm {
+    /// The pattern of the arm, `None` for an `else` arm.
+    pub(crate) pat: Option<P<ast::Pat>>,
+    /// The expression whose value is matched against the pattern, if any.
+    pub(crate) expr: Option<P<ast::Expr>>,
+    pub(crate) guard: Option<P<ast::Expr>>,
+    /// Whether the guard is separated from the rest of the arm by a comma, as in `tokio::select!`.
+    pub(crate) guard_after_comma: bool,
+    /// The span between the end of the guard (or the pattern or expression) and the body.
+    pub(crate) arrow_span: Span,
+    pub(crate) body: P<ast::Expr>,
+}
+
+macro_rules! parse_or {
+    ($parser:ident, $method:ident $(,)* $($arg:expr),* $(,)*) => {
+        match $parser.$method($($arg,)*) {
+            Ok(val) => {
+                if $parser.psess.dcx().has_errors().is_some() {
+                    $parser.psess.dcx().reset_err_count();
+                    return None;
+                } else {
+                    val
+                }
+            }
+            Err(err) => {
+                err.cancel();
+                $parser.psess.dcx().reset_err_count();
+                return None;
+            }
+        }
+    }
+}
+
+/// Parses comma-separated expressions, and returns them with whether there is a trailing comma.
+pub(crate) fn parse_exprs(
+    context: &RewriteContext<'_>,
+    ts: TokenStream,
+) -> Option<(Vec<P<ast::Expr>>, bool)> {
+    let mut parser = super::build_parser(context, ts);
+    let mut exprs = vec![];
+    while parser.token.kind != TokenKind::Eof {
+        exprs.push(parse_or!(parser, parse_expr));
+        if parser.token.kind == TokenKind::Eof {
+            return Some((exprs, false));
+        }
+        parse_or!(parser, expect, &TokenKind::Comma);
+    }
+    let trailing_comma = !exprs.is_empty();
+    Some((exprs, trailing_comma))
+}
+
+pub(crate) fn parse_items(
+    context: &RewriteContext<'_>,
+    ts: TokenStream,
+) -> Option<Vec<P<ast::Item>>> {
+    let mut parser = super::build_parser(context, ts);
+    let mut items = vec![];
+    while parser.token.kind != TokenKind::Eof {
+        items.push(parse_or!(parser, parse_item, ForceCollect::No)?);
+    }
+    Some(items)
+}
+
+pub(crate) fn parse_match_arms(
+    context: &RewriteContext<'_>,
+    ts: TokenStream,
+) -> Option<Vec<MacroArm>> {
+    let mut parser = super::build_parser(context, ts);
+    let mut arms = vec![];
+    while parser.token.kind != TokenKind::Eof {
+        let pat = if parser.eat_keyword(kw::Else) {
+            None
+        } else {
+            Some(parse_or!(
+                parser,
+                parse_pat_allow_top_alt,
+                None,
+                RecoverComma::No,
+                RecoverColon::No,
+                CommaRecoveryMode::EitherTupleOrPipe,
+            ))
+        };
+        let expr = if pat.is_some() && parser.eat(&TokenKind::Eq) {
+            Some(parse_or!(parser, parse_expr))
+        } else {
+            None
+        };
+        let guard_after_comma =
+            parser.token.kind == TokenKind::Comma && parser.look_ahead(1, |t| t.is_keyword(kw::If));
+        if guard_after_comma {
+            parser.bump();
+        }
+        let guard = if parser.eat_keyword(kw::If) {
+            Some(parse_or!(parser, parse_expr))
+        } else {
+            None
+        };
+        let arrow_lo = parser.prev_token.span.hi();
+        parse_or!(parser, expect, &TokenKind::FatArrow);
+        let body = parse_or!(parser, parse_expr);
+        let arrow_span = mk_sp(arrow_lo, body.span.lo());
+        // A comma is only optional after a block and after the last arm.
+        let is_block = matches!(body.kind, ast::ExprKind::Block(..));
+        if !parser.eat(&TokenKind::Comma) && !is_block && parser.token.kind != TokenKind::Eof {
+            return None;
+        }
+        arms.push(MacroArm {
+            pat,
+            expr,
+            guard,
+            guard_after_comma,
+            arrow_span,
+            body,
+        });
+    }
+    Some(arms)
+}
@@ -12,6 +12,7 @@
 
 pub(crate) mod asm;
 pub(crate) mod cfg_if;
+pub(crate) mod grammar;
 pub(crate) mod lazy_static;
 
 fn build_stream_parser<'a>(psess: &'a ParseSess, tokens: TokenStream) -> Parser<'a> {
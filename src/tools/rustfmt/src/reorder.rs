@@ -8,9 +8,12 @@
 
 use std::cmp::Ordering;
 
+use itertools::Itertools;
+
 use rustc_ast::{ast, attr};
 use rustc_span::{Span, symbol::sym};
 
+use crate::config::import_groups::ImportGroupMatcher;
 use crate::config::{Config, GroupImportsTactic};
 use crate::imports::{UseSegmentKind, UseTree, normalize_use_trees_with_granularity};
 use crate::items::{is_mod_decl, rewrite_extern_crate, rewrite_mod};
@@ -113,6 +116,12 @@ fn rewrite_reorderable_or_regroupable_items(
             );
 
             let mut regrouped_items = match context.config.group_imports() {
+                _ if !context.config.import_groups().is_empty() => {
+                    let matcher = context
+                        .import_group_matcher
+                        .get_or_init(|| context.config.import_groups().matcher());
+                    group_imports_by_patterns(normalized_items, matcher)
+                }
                 GroupImportsTactic::Preserve | GroupImportsTactic::One => {
                     vec![normalized_items]
                 }
@@ -205,6 +214,36 @@ fn group_imports(uts: Vec<UseTree>) -> Vec<Vec<UseTree>> {
     vec![std_imports, external_imports, local_imports]
 }
 
+/// Divides imports into the groups given by `import_groups`.
+fn group_imports_by_patterns(uts: Vec<UseTree>, matcher: &ImportGroupMatcher) -> Vec<Vec<UseTree>> {
+    let mut groups: Vec<_> = (0..matcher.len()).map(|_| Vec::new()).collect();
+
+    for ut in uts.into_iter() {
+        // Match the leading segments of the path, up to a nested list or glob.
+        let path = ut
+            .path
+            .iter()
+            .map_while(|segment| match &segment.kind {
+                UseSegmentKind::Ident(name, _) => Some(name.as_str()),
+                UseSegmentKind::Slf(_) => Some("self"),
+                UseSegmentKind::Super(_) => Some("super"),
+                UseSegmentKind::Crate(_) => Some("crate"),
+                UseSegmentKind::Glob | UseSegmentKind::List(_) => None,
+            })
+            .join("::");
+        // `::serde::Serialize` is matched like `serde::Serialize`.
+        let path = path.strip_prefix("::").unwrap_or(&path);
+        groups[matcher.group(path)].push(ut);
+    }
+
+    groups
+}
+
+/// Whether imports are regrouped across blank lines.
+fn regroups_imports(config: &Config) -> bool {
+    config.group_imports() != GroupImportsTactic::Preserve || !config.import_groups().is_empty()
+}
+
 /// A simplified version of `ast::ItemKind`.
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 enum ReorderableItemKind {
@@ -247,14 +286,14 @@ fn is_regroupable(self, config: &Config) -> bool {
             ReorderableItemKind::ExternCrate
             | ReorderableItemKind::Mod
             | ReorderableItemKind::Other => false,
-            ReorderableItemKind::Use => config.group_imports() != GroupImportsTactic::Preserve,
+            ReorderableItemKind::Use => regroups_imports(config),
         }
     }
 
     fn in_group(self, config: &Config) -> bool {
         match self {
             ReorderableItemKind::ExternCrate | ReorderableItemKind::Mod => true,
-            ReorderableItemKind::Use => config.group_imports() == GroupImportsTactic::Preserve,
+            ReorderableItemKind::Use => !regroups_imports(config),
             ReorderableItemKind::Other => false,
         }
     }
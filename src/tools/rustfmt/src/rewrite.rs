@@ -1,6 +1,6 @@
 // A generic trait to abstract the rewriting of an element (of the AST).
 
-use std::cell::{Cell, RefCell};
+use std::cell::{Cell, OnceCell, RefCell};
 use std::rc::Rc;
 
 use rustc_ast::ptr;
@@ -8,6 +8,7 @@
 use thiserror::Error;
 
 use crate::FormatReport;
+use crate::config::import_groups::ImportGroupMatcher;
 use crate::config::{Config, IndentStyle};
 use crate::parse::session::ParseSess;
 use crate::shape::Shape;
@@ -109,6 +110,8 @@ pub(crate) struct RewriteContext<'a> {
     pub(crate) report: FormatReport,
     pub(crate) skip_context: SkipContext,
     pub(crate) skipped_range: Rc<RefCell<Vec<(usize, usize)>>>,
+    // The compiled `import_groups` patterns, built the first time they are needed in a file.
+    pub(crate) import_group_matcher: Rc<OnceCell<ImportGroupMatcher>>,
 }
 
 pub(crate) struct InsideMacroGuard {
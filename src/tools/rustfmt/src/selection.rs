@@ -0,0 +1,121 @@
+//! Widening of byte ranges of the input to the items enclosing them.
+
+use std::ops;
+
+use rustc_ast::ast;
+use rustc_ast::visit::{self, AssocCtxt, Visitor};
+use rustc_span::{BytePos, Span};
+
+use crate::config::{Config, Range};
+use crate::parse::parser::Parser;
+use crate::parse::session::ParseSess;
+use crate::spanned::Spanned;
+use crate::{ErrorKind, Input};
+
+/// Returns the lines to format for each of the byte ranges of `text`: the lines of the smallest
+/// item (including its attributes) that encloses the byte range. If no item encloses it, the byte
+/// range is extended to all the top-level items that it overlaps, if any. Returns `None` if a byte
+/// range is out of bounds of `text`.
+///
+/// Formatting only the touched lines of an item can leave the rest of it unformatted, so editors
+/// formatting a selection should format the whole items instead.
+pub fn enclosing_item_lines(
+    text: &str,
+    config: &Config,
+    byte_ranges: &[ops::Range<usize>],
+) -> Result<Option<Vec<Range>>, ErrorKind> {
+    if byte_ranges
+        .iter()
+        .any(|bytes| bytes.start > bytes.end || bytes.end > text.len())
+    {
+        return Ok(None);
+    }
+    let items = rustc_span::create_session_if_not_set_then(config.edition().into(), |_| {
+        item_byte_ranges(text, config)
+    })?;
+    Ok(byte_ranges
+        .iter()
+        .map(|bytes| {
+            let enclosing = items
+                .all
+                .iter()
+                .filter(|item| item.start <= bytes.start && bytes.end <= item.end)
+                .min_by_key(|item| item.end - item.start)
+                .cloned()
+                .unwrap_or_else(|| {
+                    items
+                        .top_level
+                        .iter()
+                        .filter(|item| item.start < bytes.end && bytes.start < item.end)
+                        .fold(bytes.clone(), |union, item| {
+                            union.start.min(item.start)..union.end.max(item.end)
+                        })
+                });
+            Range::from_byte_range(text, enclosing)
+        })
+        .collect())
+}
+
+/// The byte ranges of the items of the input.
+#[derive(Default)]
+struct ItemRanges {
+    /// All items, including nested and associated items.
+    all: Vec<ops::Range<usize>>,
+    /// The items that are not nested in another item.
+    top_level: Vec<ops::Range<usize>>,
+}
+
+/// Returns the byte ranges of the items of `text`. Input that does not parse has no items, and its
+/// errors are reported when it is formatted.
+fn item_byte_ranges(text: &str, config: &Config) -> Result<ItemRanges, ErrorKind> {
+    let mut psess = ParseSess::new(config)?;
+    psess.set_silent_emitter();
+    let Ok(krate) = Parser::parse_crate(Input::Text(text.to_owned()), &psess) else {
+        return Ok(ItemRanges::default());
+    };
+    let mut collector = ItemCollector {
+        start_pos: psess.snippet_provider(krate.spans.inner_span).start_pos(),
+        depth: 0,
+        items: ItemRanges::default(),
+    };
+    visit::walk_crate(&mut collector, &krate);
+    Ok(collector.items)
+}
+
+struct ItemCollector {
+    start_pos: BytePos,
+    /// The number of items the visitor is in.
+    depth: usize,
+    items: ItemRanges,
+}
+
+impl ItemCollector {
+    /// Adds the item at `span`, and visits the items nested in it with `walk`.
+    fn add(&mut self, span: Span, walk: impl FnOnce(&mut Self)) {
+        if !span.from_expansion() {
+            let offset = |pos: BytePos| (pos - self.start_pos).0 as usize;
+            let bytes = offset(span.lo())..offset(span.hi());
+            if self.depth == 0 {
+                self.items.top_level.push(bytes.clone());
+            }
+            self.items.all.push(bytes);
+        }
+        self.depth += 1;
+        walk(self);
+        self.depth -= 1;
+    }
+}
+
+impl<'ast> Visitor<'ast> for ItemCollector {
+    fn visit_item(&mut self, item: &'ast ast::Item) {
+        self.add(item.span(), |this| visit::walk_item(this, item));
+    }
+
+    fn visit_assoc_item(&mut self, item: &'ast ast::AssocItem, ctxt: AssocCtxt) {
+        self.add(item.span(), |this| visit::walk_assoc_item(this, item, ctxt));
+    }
+
+    fn visit_foreign_item(&mut self, item: &'ast ast::ForeignItem) {
+        self.add(item.span(), |this| visit::walk_item(this, item));
+    }
+}
@@ -549,6 +549,65 @@ fn stdin_works_with_checkstyle() {
     );
 }
 
+/// Ensures that `--range` formats the whole items enclosing the byte ranges, but no others, and
+/// emits the edits as JSON.
+#[test]
+fn stdin_works_with_byte_ranges() {
+    init_log();
+    let input = "fn a( ) {\n    let x=1;\n}\nfn b( ) { foo( 1,2 ) }\nfn c( ) {}\n";
+    let mut child = Command::new(rustfmt().to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .args(["--range", "18..20", "--range", "50..50"])
+        .spawn()
+        .expect("failed to execute child");
+
+    {
+        let stdin = child.stdin.as_mut().expect("failed to get stdin");
+        stdin
+            .write_all(input.as_bytes())
+            .expect("failed to write stdin");
+    }
+
+    let output = child.wait_with_output().expect("failed to wait on child");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "[{\"name\":\"<stdin>\",\"edits\":[\
+         {\"start\":0,\"end\":23,\"new_text\":\"fn a() {\\n    let x = 1;\\n\"},\
+         {\"start\":48,\"end\":59,\"new_text\":\"fn c() {}\\n\"}]}]\n"
+    );
+}
+
+/// Ensures that a byte range that crosses from one item into another formats both items.
+#[test]
+fn stdin_works_with_byte_range_across_items() {
+    init_log();
+    let input = "fn a( ) {\n    let x=1;\n}\nfn b( ) { foo( 1,2 ) }\nfn c( ) {}\n";
+    let mut child = Command::new(rustfmt().to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .args(["--range", "18..30"])
+        .spawn()
+        .expect("failed to execute child");
+
+    {
+        let stdin = child.stdin.as_mut().expect("failed to get stdin");
+        stdin
+            .write_all(input.as_bytes())
+            .expect("failed to write stdin");
+    }
+
+    let output = child.wait_with_output().expect("failed to wait on child");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "[{\"name\":\"<stdin>\",\"edits\":[\
+         {\"start\":0,\"end\":23,\"new_text\":\"fn a() {\\n    let x = 1;\\n\"},\
+         {\"start\":25,\"end\":48,\"new_text\":\"fn b() {\\n    foo(1, 2)\\n}\\n\"}]}]\n"
+    );
+}
+
 #[test]
 fn stdin_disable_all_formatting_test() {
     init_log();
@@ -1,4 +1,4 @@
-use std::cell::{Cell, RefCell};
+use std::cell::{Cell, OnceCell, RefCell};
 use std::rc::Rc;
 
 use rustc_ast::{ast, token::Delimiter, visit};
@@ -8,6 +8,7 @@
 
 use crate::attr::*;
 use crate::comment::{CodeCharKind, CommentCodeSlices, contains_comment, rewrite_comment};
+use crate::config::import_groups::ImportGroupMatcher;
 use crate::config::{BraceStyle, Config, MacroSelector, StyleEdition};
 use crate::coverage::transform_missing_snippet;
 use crate::items::{
@@ -87,6 +88,8 @@ pub(crate) struct FmtVisitor<'a> {
     pub(crate) report: FormatReport,
     pub(crate) skip_context: SkipContext,
     pub(crate) is_macro_def: bool,
+    /// The compiled `import_groups` patterns, shared by all visitors of a file.
+    pub(crate) import_group_matcher: Rc<OnceCell<ImportGroupMatcher>>,
 }
 
 impl<'a> Drop for FmtVisitor<'a> {
@@ -759,6 +762,7 @@ pub(crate) fn from_context(ctx: &'a RewriteContext<'_>) -> FmtVisitor<'a> {
             ctx.report.clone(),
         );
         visitor.skip_context.update(ctx.skip_context.clone());
+        visitor.import_group_matcher = ctx.import_group_matcher.clone();
         visitor.set_parent_context(ctx);
         visitor
     }
@@ -793,6 +797,7 @@ pub(crate) fn from_psess(
             macro_rewrite_failure: false,
             report,
             skip_context,
+            import_group_matcher: Rc::default(),
         }
     }
 
@@ -1013,6 +1018,7 @@ pub(crate) fn get_context(&self) -> RewriteContext<'_> {
             report: self.report.clone(),
             skip_context: self.skip_context.clone(),
             skipped_range: self.skipped_range.clone(),
+            import_group_matcher: self.import_group_matcher.clone(),
         }
     }
 }
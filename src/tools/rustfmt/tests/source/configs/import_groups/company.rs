@@ -0,0 +1,18 @@
+// rustfmt-import_groups: [["std","core","alloc"],["company_*"],["*"],["crate","super","self"]]
+use chrono::Utc;
+use super::update::convert_publish_payload;
+use company_auth::Token;
+
+use juniper::{FieldError, FieldResult};
+use uuid::Uuid;
+use alloc::alloc::Layout;
+use company_core::{Id, Timestamp};
+
+use std::sync::Arc;
+
+use broker::database::PooledConnection;
+
+use super::schema::{Context, Payload};
+use core::f32;
+use crate::models::Event;
+use self::helpers::*;
@@ -0,0 +1,8 @@
+// rustfmt-import_groups: [["std","core","alloc"],["company_*"],["*"]]
+// rustfmt-edition: 2018
+use ::serde::Serialize;
+use ::company_core::Id;
+use ::std::sync::Arc;
+use chrono::Utc;
+use company_auth::Token;
+use std::fmt;
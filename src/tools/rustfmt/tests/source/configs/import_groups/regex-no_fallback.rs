@@ -0,0 +1,10 @@
+// rustfmt-import_groups: [["/^(std|core|alloc)(::|$)/"],["/^(crate|super|self)(::|$)/"]]
+// rustfmt-group_imports: One
+use chrono::Utc;
+use super::update::convert_publish_payload;
+use std::sync::Arc;
+
+use crate::models::Event;
+use uuid::Uuid;
+
+fn main() {}
@@ -0,0 +1,13 @@
+// rustfmt-macro_grammars: {"assert_all":"Exprs","decls":"Items"}
+
+fn main() {
+    assert_all! {  a == b,c!=d  }
+    assert_all! { some_long_condition_name == another_long_value, yet_another_condition_name != 0, last_one, }
+    assert_all! { some_long_condition_name == another_long_value, yet_another_condition_name != 0, last_one }
+    assert_all![  a == b,c!=d  ];
+}
+
+decls! {
+    const   A: u8=0;
+    fn  foo( ) -> u8 { A }
+}
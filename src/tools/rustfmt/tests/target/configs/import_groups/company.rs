@@ -0,0 +1,17 @@
+// rustfmt-import_groups: [["std","core","alloc"],["company_*"],["*"],["crate","super","self"]]
+use alloc::alloc::Layout;
+use core::f32;
+use std::sync::Arc;
+
+use company_auth::Token;
+use company_core::{Id, Timestamp};
+
+use broker::database::PooledConnection;
+use chrono::Utc;
+use juniper::{FieldError, FieldResult};
+use uuid::Uuid;
+
+use self::helpers::*;
+use super::schema::{Context, Payload};
+use super::update::convert_publish_payload;
+use crate::models::Event;
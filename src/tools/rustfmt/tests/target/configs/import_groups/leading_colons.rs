@@ -0,0 +1,10 @@
+// rustfmt-import_groups: [["std","core","alloc"],["company_*"],["*"]]
+// rustfmt-edition: 2018
+use ::std::sync::Arc;
+use std::fmt;
+
+use ::company_core::Id;
+use company_auth::Token;
+
+use ::serde::Serialize;
+use chrono::Utc;
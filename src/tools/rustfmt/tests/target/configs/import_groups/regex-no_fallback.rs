@@ -0,0 +1,11 @@
+// rustfmt-import_groups: [["/^(std|core|alloc)(::|$)/"],["/^(crate|super|self)(::|$)/"]]
+// rustfmt-group_imports: One
+use std::sync::Arc;
+
+use super::update::convert_publish_payload;
+use crate::models::Event;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+fn main() {}
@@ -0,0 +1,38 @@
+// rustfmt-macro_grammars: {"tokio::select":"MatchArms","cfg_match":"MatchArms"}
+// rustfmt-edition: 2018
+
+async fn run() {
+    tokio::select! {
+        v = rx1.recv(), if !done => {
+            println!("got {:?}", v)
+        }
+        Some(msg) = stream.next() => handle(msg),
+        _ = tokio::time::sleep(Duration::from_millis(
+            some_really_long_timeout_value_in_millis
+        )) => break,
+        else => {
+            return;
+        }
+    }
+
+    // Not declared, left alone.
+    select! {
+        v = rx1.recv() =>    { println!("got {:?}", v) }
+    }
+}
+
+cfg_match! {
+    cfg(unix) => {
+        fn foo() {}
+    }
+    _ => {
+        fn foo() {
+            bar()
+        }
+    }
+}
+
+// Doesn't match the grammar, left alone.
+tokio::select! {
+    v = rx1.recv() ;   { println!("got {:?}", v) }
+}
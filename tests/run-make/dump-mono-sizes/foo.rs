@@ -0,0 +1,11 @@
+pub fn bar<T: Default>() -> T {
+    T::default()
+}
+
+pub fn baz() -> (u32, String) {
+    (bar(), bar())
+}
+
+pub fn qux(v: &mut Vec<u64>) {
+    v.push(1);
+}
@@ -0,0 +1,78 @@
+// `-Z dump-mono-sizes` reports the machine code size of every mono item codegened in the crate,
+// read from the object files, along with the crates defining and instantiating the item and its
+// generic arguments. This test checks that the instantiations of a generic function are reported
+// with their arguments, that the sizes of instantiations of upstream generic items are attributed
+// to the local crate, and that the report is also written when the crate is not linked, or when
+// its object files contain bitcode.
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{cwd, rfs, rustc};
+
+fn report() -> Value {
+    let report = rfs::read_to_string("foo.mono_sizes.json");
+    rfs::remove_file("foo.mono_sizes.json");
+    serde_json::from_str(&report).unwrap()
+}
+
+fn item<'a>(report: &'a Value, name: &str) -> &'a Value {
+    report["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["name"] == name)
+        .unwrap_or_else(|| panic!("no item `{name}` in {report}"))
+}
+
+fn main() {
+    let dump_mono_sizes = format!("-Zdump-mono-sizes={}", cwd().display());
+
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg(&dump_mono_sizes)
+        .arg("-Zdump-mono-stats-format=json")
+        .run();
+    let report = report();
+    assert_eq!(item(&report, "foo::bar::<u32>")["generic_args"], serde_json::json!(["u32"]));
+    assert_eq!(
+        item(&report, "foo::bar::<std::string::String>")["generic_args"],
+        serde_json::json!(["std::string::String"])
+    );
+    let baz = item(&report, "foo::baz");
+    assert_eq!(baz["crate"], "foo");
+    assert_eq!(baz["instantiating_crate"], "foo");
+    let push = report["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["name"].as_str().unwrap().ends_with("Vec::<u64>::push"))
+        .unwrap_or_else(|| panic!("no item `Vec::<u64>::push` in {report}"));
+    assert_eq!(push["crate"], "alloc");
+    assert_eq!(push["instantiating_crate"], "foo");
+    assert!(baz["size"].as_u64().is_some_and(|size| size > 0), "no size for `foo::baz`: {baz}");
+    let foo = report["crates"].as_array().unwrap().iter().find(|krate| krate["name"] == "foo");
+    assert!(foo.is_some_and(|foo| foo["size"].as_u64().unwrap() > 0), "{report}");
+
+    // Without linking, the object files are read before they are written to the outputs.
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .emit("obj")
+        .arg(&dump_mono_sizes)
+        .arg("-Zdump-mono-stats-format=json")
+        .run();
+    let report = report();
+    assert!(item(&report, "foo::baz")["size"].as_u64().is_some_and(|size| size > 0));
+
+    // Bitcode object files have no machine code to measure.
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg("-Clinker-plugin-lto")
+        .arg(&dump_mono_sizes)
+        .arg("-Zdump-mono-stats-format=json")
+        .run()
+        .assert_stderr_contains("the sizes of the mono items in");
+    let report = report();
+    assert!(item(&report, "foo::baz")["size"].is_null());
+}
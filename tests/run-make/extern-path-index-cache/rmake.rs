@@ -0,0 +1,50 @@
+// The index of the public paths of the extern crates used for import suggestions is cached in
+// the incremental directory. This test checks that the cache is written and then used instead of
+// building the index again, that it's not used anymore once the extern crate changes, and that a
+// truncated or corrupted cache is ignored.
+
+//@ ignore-cross-compile
+
+use run_make_support::{rfs, rustc};
+
+/// Checks the import suggested for the unresolved name of `main.rs`, and returns whether the index
+/// of `dep` was built rather than read from the cache.
+fn suggest(expected: &str) -> bool {
+    let output = rustc()
+        .input("main.rs")
+        .edition("2021")
+        .extern_("dep", "libdep.rlib")
+        .incremental("incr")
+        .arg("-Ztime-passes")
+        .run_fail();
+    output.assert_stderr_contains(format!("use {expected};"));
+    output.stderr_utf8().contains("build_extern_path_index")
+}
+
+fn main() {
+    rfs::write("dep.rs", "pub mod a { pub mod b { pub struct Thing; } }");
+    rustc().crate_type("rlib").input("dep.rs").run();
+    assert!(suggest("dep::a::b::Thing"));
+
+    let crate_dir = rfs::read_dir("incr").next().unwrap().unwrap().path();
+    let cache = crate_dir.join("extern-path-index.bin");
+    assert!(cache.exists());
+    assert!(!suggest("dep::a::b::Thing"));
+
+    // Truncate the cache, keeping the marker ending the files written by the compiler.
+    let data = rfs::read(&cache);
+    rfs::write(&cache, [&data[..data.len() / 2], b"rust-end-file"].concat());
+    assert!(suggest("dep::a::b::Thing"));
+
+    // Corrupt the contents of the cache, keeping its length.
+    let mut data = rfs::read(&cache);
+    let contents_end = data.len() - b"rust-end-file".len() - 8;
+    data[contents_end / 2..contents_end].fill(0xff);
+    rfs::write(&cache, data);
+    assert!(suggest("dep::a::b::Thing"));
+    assert!(!suggest("dep::a::b::Thing"));
+
+    rfs::write("dep.rs", "pub mod a { pub mod b { pub struct Thing; } } pub use a::b::Thing;");
+    rustc().crate_type("rlib").input("dep.rs").run();
+    assert!(suggest("dep::Thing"));
+}
@@ -0,0 +1,12 @@
+fn main() {
+    used::used();
+    assert_eq!(macros::answer!(), 42);
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn check() {
+        test_only::check();
+    }
+}
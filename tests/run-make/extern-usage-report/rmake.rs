@@ -0,0 +1,48 @@
+// `-Z extern-usage-report` writes how each `--extern` crate is used as JSON. This test checks
+// that the crates referenced by the code, only through their macros, only by tests, or not at all
+// are told apart, both when compiling the binary and its tests.
+
+//@ ignore-cross-compile
+
+use run_make_support::{rfs, rustc, serde_json};
+
+fn usages(test: bool) -> Vec<(String, String)> {
+    let mut rustc = rustc();
+    rustc.input("main.rs").arg("-Zextern-usage-report=report.json");
+    for name in ["used", "macros", "test_only", "unused"] {
+        rustc.extern_(name, format!("lib{name}.rlib"));
+    }
+    if test {
+        rustc.arg("--test");
+    }
+    rustc.run();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&rfs::read_to_string("report.json")).unwrap();
+    assert_eq!(report["test"], test);
+    report["externs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|extern_| {
+            (
+                extern_["name"].as_str().unwrap().to_owned(),
+                extern_["usage"].as_str().unwrap().to_owned(),
+            )
+        })
+        .collect()
+}
+
+fn main() {
+    for name in ["used", "macros", "test_only", "unused"] {
+        rustc().crate_type("rlib").input(format!("{name}.rs")).run();
+    }
+
+    let expected = |test_only: &str| {
+        [("macros", "macros"), ("test_only", test_only), ("unused", "unused"), ("used", "used")]
+            .map(|(name, usage)| (name.to_owned(), usage.to_owned()))
+            .to_vec()
+    };
+    assert_eq!(usages(false), expected("unused"));
+    assert_eq!(usages(true), expected("test"));
+}
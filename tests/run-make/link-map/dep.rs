@@ -0,0 +1,9 @@
+#[inline(never)]
+pub fn used() -> u32 {
+    std::hint::black_box(1)
+}
+
+#[inline(never)]
+pub fn unused() -> u32 {
+    std::hint::black_box(2)
+}
@@ -0,0 +1,17 @@
+// `-Z link-map` asks the linker for a map file, and summarises the Rust symbols removed by the
+// linker's garbage collection of sections, per crate. This test checks that a function of a
+// dependency which isn't used by the executable is reported as removed.
+
+//@ only-linux
+//@ ignore-cross-compile
+
+use run_make_support::{path, rfs, rustc};
+
+fn main() {
+    rustc().crate_type("rlib").input("dep.rs").run();
+    rustc().input("main.rs").arg("-Zlink-map=main.map").run();
+    assert!(path("main.map").exists());
+    let summary = rfs::read_to_string("main.map.gc.md");
+    assert!(summary.contains("- `dep::unused`"));
+    assert!(!summary.contains("- `dep::used`"));
+}
@@ -0,0 +1,22 @@
+//! A crate with a few traits.
+
+pub trait Shape {}
+
+/// A shape with corners.
+pub trait Polygon: Shape {}
+
+pub struct Square;
+pub struct Circle;
+
+impl Shape for Square {}
+impl Shape for Circle {}
+impl Polygon for Square {}
+
+pub mod extra {
+    pub mod solids {
+        pub struct Cube;
+        impl crate::Shape for Cube {}
+    }
+
+    pub use crate::Square;
+}
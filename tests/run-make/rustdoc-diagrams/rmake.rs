@@ -0,0 +1,41 @@
+// This test ensures that `--generate-diagrams` writes the diagrams pages of the traits and of the
+// crate, along with their DOT sources, whether or not Graphviz is installed.
+
+use run_make_support::rfs::read_to_string;
+use run_make_support::{path, rustdoc};
+
+fn main() {
+    rustdoc()
+        .input("foo.rs")
+        .arg("-Zunstable-options")
+        .arg("--generate-diagrams")
+        .out_dir("doc")
+        .run();
+
+    let root = path("doc").join("foo");
+    assert!(
+        read_to_string(root.join("trait.Shape.html"))
+            .contains("href=\"trait.Shape.diagrams.html\"")
+    );
+    assert!(read_to_string(root.join("index.html")).contains("href=\"diagrams.html\""));
+    assert!(root.join("trait.Shape.diagrams.html").exists());
+    assert!(root.join("diagrams.html").exists());
+
+    // `Polygon` is a subtrait of `Shape`.
+    let supertraits = read_to_string(root.join("trait.Shape.supertraits.dot"));
+    assert!(supertraits.starts_with("digraph supertraits {\n"));
+    assert!(supertraits.contains("HREF=\"trait.Polygon.html\"><TR><TD>foo::Polygon</TD>"));
+    assert!(supertraits.contains("n1 -> n0[style=\"solid\"];"));
+
+    let implementors = read_to_string(root.join("trait.Shape.implementors.dot"));
+    assert!(implementors.contains("HREF=\"struct.Square.html\"><TR><TD>Square</TD>"));
+    assert!(implementors.contains("HREF=\"struct.Circle.html\"><TR><TD>Circle</TD>"));
+    assert!(implementors.contains("HREF=\"extra/solids/struct.Cube.html\"><TR><TD>Cube</TD>"));
+
+    // `extra` re-exports `Square` from the crate root.
+    let modules = read_to_string(root.join("modules.dot"));
+    assert!(modules.contains("HREF=\"extra/solids/index.html\"><TR><TD>foo::extra::solids</TD>"));
+    assert!(modules.contains("n0 -> n1[style=\"solid\"];"));
+    assert!(modules.contains("n1 -> n2[style=\"solid\"];"));
+    assert!(modules.contains("n1 -> n0[style=\"dashed\"];"));
+}
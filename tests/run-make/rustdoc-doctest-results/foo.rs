@@ -0,0 +1,20 @@
+/// Greets.
+///
+/// ```
+/// println!("hello from a doctest");
+/// ```
+pub fn greet() {}
+
+/// Panics.
+///
+/// ```should_panic
+/// panic!("the expected panic");
+/// ```
+pub fn explode() {}
+
+/// Fails.
+///
+/// ```
+/// assert_eq!(1 + 1, 3);
+/// ```
+pub fn broken() {}
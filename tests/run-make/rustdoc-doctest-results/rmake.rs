@@ -0,0 +1,39 @@
+// This test ensures that the results written by `--write-doctest-results` during `--test`
+// are shown under the matching examples by `--doctest-results`.
+
+//@ ignore-cross-compile
+
+use run_make_support::rfs::read_to_string;
+use run_make_support::{path, rustc, rustdoc};
+
+fn main() {
+    rustc().input("foo.rs").crate_type("lib").run();
+
+    rustdoc()
+        .input("foo.rs")
+        .arg("--test")
+        .arg("-Zunstable-options")
+        .arg("--write-doctest-results=results.json")
+        .extern_("foo", path("libfoo.rlib"))
+        .run_fail();
+
+    let results = read_to_string("results.json");
+    assert_eq!(results.lines().count(), 3);
+    assert!(results.contains(r#""stdout":"hello from a doctest\n""#));
+    assert!(results.contains(r#""panic_message":"the expected panic""#));
+    assert!(results.contains(r#""outcome":"failed""#));
+
+    rustdoc()
+        .input("foo.rs")
+        .arg("-Zunstable-options")
+        .arg("--doctest-results=results.json")
+        .out_dir("doc")
+        .run();
+
+    let html = read_to_string(path("doc").join("foo").join("fn.greet.html"));
+    assert!(html.contains("<code>hello from a doctest</code>"));
+    let html = read_to_string(path("doc").join("foo").join("fn.explode.html"));
+    assert!(html.contains("Panicked with: <code>the expected panic</code>"));
+    let html = read_to_string(path("doc").join("foo").join("fn.broken.html"));
+    assert!(html.contains("doctest-result failed"));
+}
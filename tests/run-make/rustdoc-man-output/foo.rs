@@ -0,0 +1,58 @@
+//! The crate root, which links to [`Widget`] and [`parts::Gear`].
+
+/// A widget, made with [`Widget::new`].
+pub struct Widget {
+    /// The size of the widget.
+    pub size: u32,
+}
+
+impl Widget {
+    /// Makes a [`Widget`].
+    pub fn new() -> Self {
+        Widget { size: 0 }
+    }
+}
+
+impl Widget {
+    /// The largest size of a widget.
+    pub const MAX_SIZE: u32 = 10;
+
+    /// Grows the widget.
+    pub fn grow(&mut self) {}
+}
+
+/// Turns a widget.
+///
+/// # Errors
+///
+/// Returns an error if the widget is stuck.
+///
+/// # Safety
+///
+/// `widget` must point to a valid `Widget`.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() {
+/// unsafe { foo::turn(&mut foo::Widget::new()).unwrap() };
+/// # }
+/// ```
+pub unsafe fn turn(widget: *mut Widget) -> Result<(), ()> {
+    Ok(())
+}
+
+/// Lists the parts of a widget.
+pub fn parts() -> &'static [&'static str] {
+    &["gear"]
+}
+
+/// The parts of a widget.
+pub mod parts {
+    /// A gear, used by [`crate::Widget`].
+    ///
+    /// # See also
+    ///
+    /// The [`crate::parts()`] function.
+    pub struct Gear;
+}
@@ -0,0 +1,65 @@
+// This test ensures that `--output-format man` writes one man page per module, function, type
+// and trait, with the headings of the documentation mapped to sections of the pages. The pages
+// are named after the path and the kind of the items, so that a module and a function with the
+// same name get a page each.
+
+use run_make_support::rfs::read_to_string;
+use run_make_support::{path, rustdoc};
+
+fn main() {
+    rustdoc()
+        .input("foo.rs")
+        .arg("-Zunstable-options")
+        .arg("--output-format=man")
+        .arg("--crate-version=1.0")
+        .out_dir("doc")
+        .run();
+
+    let man3 = path("doc").join("man3");
+    let root = read_to_string(man3.join("foo.3"));
+    assert!(root.starts_with(".TH \"foo\" 3 \"\" \"foo 1.0\" \"Rust Library Functions\"\n"));
+    assert!(
+        root.contains(".SH NAME\nfoo \\- The crate root, which links to Widget and parts::Gear.\n")
+    );
+    assert!(root.contains(".TP\n\\fBfoo.fn.turn\\fR(3)\nTurns a widget.\n"));
+
+    let widget = read_to_string(man3.join("foo.struct.Widget.3"));
+    assert!(widget.starts_with(".TH \"foo.struct.Widget\" 3 "));
+    assert!(widget.contains(".SH NAME\nfoo::Widget \\- A widget, made with Widget::new.\n"));
+    assert!(
+        widget.contains(".SH SYNOPSIS\n.nf\npub struct Widget {\n    pub size: u32,\n}\n.fi\n")
+    );
+    assert!(widget.contains(".SH FIELDS\n.SS \"size: u32\"\n.PP\nThe size of the widget.\n"));
+    // The items of all the inherent impls are listed together, in a section for each kind.
+    assert!(widget.contains(".SH \"ASSOCIATED CONSTANTS\"\n.SS \"pub const MAX_SIZE: u32\"\n"));
+    assert!(widget.contains(".SH \"METHODS\"\n.SS \"pub fn new() \\-> Self\"\n"));
+    assert!(widget.contains("\n.SS \"pub fn grow(&mut self)\"\n"));
+    assert_eq!(widget.matches(".SH \"METHODS\"").count(), 1);
+
+    let turn = read_to_string(man3.join("foo.fn.turn.3"));
+    assert!(turn.contains(".SH DESCRIPTION\n.PP\nTurns a widget.\n.SH \"ERRORS\"\n"));
+    assert!(
+        turn.contains(
+            ".SH \"SAFETY\"\n.PP\n\\fBwidget\\fR must point to a valid \\fBWidget\\fR.\n"
+        )
+    );
+    // Hidden lines of examples are removed.
+    assert!(turn.contains(
+        ".SH \"EXAMPLES\"\n.PP\n.RS 4\n.nf\nunsafe { foo::turn(&mut foo::Widget::new()).unwrap() };\n.fi\n"
+    ));
+    assert!(turn.contains(".SH \"SEE ALSO\"\n\\fBfoo\\fR(3)\n"));
+
+    let parts_fn = read_to_string(man3.join("foo.fn.parts.3"));
+    assert!(parts_fn.contains(".SH NAME\nfoo::parts \\- Lists the parts of a widget.\n"));
+    let parts_mod = read_to_string(man3.join("foo.mod.parts.3"));
+    assert!(parts_mod.contains(".SH NAME\nfoo::parts \\- The parts of a widget.\n"));
+    assert!(parts_mod.contains(".TP\n\\fBfoo.parts.struct.Gear\\fR(3)\n"));
+
+    // The pages linked to are added to the `See also` section of the documentation.
+    let gear = read_to_string(man3.join("foo.parts.struct.Gear.3"));
+    assert!(gear.contains(
+        ".SH \"SEE ALSO\"\n.PP\nThe \\fBcrate::parts()\\fR function.\n.PP\n\\fBfoo.struct.Widget\\fR(3),\n\
+         \\fBfoo.fn.parts\\fR(3),\n\\fBfoo.mod.parts\\fR(3)\n"
+    ));
+    assert_eq!(gear.matches(".SH \"SEE ALSO\"").count(), 1);
+}
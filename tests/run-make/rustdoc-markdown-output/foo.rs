@@ -0,0 +1,27 @@
+//! The crate root, which links to [`Widget`] and [`parts::Gear`].
+//!
+//! # Examples
+//!
+//! ```
+//! # fn main() {
+//! let w = foo::Widget::new();
+//! # }
+//! ```
+
+/// A widget, made with [`Widget::new`].
+pub struct Widget {
+    /// The size of the widget.
+    pub size: u32,
+}
+
+impl Widget {
+    /// Makes a [`Widget`] out of a [gear](parts::Gear).
+    pub fn new() -> Self {
+        Widget { size: 0 }
+    }
+}
+
+pub mod parts {
+    /// A gear, used by [`crate::Widget`].
+    pub struct Gear;
+}
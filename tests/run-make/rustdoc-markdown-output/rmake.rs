@@ -0,0 +1,34 @@
+// This test ensures that `--output-format markdown` writes one page per module and item,
+// with intra-doc links rewritten into relative links between the pages.
+
+use run_make_support::rfs::read_to_string;
+use run_make_support::{path, rustdoc};
+
+fn main() {
+    rustdoc()
+        .input("foo.rs")
+        .arg("-Zunstable-options")
+        .arg("--output-format=markdown")
+        .out_dir("doc")
+        .run();
+
+    let root = path("doc").join("foo");
+    let index = read_to_string(root.join("index.md"));
+    assert!(index.starts_with("# Crate `foo`\n"));
+    assert!(index.contains("[`Widget`](struct.Widget.md)"));
+    assert!(index.contains("[`parts::Gear`](parts/struct.Gear.md)"));
+    // Headings of the documentation are demoted below the title of the page,
+    // and hidden lines of examples are removed.
+    assert!(index.contains("## Examples\n\n```rust\nlet w = foo::Widget::new();\n```"));
+    assert!(index.contains("- [`parts`](parts/index.md)"));
+
+    let widget = read_to_string(root.join("struct.Widget.md"));
+    assert!(widget.contains("```rust\npub struct Widget {\n    pub size: u32,\n}\n```"));
+    assert!(widget.contains("[`Widget::new`](struct.Widget.md#method.new)"));
+    assert!(widget.contains("<a id=\"method.new\"></a>\n#### `pub fn new() -> Self`"));
+    assert!(widget.contains("[gear](parts/struct.Gear.md)"));
+
+    let gear = read_to_string(root.join("parts").join("struct.Gear.md"));
+    assert!(gear.contains("[`crate::Widget`](../struct.Widget.md)"));
+    assert!(path("doc").join("foo").join("parts").join("index.md").exists());
+}
@@ -0,0 +1,14 @@
+/// A collection of widgets.
+pub struct Widgets(Vec<u32>);
+
+impl Widgets {
+    /// Counts the widgets.
+    pub fn count_widgets(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Makes a widget.
+pub fn frobnicate(seed: u32) -> Widgets {
+    Widgets(vec![seed])
+}
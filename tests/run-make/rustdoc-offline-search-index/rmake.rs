@@ -0,0 +1,30 @@
+// This test ensures that `--offline-search-index` writes a search index in the
+// format of `src/rustdoc-search-index` into the crate's documentation directory,
+// and that it isn't written without the flag.
+
+use run_make_support::rfs::read;
+use run_make_support::{path, rustdoc};
+
+fn main() {
+    let crate_name = "foo";
+
+    rustdoc().input("foo.rs").crate_name(crate_name).out_dir("plain").run();
+    assert!(!path("plain").join(crate_name).join("search-index.rsidx").exists());
+
+    rustdoc()
+        .input("foo.rs")
+        .crate_name(crate_name)
+        .arg("-Zunstable-options")
+        .arg("--offline-search-index")
+        .out_dir("out")
+        .run();
+
+    let index = read(path("out").join(crate_name).join("search-index.rsidx"));
+    assert!(index.starts_with(b"RDSI"), "missing magic bytes");
+    for name in ["Widgets", "count_widgets", "frobnicate", "Counts the widgets."] {
+        assert!(
+            index.windows(name.len()).any(|window| window == name.as_bytes()),
+            "`{name}` is missing from the index"
+        );
+    }
+}
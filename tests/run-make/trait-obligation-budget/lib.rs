@@ -0,0 +1,9 @@
+pub trait Expensive {}
+
+impl<T: Copy> Expensive for T {}
+
+fn require<T: Expensive>(_: T) {}
+
+pub fn f() {
+    require(1u8);
+}
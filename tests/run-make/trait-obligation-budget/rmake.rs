@@ -0,0 +1,23 @@
+// `-Z trait-obligation-budget` reports the trait obligations whose solving takes longer than the
+// budget, at the code requiring them. The times are reported once the crate has been analyzed
+// rather than by the queries measuring them, so an incremental rebuild reusing the results of the
+// queries does not report them again.
+
+use run_make_support::rustc;
+
+fn main() {
+    let compile = || {
+        rustc()
+            .input("lib.rs")
+            .crate_type("lib")
+            .incremental("incr")
+            .arg("-Ztrait-obligation-budget=0")
+            .run()
+    };
+    compile()
+        .assert_stderr_contains("warning: solving the trait obligation `u8: Expensive` took")
+        .assert_stderr_contains(
+            "this exceeds the budget of 0ms set with `-Z trait-obligation-budget`",
+        );
+    compile().assert_stderr_not_contains("solving the trait obligation");
+}
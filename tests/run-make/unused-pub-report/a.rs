@@ -0,0 +1,30 @@
+pub struct UsedByB;
+pub struct UsedByMain;
+pub struct UsedThroughValue;
+pub struct Unused;
+
+impl UsedByMain {
+    pub fn used_method(&self) {}
+    pub fn unused_method(&self) {}
+}
+
+pub fn value() -> UsedThroughValue {
+    used_internally();
+    UsedThroughValue
+}
+
+pub fn used_internally() {}
+
+pub fn used_by_tool() {}
+
+pub fn unused_fn() {}
+
+// A function and a struct with the same def path.
+pub struct Shared {}
+#[allow(non_snake_case)]
+pub fn Shared() {}
+
+pub mod inner {
+    pub fn used_inner() {}
+    pub fn unused_inner() {}
+}
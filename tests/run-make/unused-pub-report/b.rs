@@ -0,0 +1,6 @@
+pub fn use_a() -> a::UsedByB {
+    a::inner::used_inner();
+    a::UsedByB
+}
+
+pub fn unused_in_b() {}
@@ -0,0 +1,5 @@
+fn main() {
+    b::use_a();
+    a::UsedByMain.used_method();
+    let _ = a::value();
+}
@@ -0,0 +1,89 @@
+// `-Z unused-pub-report` lists the public items of the crates compiled with
+// `-Z record-used-extern-items`, with the other crates using them. This test checks that the items
+// used by a dependency, by the final crate, or only through the value of another item are reported
+// as used, while the others, including those only used within their own crate, are not. It also
+// checks that the items sharing a def path get distinct keys, and that the reports of two final
+// crates using different items can be merged.
+
+//@ ignore-cross-compile
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{rfs, rustc};
+
+fn report(leaf: &str) -> Value {
+    let report = format!("{leaf}.json");
+    rustc()
+        .input(format!("{leaf}.rs"))
+        .extern_("a", "liba.rlib")
+        .extern_("b", "libb.rlib")
+        .arg(format!("-Zunused-pub-report={report}"))
+        .run();
+    serde_json::from_str(&rfs::read_to_string(&report)).unwrap()
+}
+
+fn used_by(report: &Value, item: &str) -> Vec<String> {
+    let used_by = &report["items"][item]["used_by"];
+    assert!(used_by.is_array(), "`{item}` is not reported:\n{report:#}");
+    used_by.as_array().unwrap().iter().map(|krate| krate.as_str().unwrap().to_owned()).collect()
+}
+
+fn main() {
+    rustc().crate_type("rlib").input("a.rs").arg("-Zrecord-used-extern-items").run();
+    rustc()
+        .crate_type("rlib")
+        .input("b.rs")
+        .extern_("a", "liba.rlib")
+        .arg("-Zrecord-used-extern-items")
+        .run();
+
+    let main = report("main");
+    assert_eq!(main["crate_name"], "main");
+    assert_eq!(main["items"]["a::Unused"]["crate"], "a");
+    assert_eq!(main["items"]["a::Unused"]["kind"], "struct");
+    for (item, crates) in [
+        ("a::UsedByB", &["b", "main"][..]),
+        ("a::UsedByMain", &["main"]),
+        ("a::UsedThroughValue", &["main"]),
+        ("a::value", &["main"]),
+        ("a::inner::used_inner", &["b"]),
+        ("a::UsedByMain::used_method", &["main"]),
+        ("b::use_a", &["main"]),
+        ("a::Unused", &[]),
+        ("a::unused_fn", &[]),
+        ("a::used_internally", &[]),
+        ("a::used_by_tool", &[]),
+        ("a::inner::unused_inner", &[]),
+        ("a::UsedByMain::unused_method", &[]),
+        ("b::unused_in_b", &[]),
+    ] {
+        assert_eq!(used_by(&main, item), crates, "wrong uses of `{item}`");
+    }
+
+    // The function and the struct named `Shared` are both reported, under distinct keys.
+    let mut shared_kinds: Vec<&str> = main["items"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .filter(|(key, _)| key.starts_with("a::Shared#"))
+        .map(|(_, item)| item["kind"].as_str().unwrap())
+        .collect();
+    shared_kinds.sort();
+    assert_eq!(shared_kinds, ["function", "struct"]);
+
+    // The report of another final crate, using other items, is merged by joining the crates
+    // using each item.
+    let tool = report("tool");
+    assert_eq!(used_by(&tool, "a::used_by_tool"), ["tool"]);
+    assert_eq!(used_by(&tool, "a::UsedByMain"), Vec::<String>::new());
+    let merged_used_by = |item| {
+        let mut crates = used_by(&main, item);
+        crates.extend(used_by(&tool, item));
+        crates.sort();
+        crates.dedup();
+        crates
+    };
+    assert_eq!(merged_used_by("a::used_by_tool"), ["tool"]);
+    assert_eq!(merged_used_by("a::UsedByMain"), ["main"]);
+    assert_eq!(merged_used_by("a::UsedByB"), ["b", "main"]);
+    assert_eq!(merged_used_by("a::Unused"), Vec::<String>::new());
+}
@@ -0,0 +1,3 @@
+fn main() {
+    a::used_by_tool();
+}
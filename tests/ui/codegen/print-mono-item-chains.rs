@@ -0,0 +1,18 @@
+// Checks that `-Z print-mono-item-chains` prints how each matching instance was reached from a
+// root, and that `-Z print-mono-item-counts` prints the generic functions with the most
+// instances.
+
+//@ compile-flags: -Z print-mono-item-chains=leaf -Z print-mono-item-counts=2 --crate-type=lib
+//@ build-pass
+//@ ignore-pass
+
+pub fn root() {
+    middle::<u8>();
+    middle::<u16>();
+}
+
+fn middle<T>() {
+    leaf::<T>();
+}
+
+fn leaf<T>() {}
@@ -0,0 +1,31 @@
+// Checks that `-Z const-eval-step-budget` reports the constants whose evaluation exceeds it.
+
+//@ check-pass
+//@ compile-flags: -Z const-eval-step-budget=100
+//@ normalize-stderr-test: "\d+ms and \d+ steps" -> "Nms and N steps"
+
+const CHEAP: u32 = 1 + 1;
+
+const EXPENSIVE: u64 = {
+    //~^ WARN evaluating `EXPENSIVE` took
+    let mut sum = 0;
+    let mut i = 0;
+    while i < 1000 {
+        sum += i;
+        i += 1;
+    }
+    sum
+};
+
+#[allow(compile_time_budget_exceeded)]
+const ALLOWED: u64 = {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < 1000 {
+        sum += i;
+        i += 1;
+    }
+    sum
+};
+
+fn main() {}
@@ -0,0 +1,31 @@
+// Check that `-Z explain-borrow-constraints` points at the chain of outlives constraints causing
+// a lifetime error, in order, for errors between free regions, for borrows that must outlive one,
+// and for borrows that are used later. The consecutive constraints with the same span are merged
+// into a single label.
+
+//@ compile-flags: -Z explain-borrow-constraints
+
+fn reborrows<'a>(x: &'a u32) -> &'static u32 {
+    let y = &*x;
+    let z = y;
+    z //~ ERROR lifetime may not live long enough
+}
+
+fn store(v: &mut Vec<&'static u32>) {
+    let x = 0;
+    let r = &x; //~ ERROR `x` does not live long enough
+    let s = r;
+    v.push(s);
+}
+
+fn used_later() {
+    let mut x = 0;
+    let r = &x;
+    let s = r;
+    x = 1; //~ ERROR cannot assign to `x` because it is borrowed
+    consume(s);
+}
+
+fn consume(_: &u32) {}
+
+fn main() {}
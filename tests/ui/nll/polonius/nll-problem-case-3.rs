@@ -0,0 +1,34 @@
+// NLL problem case #3: a borrow returned from the function on one branch of a conditional is not
+// live on the other branches, where the borrowed place can be used again. NLLs reject these
+// functions because the returned loan is contained in a free region at every point, while the
+// location-sensitive `-Zpolonius=next` analysis accepts them.
+
+//@ revisions: nll polonius
+//@ [polonius] check-pass
+//@ [polonius] compile-flags: -Zpolonius=next
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+fn get_default<K: Hash + Eq + Copy, V: Default>(map: &mut HashMap<K, V>, key: K) -> &mut V {
+    match map.get_mut(&key) {
+        Some(value) => value,
+        None => {
+            map.insert(key, V::default());
+            //[nll]~^ ERROR cannot borrow `*map` as mutable more than once at a time
+            map.get_mut(&key).unwrap()
+            //[nll]~^ ERROR cannot borrow `*map` as mutable more than once at a time
+        }
+    }
+}
+
+fn get_or_insert<'a>(map: &'a mut HashMap<u32, String>, key: u32) -> &'a String {
+    if let Some(value) = map.get(&key) {
+        return value;
+    }
+    map.insert(key, String::new());
+    //[nll]~^ ERROR cannot borrow `*map` as mutable because it is also borrowed as immutable
+    map.get(&key).unwrap()
+}
+
+fn main() {}
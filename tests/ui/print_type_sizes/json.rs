@@ -0,0 +1,33 @@
+//@ compile-flags: -Z print-type-sizes -Z print-type-sizes-format=json --crate-type=lib
+//@ build-pass
+//@ ignore-pass
+//@ only-64bit
+// ^-- the niche of `Option<&u8>` is pointer-sized.
+
+// This file illustrates the JSON output of `-Z print-type-sizes`, which also describes the tag
+// encoding and the largest niche of each type, and the generic arguments of instantiations of
+// generic types.
+
+#![allow(dead_code)]
+
+#[repr(u8)]
+enum Repru8 {
+    A(u16),
+    B,
+}
+
+struct S {
+    a: bool,
+    g: u16,
+}
+
+struct Wrapper<T> {
+    value: T,
+    flag: bool,
+}
+
+pub fn start() {
+    // The tag of `None` is stored in the niche of the reference.
+    let _o: Option<&u8> = None;
+    let _w = Wrapper { value: 0u32, flag: false };
+}
@@ -0,0 +1,15 @@
+//@ force-host
+//@ no-prefer-dynamic
+//@ edition:2021
+
+// The items of `proc_macro` are suggested in proc-macro crates, with a note to add the
+// `extern crate` item needed to use them.
+
+#![crate_type = "proc-macro"]
+
+#[proc_macro]
+pub fn foo(input: TokenStream) -> TokenStream {
+    //~^ ERROR cannot find type `TokenStream` in this scope
+    //~| ERROR cannot find type `TokenStream` in this scope
+    input
+}
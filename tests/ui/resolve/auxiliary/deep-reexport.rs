@@ -0,0 +1,9 @@
+pub mod reexports {
+    pub use crate::a::b::Thing;
+}
+
+pub mod a {
+    pub mod b {
+        pub struct Thing;
+    }
+}
@@ -0,0 +1,10 @@
+//@ edition:2021
+
+// The items of `alloc` are suggested in `#![no_std]` crates, with a note to add the `extern crate`
+// item needed to use them.
+
+#![no_std]
+#![crate_type = "lib"]
+
+pub struct S(Rc<u8>);
+//~^ ERROR cannot find type `Rc` in this scope
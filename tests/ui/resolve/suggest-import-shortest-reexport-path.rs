@@ -0,0 +1,10 @@
+//@ aux-crate:deep_reexport=deep-reexport.rs
+//@ edition:2021
+
+// The import suggestions for the items of extern crates use the shortest public path to them,
+// even when it goes through a re-export.
+
+struct S(Thing);
+//~^ ERROR cannot find type `Thing` in this scope
+
+fn main() {}
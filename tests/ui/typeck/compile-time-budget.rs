@@ -0,0 +1,21 @@
+// Checks that `-Z compile-time-budget` reports the bodies whose type-checking exceeds it, with the
+// time of the self-profile activities it consists of.
+
+//@ check-pass
+//@ compile-flags: -Z compile-time-budget=0
+//@ normalize-stderr-test: "\d+ms" -> "Nms"
+
+fn sum(values: &[u32]) -> u32 {
+    //~^ WARN type-checking `sum` took
+    values.iter().copied().sum()
+}
+
+#[allow(compile_time_budget_exceeded)]
+fn allowed() {}
+
+fn main() {
+    //~^ WARN type-checking `main` took
+    let values = [1, 2];
+    allowed();
+    sum(&values);
+}